@@ -6,6 +6,7 @@ use ockam_core::compat::{
     string::{String, ToString},
     vec::Vec,
 };
+use ockam_core::flow_control::FlowControls;
 use ockam_core::{
     route, Address, AllowAll, AllowSourceAddress, DenyAll, Mailbox, Mailboxes,
     OutgoingAccessControl, Result, Route,
@@ -70,6 +71,7 @@ impl RemoteForwarder {
         registration_payload: String,
         heartbeat: Option<DelayedEvent<Vec<u8>>>,
         heartbeat_interval: Duration,
+        flow_controls: Option<FlowControls>,
     ) -> Self {
         Self {
             addresses,
@@ -78,6 +80,7 @@ impl RemoteForwarder {
             registration_payload,
             heartbeat,
             heartbeat_interval,
+            flow_controls,
         }
     }
 
@@ -112,6 +115,7 @@ impl RemoteForwarder {
             alias.into(),
             Some(heartbeat),
             Duration::from_secs(5),
+            options.flow_controls,
         );
 
         debug!(
@@ -159,6 +163,7 @@ impl RemoteForwarder {
             "register".to_string(),
             None,
             Duration::from_secs(10),
+            options.flow_controls,
         );
 
         debug!(
@@ -206,6 +211,7 @@ impl RemoteForwarder {
             alias.into(),
             None,
             Duration::from_secs(10),
+            options.flow_controls,
         );
 
         debug!(
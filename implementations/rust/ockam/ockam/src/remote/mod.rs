@@ -13,6 +13,7 @@ pub use options::*;
 use crate::remote::addresses::Addresses;
 use core::time::Duration;
 use ockam_core::compat::{string::String, vec::Vec};
+use ockam_core::flow_control::FlowControls;
 use ockam_core::Route;
 use ockam_node::DelayedEvent;
 
@@ -26,4 +27,5 @@ pub struct RemoteForwarder {
     // We only use Heartbeat for static RemoteForwarder
     heartbeat: Option<DelayedEvent<Vec<u8>>>,
     heartbeat_interval: Duration,
+    flow_controls: Option<FlowControls>,
 }
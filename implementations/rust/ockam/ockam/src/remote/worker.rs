@@ -26,6 +26,15 @@ impl Worker for RemoteForwarder {
         Ok(())
     }
 
+    async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+        if let Some(flow_controls) = &self.flow_controls {
+            flow_controls.cleanup_address(&self.addresses.main_remote);
+            flow_controls.cleanup_address(&self.addresses.main_internal);
+        }
+
+        Ok(())
+    }
+
     async fn handle_message(
         &mut self,
         ctx: &mut Context,
@@ -6,16 +6,21 @@ use ockam_core::compat::fmt::Formatter;
 use ockam_core::compat::str;
 use ockam_core::compat::vec::vec;
 use ockam_core::Result;
-use ockam_core::{IncomingAccessControl, RelayMessage};
+use ockam_core::{IncomingAccessControl, LocalMessage, RelayMessage, TransportPeerInfo};
 use tracing as log;
 
+use crate::audit::record;
+use crate::explain::{explain, Explanation};
 use crate::expr::str;
+use crate::types::{Action, Resource};
 use crate::Expr::*;
-use crate::{eval, Env, Expr};
+use crate::{eval, Decision, DecisionLog, Env, Expr};
 use ockam_core::compat::format;
-use ockam_core::compat::string::ToString;
+use ockam_core::compat::string::{String, ToString};
 use ockam_core::compat::sync::Arc;
-use ockam_identity::{IdentitiesRepository, IdentitySecureChannelLocalInfo};
+use ockam_identity::{
+    AttributesEntry, IdentitiesRepository, IdentityIdentifier, IdentitySecureChannelLocalInfo,
+};
 
 /// This AccessControl uses a storage for authenticated attributes in order
 /// to verify if a policy expression is valid
@@ -25,6 +30,8 @@ pub struct AbacAccessControl {
     repository: Arc<dyn IdentitiesRepository>,
     expression: Expr,
     environment: Env,
+    resource: Option<(Resource, Action)>,
+    decision_log: Option<Arc<dyn DecisionLog>>,
 }
 
 /// Debug implementation printing out the policy expression only
@@ -46,9 +53,48 @@ impl AbacAccessControl {
             repository,
             expression,
             environment,
+            resource: None,
+            decision_log: None,
         }
     }
 
+    /// Record the decisions taken by this access control in a decision log
+    pub fn with_decision_log(mut self, decision_log: Arc<dyn DecisionLog>) -> Self {
+        self.decision_log = Some(decision_log);
+        self
+    }
+
+    /// Set the resource and action protected by this access control, as
+    /// recorded in its decisions
+    pub fn with_resource_action(mut self, resource: Resource, action: Action) -> Self {
+        self.resource = Some((resource, action));
+        self
+    }
+
+    /// Evaluate the policy against the attributes of an identity, without
+    /// any message being sent, and explain the result
+    ///
+    /// The facts about the channel and transport of a message are not bound.
+    pub async fn explain(&self, id: &IdentityIdentifier) -> Result<Explanation> {
+        let mut environment = self.environment.clone();
+        let attributes = self.repository.get_attributes(id).await?;
+        add_subject_attributes(&self.expression, &mut environment, id, attributes);
+        Ok(explain(&self.expression, &environment))
+    }
+
+    /// Record a decision in the decision log, if any
+    async fn record(&self, decision: Decision) {
+        let decision = match &self.resource {
+            Some((r, a)) => decision.with_resource(r).with_action(a),
+            None => decision,
+        };
+        record(
+            self.decision_log.as_ref(),
+            decision.with_policy(&self.expression),
+        )
+        .await
+    }
+
     /// Create an AccessControl which will verify that the sender of
     /// a message has an authenticated attribute with the correct name and value
     pub fn create(
@@ -71,87 +117,143 @@ impl IncomingAccessControl for AbacAccessControl {
     /// Return true if the sender of the message is validated by the expression stored in AbacAccessControl
     async fn is_authorized(&self, msg: &RelayMessage) -> Result<bool> {
         // Get identity identifier from message metadata:
-        let id = if let Ok(info) = IdentitySecureChannelLocalInfo::find_info(msg.local_message()) {
-            info.their_identity_id().clone()
+        let info = if let Ok(info) = IdentitySecureChannelLocalInfo::find_info(msg.local_message())
+        {
+            info
         } else {
             log::debug! {
                 policy = %self.expression,
                 "identity identifier not found; access denied"
             }
+            self.record(Decision::new(false, "identity identifier not found"))
+                .await;
             return Ok(false);
         };
 
+        let id = info.their_identity_id();
+
         let mut environment = self.environment.clone();
 
+        // Add the facts about the channel and transport of the message:
+        add_message_attributes(&mut environment, msg.local_message(), &info);
+
         // Get identity attributes and populate the environment:
-        if let Some(attrs) = self.repository.get_attributes(&id).await? {
-            for (key, value) in attrs.attrs() {
-                if key.find(|c: char| c.is_whitespace()).is_some() {
-                    log::warn! {
-                        policy = %self.expression,
-                        id     = %id,
-                        key    = %key,
-                        "attribute key with whitespace ignored"
-                    }
+        let attributes = self.repository.get_attributes(&id).await?;
+        add_subject_attributes(&self.expression, &mut environment, &id, attributes);
+
+        // Finally, evaluate the expression, record and return the result:
+        let (allowed, reason) = evaluate(&self.expression, &environment, &id);
+        self.record(Decision::new(allowed, reason).with_subject(&id))
+            .await;
+        Ok(allowed)
+    }
+}
+
+/// Add the attributes of an identity, and its identifier, to an environment
+/// as `subject.<name>` string entries
+///
+/// Policies can convert them with `int`, `bool` or `timestamp` to compare them with other types.
+pub(crate) fn add_subject_attributes(
+    expression: &Expr,
+    environment: &mut Env,
+    id: &IdentityIdentifier,
+    attributes: Option<AttributesEntry>,
+) {
+    if let Some(attrs) = attributes {
+        for (key, value) in attrs.attrs() {
+            if key.find(|c: char| c.is_whitespace()).is_some() {
+                log::warn! {
+                    policy = %expression,
+                    id     = %id,
+                    key    = %key,
+                    "attribute key with whitespace ignored"
                 }
-                match str::from_utf8(value) {
-                    Ok(s) => {
-                        if environment.contains(key) {
-                            log::debug! {
-                                policy = %self.expression,
-                                id     = %id,
-                                key    = %key,
-                                "attribute already present"
-                            }
-                        } else {
-                            environment.put(format!("subject.{key}"), str(s.to_string()));
-                        }
-                    }
-                    Err(e) => {
-                        log::warn! {
-                            policy = %self.expression,
+            }
+            match str::from_utf8(value) {
+                Ok(s) => {
+                    if environment.contains(key) {
+                        log::debug! {
+                            policy = %expression,
                             id     = %id,
                             key    = %key,
-                            err    = %e,
-                            "failed to interpret attribute as string"
+                            "attribute already present"
                         }
+                    } else {
+                        environment.put(format!("subject.{key}"), str(s.to_string()));
+                    }
+                }
+                Err(e) => {
+                    log::warn! {
+                        policy = %expression,
+                        id     = %id,
+                        key    = %key,
+                        err    = %e,
+                        "failed to interpret attribute as string"
                     }
                 }
             }
-        };
+        }
+    };
 
-        // add the identifier itself as a subject parameter
-        environment.put("subject.identifier", str(id.to_string()));
-
-        // Finally, evaluate the expression and return the result:
-        match eval(&self.expression, &environment) {
-            Ok(Expr::Bool(b)) => {
-                log::debug! {
-                    policy        = %self.expression,
-                    id            = %id,
-                    is_authorized = %b,
-                    "policy evaluated"
-                }
-                Ok(b)
+    // add the identifier itself as a subject parameter
+    environment.put("subject.identifier", str(id.to_string()));
+}
+
+/// Add the facts about the secure channel and the transport connection a message
+/// was received from to an environment
+///
+/// `channel.initiator` is true if the secure channel was initiated by this node, and
+/// `transport.peer_ip` is the IP address of the peer of the transport connection,
+/// if the message was received from one.
+fn add_message_attributes(
+    environment: &mut Env,
+    msg: &LocalMessage,
+    info: &IdentitySecureChannelLocalInfo,
+) {
+    environment.put("channel.initiator", Bool(info.is_initiator()));
+    if let Some(peer) = TransportPeerInfo::find_info(msg) {
+        environment.put("transport.peer_ip", str(peer.ip()));
+    }
+}
+
+/// Evaluate a policy expression, any result other than `true` denies access
+///
+/// Return the result of the evaluation together with the reason for it.
+pub(crate) fn evaluate(
+    expression: &Expr,
+    environment: &Env,
+    id: &IdentityIdentifier,
+) -> (bool, String) {
+    match eval(expression, environment) {
+        Ok(Expr::Bool(b)) => {
+            log::debug! {
+                policy        = %expression,
+                id            = %id,
+                is_authorized = %b,
+                "policy evaluated"
             }
-            Ok(x) => {
-                log::warn! {
-                    policy = %self.expression,
-                    id     = %id,
-                    expr   = %x,
-                    "evaluation did not yield a boolean result"
-                }
-                Ok(false)
+            (b, format!("policy evaluated to {b}"))
+        }
+        Ok(x) => {
+            log::warn! {
+                policy = %expression,
+                id     = %id,
+                expr   = %x,
+                "evaluation did not yield a boolean result"
             }
-            Err(e) => {
-                log::warn! {
-                    policy = %self.expression,
-                    id     = %id,
-                    err    = %e,
-                    "policy evaluation failed"
-                }
-                Ok(false)
+            (
+                false,
+                format!("evaluation did not yield a boolean result: {x}"),
+            )
+        }
+        Err(e) => {
+            log::warn! {
+                policy = %expression,
+                id     = %id,
+                err    = %e,
+                "policy evaluation failed"
             }
+            (false, format!("policy evaluation failed: {e}"))
         }
     }
 }
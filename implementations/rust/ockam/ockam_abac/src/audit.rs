@@ -0,0 +1,135 @@
+use crate::expr::Expr;
+use crate::DecisionLog;
+use core::fmt::Write;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::sync::Arc;
+use ockam_identity::Timestamp;
+use sha2::{Digest, Sha256};
+use tracing as log;
+
+/// An allow or deny decision taken by an access control evaluating a policy
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Decision {
+    #[n(1)] timestamp: u64,
+    #[n(2)] resource: Option<String>,
+    #[n(3)] action: Option<String>,
+    #[n(4)] subject: Option<String>,
+    #[n(5)] policy_hash: Option<String>,
+    #[n(6)] allowed: bool,
+    #[n(7)] reason: String,
+}
+
+impl Decision {
+    /// Create a new decision, taken now
+    pub fn new(allowed: bool, reason: impl Into<String>) -> Self {
+        Self {
+            timestamp: Timestamp::now().map(|t| t.unix_time()).unwrap_or_default(),
+            resource: None,
+            action: None,
+            subject: None,
+            policy_hash: None,
+            allowed,
+            reason: reason.into(),
+        }
+    }
+
+    /// Set the time of the decision, in seconds since the UNIX epoch
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Set the resource which was accessed
+    pub fn with_resource(mut self, resource: impl ToString) -> Self {
+        self.resource = Some(resource.to_string());
+        self
+    }
+
+    /// Set the action which was performed on the resource
+    pub fn with_action(mut self, action: impl ToString) -> Self {
+        self.action = Some(action.to_string());
+        self
+    }
+
+    /// Set the identifier of the identity accessing the resource
+    pub fn with_subject(mut self, subject: impl ToString) -> Self {
+        self.subject = Some(subject.to_string());
+        self
+    }
+
+    /// Set the hash of the evaluated policy, see [`policy_hash`]
+    pub fn with_policy(self, policy: &Expr) -> Self {
+        self.with_policy_hash(policy_hash(policy))
+    }
+
+    /// Set the hash of the evaluated policy
+    pub fn with_policy_hash(mut self, policy_hash: impl Into<String>) -> Self {
+        self.policy_hash = Some(policy_hash.into());
+        self
+    }
+
+    /// Time of the decision, in seconds since the UNIX epoch
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Resource which was accessed, if known
+    pub fn resource(&self) -> Option<&str> {
+        self.resource.as_deref()
+    }
+
+    /// Action which was performed on the resource, if known
+    pub fn action(&self) -> Option<&str> {
+        self.action.as_deref()
+    }
+
+    /// Identifier of the identity accessing the resource, if known
+    pub fn subject(&self) -> Option<&str> {
+        self.subject.as_deref()
+    }
+
+    /// Hash of the evaluated policy, if any
+    pub fn policy_hash(&self) -> Option<&str> {
+        self.policy_hash.as_deref()
+    }
+
+    /// Return true if the access was allowed
+    pub fn is_allowed(&self) -> bool {
+        self.allowed
+    }
+
+    /// Explanation of the decision
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+/// Hash identifying a policy expression: the hex encoded SHA-256 digest of its
+/// textual representation
+pub fn policy_hash(policy: &Expr) -> String {
+    let digest = Sha256::digest(policy.to_string().as_bytes());
+    let mut hash = String::with_capacity(2 * digest.len());
+    for b in digest {
+        let _ = write!(hash, "{b:02x}");
+    }
+    hash
+}
+
+/// Append a decision to a decision log, if any
+///
+/// Failures are logged but don't change the decision.
+pub(crate) async fn record(decision_log: Option<&Arc<dyn DecisionLog>>, decision: Decision) {
+    if let Some(decision_log) = decision_log {
+        if let Err(e) = decision_log.record(&decision).await {
+            log::warn! {
+                resource = ?decision.resource(),
+                action   = ?decision.action(),
+                err      = %e,
+                "failed to record a policy decision"
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use ockam_abac::{eval, parse, Env, Expr};
+use ockam_abac::{eval, explain, parse, Env, Expr};
 use rustyline::error::ReadlineError;
 use rustyline::highlight::MatchingBracketHighlighter;
 use rustyline::validate::MatchingBracketValidator;
@@ -7,6 +7,7 @@ use rustyline_derive::{Completer, Helper, Highlighter, Hinter, Validator};
 
 const HELP: &str = r#"Available commands:
   :def <id> <expression>  -- Add an expression to the environment.
+  :explain <expression>   -- Evaluate an expression and show its evaluation trace.
   :env                    -- Show all current environment entries.
   :clear                  -- Remove all bindings from the environment.
   :help | :h | :?         -- Show this help message."#;
@@ -80,6 +81,18 @@ fn on_command(line: &str, env: &mut Env) {
             Ok(_) => eprintln!("invalid :def command"),
             Err(e) => eprintln!("error: {e}"),
         },
+        (":explain", rest) => match parse(rest) {
+            Ok(Some(e)) => {
+                let x = explain(&e, env);
+                print!("{x}");
+                let unbound = x.unbound();
+                if !unbound.is_empty() {
+                    println!("unbound: {}", unbound.join(" "))
+                }
+            }
+            Ok(None) => eprintln!("invalid :explain command"),
+            Err(e) => eprintln!("error: {e}"),
+        },
         (":env", _) => {
             for (id, expr) in env.entries() {
                 println!("{id} {expr}")
@@ -0,0 +1,252 @@
+use crate::traits::PolicyStorage;
+use crate::types::{Action, Resource};
+use crate::Expr;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::format;
+use ockam_core::compat::string::ToString;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::vault::Signature;
+use ockam_core::{Error, Result};
+use ockam_identity::{Identities, Identity, IdentityIdentifier, SignedPolicyBundle, Timestamp};
+
+/// Prefix of the data signed for a policy bundle, so that the signature of a
+/// bundle can't be mistaken for the signature of other data of its issuer
+const SIGNATURE_DOMAIN: &[u8] = b"ockam:policy-bundle:v1:";
+
+/// A policy of a [`PolicyBundle`]
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyBundleEntry {
+    #[n(1)] resource: Resource,
+    #[n(2)] action: Action,
+    #[n(3)] expression: Expr,
+}
+
+impl PolicyBundleEntry {
+    pub fn new(resource: Resource, action: Action, expression: Expr) -> Self {
+        Self {
+            resource,
+            action,
+            expression,
+        }
+    }
+
+    pub fn resource(&self) -> &Resource {
+        &self.resource
+    }
+
+    pub fn action(&self) -> &Action {
+        &self.action
+    }
+
+    pub fn expression(&self) -> &Expr {
+        &self.expression
+    }
+}
+
+/// A versioned set of policies published by an authority for the nodes of
+/// its trust context
+///
+/// Applying a bundle to a node replaces the policies of the resources and
+/// actions it contains, the other policies of the node are left untouched.
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyBundle {
+    #[n(1)] issuer: IdentityIdentifier,
+    #[n(2)] version: u64,
+    #[n(3)] created: u64,
+    #[n(4)] policies: Vec<PolicyBundleEntry>,
+}
+
+impl PolicyBundle {
+    /// Create a new bundle, created now
+    pub fn new(issuer: IdentityIdentifier, version: u64, policies: Vec<PolicyBundleEntry>) -> Self {
+        Self {
+            issuer,
+            version,
+            created: Timestamp::now().map(|t| t.unix_time()).unwrap_or_default(),
+            policies,
+        }
+    }
+
+    /// The authority which published the bundle
+    pub fn issuer(&self) -> &IdentityIdentifier {
+        &self.issuer
+    }
+
+    /// Version of the bundle, increasing with each publication
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Time of the publication, in seconds since the UNIX epoch
+    pub fn created(&self) -> u64 {
+        self.created
+    }
+
+    /// The policies of the bundle
+    pub fn policies(&self) -> &[PolicyBundleEntry] {
+        &self.policies
+    }
+
+    /// Sign the bundle with the key of its issuer
+    pub async fn sign(
+        &self,
+        identities: &Identities,
+        issuer: &Identity,
+    ) -> Result<SignedPolicyBundle> {
+        let data = minicbor::to_vec(self)?;
+        let signature = identities
+            .identities_keys()
+            .create_signature(issuer, &signed_data(&data), None)
+            .await?;
+        Ok(SignedPolicyBundle::new(data, signature.as_ref().to_vec()))
+    }
+
+    /// Decode a signed bundle, checking that it was signed by the given authority
+    pub async fn verify(
+        identities: &Identities,
+        authority: &Identity,
+        signed: &SignedPolicyBundle,
+    ) -> Result<PolicyBundle> {
+        let bundle: PolicyBundle = minicbor::decode(signed.unverified_data())?;
+        if bundle.issuer != authority.identifier() {
+            let msg = format!(
+                "policy bundle issued by the unknown authority {}",
+                bundle.issuer
+            );
+            return Err(Error::new(Origin::Application, Kind::Invalid, msg));
+        }
+        let signature = Signature::new(signed.signature().to_vec());
+        if !identities
+            .identities_keys()
+            .verify_signature(
+                authority,
+                &signature,
+                &signed_data(signed.unverified_data()),
+                None,
+            )
+            .await?
+        {
+            return Err(Error::new(
+                Origin::Application,
+                Kind::Invalid,
+                "invalid policy bundle signature",
+            ));
+        }
+        Ok(bundle)
+    }
+
+    /// Store the policies of the bundle, as new versions authored by the
+    /// issuer of the bundle, and return the number of changed policies
+    ///
+    /// Policies which are already identical to the ones of the bundle are
+    /// not changed, so applying the same bundle again has no effect.
+    pub async fn apply(&self, storage: &dyn PolicyStorage) -> Result<usize> {
+        let comment = format!("policy bundle version {}", self.version);
+        let mut changed = 0;
+        for p in &self.policies {
+            let current = storage.get_policy(&p.resource, &p.action).await?;
+            if current.map(|e| e.to_string()) == Some(p.expression.to_string()) {
+                continue;
+            }
+            storage
+                .update_policy(
+                    &p.resource,
+                    &p.action,
+                    &p.expression,
+                    Some(&self.issuer),
+                    Some(&comment),
+                )
+                .await?;
+            changed += 1
+        }
+        Ok(changed)
+    }
+}
+
+/// Data covered by the signature of an encoded bundle
+fn signed_data(data: &[u8]) -> Vec<u8> {
+    [SIGNATURE_DOMAIN, data].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{int, str};
+    use crate::mem::Memory;
+    use ockam_identity::secure_channels::secure_channels;
+
+    #[tokio::test]
+    async fn sign_verify_apply() {
+        let secure_channels = secure_channels();
+        let identities = secure_channels.identities();
+        let creation = identities.identities_creation();
+        let authority = creation.create_identity().await.unwrap();
+        let other = creation.create_identity().await.unwrap();
+
+        let bundle = PolicyBundle::new(
+            authority.identifier(),
+            1,
+            vec![PolicyBundleEntry::new(
+                Resource::new("echoer"),
+                Action::new("handle_message"),
+                str("x"),
+            )],
+        );
+        let signed = bundle.sign(&identities, &authority).await.unwrap();
+        assert!(PolicyBundle::verify(&identities, &other, &signed)
+            .await
+            .is_err());
+        let mut tampered = signed.clone();
+        tampered.data =
+            minicbor::to_vec(PolicyBundle::new(authority.identifier(), 2, vec![])).unwrap();
+        assert!(PolicyBundle::verify(&identities, &authority, &tampered)
+            .await
+            .is_err());
+        // The signature of the same data by the issuer for another purpose is rejected
+        let signature = identities
+            .identities_keys()
+            .create_signature(&authority, signed.unverified_data(), None)
+            .await
+            .unwrap();
+        let other_purpose = SignedPolicyBundle::new(
+            signed.unverified_data().to_vec(),
+            signature.as_ref().to_vec(),
+        );
+        assert!(
+            PolicyBundle::verify(&identities, &authority, &other_purpose)
+                .await
+                .is_err()
+        );
+        let verified = PolicyBundle::verify(&identities, &authority, &signed)
+            .await
+            .unwrap();
+        assert_eq!(verified.version(), 1);
+
+        let storage = Memory::new();
+        let local = (Resource::new("outlet"), Action::new("handle_message"));
+        storage
+            .set_policy(&local.0, &local.1, &int(1))
+            .await
+            .unwrap();
+        assert_eq!(verified.apply(&storage).await.unwrap(), 1);
+        assert_eq!(verified.apply(&storage).await.unwrap(), 0);
+
+        // Local policies which are not part of the bundle are kept
+        assert!(storage
+            .get_policy(&local.0, &local.1)
+            .await
+            .unwrap()
+            .is_some());
+        let history = storage
+            .policy_history(&Resource::new("echoer"), &Action::new("handle_message"))
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].author(), Some(&authority.identifier()));
+    }
+}
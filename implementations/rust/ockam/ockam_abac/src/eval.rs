@@ -3,9 +3,25 @@ use core::cmp::Ordering;
 use crate::env::Env;
 use crate::error::EvalError;
 use crate::expr::{unit, Expr};
-use ockam_core::compat::string::ToString;
+use crate::time;
+use ockam_core::compat::string::{String, ToString};
 use ockam_core::compat::vec::Vec;
 
+#[cfg(feature = "std")]
+use ockam_core::compat::format;
+
+/// Evaluate an expression in an environment.
+///
+/// Besides `and`, `or`, `not`, `if`, `<`, `>`, `=`, `!=`, `member?` and `exists?`
+/// the following operators are supported:
+///
+/// - `(let ((x e1) (y e2) ...) body)` binds names in order, for use in `body`,
+/// - `(now)` is the current time in seconds since the UNIX epoch,
+/// - `(timestamp "YYYY-MM-DDTHH:MM:SSZ")` converts a UTC time to seconds since the UNIX epoch,
+/// - `(int "42")` and `(bool "true")` convert strings, e.g. attribute values, to ints and booleans,
+/// - `(between? x lo hi)` checks that `lo <= x <= hi`, e.g. to check time windows,
+/// - `(starts-with? s p)`, `(ends-with? s p)` and `(matches? s regex)` test strings,
+/// - `(intersection xs ys)` and `(subset? xs ys)` operate on sequences.
 #[rustfmt::skip]
 pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
     /// A stack operation.
@@ -24,19 +40,38 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
         Lt(usize),
         Member,
         Seq(usize),
+        Between,
+        StartsWith,
+        EndsWith,
+        Matches,
+        Intersection,
+        Subset,
+        Timestamp,
+        ToInt,
+        ToBool,
+        Bind(&'a str),
+        Unbind(usize),
     }
 
     // Control stack.
     let mut ctrl: Vec<Op> = Vec::new();
     // Arguments stack.
     let mut args: Vec<Expr> = Vec::new();
+    // Values bound by `let` expressions, innermost binding last.
+    let mut scope: Vec<(&str, Expr)> = Vec::new();
 
     // Start with the toplevel expression.
     ctrl.push(Op::Eval(expr));
 
     while let Some(x) = ctrl.pop() {
         match x {
-            Op::Eval(Expr::Ident(id)) => ctrl.push(Op::Eval(env.get(id)?)),
+            Op::Eval(Expr::Ident(id)) => {
+                if let Some((_, v)) = scope.iter().rev().find(|(k, _)| *k == id.as_str()) {
+                    args.push(v.clone())
+                } else {
+                    ctrl.push(Op::Eval(env.get(id)?))
+                }
+            }
             Op::Eval(Expr::List(xs))  => match &xs[..] {
                 []                    => args.push(unit()),
                 [Expr::Ident(id), ..] => {
@@ -136,7 +171,7 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                             let mut b = true;
                             for x in &xs[1 ..] {
                                 match x {
-                                    Expr::Ident(id) => if !env.contains(id) {
+                                    Expr::Ident(id) => if !env.contains(id) && !scope.iter().any(|(k, _)| *k == id.as_str()) {
                                         b = false;
                                         break
                                     }
@@ -149,6 +184,90 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                             args.push(Expr::Bool(b));
                             continue
                         }
+                        "let" => {
+                            // The bindings are evaluated in order, each one seeing the
+                            // previous ones, then the body is evaluated and finally the
+                            // bindings are removed again.
+                            let (bindings, body) = let_bindings(xs)?;
+                            ctrl.push(Op::Unbind(bindings.len()));
+                            ctrl.push(Op::Eval(body));
+                            for (k, v) in bindings.into_iter().rev() {
+                                ctrl.push(Op::Bind(k));
+                                ctrl.push(Op::Eval(v))
+                            }
+                            continue
+                        }
+                        "now" => {
+                            if nargs != 0 {
+                                return Err(EvalError::malformed("'now' takes no arguments"))
+                            }
+                            match time::now() {
+                                Some(t) => args.push(Expr::Int(t)),
+                                None    => return Err(EvalError::malformed("current time is unavailable"))
+                            }
+                            continue
+                        }
+                        "timestamp" => {
+                            if nargs != 1 {
+                                let msg = "'timestamp' requires one argument";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Timestamp)
+                        }
+                        "int" => {
+                            if nargs != 1 {
+                                return Err(EvalError::malformed("'int' requires one argument"))
+                            }
+                            ctrl.push(Op::ToInt)
+                        }
+                        "bool" => {
+                            if nargs != 1 {
+                                return Err(EvalError::malformed("'bool' requires one argument"))
+                            }
+                            ctrl.push(Op::ToBool)
+                        }
+                        "between?" => {
+                            if nargs != 3 {
+                                let msg = "'between?' requires three arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Between)
+                        }
+                        "starts-with?" => {
+                            if nargs != 2 {
+                                let msg = "'starts-with?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::StartsWith)
+                        }
+                        "ends-with?" => {
+                            if nargs != 2 {
+                                let msg = "'ends-with?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::EndsWith)
+                        }
+                        "matches?" => {
+                            if nargs != 2 {
+                                let msg = "'matches?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Matches)
+                        }
+                        "intersection" => {
+                            if nargs != 2 {
+                                let msg = "'intersection' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Intersection)
+                        }
+                        "subset?" => {
+                            if nargs != 2 {
+                                let msg = "'subset?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Subset)
+                        }
                         _  => return Err(EvalError::Unknown(id.to_string()))
                     }
                     for x in xs[1 ..].iter().rev() {
@@ -259,6 +378,94 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                 let s = args.split_off(args.len() - n);
                 args.push(Expr::Seq(s))
             }
+            Op::Between => {
+                let hi = pop(&mut args);
+                let lo = pop(&mut args);
+                let x  = pop(&mut args);
+                let b = matches!(lo.compare(&x)?, Some(Ordering::Less | Ordering::Equal))
+                    && matches!(x.compare(&hi)?, Some(Ordering::Less | Ordering::Equal));
+                args.push(Expr::Bool(b))
+            }
+            Op::StartsWith => {
+                let (s, p) = pop_strings(&mut args, "'starts-with?' expects string arguments")?;
+                args.push(Expr::Bool(s.starts_with(p.as_str())))
+            }
+            Op::EndsWith => {
+                let (s, p) = pop_strings(&mut args, "'ends-with?' expects string arguments")?;
+                args.push(Expr::Bool(s.ends_with(p.as_str())))
+            }
+            Op::Matches => {
+                let (s, p) = pop_strings(&mut args, "'matches?' expects string arguments")?;
+                args.push(Expr::Bool(is_match(&s, &p)?))
+            }
+            Op::Intersection => {
+                let (xs, ys) = pop_seqs(&mut args, "'intersection' expects sequence arguments")?;
+                let mut zs = Vec::new();
+                for x in xs {
+                    if contains(&ys, &x)? && !contains(&zs, &x)? {
+                        zs.push(x)
+                    }
+                }
+                args.push(Expr::Seq(zs))
+            }
+            Op::Subset => {
+                let (xs, ys) = pop_seqs(&mut args, "'subset?' expects sequence arguments")?;
+                let mut b = true;
+                for x in &xs {
+                    if !contains(&ys, x)? {
+                        b = false;
+                        break
+                    }
+                }
+                args.push(Expr::Bool(b))
+            }
+            Op::Timestamp => {
+                match pop(&mut args) {
+                    Expr::Str(s) => match time::parse_timestamp(&s) {
+                        Some(t) => args.push(Expr::Int(t)),
+                        None    => {
+                            let msg = "'timestamp' expects a YYYY-MM-DDTHH:MM:SSZ string";
+                            return Err(EvalError::InvalidType(Expr::Str(s), msg))
+                        }
+                    }
+                    other => {
+                        let msg = "'timestamp' expects a string argument";
+                        return Err(EvalError::InvalidType(other, msg))
+                    }
+                }
+            }
+            Op::ToInt => {
+                match pop(&mut args) {
+                    Expr::Int(i) => args.push(Expr::Int(i)),
+                    Expr::Str(s) => match s.parse::<i64>() {
+                        Ok(i)  => args.push(Expr::Int(i)),
+                        Err(_) => {
+                            let msg = "'int' expects a string holding an integer";
+                            return Err(EvalError::InvalidType(Expr::Str(s), msg))
+                        }
+                    }
+                    other => {
+                        let msg = "'int' expects a string or an int argument";
+                        return Err(EvalError::InvalidType(other, msg))
+                    }
+                }
+            }
+            Op::ToBool => {
+                match pop(&mut args) {
+                    Expr::Bool(b) => args.push(Expr::Bool(b)),
+                    Expr::Str(s) if s == "true"  => args.push(Expr::Bool(true)),
+                    Expr::Str(s) if s == "false" => args.push(Expr::Bool(false)),
+                    other => {
+                        let msg = "'bool' expects \"true\", \"false\" or a boolean argument";
+                        return Err(EvalError::InvalidType(other, msg))
+                    }
+                }
+            }
+            Op::Bind(k) => {
+                let v = pop(&mut args);
+                scope.push((k, v))
+            }
+            Op::Unbind(n) => scope.truncate(scope.len() - n),
         }
     }
 
@@ -275,6 +482,104 @@ fn pop<T>(s: &mut Vec<T>) -> T {
     s.pop().expect("stack is not empty")
 }
 
+/// Names bound by a `let` expression, with their unevaluated values.
+type Bindings<'a> = Vec<(&'a str, &'a Expr)>;
+
+/// Split a `(let ((name value) ...) body)` expression into its bindings and body.
+pub(crate) fn let_bindings(xs: &[Expr]) -> Result<(Bindings<'_>, &Expr), EvalError> {
+    let (bindings, body) = match xs {
+        [_, Expr::List(bindings), body] => (bindings, body),
+        _ => {
+            let msg = "'let' requires a list of bindings and a body";
+            return Err(EvalError::malformed(msg));
+        }
+    };
+    let mut result = Vec::with_capacity(bindings.len());
+    for b in bindings {
+        match b {
+            Expr::List(kv) => match &kv[..] {
+                [Expr::Ident(k), v] => result.push((k.as_str(), v)),
+                _ => {
+                    let msg = "'let' bindings must be of the form (name value)";
+                    return Err(EvalError::InvalidType(b.clone(), msg));
+                }
+            },
+            other => {
+                let msg = "'let' bindings must be of the form (name value)";
+                return Err(EvalError::InvalidType(other.clone(), msg));
+            }
+        }
+    }
+    Ok((result, body))
+}
+
+/// Pop off the two topmost arguments, which must be strings.
+fn pop_strings(args: &mut Vec<Expr>, msg: &'static str) -> Result<(String, String), EvalError> {
+    let y = pop(args);
+    let x = pop(args);
+    match (x, y) {
+        (Expr::Str(x), Expr::Str(y)) => Ok((x, y)),
+        (Expr::Str(_), other) | (other, _) => Err(EvalError::InvalidType(other, msg)),
+    }
+}
+
+/// Pop off the two topmost arguments, which must be sequences.
+fn pop_seqs(args: &mut Vec<Expr>, msg: &'static str) -> Result<(Vec<Expr>, Vec<Expr>), EvalError> {
+    let y = pop(args);
+    let x = pop(args);
+    match (x, y) {
+        (Expr::Seq(x), Expr::Seq(y)) => Ok((x, y)),
+        (Expr::Seq(_), other) | (other, _) => Err(EvalError::InvalidType(other, msg)),
+    }
+}
+
+/// Check if a sequence contains an element.
+fn contains(xs: &[Expr], y: &Expr) -> Result<bool, EvalError> {
+    for x in xs {
+        if y.equals(x)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Check if a string matches a regular expression.
+///
+/// Regular expressions are compiled once and cached, since the same policies
+/// are evaluated for every message.
+#[cfg(feature = "std")]
+fn is_match(s: &str, pattern: &str) -> Result<bool, EvalError> {
+    use ockam_core::compat::collections::BTreeMap;
+    use ockam_core::compat::sync::Mutex;
+    use once_cell::sync::Lazy;
+    use regex::Regex;
+
+    /// Maximum number of cached regular expressions
+    const MAX_REGEXES: usize = 256;
+
+    static REGEXES: Lazy<Mutex<BTreeMap<String, Regex>>> = Lazy::new(Default::default);
+
+    let mut regexes = REGEXES.lock().unwrap();
+    if let Some(r) = regexes.get(pattern) {
+        return Ok(r.is_match(s));
+    }
+    let r = Regex::new(pattern)
+        .map_err(|e| EvalError::malformed(format!("invalid regular expression: {e}")))?;
+    let b = r.is_match(s);
+    if regexes.len() >= MAX_REGEXES {
+        regexes.clear()
+    }
+    regexes.insert(pattern.to_string(), r);
+    Ok(b)
+}
+
+#[cfg(not(feature = "std"))]
+fn is_match(_s: &str, _pattern: &str) -> Result<bool, EvalError> {
+    Err(EvalError::malformed(
+        "'matches?' requires regular expressions support",
+    ))
+}
+
 /// Evaluate a predicate against the `n` topmost arguments.
 fn eval_predicate<F>(n: usize, args: &mut Vec<Expr>, f: F) -> Result<(), EvalError>
 where
@@ -292,3 +597,192 @@ where
     args.push(Expr::Bool(b));
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::eval;
+    use crate::env::Env;
+    use crate::expr::{ident, int, seq, str, Expr};
+    use crate::parser::parse;
+    use quickcheck::{QuickCheck, TestResult};
+
+    fn call<I: IntoIterator<Item = Expr>>(op: &str, args: I) -> Expr {
+        Expr::List([ident(op)].into_iter().chain(args).collect())
+    }
+
+    fn ints(xs: &[i64]) -> Expr {
+        seq(xs.iter().copied().map(int))
+    }
+
+    fn is_true(e: Expr) -> bool {
+        eval(&e, &Env::new()).unwrap().is_true()
+    }
+
+    #[test]
+    fn intersection_is_subset() {
+        fn property(a: Vec<i64>, b: Vec<i64>) -> bool {
+            let i = call("intersection", [ints(&a), ints(&b)]);
+            is_true(call("subset?", [i.clone(), ints(&a)]))
+                && is_true(call("subset?", [i, ints(&b)]))
+        }
+        QuickCheck::new()
+            .tests(1000)
+            .quickcheck(property as fn(_, _) -> bool)
+    }
+
+    #[test]
+    fn intersection_members() {
+        fn property(a: Vec<i64>, b: Vec<i64>, x: i64) -> bool {
+            let i = call("intersection", [ints(&a), ints(&b)]);
+            is_true(call("member?", [int(x), i])) == (a.contains(&x) && b.contains(&x))
+        }
+        QuickCheck::new()
+            .tests(1000)
+            .quickcheck(property as fn(_, _, _) -> bool)
+    }
+
+    #[test]
+    fn subset() {
+        fn property(a: Vec<i64>, b: Vec<i64>) -> bool {
+            let expected = a.iter().all(|x| b.contains(x));
+            is_true(call("subset?", [ints(&a), ints(&a)]))
+                && is_true(call(
+                    "subset?",
+                    [ints(&a), ints(&[&a[..], &b[..]].concat())],
+                ))
+                && is_true(call("subset?", [ints(&a), ints(&b)])) == expected
+        }
+        QuickCheck::new()
+            .tests(1000)
+            .quickcheck(property as fn(_, _) -> bool)
+    }
+
+    #[test]
+    fn prefix_suffix() {
+        fn property(a: String, b: String) -> bool {
+            let s = format!("{a}{b}");
+            is_true(call("starts-with?", [str(s.clone()), str(a.clone())]))
+                && is_true(call("ends-with?", [str(s.clone()), str(b.clone())]))
+                && is_true(call("starts-with?", [str(a.clone()), str(s.clone())])) == b.is_empty()
+                && is_true(call("ends-with?", [str(b), str(s)])) == a.is_empty()
+        }
+        QuickCheck::new()
+            .tests(1000)
+            .quickcheck(property as fn(_, _) -> bool)
+    }
+
+    #[test]
+    fn matches_escaped() {
+        fn property(a: String) -> bool {
+            let r = format!("^{}$", regex::escape(&a));
+            is_true(call("matches?", [str(a), str(r)]))
+        }
+        QuickCheck::new()
+            .tests(1000)
+            .quickcheck(property as fn(_) -> bool)
+    }
+
+    #[test]
+    fn conversions() {
+        fn property(x: i64) -> bool {
+            is_true(call("=", [call("int", [str(x.to_string())]), int(x)]))
+                && is_true(call("=", [call("int", [int(x)]), int(x)]))
+        }
+        QuickCheck::new()
+            .tests(1000)
+            .quickcheck(property as fn(_) -> bool);
+        assert!(is_true(call("bool", [str("true")])));
+        assert!(!is_true(call("bool", [str("false")])));
+        assert!(eval(&call("int", [str("forty-two")]), &Env::new()).is_err());
+        assert!(eval(&call("bool", [str("yes")]), &Env::new()).is_err());
+    }
+
+    #[test]
+    fn between() {
+        fn property(x: i64, lo: i64, hi: i64) -> bool {
+            is_true(call("between?", [int(x), int(lo), int(hi)])) == (lo <= x && x <= hi)
+        }
+        QuickCheck::new()
+            .tests(1000)
+            .quickcheck(property as fn(_, _, _) -> bool)
+    }
+
+    #[test]
+    fn let_bindings() {
+        fn property(a: i64, b: i64) -> bool {
+            // inner bindings shadow outer ones
+            let e = call(
+                "let",
+                [
+                    Expr::List(vec![Expr::List(vec![ident("x"), int(a)])]),
+                    call(
+                        "let",
+                        [
+                            Expr::List(vec![Expr::List(vec![ident("x"), int(b)])]),
+                            ident("x"),
+                        ],
+                    ),
+                ],
+            );
+            // bindings see the previous ones
+            let f = call(
+                "let",
+                [
+                    Expr::List(vec![
+                        Expr::List(vec![ident("x"), int(a)]),
+                        Expr::List(vec![ident("y"), call("<", [ident("x"), int(b)])]),
+                    ]),
+                    ident("y"),
+                ],
+            );
+            eval(&e, &Env::new()).unwrap().equals(&int(b)).unwrap() && is_true(f) == (a < b)
+        }
+        QuickCheck::new()
+            .tests(1000)
+            .quickcheck(property as fn(_, _) -> bool)
+    }
+
+    #[test]
+    fn let_bindings_are_scoped() {
+        fn property(a: i64) -> TestResult {
+            let mut env = Env::new();
+            env.put("x", int(a));
+            let e = call(
+                "and",
+                [
+                    call(
+                        "let",
+                        [
+                            Expr::List(vec![Expr::List(vec![ident("x"), str("bound")])]),
+                            call("=", [ident("x"), str("bound")]),
+                        ],
+                    ),
+                    call("=", [ident("x"), int(a)]),
+                ],
+            );
+            TestResult::from_bool(eval(&e, &env).unwrap().is_true())
+        }
+        QuickCheck::new()
+            .tests(1000)
+            .quickcheck(property as fn(_) -> TestResult)
+    }
+
+    #[test]
+    fn time_window() {
+        let e = parse(
+            r#"(and (between? (now) (timestamp "2020-01-01T00:00:00Z") (timestamp "9999-12-31T23:59:59Z"))
+                    (not (between? (now) 0 (timestamp "2020-01-01T00:00:00Z"))))"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert!(is_true(e))
+    }
+
+    #[test]
+    fn malformed_let() {
+        assert!(parse("(let (x 1) x)").is_err());
+        assert!(parse("(let ((1 1)) x)").is_err());
+        assert!(parse("(let ((x 1)))").is_err());
+        assert!(parse("(let ((x 1) (y x)) (= x y))").unwrap().is_some())
+    }
+}
@@ -0,0 +1,203 @@
+use core::fmt;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
+
+use crate::env::Env;
+use crate::eval::{eval, let_bindings};
+use crate::expr::Expr;
+
+/// The trace of the evaluation of an expression
+///
+/// Each sub-expression which is not a literal is explained by its own trace,
+/// so that the sub-expressions responsible for a denial can be found.
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Explanation {
+    #[n(1)] expr: Expr,
+    #[n(2)] value: Option<Expr>,
+    #[n(3)] error: Option<String>,
+    #[n(4)] children: Vec<Explanation>,
+}
+
+impl Explanation {
+    /// The explained expression
+    pub fn expr(&self) -> &Expr {
+        &self.expr
+    }
+
+    /// The value of the expression, if its evaluation succeeded
+    pub fn value(&self) -> Option<&Expr> {
+        self.value.as_ref()
+    }
+
+    /// The evaluation error, if its evaluation failed
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// The explanations of the sub-expressions
+    pub fn children(&self) -> &[Explanation] {
+        &self.children
+    }
+
+    /// Return true if the expression evaluated to `true`
+    pub fn is_true(&self) -> bool {
+        self.value.as_ref().map(Expr::is_true).unwrap_or(false)
+    }
+
+    /// The sub-expressions, including this one, which evaluated to `false`
+    pub fn falsified(&self) -> Vec<&Expr> {
+        let mut result = Vec::new();
+        self.visit(&mut |e| {
+            if e.value.as_ref().map(Expr::is_false).unwrap_or(false) {
+                result.push(&e.expr)
+            }
+        });
+        result
+    }
+
+    /// The identifiers which were not bound in the environment
+    pub fn unbound(&self) -> Vec<&str> {
+        let mut result: Vec<&str> = Vec::new();
+        self.visit(&mut |e| {
+            if let (Expr::Ident(id), None) = (&e.expr, &e.value) {
+                if !result.contains(&id.as_str()) {
+                    result.push(id)
+                }
+            }
+        });
+        result
+    }
+
+    fn visit<'a, F: FnMut(&'a Explanation)>(&'a self, f: &mut F) {
+        f(self);
+        for c in &self.children {
+            c.visit(f)
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}{} => ", "", self.expr, indent = 2 * depth)?;
+        match (&self.value, &self.error) {
+            (Some(v), _) => writeln!(f, "{v}")?,
+            (None, Some(e)) => writeln!(f, "error: {e}")?,
+            (None, None) => writeln!(f, "?")?,
+        }
+        for c in &self.children {
+            c.write(f, depth + 1)?
+        }
+        Ok(())
+    }
+}
+
+/// Print the evaluation trace as a tree, one expression per line
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+/// Evaluate an expression in an environment and explain the result.
+///
+/// The value of the explained expression is the one returned by [`eval`].
+/// Contrary to [`eval`], all arguments of `and` and `or` are explained, even
+/// those which would not have to be evaluated.
+pub fn explain(expr: &Expr, env: &Env) -> Explanation {
+    let children = match expr {
+        Expr::List(xs) => match &xs[..] {
+            [Expr::Ident(id), ..] if id == "exists?" => Vec::new(),
+            [Expr::Ident(id), ..] if id == "let" => explain_let(xs, env),
+            [Expr::Ident(_), args @ ..] => explain_all(args, env),
+            _ => Vec::new(),
+        },
+        Expr::Seq(xs) => explain_all(xs, env),
+        _ => Vec::new(),
+    };
+    let (value, error) = match eval(expr, env) {
+        Ok(v) => (Some(v), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    Explanation {
+        expr: expr.clone(),
+        value,
+        error,
+        children,
+    }
+}
+
+/// Explain the expressions which are not literals.
+fn explain_all(xs: &[Expr], env: &Env) -> Vec<Explanation> {
+    xs.iter()
+        .filter(|x| matches!(x, Expr::Ident(_) | Expr::List(_) | Expr::Seq(_)))
+        .map(|x| explain(x, env))
+        .collect()
+}
+
+/// Explain the bound values and the body of a `let` expression, each one
+/// in the environment extended with the previous bindings.
+fn explain_let(xs: &[Expr], env: &Env) -> Vec<Explanation> {
+    let (bindings, body) = match let_bindings(xs) {
+        Ok(x) => x,
+        Err(_) => return Vec::new(),
+    };
+    let mut env = env.clone();
+    let mut result = Vec::with_capacity(bindings.len() + 1);
+    for (k, v) in bindings {
+        let e = explain(v, &env);
+        match &e.value {
+            Some(x) => {
+                env.put(k, x.clone());
+            }
+            None => env.del(k),
+        }
+        result.push(e)
+    }
+    result.push(explain(body, &env));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::explain;
+    use crate::env::Env;
+    use crate::expr::{int, str};
+    use crate::parser::parse;
+
+    #[test]
+    fn explain_denial() {
+        let policy = r#"
+            (and (= subject.role "admin")
+                 (or (= subject.team "ops") (= subject.level 3)))
+        "#;
+        let policy = parse(policy).unwrap().unwrap();
+
+        let mut env = Env::new();
+        env.put("subject.role", str("admin"))
+            .put("subject.level", int(2));
+
+        let e = explain(&policy, &env);
+        assert!(!e.is_true());
+        assert_eq!(e.unbound(), vec!["subject.team"]);
+        let falsified: Vec<String> = e.falsified().iter().map(|x| x.to_string()).collect();
+        assert!(falsified.contains(&"(= subject.level 3)".to_string()));
+        assert!(!falsified.contains(&r#"(= subject.role "admin")"#.to_string()));
+
+        env.put("subject.team", str("ops"));
+        let e = explain(&policy, &env);
+        assert!(e.is_true());
+        assert!(e.unbound().is_empty());
+    }
+
+    #[test]
+    fn explain_let() {
+        let policy = parse("(let ((x subject.n)) (> x 1))").unwrap().unwrap();
+        let mut env = Env::new();
+        env.put("subject.n", int(2));
+        let e = explain(&policy, &env);
+        assert!(e.is_true());
+        assert!(e.unbound().is_empty());
+        assert_eq!(e.children().len(), 2);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::expr::Expr;
+use crate::types::{Action, Resource};
+use minicbor::{Decode, Encode};
+use ockam_core::compat::format;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use ockam_identity::{IdentityIdentifier, Timestamp};
+
+/// A version of the policy of a resource and action
+///
+/// Versions are numbered from 1 and every change of a policy, including a
+/// rollback or a deletion, creates a new version. The version created by a
+/// deletion keeps the expression of the deleted policy.
+#[derive(Debug, Clone, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyVersion {
+    #[n(1)] version: u64,
+    #[n(2)] expression: Expr,
+    #[n(3)] author: Option<IdentityIdentifier>,
+    #[n(4)] timestamp: u64,
+    #[n(5)] comment: Option<String>,
+    #[n(6)] deleted: Option<bool>,
+}
+
+impl PolicyVersion {
+    /// Create a new version of a policy, changed now
+    pub fn new(
+        version: u64,
+        expression: Expr,
+        author: Option<IdentityIdentifier>,
+        comment: Option<String>,
+    ) -> Self {
+        Self {
+            version,
+            expression,
+            author,
+            timestamp: Timestamp::now().map(|t| t.unix_time()).unwrap_or_default(),
+            comment,
+            deleted: None,
+        }
+    }
+
+    /// Create a new version of a policy recording its deletion now
+    pub fn deletion(version: u64, expression: Expr) -> Self {
+        Self {
+            deleted: Some(true),
+            ..Self::new(version, expression, None, Some("deleted".to_string()))
+        }
+    }
+
+    /// Set the time of the change, in seconds since the UNIX epoch
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Version number
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Policy expression of this version
+    pub fn expression(&self) -> &Expr {
+        &self.expression
+    }
+
+    /// Identifier of the identity which made the change, if known
+    pub fn author(&self) -> Option<&IdentityIdentifier> {
+        self.author.as_ref()
+    }
+
+    /// Time of the change, in seconds since the UNIX epoch, 0 if unknown
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+
+    /// Comment describing the change, if any
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
+
+    /// Return true if this version records the deletion of the policy
+    pub fn is_deleted(&self) -> bool {
+        self.deleted == Some(true)
+    }
+}
+
+/// Number of the next version following a policy history
+pub(crate) fn next_version(history: &[PolicyVersion]) -> u64 {
+    history.last().map(|v| v.version + 1).unwrap_or(1)
+}
+
+/// Find the version of a policy to roll back to, returning its expression and
+/// the comment of the version created by the rollback
+///
+/// Rolling back to a version recording a deletion is refused, the previous
+/// version has to be used instead.
+pub(crate) fn rollback_to(
+    history: &[PolicyVersion],
+    r: &Resource,
+    a: &Action,
+    version: u64,
+) -> Result<(Expr, String)> {
+    let previous = history
+        .iter()
+        .find(|v| v.version == version)
+        .ok_or_else(|| {
+            let msg = format!("version {version} of the policy {r}/{a} not found");
+            Error::new(Origin::Application, Kind::NotFound, msg)
+        })?;
+    if previous.is_deleted() {
+        let msg = format!("version {version} of the policy {r}/{a} is a deletion");
+        return Err(Error::new(Origin::Application, Kind::Invalid, msg));
+    }
+    let comment = format!("rollback to version {version}");
+    Ok((previous.expression.clone(), comment))
+}
@@ -3,11 +3,18 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+mod audit;
+mod bundle;
 mod env;
 mod error;
 mod eval;
+mod explain;
+mod history;
+mod outgoing;
 mod policy;
+mod time;
 mod traits;
+mod trust_policy;
 mod types;
 
 #[cfg(feature = "std")]
@@ -19,17 +26,26 @@ pub mod mem;
 mod storage;
 
 pub use attribute_access_control::AbacAccessControl;
+pub use audit::{policy_hash, Decision};
+pub use bundle::{PolicyBundle, PolicyBundleEntry};
 pub use env::Env;
 pub use error::{EvalError, ParseError};
 pub use eval::eval;
+pub use explain::{explain, Explanation};
 pub use expr::Expr;
+pub use history::PolicyVersion;
+pub use outgoing::AbacOutgoingAccessControl;
 pub use policy::PolicyAccessControl;
-pub use traits::PolicyStorage;
+pub use traits::{DecisionFilter, DecisionLog, PolicyStorage};
+pub use trust_policy::AbacTrustPolicy;
 pub use types::{Action, Resource, Subject};
 
 #[cfg(feature = "std")]
 pub use parser::parse;
 
+#[cfg(feature = "std")]
+pub use storage::FileDecisionLog;
+
 #[cfg(not(feature = "std"))]
 pub use ockam_executor::tokio;
 
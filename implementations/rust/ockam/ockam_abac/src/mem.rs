@@ -1,13 +1,17 @@
 use crate::expr::Expr;
-use crate::traits::PolicyStorage;
+use crate::history::{next_version, rollback_to};
+use crate::traits::{DecisionFilter, DecisionLog, PolicyStorage};
 use crate::types::{Action, Resource};
+use crate::{Decision, PolicyVersion};
 use core::fmt;
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::ToString;
 use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
+use ockam_identity::IdentityIdentifier;
 
 #[derive(Default)]
 pub struct Memory {
@@ -31,6 +35,8 @@ impl Memory {
 #[derive(Default)]
 pub struct Inner {
     policies: BTreeMap<Resource, BTreeMap<Action, Expr>>,
+    history: BTreeMap<(Resource, Action), Vec<PolicyVersion>>,
+    decisions: Vec<Decision>,
 }
 
 impl Inner {
@@ -40,7 +46,10 @@ impl Inner {
 
     fn del_policy(&mut self, r: &Resource, a: &Action) {
         if let Some(p) = self.policies.get_mut(r) {
-            p.remove(a);
+            if let Some(expr) = p.remove(a) {
+                let history = self.history.entry((r.clone(), a.clone())).or_default();
+                history.push(PolicyVersion::deletion(next_version(history), expr));
+            }
             if p.is_empty() {
                 self.policies.remove(r);
             }
@@ -58,6 +67,44 @@ impl Inner {
             .insert(a.clone(), p.clone());
     }
 
+    fn update_policy(
+        &mut self,
+        r: &Resource,
+        a: &Action,
+        p: &Expr,
+        author: Option<&IdentityIdentifier>,
+        comment: Option<&str>,
+    ) -> PolicyVersion {
+        self.set_policy(r, a, p);
+        let history = self.history.entry((r.clone(), a.clone())).or_default();
+        let version = PolicyVersion::new(
+            next_version(history),
+            p.clone(),
+            author.cloned(),
+            comment.map(|c| c.to_string()),
+        );
+        history.push(version.clone());
+        version
+    }
+
+    fn rollback_policy(
+        &mut self,
+        r: &Resource,
+        a: &Action,
+        version: u64,
+        author: Option<&IdentityIdentifier>,
+    ) -> Result<PolicyVersion> {
+        let (expr, comment) = rollback_to(&self.policy_history(r, a), r, a, version)?;
+        Ok(self.update_policy(r, a, &expr, author, Some(&comment)))
+    }
+
+    fn policy_history(&self, r: &Resource, a: &Action) -> Vec<PolicyVersion> {
+        self.history
+            .get(&(r.clone(), a.clone()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
     fn policies(&self, r: &Resource) -> Vec<(Action, Expr)> {
         if let Some(p) = self.policies.get(r) {
             p.iter()
@@ -80,9 +127,36 @@ impl PolicyStorage for Memory {
         Ok(self.inner.read().unwrap().get_policy(r, a))
     }
 
-    async fn set_policy(&self, r: &Resource, a: &Action, p: &Expr) -> Result<()> {
-        self.inner.write().unwrap().set_policy(r, a, p);
-        Ok(())
+    async fn update_policy(
+        &self,
+        r: &Resource,
+        a: &Action,
+        p: &Expr,
+        author: Option<&IdentityIdentifier>,
+        comment: Option<&str>,
+    ) -> Result<PolicyVersion> {
+        Ok(self
+            .inner
+            .write()
+            .unwrap()
+            .update_policy(r, a, p, author, comment))
+    }
+
+    async fn policy_history(&self, r: &Resource, a: &Action) -> Result<Vec<PolicyVersion>> {
+        Ok(self.inner.read().unwrap().policy_history(r, a))
+    }
+
+    async fn rollback_policy(
+        &self,
+        r: &Resource,
+        a: &Action,
+        version: u64,
+        author: Option<&IdentityIdentifier>,
+    ) -> Result<PolicyVersion> {
+        self.inner
+            .write()
+            .unwrap()
+            .rollback_policy(r, a, version, author)
     }
 
     async fn policies(&self, r: &Resource) -> Result<Vec<(Action, Expr)>> {
@@ -90,6 +164,32 @@ impl PolicyStorage for Memory {
     }
 }
 
+#[async_trait]
+impl DecisionLog for Memory {
+    async fn record(&self, decision: &Decision) -> Result<()> {
+        self.inner.write().unwrap().decisions.push(decision.clone());
+        Ok(())
+    }
+
+    async fn decisions(
+        &self,
+        filter: DecisionFilter,
+        limit: Option<usize>,
+    ) -> Result<Vec<Decision>> {
+        let inner = self.inner.read().unwrap();
+        let mut decisions: Vec<Decision> = inner
+            .decisions
+            .iter()
+            .rev()
+            .filter(|d| filter(d))
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect();
+        decisions.reverse();
+        Ok(decisions)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::env::Env;
@@ -98,6 +198,51 @@ mod tests {
     use crate::mem::Memory;
     use crate::parser::parse;
     use crate::types::{Action, Resource};
+    use crate::PolicyStorage;
+
+    #[tokio::test]
+    async fn history_and_rollback() {
+        let action = Action::new("r");
+        let resource = Resource::new("/foo");
+        let store = Memory::new();
+
+        store.set_policy(&resource, &action, &int(1)).await.unwrap();
+        store
+            .update_policy(&resource, &action, &int(2), None, Some("two"))
+            .await
+            .unwrap();
+        store.del_policy(&resource, &action).await.unwrap();
+        assert!(store
+            .get_policy(&resource, &action)
+            .await
+            .unwrap()
+            .is_none());
+
+        let v = store
+            .rollback_policy(&resource, &action, 1, None)
+            .await
+            .unwrap();
+        assert_eq!(v.version(), 4);
+        assert_eq!(v.comment(), Some("rollback to version 1"));
+
+        assert!(store
+            .rollback_policy(&resource, &action, 3, None)
+            .await
+            .is_err());
+
+        let history = store.policy_history(&resource, &action).await.unwrap();
+        let versions: Vec<u64> = history.iter().map(|v| v.version()).collect();
+        assert_eq!(versions, [1, 2, 3, 4]);
+        assert_eq!(history[1].comment(), Some("two"));
+        assert!(history[2].is_deleted());
+        let current = store.get_policy(&resource, &action).await.unwrap();
+        assert!(matches!(current, Some(crate::Expr::Int(1))));
+
+        assert!(store
+            .rollback_policy(&resource, &action, 5, None)
+            .await
+            .is_err());
+    }
 
     #[test]
     fn example1() {
@@ -0,0 +1,201 @@
+use core::fmt;
+use core::fmt::{Debug, Formatter};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::format;
+use ockam_core::compat::sync::Arc;
+use ockam_core::{OutgoingAccessControl, RelayMessage, Result};
+use ockam_identity::{IdentitiesRepository, SecureChannelRegistry, SecureChannelRegistryEntry};
+use tracing as log;
+
+use crate::attribute_access_control::{add_subject_attributes, evaluate};
+use crate::audit::record;
+use crate::expr::Expr::Bool;
+use crate::types::{Action, Resource};
+use crate::{Decision, DecisionLog, Env, Expr};
+
+/// This AccessControl evaluates a policy expression against the attributes of
+/// the identity a message is sent to
+///
+/// The message must be sent to the encryptor of a secure channel. The identity
+/// at the other end of this channel is the subject of the policy: its attributes
+/// are bound as `subject.<name>` and `channel.initiator` is true if the channel
+/// was initiated by this node. Messages which are not sent to a secure channel
+/// are denied.
+pub struct AbacOutgoingAccessControl {
+    repository: Arc<dyn IdentitiesRepository>,
+    secure_channels: SecureChannelRegistry,
+    expression: Expr,
+    environment: Env,
+    resource: Option<(Resource, Action)>,
+    decision_log: Option<Arc<dyn DecisionLog>>,
+}
+
+/// Debug implementation printing out the policy expression only
+impl Debug for AbacOutgoingAccessControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let expression = self.expression.clone();
+        f.write_str(format!("{expression:?}").as_str())
+    }
+}
+
+impl AbacOutgoingAccessControl {
+    /// Create a new AccessControl using a specific policy for checking the
+    /// attributes of the recipients of messages
+    pub fn new(
+        repository: Arc<dyn IdentitiesRepository>,
+        secure_channels: SecureChannelRegistry,
+        expression: Expr,
+        environment: Env,
+    ) -> Self {
+        Self {
+            repository,
+            secure_channels,
+            expression,
+            environment,
+            resource: None,
+            decision_log: None,
+        }
+    }
+
+    /// Record the decisions taken by this access control in a decision log
+    pub fn with_decision_log(mut self, decision_log: Arc<dyn DecisionLog>) -> Self {
+        self.decision_log = Some(decision_log);
+        self
+    }
+
+    /// Set the resource and action protected by this access control, as
+    /// recorded in its decisions
+    pub fn with_resource_action(mut self, resource: Resource, action: Action) -> Self {
+        self.resource = Some((resource, action));
+        self
+    }
+
+    /// Record a decision in the decision log, if any
+    async fn record(&self, decision: Decision) {
+        let decision = match &self.resource {
+            Some((r, a)) => decision.with_resource(r).with_action(a),
+            None => decision,
+        };
+        record(
+            self.decision_log.as_ref(),
+            decision.with_policy(&self.expression),
+        )
+        .await
+    }
+}
+
+/// Return the secure channel whose encryptor is the next hop of a message, if any
+pub(crate) fn destination_channel(
+    secure_channels: &SecureChannelRegistry,
+    msg: &RelayMessage,
+) -> Option<SecureChannelRegistryEntry> {
+    let next = msg.onward_route().next().ok()?;
+    secure_channels.get_channel_by_encryptor_address(next)
+}
+
+#[async_trait]
+impl OutgoingAccessControl for AbacOutgoingAccessControl {
+    /// Return true if the recipient of the message is validated by the expression
+    async fn is_authorized(&self, msg: &RelayMessage) -> Result<bool> {
+        let channel = if let Some(channel) = destination_channel(&self.secure_channels, msg) {
+            channel
+        } else {
+            log::debug! {
+                policy      = %self.expression,
+                destination = %msg.destination(),
+                "message not sent to a secure channel; access denied"
+            }
+            self.record(Decision::new(false, "message not sent to a secure channel"))
+                .await;
+            return Ok(false);
+        };
+
+        let id = channel.their_id();
+
+        let mut environment = self.environment.clone();
+        environment.put("channel.initiator", Bool(channel.is_initiator()));
+
+        // Get the recipient attributes and populate the environment:
+        let attributes = self.repository.get_attributes(&id).await?;
+        add_subject_attributes(&self.expression, &mut environment, &id, attributes);
+
+        // Finally, evaluate the expression, record and return the result:
+        let (allowed, reason) = evaluate(&self.expression, &environment, &id);
+        self.record(Decision::new(allowed, reason).with_subject(&id))
+            .await;
+        Ok(allowed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{eq, ident, str};
+    use ockam_core::compat::collections::BTreeMap;
+    use ockam_core::{route, Address, LocalMessage, TransportMessage};
+    use ockam_identity::secure_channels::secure_channels;
+    use ockam_identity::{AttributesEntry, IdentityIdentifier, Timestamp};
+
+    fn message_to(next: &Address) -> RelayMessage {
+        let msg = LocalMessage::new(
+            TransportMessage::v1(route![next.clone(), "outlet"], route![], vec![]),
+            vec![],
+        );
+        RelayMessage::new(Address::random_local(), next.clone(), msg)
+    }
+
+    async fn set_role(repository: &Arc<dyn IdentitiesRepository>, id: &IdentityIdentifier) {
+        let attrs = BTreeMap::from([("role".to_string(), b"ops".to_vec())]);
+        let entry = AttributesEntry::new(attrs, Timestamp::now().unwrap(), None, None);
+        repository
+            .as_attributes_writer()
+            .put_attributes(id, entry)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn recipient_attributes() {
+        let secure_channels = secure_channels();
+        let identities = secure_channels.identities();
+        let repository = identities.repository();
+        let creation = identities.identities_creation();
+        let me = creation.create_identity().await.unwrap();
+        let ops = creation.create_identity().await.unwrap();
+        let other = creation.create_identity().await.unwrap();
+        set_role(&repository, &ops.identifier()).await;
+
+        let registry = secure_channels.secure_channel_registry();
+        let mut encryptors = Vec::new();
+        for peer in [&ops, &other] {
+            let encryptor = Address::random_local();
+            registry
+                .register_channel(SecureChannelRegistryEntry::new(
+                    encryptor.clone(),
+                    Address::random_local(),
+                    Address::random_local(),
+                    Address::random_local(),
+                    true,
+                    me.identifier(),
+                    peer.identifier(),
+                ))
+                .unwrap();
+            encryptors.push(encryptor);
+        }
+
+        let ac = AbacOutgoingAccessControl::new(
+            repository,
+            registry,
+            eq([ident("subject.role"), str("ops")]),
+            Env::new(),
+        );
+        assert!(ac.is_authorized(&message_to(&encryptors[0])).await.unwrap());
+        assert!(!ac.is_authorized(&message_to(&encryptors[1])).await.unwrap());
+        // Not a secure channel
+        assert!(!ac
+            .is_authorized(&message_to(&Address::random_local()))
+            .await
+            .unwrap());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::eval::let_bindings;
 use crate::expr::Expr;
 use crate::{error::ParseError, EvalError};
 use core::str;
@@ -123,6 +124,11 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
                     }
                 }
                 v.reverse();
+                if matches!(v.first(), Some(Expr::Ident(id)) if id == "let") {
+                    if let Err(e) = let_bindings(&v) {
+                        return Err(ParseError::message(e.to_string()))
+                    }
+                }
                 ctrl.push(Op::Value(Expr::List(v)));
                 ctrl.push(Op::Next)
             }
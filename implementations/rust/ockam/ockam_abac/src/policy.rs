@@ -1,27 +1,39 @@
-use crate::traits::PolicyStorage;
+use crate::audit::record;
+use crate::outgoing::destination_channel;
+use crate::traits::{DecisionLog, PolicyStorage};
 use crate::types::{Action, Resource};
-use crate::AbacAccessControl;
-use crate::{Env, Expr};
+use crate::{AbacAccessControl, AbacOutgoingAccessControl};
+use crate::{Decision, Env, Expr};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::format;
 use ockam_core::compat::sync::Arc;
 use ockam_core::{async_trait, RelayMessage};
-use ockam_core::{IncomingAccessControl, Result};
-use ockam_identity::IdentitiesRepository;
+use ockam_core::{IncomingAccessControl, OutgoingAccessControl, Result};
+use ockam_identity::{
+    IdentitiesRepository, IdentityIdentifier, IdentitySecureChannelLocalInfo, SecureChannelRegistry,
+};
 use tracing as log;
 
 /// Evaluates a policy expression against an environment of attributes.
 ///
 /// Attributes come from a pre-populated environment and are augmented
 /// by subject attributes from credential data.
+///
+/// As an incoming access control the subject is the sender of a message. As
+/// an outgoing access control the subject is the recipient of a message, the
+/// identity at the other end of the secure channel it is sent to, see
+/// [`AbacOutgoingAccessControl`].
 pub struct PolicyAccessControl {
     resource: Resource,
     action: Action,
+    fallback_action: Option<Action>,
     policies: Arc<dyn PolicyStorage>,
     repository: Arc<dyn IdentitiesRepository>,
     environment: Env,
+    decision_log: Option<Arc<dyn DecisionLog>>,
+    secure_channels: Option<SecureChannelRegistry>,
 }
 
 /// Debug implementation writing out the resource, action and initial environment
@@ -52,41 +64,147 @@ impl PolicyAccessControl {
         Self {
             resource: r,
             action: a,
+            fallback_action: None,
             policies,
             repository,
             environment: env,
+            decision_log: None,
+            secure_channels: None,
         }
     }
-}
 
-#[async_trait]
-impl IncomingAccessControl for PolicyAccessControl {
-    async fn is_authorized(&self, msg: &RelayMessage) -> Result<bool> {
-        // Load the policy expression for resource and action:
-        let expr = if let Some(expr) = self
+    /// Record the decisions taken by this access control in a decision log
+    pub fn with_decision_log(mut self, decision_log: Arc<dyn DecisionLog>) -> Self {
+        self.decision_log = Some(decision_log);
+        self
+    }
+
+    /// Evaluate the policy of another action on the same resource when there
+    /// is no policy for this action
+    ///
+    /// The fallback policy is loaded on every evaluation, so that updating it
+    /// is reflected immediately.
+    pub fn with_fallback_action(mut self, action: Action) -> Self {
+        self.fallback_action = Some(action);
+        self
+    }
+
+    /// Set the registry of the secure channels of the node, which is needed
+    /// to find the recipients of messages when used as an outgoing access control
+    pub fn with_secure_channel_registry(mut self, secure_channels: SecureChannelRegistry) -> Self {
+        self.secure_channels = Some(secure_channels);
+        self
+    }
+
+    /// Record a decision taken without evaluating a policy against the message subject
+    async fn record(&self, subject: Option<IdentityIdentifier>, decision: Decision) {
+        if self.decision_log.is_none() {
+            return;
+        }
+        let decision = decision
+            .with_resource(&self.resource)
+            .with_action(&self.action);
+        let decision = match subject {
+            Some(id) => decision.with_subject(id),
+            None => decision,
+        };
+        record(self.decision_log.as_ref(), decision).await
+    }
+
+    /// Load the policy expression for resource and action
+    ///
+    /// The policy of the fallback action is used if there is no policy for the action.
+    /// Return the decision instead, once recorded, if the policy is a constant
+    /// or if no policy exists, in which case access is denied.
+    async fn load_policy(
+        &self,
+        subject: impl FnOnce() -> Option<IdentityIdentifier>,
+    ) -> Result<core::result::Result<Expr, bool>> {
+        let mut policy = self
             .policies
             .get_policy(&self.resource, &self.action)
-            .await?
-        {
+            .await?;
+        if policy.is_none() {
+            if let Some(fallback) = &self.fallback_action {
+                policy = self.policies.get_policy(&self.resource, fallback).await?
+            }
+        }
+        if let Some(expr) = policy {
             if let Expr::Bool(b) = expr {
                 // If the policy is a constant there is no need to populate
                 // the environment or look for message metadata.
-                return Ok(b);
+                let decision = Decision::new(b, "constant policy").with_policy(&expr);
+                self.record(subject(), decision).await;
+                Ok(Err(b))
             } else {
-                expr
+                Ok(Ok(expr))
             }
         } else {
-            // If no policy exists for this resource and action access is denied:
             log::debug! {
                 resource = %self.resource,
                 action   = %self.action,
                 "no policy found; access denied"
             }
-            return Ok(false);
+            self.record(subject(), Decision::new(false, "no policy found"))
+                .await;
+            Ok(Err(false))
+        }
+    }
+}
+
+#[async_trait]
+impl IncomingAccessControl for PolicyAccessControl {
+    async fn is_authorized(&self, msg: &RelayMessage) -> Result<bool> {
+        let sender = || {
+            IdentitySecureChannelLocalInfo::find_info(msg.local_message())
+                .ok()
+                .map(|info| info.their_identity_id())
+        };
+        let expr = match self.load_policy(sender).await? {
+            Ok(expr) => expr,
+            Err(decision) => return Ok(decision),
         };
 
-        AbacAccessControl::new(self.repository.clone(), expr, self.environment.clone())
-            .is_authorized(msg)
-            .await
+        let mut ac =
+            AbacAccessControl::new(self.repository.clone(), expr, self.environment.clone())
+                .with_resource_action(self.resource.clone(), self.action.clone());
+        if let Some(decision_log) = &self.decision_log {
+            ac = ac.with_decision_log(decision_log.clone());
+        }
+        ac.is_authorized(msg).await
+    }
+}
+
+#[async_trait]
+impl OutgoingAccessControl for PolicyAccessControl {
+    async fn is_authorized(&self, msg: &RelayMessage) -> Result<bool> {
+        let secure_channels = match &self.secure_channels {
+            Some(secure_channels) => secure_channels,
+            None => {
+                log::warn! {
+                    resource = %self.resource,
+                    action   = %self.action,
+                    "no secure channel registry to find the recipient; access denied"
+                }
+                return Ok(false);
+            }
+        };
+        let recipient = || destination_channel(secure_channels, msg).map(|c| c.their_id());
+        let expr = match self.load_policy(recipient).await? {
+            Ok(expr) => expr,
+            Err(decision) => return Ok(decision),
+        };
+
+        let mut ac = AbacOutgoingAccessControl::new(
+            self.repository.clone(),
+            secure_channels.clone(),
+            expr,
+            self.environment.clone(),
+        )
+        .with_resource_action(self.resource.clone(), self.action.clone());
+        if let Some(decision_log) = &self.decision_log {
+            ac = ac.with_decision_log(decision_log.clone());
+        }
+        ac.is_authorized(msg).await
     }
 }
@@ -0,0 +1,378 @@
+use crate::tokio::fs::{self, File, OpenOptions};
+use crate::tokio::io::AsyncWriteExt;
+use crate::tokio::sync::Mutex;
+use crate::tokio::task::{spawn_blocking, JoinError};
+use crate::{Decision, DecisionFilter, DecisionLog};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing as log;
+
+/// Size of the blocks read when reading the log file from its end
+const READ_BLOCK_SIZE: u64 = 8 * 1024;
+
+/// Decision log appending one line per decision to a file
+///
+/// A line contains the tab-separated timestamp, result (`allow` or `deny`),
+/// resource, action, subject identifier, policy hash and reason of a
+/// decision, unknown values being left empty.
+///
+/// The file is kept open and each decision is flushed to it when it is
+/// recorded, so that no decision is lost if the node stops.
+///
+/// When the file would exceed its maximum size it is renamed with a `.1`
+/// suffix, previous `.1` file becoming `.2` and so on, up to a maximum
+/// number of rotated files, the oldest one being deleted.
+#[derive(Clone)]
+pub struct FileDecisionLog {
+    path: PathBuf,
+    max_size: u64,
+    max_rotated_files: usize,
+    writer: Arc<Mutex<Writer>>,
+}
+
+/// Open log file and its current size
+struct Writer {
+    file: Option<File>,
+    size: u64,
+}
+
+impl FileDecisionLog {
+    /// Default maximum size of the log file: 10 MiB
+    pub const DEFAULT_MAX_SIZE: u64 = 10 * 1024 * 1024;
+    /// Default maximum number of rotated files
+    pub const DEFAULT_MAX_ROTATED_FILES: usize = 5;
+
+    /// Create a decision log at the given path, with default rotation settings
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self::with_rotation(
+            path,
+            Self::DEFAULT_MAX_SIZE,
+            Self::DEFAULT_MAX_ROTATED_FILES,
+        )
+    }
+
+    /// Create a decision log at the given path, rotated when reaching `max_size`
+    /// bytes and keeping at most `max_rotated_files` previous files
+    pub fn with_rotation(path: impl AsRef<Path>, max_size: u64, max_rotated_files: usize) -> Self {
+        let writer = Writer {
+            file: None,
+            size: 0,
+        };
+        Self {
+            path: path.as_ref().to_path_buf(),
+            max_size,
+            max_rotated_files,
+            writer: Arc::new(Mutex::new(writer)),
+        }
+    }
+
+    /// Path of the current log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{n}"));
+        path.into()
+    }
+
+    async fn append(&self, writer: &mut Writer, line: &str) -> io::Result<()> {
+        let mut file = match writer.file.take() {
+            Some(file) => file,
+            None => self.open(writer).await?,
+        };
+        if writer.size > 0 && writer.size + line.len() as u64 > self.max_size {
+            file.flush().await?;
+            drop(file);
+            self.rotate().await?;
+            file = self.open(writer).await?
+        }
+        file.write_all(line.as_bytes()).await?;
+        file.flush().await?;
+        writer.size += line.len() as u64;
+        writer.file = Some(file);
+        Ok(())
+    }
+
+    async fn open(&self, writer: &mut Writer) -> io::Result<File> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        writer.size = file.metadata().await?.len();
+        Ok(file)
+    }
+
+    async fn rotate(&self) -> io::Result<()> {
+        if self.max_rotated_files == 0 {
+            return fs::remove_file(&self.path).await;
+        }
+        ignore_not_found(fs::remove_file(self.rotated_path(self.max_rotated_files)).await)?;
+        for n in (1..self.max_rotated_files).rev() {
+            ignore_not_found(fs::rename(self.rotated_path(n), self.rotated_path(n + 1)).await)?;
+        }
+        fs::rename(&self.path, self.rotated_path(1)).await
+    }
+
+    /// Read the files from the most recent line backwards, stopping once
+    /// `limit` decisions have been selected
+    fn read(&self, filter: DecisionFilter, limit: Option<usize>) -> io::Result<Vec<Decision>> {
+        let limit = limit.unwrap_or(usize::MAX);
+        let paths = (1..=self.max_rotated_files).map(|n| self.rotated_path(n));
+        let mut decisions = Vec::new();
+        for path in std::iter::once(self.path.clone()).chain(paths) {
+            if decisions.len() >= limit {
+                break;
+            }
+            let file = match std::fs::File::open(&path) {
+                Ok(file) => file,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            read_lines_backwards(file, |line| {
+                match decode_line(line) {
+                    Some(d) if filter(&d) => decisions.push(d),
+                    Some(_) => {}
+                    None => log::warn!(path = %path.display(), "malformed decision log line"),
+                }
+                decisions.len() < limit
+            })?;
+        }
+        decisions.reverse();
+        Ok(decisions)
+    }
+}
+
+#[async_trait]
+impl DecisionLog for FileDecisionLog {
+    async fn record(&self, decision: &Decision) -> Result<()> {
+        let line = encode_line(decision);
+        let mut writer = self.writer.lock().await;
+        self.append(&mut writer, &line).await.map_err(map_io_err)
+    }
+
+    async fn decisions(
+        &self,
+        filter: DecisionFilter,
+        limit: Option<usize>,
+    ) -> Result<Vec<Decision>> {
+        // Hold the writer until the files are read, so that they are not rotated meanwhile
+        let _writer = self.writer.lock().await;
+        let this = self.clone();
+        let t = move || this.read(filter, limit).map_err(map_io_err);
+        spawn_blocking(t).await.map_err(map_join_err)?
+    }
+}
+
+/// Call `f` on the lines of a file, last line first, until it returns `false`
+fn read_lines_backwards(
+    mut file: std::fs::File,
+    mut f: impl FnMut(&str) -> bool,
+) -> io::Result<()> {
+    let mut position = file.seek(SeekFrom::End(0))?;
+    let mut rest: Vec<u8> = Vec::new();
+    while position > 0 {
+        let n = READ_BLOCK_SIZE.min(position);
+        position -= n;
+        file.seek(SeekFrom::Start(position))?;
+        let mut block = vec![0; n as usize];
+        file.read_exact(&mut block)?;
+        block.extend_from_slice(&rest);
+        rest = block;
+        while let Some(i) = rest.iter().rposition(|b| *b == b'\n') {
+            let line = rest.split_off(i + 1);
+            rest.truncate(i);
+            if !line.is_empty() && !f(&String::from_utf8_lossy(&line)) {
+                return Ok(());
+            }
+        }
+    }
+    if !rest.is_empty() {
+        f(&String::from_utf8_lossy(&rest));
+    }
+    Ok(())
+}
+
+fn ignore_not_found(r: io::Result<()>) -> io::Result<()> {
+    match r {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        r => r,
+    }
+}
+
+fn encode_line(d: &Decision) -> String {
+    let result = if d.is_allowed() { "allow" } else { "deny" };
+    format!(
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+        d.timestamp(),
+        result,
+        escape(d.resource().unwrap_or_default()),
+        escape(d.action().unwrap_or_default()),
+        escape(d.subject().unwrap_or_default()),
+        escape(d.policy_hash().unwrap_or_default()),
+        escape(d.reason())
+    )
+}
+
+fn decode_line(line: &str) -> Option<Decision> {
+    let fields: Vec<&str> = line.split('\t').collect();
+    if let [timestamp, result, resource, action, subject, policy_hash, reason] = fields[..] {
+        let allowed = match result {
+            "allow" => true,
+            "deny" => false,
+            _ => return None,
+        };
+        let mut d =
+            Decision::new(allowed, unescape(reason)).with_timestamp(timestamp.parse().ok()?);
+        if !resource.is_empty() {
+            d = d.with_resource(unescape(resource))
+        }
+        if !action.is_empty() {
+            d = d.with_action(unescape(action))
+        }
+        if !subject.is_empty() {
+            d = d.with_subject(unescape(subject))
+        }
+        if !policy_hash.is_empty() {
+            d = d.with_policy_hash(unescape(policy_hash))
+        }
+        Some(d)
+    } else {
+        None
+    }
+}
+
+fn escape(s: &str) -> String {
+    let mut r = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => r.push_str("\\\\"),
+            '\t' => r.push_str("\\t"),
+            '\n' => r.push_str("\\n"),
+            '\r' => r.push_str("\\r"),
+            c => r.push(c),
+        }
+    }
+    r
+}
+
+fn unescape(s: &str) -> String {
+    let mut r = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('t') => r.push('\t'),
+                Some('n') => r.push('\n'),
+                Some('r') => r.push('\r'),
+                Some(c) => r.push(c),
+                None => r.push('\\'),
+            }
+        } else {
+            r.push(c)
+        }
+    }
+    r
+}
+
+fn map_join_err(err: JoinError) -> Error {
+    Error::new(Origin::Application, Kind::Io, err)
+}
+
+fn map_io_err(err: io::Error) -> Error {
+    Error::new(Origin::Application, Kind::Io, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::str;
+
+    #[test]
+    fn line_roundtrip() {
+        let d = Decision::new(false, "policy evaluated to false\twith\nnewlines \\")
+            .with_resource("tcp-outlet")
+            .with_action("handle_message")
+            .with_subject("Pabcd")
+            .with_policy(&str("x"));
+        let line = encode_line(&d);
+        assert_eq!(line.matches('\n').count(), 1);
+        assert_eq!(decode_line(line.trim_end_matches('\n')), Some(d));
+
+        let d = Decision::new(true, "constant policy");
+        assert_eq!(decode_line(encode_line(&d).trim_end_matches('\n')), Some(d));
+    }
+
+    #[tokio::test]
+    async fn rotation() {
+        let dir = std::env::temp_dir().join(format!("decision_log_{}", rand::random::<u64>()));
+        let log = FileDecisionLog::with_rotation(dir.join("audit.log"), 200, 2);
+
+        let decisions: Vec<Decision> = (0..20)
+            .map(|i| Decision::new(i % 2 == 0, format!("decision {i}")).with_resource("r"))
+            .collect();
+        for d in &decisions {
+            log.record(d).await.unwrap();
+        }
+
+        // Only the most recent decisions are kept, in order
+        let kept = log.decisions(Box::new(|_| true), None).await.unwrap();
+        assert!(!kept.is_empty() && kept.len() < decisions.len());
+        assert_eq!(kept[..], decisions[decisions.len() - kept.len()..]);
+        assert!(log.rotated_path(2).exists());
+        assert!(!log.rotated_path(3).exists());
+
+        // The limit applies to the most recent decisions selected by the filter
+        let denied = log
+            .decisions(Box::new(|d| !d.is_allowed()), Some(2))
+            .await
+            .unwrap();
+        assert_eq!(denied, vec![decisions[17].clone(), decisions[19].clone()]);
+
+        std::fs::remove_dir_all(dir).unwrap()
+    }
+
+    #[tokio::test]
+    async fn decisions_are_written_when_recorded() {
+        let dir = std::env::temp_dir().join(format!("decision_log_{}", rand::random::<u64>()));
+        let log = FileDecisionLog::new(dir.join("audit.log"));
+
+        let d = Decision::new(true, "constant policy").with_resource("r");
+        log.record(&d).await.unwrap();
+
+        // The decision is in the file without waiting for another record or a read
+        let content = std::fs::read_to_string(log.path()).unwrap();
+        assert_eq!(content, encode_line(&d));
+
+        std::fs::remove_dir_all(dir).unwrap()
+    }
+
+    #[test]
+    fn lines_are_read_backwards() {
+        let path = std::env::temp_dir().join(format!("lines_{}", rand::random::<u64>()));
+        let lines: Vec<String> = (0..2000).map(|i| format!("line {i}")).collect();
+        std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+
+        let mut read = Vec::new();
+        let file = std::fs::File::open(&path).unwrap();
+        read_lines_backwards(file, |line| {
+            read.push(line.to_string());
+            read.len() < 1500
+        })
+        .unwrap();
+        let expected: Vec<String> = lines.iter().rev().take(1500).cloned().collect();
+        assert_eq!(read, expected);
+
+        std::fs::remove_file(path).unwrap()
+    }
+}
@@ -1,14 +1,15 @@
+use crate::history::{next_version, rollback_to};
 use crate::tokio::task::{spawn_blocking, JoinError};
-use crate::{Action, Expr, PolicyStorage, Resource};
+use crate::{Action, Expr, PolicyStorage, PolicyVersion, Resource};
 use core::str;
-use lmdb::{Cursor, Transaction};
+use lmdb::{Cursor, Database, RwTransaction, Transaction, WriteFlags};
 use minicbor::{Decode, Encode};
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::fmt::Vec;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
-use ockam_identity::LmdbStorage;
+use ockam_identity::{IdentityIdentifier, LmdbStorage};
 use std::borrow::Cow;
 use tracing as log;
 
@@ -22,6 +23,19 @@ struct PolicyEntry<'a> {
     #[b(0)] expr: Cow<'a, Expr>,
 }
 
+/// Versions of a policy, oldest first.
+///
+/// Stored under its own key, next to the policy entry, so that reading
+/// the current policy does not require decoding its whole history.
+#[derive(Debug, Encode, Decode)]
+#[rustfmt::skip]
+struct PolicyHistory {
+    #[n(0)] versions: Vec<PolicyVersion>,
+}
+
+/// Suffix of the keys of policy histories.
+const HISTORY_SUFFIX: &str = ":history";
+
 #[async_trait]
 impl PolicyStorage for LmdbStorage {
     async fn get_policy(&self, r: &Resource, a: &Action) -> Result<Option<Expr>> {
@@ -41,15 +55,82 @@ impl PolicyStorage for LmdbStorage {
         spawn_blocking(t).await.map_err(map_join_err)?
     }
 
-    async fn set_policy(&self, r: &Resource, a: &Action, c: &Expr) -> Result<()> {
-        let v = minicbor::to_vec(PolicyEntry {
-            expr: Cow::Borrowed(c),
-        })?;
-        self.write(format!("{r}:{a}"), v).await
+    async fn update_policy(
+        &self,
+        r: &Resource,
+        a: &Action,
+        c: &Expr,
+        author: Option<&IdentityIdentifier>,
+        comment: Option<&str>,
+    ) -> Result<PolicyVersion> {
+        let d = self.clone();
+        let (r, a, c) = (r.clone(), a.clone(), c.clone());
+        let author = author.cloned();
+        let comment = comment.map(String::from);
+        let t = move || {
+            let mut w = d.env.begin_rw_txn().map_err(map_lmdb_err)?;
+            let versions = read_history(&w, d.map, &r, &a)?;
+            let version = write_version(&mut w, d.map, &r, &a, versions, c, author, comment)?;
+            w.commit().map_err(map_lmdb_err)?;
+            Ok(version)
+        };
+        spawn_blocking(t).await.map_err(map_join_err)?
+    }
+
+    async fn policy_history(&self, r: &Resource, a: &Action) -> Result<Vec<PolicyVersion>> {
+        let d = self.clone();
+        let (r, a) = (r.clone(), a.clone());
+        let t = move || {
+            let tx = d.env.begin_ro_txn().map_err(map_lmdb_err)?;
+            read_history(&tx, d.map, &r, &a)
+        };
+        spawn_blocking(t).await.map_err(map_join_err)?
+    }
+
+    async fn rollback_policy(
+        &self,
+        r: &Resource,
+        a: &Action,
+        version: u64,
+        author: Option<&IdentityIdentifier>,
+    ) -> Result<PolicyVersion> {
+        let d = self.clone();
+        let (r, a) = (r.clone(), a.clone());
+        let author = author.cloned();
+        let t = move || {
+            let mut w = d.env.begin_rw_txn().map_err(map_lmdb_err)?;
+            let versions = read_history(&w, d.map, &r, &a)?;
+            let (c, comment) = rollback_to(&versions, &r, &a, version)?;
+            let version = write_version(&mut w, d.map, &r, &a, versions, c, author, Some(comment))?;
+            w.commit().map_err(map_lmdb_err)?;
+            Ok(version)
+        };
+        spawn_blocking(t).await.map_err(map_join_err)?
     }
 
     async fn del_policy(&self, r: &Resource, a: &Action) -> Result<()> {
-        self.delete(format!("{r}:{a}")).await
+        let d = self.clone();
+        let (r, a) = (r.clone(), a.clone());
+        let t = move || {
+            let mut w = d.env.begin_rw_txn().map_err(map_lmdb_err)?;
+            let k = format!("{r}:{a}");
+            let expr = match w.get(d.map, &k) {
+                Ok(value) => {
+                    let e: PolicyEntry = minicbor::decode(value)?;
+                    e.expr.into_owned()
+                }
+                Err(lmdb::Error::NotFound) => return Ok(()),
+                Err(e) => return Err(map_lmdb_err(e)),
+            };
+            let mut versions = read_history(&w, d.map, &r, &a)?;
+            versions.push(PolicyVersion::deletion(next_version(&versions), expr));
+            let history = minicbor::to_vec(PolicyHistory { versions })?;
+            w.put(d.map, &history_key(&r, &a), &history, WriteFlags::empty())
+                .map_err(map_lmdb_err)?;
+            w.del(d.map, &k, None).map_err(map_lmdb_err)?;
+            w.commit().map_err(map_lmdb_err)
+        };
+        spawn_blocking(t).await.map_err(map_join_err)?
     }
 
     async fn policies(&self, r: &Resource) -> Result<Vec<(Action, Expr)>> {
@@ -66,6 +147,9 @@ impl PolicyStorage for LmdbStorage {
                     if prefix != r.as_str() {
                         break;
                     }
+                    if a.ends_with(HISTORY_SUFFIX) {
+                        continue;
+                    }
                     let x: PolicyEntry = minicbor::decode(v)?;
                     xs.push((Action::new(a), x.expr.into_owned()))
                 } else {
@@ -78,6 +162,63 @@ impl PolicyStorage for LmdbStorage {
     }
 }
 
+fn history_key(r: &Resource, a: &Action) -> String {
+    format!("{r}:{a}{HISTORY_SUFFIX}")
+}
+
+/// Read the versions of a policy.
+///
+/// A policy stored before versioning was introduced has no history and is
+/// returned as its first version, without author nor timestamp.
+fn read_history<T: Transaction>(
+    tx: &T,
+    db: Database,
+    r: &Resource,
+    a: &Action,
+) -> Result<Vec<PolicyVersion>> {
+    match tx.get(db, &history_key(r, a)) {
+        Ok(value) => {
+            let h: PolicyHistory = minicbor::decode(value)?;
+            Ok(h.versions)
+        }
+        Err(lmdb::Error::NotFound) => match tx.get(db, &format!("{r}:{a}")) {
+            Ok(value) => {
+                let e: PolicyEntry = minicbor::decode(value)?;
+                let v = PolicyVersion::new(1, e.expr.into_owned(), None, None).with_timestamp(0);
+                Ok(vec![v])
+            }
+            Err(lmdb::Error::NotFound) => Ok(Vec::new()),
+            Err(e) => Err(map_lmdb_err(e)),
+        },
+        Err(e) => Err(map_lmdb_err(e)),
+    }
+}
+
+/// Write a new version of a policy, as the current policy and at the end of its history
+#[allow(clippy::too_many_arguments)]
+fn write_version(
+    w: &mut RwTransaction,
+    db: Database,
+    r: &Resource,
+    a: &Action,
+    mut versions: Vec<PolicyVersion>,
+    c: Expr,
+    author: Option<IdentityIdentifier>,
+    comment: Option<String>,
+) -> Result<PolicyVersion> {
+    let version = PolicyVersion::new(next_version(&versions), c.clone(), author, comment);
+    versions.push(version.clone());
+    let entry = minicbor::to_vec(PolicyEntry {
+        expr: Cow::Borrowed(&c),
+    })?;
+    let history = minicbor::to_vec(PolicyHistory { versions })?;
+    w.put(db, &format!("{r}:{a}"), &entry, WriteFlags::empty())
+        .map_err(map_lmdb_err)?;
+    w.put(db, &history_key(r, a), &history, WriteFlags::empty())
+        .map_err(map_lmdb_err)?;
+    Ok(version)
+}
+
 fn map_join_err(err: JoinError) -> Error {
     Error::new(Origin::Application, Kind::Io, err)
 }
@@ -89,3 +230,106 @@ fn map_lmdb_err(err: lmdb::Error) -> Error {
 fn from_utf8_err(err: str::Utf8Error) -> Error {
     Error::new(Origin::Other, Kind::Invalid, err)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::int;
+
+    /// Create a storage in a new directory, returned to be deleted at the end of a test
+    async fn storage() -> (LmdbStorage, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("policies_{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        (
+            LmdbStorage::new(dir.join("policies.lmdb")).await.unwrap(),
+            dir,
+        )
+    }
+
+    #[tokio::test]
+    async fn history_rollback_and_deletion() {
+        let (store, dir) = storage().await;
+        let (r, a) = (Resource::new("r"), Action::new("a"));
+
+        store.set_policy(&r, &a, &int(1)).await.unwrap();
+        let v = store
+            .update_policy(&r, &a, &int(2), None, Some("two"))
+            .await
+            .unwrap();
+        assert_eq!(v.version(), 2);
+        assert!(matches!(
+            store.get_policy(&r, &a).await.unwrap(),
+            Some(Expr::Int(2))
+        ));
+
+        let v = store.rollback_policy(&r, &a, 1, None).await.unwrap();
+        assert_eq!(v.version(), 3);
+        assert_eq!(v.comment(), Some("rollback to version 1"));
+        assert!(matches!(
+            store.get_policy(&r, &a).await.unwrap(),
+            Some(Expr::Int(1))
+        ));
+        assert!(store.rollback_policy(&r, &a, 9, None).await.is_err());
+
+        // The deletion is the last version of the policy, which can't be rolled back to
+        store.del_policy(&r, &a).await.unwrap();
+        assert!(store.get_policy(&r, &a).await.unwrap().is_none());
+        assert!(store.policies(&r).await.unwrap().is_empty());
+        let history = store.policy_history(&r, &a).await.unwrap();
+        let versions: Vec<u64> = history.iter().map(|v| v.version()).collect();
+        assert_eq!(versions, [1, 2, 3, 4]);
+        assert_eq!(history[1].comment(), Some("two"));
+        assert!(!history[2].is_deleted());
+        assert!(history[3].is_deleted());
+        assert!(matches!(history[3].expression(), Expr::Int(1)));
+        assert!(store.rollback_policy(&r, &a, 4, None).await.is_err());
+
+        // Deleting a policy which doesn't exist doesn't change its history
+        store.del_policy(&r, &a).await.unwrap();
+        assert_eq!(store.policy_history(&r, &a).await.unwrap().len(), 4);
+
+        let v = store.rollback_policy(&r, &a, 2, None).await.unwrap();
+        assert_eq!(v.version(), 5);
+        assert!(matches!(
+            store.get_policy(&r, &a).await.unwrap(),
+            Some(Expr::Int(2))
+        ));
+
+        std::fs::remove_dir_all(dir).unwrap()
+    }
+
+    #[tokio::test]
+    async fn legacy_entries_are_their_first_version() {
+        let (store, dir) = storage().await;
+        let (r, a) = (Resource::new("r"), Action::new("a"));
+
+        // A policy stored before versioning, without history
+        let entry = minicbor::to_vec(PolicyEntry {
+            expr: Cow::Owned(int(1)),
+        })
+        .unwrap();
+        let mut w = store.env.begin_rw_txn().unwrap();
+        w.put(store.map, &"r:a", &entry, WriteFlags::empty())
+            .unwrap();
+        w.commit().unwrap();
+
+        let history = store.policy_history(&r, &a).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].version(), 1);
+        assert_eq!(history[0].timestamp(), 0);
+        assert!(history[0].author().is_none());
+
+        // Its history is kept when it is updated
+        store.set_policy(&r, &a, &int(2)).await.unwrap();
+        let history = store.policy_history(&r, &a).await.unwrap();
+        let versions: Vec<u64> = history.iter().map(|v| v.version()).collect();
+        assert_eq!(versions, [1, 2]);
+        store.rollback_policy(&r, &a, 1, None).await.unwrap();
+        assert!(matches!(
+            store.get_policy(&r, &a).await.unwrap(),
+            Some(Expr::Int(1))
+        ));
+
+        std::fs::remove_dir_all(dir).unwrap()
+    }
+}
@@ -3,3 +3,9 @@ pub mod lmdb_storage;
 
 #[cfg(feature = "std")]
 pub use lmdb_storage::*;
+
+#[cfg(feature = "std")]
+mod decision_log;
+
+#[cfg(feature = "std")]
+pub use decision_log::*;
@@ -0,0 +1,88 @@
+use ockam_identity::Timestamp;
+
+/// Return the current time as a number of seconds since the UNIX epoch,
+/// if a clock is available.
+pub(crate) fn now() -> Option<i64> {
+    Timestamp::now().and_then(|t| i64::try_from(t.unix_time()).ok())
+}
+
+/// Parse an RFC 3339 UTC timestamp of the form `YYYY-MM-DDTHH:MM:SSZ`
+/// into a number of seconds since the UNIX epoch.
+pub(crate) fn parse_timestamp(s: &str) -> Option<i64> {
+    let b = s.as_bytes();
+    if b.len() != 20
+        || b[4] != b'-'
+        || b[7] != b'-'
+        || !(b[10] == b'T' || b[10] == b't')
+        || b[13] != b':'
+        || b[16] != b':'
+        || !(b[19] == b'Z' || b[19] == b'z')
+    {
+        return None;
+    }
+
+    let num = |from: usize, to: usize| -> Option<i64> {
+        let digits = &b[from..to];
+        if !digits.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        Some(digits.iter().fold(0, |n, d| n * 10 + i64::from(d - b'0')))
+    };
+
+    let year = num(0, 4)?;
+    let month = num(5, 7)?;
+    let day = num(8, 10)?;
+    let hour = num(11, 13)?;
+    let minute = num(14, 16)?;
+    let second = num(17, 19)?;
+
+    if !(1..=12).contains(&month)
+        || day < 1
+        || day > days_in_month(year, month)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    Some(days * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Number of days since 1970-01-01 of a date of the proleptic Gregorian calendar.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_timestamp;
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(Some(0), parse_timestamp("1970-01-01T00:00:00Z"));
+        assert_eq!(Some(951782400), parse_timestamp("2000-02-29T00:00:00Z"));
+        assert_eq!(Some(1700000000), parse_timestamp("2023-11-14T22:13:20Z"));
+        assert_eq!(None, parse_timestamp("2023-02-29T00:00:00Z"));
+        assert_eq!(None, parse_timestamp("2023-11-14 22:13:20Z"));
+        assert_eq!(None, parse_timestamp("2023-11-14T24:00:00Z"));
+        assert_eq!(None, parse_timestamp("1700000000"));
+    }
+}
@@ -1,13 +1,65 @@
-use crate::{Action, Expr, Resource};
+use crate::{Action, Decision, Expr, PolicyVersion, Resource};
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
+use ockam_identity::IdentityIdentifier;
 
 #[async_trait]
 pub trait PolicyStorage: Send + Sync + 'static {
     async fn get_policy(&self, r: &Resource, a: &Action) -> Result<Option<Expr>>;
-    async fn set_policy(&self, r: &Resource, a: &Action, c: &Expr) -> Result<()>;
+    /// Delete a policy, recording the deletion as a new version of it
     async fn del_policy(&self, r: &Resource, a: &Action) -> Result<()>;
     async fn policies(&self, r: &Resource) -> Result<Vec<(Action, Expr)>>;
+
+    /// Set a policy, creating a new version of it without author nor comment
+    async fn set_policy(&self, r: &Resource, a: &Action, c: &Expr) -> Result<()> {
+        self.update_policy(r, a, c, None, None).await.map(|_| ())
+    }
+
+    /// Set a policy, creating a new version of it, and return that version
+    async fn update_policy(
+        &self,
+        r: &Resource,
+        a: &Action,
+        c: &Expr,
+        author: Option<&IdentityIdentifier>,
+        comment: Option<&str>,
+    ) -> Result<PolicyVersion>;
+
+    /// Return all the versions of a policy, oldest first
+    ///
+    /// The history of a policy is kept when the policy is deleted, its last
+    /// version then being the deletion, see [`PolicyVersion::is_deleted`].
+    async fn policy_history(&self, r: &Resource, a: &Action) -> Result<Vec<PolicyVersion>>;
+
+    /// Set a policy to the expression of one of its previous versions,
+    /// creating a new version of it, and return that version
+    ///
+    /// The history is read and updated atomically. An error of kind
+    /// `Kind::NotFound` is returned if the version does not exist.
+    async fn rollback_policy(
+        &self,
+        r: &Resource,
+        a: &Action,
+        version: u64,
+        author: Option<&IdentityIdentifier>,
+    ) -> Result<PolicyVersion>;
+}
+
+/// Predicate selecting the decisions read from a decision log
+pub type DecisionFilter = Box<dyn Fn(&Decision) -> bool + Send + Sync>;
+
+/// Append-only log of the decisions taken by access controls
+#[async_trait]
+pub trait DecisionLog: Send + Sync + 'static {
+    /// Append a decision to the log
+    async fn record(&self, decision: &Decision) -> Result<()>;
+    /// Return the most recent logged decisions selected by the filter, at most
+    /// `limit` of them if given, oldest first
+    async fn decisions(
+        &self,
+        filter: DecisionFilter,
+        limit: Option<usize>,
+    ) -> Result<Vec<Decision>>;
 }
@@ -0,0 +1,99 @@
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::fmt;
+use ockam_core::compat::fmt::Debug;
+use ockam_core::compat::fmt::Formatter;
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+use ockam_identity::{Identities, SecureChannelTrustInfo, TrustContext, TrustPolicy};
+use tracing as log;
+
+use crate::attribute_access_control::{add_subject_attributes, evaluate};
+use crate::{Env, Expr};
+
+/// This TrustPolicy evaluates a policy expression against the attributes of the
+/// credentials presented with the identity of the other side of a secure channel
+///
+/// The credentials are sent, encrypted, once the key exchange of the channel is
+/// completed. They are verified with the authorities of a trust context, and their
+/// attributes are only stored when they satisfy the expression. A peer whose attributes
+/// don't satisfy the expression never gets a channel, contrary to [`crate::AbacAccessControl`]
+/// which is checked for each message.
+///
+/// Note that when this policy is used by a listener, the initiator is not notified of the
+/// rejection: its end of the channel is created but the listener never registers its end.
+pub struct AbacTrustPolicy {
+    identities: Arc<Identities>,
+    trust_context: TrustContext,
+    expression: Expr,
+    environment: Env,
+}
+
+/// Debug implementation printing out the policy expression only
+impl Debug for AbacTrustPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.expression)
+    }
+}
+
+impl AbacTrustPolicy {
+    /// Create a new TrustPolicy using a specific policy for checking attributes
+    pub fn new(
+        identities: Arc<Identities>,
+        trust_context: TrustContext,
+        expression: Expr,
+        environment: Env,
+    ) -> Self {
+        Self {
+            identities,
+            trust_context,
+            expression,
+            environment,
+        }
+    }
+}
+
+#[async_trait]
+impl TrustPolicy for AbacTrustPolicy {
+    /// Return true if the attributes of the other side of the channel are validated by the expression
+    async fn check(&self, trust_info: &SecureChannelTrustInfo) -> Result<bool> {
+        let id = trust_info.their_identity_id();
+        let repository = self.identities.repository();
+        let credentials = self.identities.credentials();
+
+        let mut attributes = repository.get_attributes(id).await?;
+        let mut received = false;
+        for credential in trust_info.their_credentials() {
+            match credentials
+                .verify_trust_context_credential(
+                    id,
+                    &self.trust_context,
+                    attributes.clone(),
+                    credential.clone(),
+                )
+                .await
+            {
+                Ok(entry) => {
+                    attributes = Some(entry);
+                    received = true;
+                }
+                Err(e) => log::warn! {
+                    policy = %self.expression,
+                    id     = %id,
+                    err    = %e,
+                    "invalid credential presented with the identity"
+                },
+            }
+        }
+
+        let mut environment = self.environment.clone();
+        add_subject_attributes(&self.expression, &mut environment, id, attributes.clone());
+        let trusted = evaluate(&self.expression, &environment, id).0;
+
+        // The attributes of the presented credentials are only stored for trusted peers
+        if let (true, true, Some(attributes)) = (trusted, received, attributes) {
+            repository.put_attributes(id, attributes).await?;
+        }
+        Ok(trusted)
+    }
+}
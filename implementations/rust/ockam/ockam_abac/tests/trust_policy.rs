@@ -0,0 +1,137 @@
+use core::time::Duration;
+use ockam_abac::expr::{eq, ident, str};
+use ockam_abac::{AbacTrustPolicy, Env};
+use ockam_core::{route, Result};
+use ockam_identity::secure_channels::secure_channels;
+use ockam_identity::{
+    AuthorityService, CredentialData, SecureChannelListenerOptions, SecureChannelOptions,
+    TrustContext,
+};
+use ockam_node::tokio::time::sleep;
+use ockam_node::Context;
+
+#[ockam_macros::test]
+async fn credentials_checked_before_the_channel_is_established(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities = secure_channels.identities();
+    let identities_creation = identities.identities_creation();
+    let credentials = identities.credentials();
+
+    let authority = identities_creation.create_identity().await?;
+    let server = identities_creation.create_identity().await?;
+    let admin = identities_creation.create_identity().await?;
+    let guest = identities_creation.create_identity().await?;
+    let outsider = identities_creation.create_identity().await?;
+
+    let trust_context = TrustContext::new(
+        "test_trust_context_id".to_string(),
+        Some(AuthorityService::new(
+            credentials.clone(),
+            authority.clone(),
+            None,
+        )),
+    );
+
+    let trust_policy = AbacTrustPolicy::new(
+        identities.clone(),
+        trust_context,
+        eq([ident("subject.role"), str("admin")]),
+        Env::new(),
+    );
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &server,
+            "listener",
+            SecureChannelListenerOptions::new().with_trust_policy(trust_policy),
+        )
+        .await?;
+
+    let admin_credential = credentials
+        .issue_credential(
+            &authority,
+            CredentialData::builder(admin.identifier(), authority.identifier())
+                .with_attribute("role", b"admin")
+                .build()?,
+        )
+        .await?;
+    let guest_credential = credentials
+        .issue_credential(
+            &authority,
+            CredentialData::builder(guest.identifier(), authority.identifier())
+                .with_attribute("role", b"guest")
+                .build()?,
+        )
+        .await?;
+
+    // The listener registers its end of a channel once it accepted the identity of the initiator
+    let listener_channels = || {
+        secure_channels
+            .secure_channel_registry()
+            .get_channel_list()
+            .into_iter()
+            .filter(|c| !c.is_initiator())
+            .map(|c| c.their_id())
+            .collect::<Vec<_>>()
+    };
+
+    // A peer presenting the required attributes gets a channel
+    secure_channels
+        .create_secure_channel(
+            ctx,
+            &admin,
+            route!["listener"],
+            SecureChannelOptions::new().with_credential(admin_credential),
+        )
+        .await?;
+    let mut channels = listener_channels();
+    for _ in 0..50 {
+        if !channels.is_empty() {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+        channels = listener_channels();
+    }
+    assert_eq!(channels, vec![admin.identifier()]);
+    assert!(identities
+        .repository()
+        .get_attributes(&admin.identifier())
+        .await?
+        .is_some());
+
+    // A peer without the required attributes is refused by the listener. The initiator
+    // completes the key exchange before the listener checks its identity, so it is not
+    // notified, but the listener never registers the channel
+    let _ = secure_channels
+        .create_secure_channel_extended(
+            ctx,
+            &guest,
+            route!["listener"],
+            SecureChannelOptions::new().with_credential(guest_credential),
+            Duration::from_millis(500),
+        )
+        .await;
+
+    // So is a peer which doesn't present any credential
+    let _ = secure_channels
+        .create_secure_channel_extended(
+            ctx,
+            &outsider,
+            route!["listener"],
+            SecureChannelOptions::new(),
+            Duration::from_millis(500),
+        )
+        .await;
+
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(listener_channels(), vec![admin.identifier()]);
+
+    // and the attributes of rejected credentials are not stored
+    assert!(identities
+        .repository()
+        .get_attributes(&guest.identifier())
+        .await?
+        .is_none());
+
+    ctx.stop().await
+}
@@ -1,26 +1,35 @@
+mod enrollment_tokens;
 pub mod types;
 
+pub use enrollment_tokens::EnrollmentTokens;
+
 use core::str;
-use lru::LruCache;
 use minicbor::Decoder;
-use ockam::identity::{AttributesEntry, IdentityAttributesWriter};
+use ockam::identity::{AttributesEntry, IdentityAttributesReader, IdentityAttributesWriter};
+use ockam::identity::{CredentialSchemas, PROJECT_MEMBER_SCHEMA};
 use ockam::identity::{IdentityIdentifier, IdentitySecureChannelLocalInfo};
-use ockam::identity::{OneTimeCode, Timestamp};
+use ockam::identity::{OneTimeCode, RevocationsStorage, Timestamp};
 use ockam_core::api::{self, Method, Request, Response, Status};
-use ockam_core::compat::sync::{Arc, RwLock};
-use ockam_core::errcode::{Kind, Origin};
+use ockam_core::compat::sync::Arc;
 use ockam_core::{self, CowStr, Result, Routed, Worker};
+use ockam_identity::credential::Attributes;
 use ockam_identity::{secure_channel_required, LEGACY_ID, TRUST_CONTEXT_ID};
 use ockam_node::{Context, RpcClient};
 use std::collections::HashMap;
-use std::num::NonZeroUsize;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 use tracing::{trace, warn};
-use types::AddMember;
+use types::{AddMember, EnrollmentToken, RevokeMember};
 
 use crate::authenticator::direct::types::CreateToken;
 
-const MAX_TOKEN_DURATION: Duration = Duration::from_secs(600);
+/// Duration of an enrollment token, unless specified when it is created
+const DEFAULT_TOKEN_DURATION: Duration = Duration::from_secs(600);
+
+/// Maximum duration of an enrollment token, unless configured otherwise
+pub const DEFAULT_MAX_TOKEN_DURATION: Duration = Duration::from_secs(30 * 24 * 3600);
+
+/// Number of times an enrollment token can be used, unless specified when it is created
+const DEFAULT_TOKEN_USAGE_COUNT: u64 = 1;
 
 /// Schema identifier for a project membership credential.
 ///
@@ -30,6 +39,43 @@ const MAX_TOKEN_DURATION: Duration = Duration::from_secs(600);
 /// - `role`: b"member"
 pub const LEGACY_MEMBER: &str = "member";
 
+/// Attribute holding the role of an identity in a trust context
+const OCKAM_ROLE: &str = "ockam-role";
+
+/// Role of the identities allowed to manage the members of a trust context
+const ENROLLER: &str = "enroller";
+
+/// Role of the identities allowed to publish the policy bundles of the trust context
+const POLICY_PUBLISHER: &str = "policy-publisher";
+
+/// Return true if an identity has the enroller role in the trust context
+pub(crate) async fn is_enroller(
+    attributes_reader: &dyn IdentityAttributesReader,
+    id: &IdentityIdentifier,
+) -> Result<bool> {
+    has_role(attributes_reader, id, ENROLLER).await
+}
+
+/// Return true if an identity has the policy publisher role in the trust context
+pub(crate) async fn is_policy_publisher(
+    attributes_reader: &dyn IdentityAttributesReader,
+    id: &IdentityIdentifier,
+) -> Result<bool> {
+    has_role(attributes_reader, id, POLICY_PUBLISHER).await
+}
+
+async fn has_role(
+    attributes_reader: &dyn IdentityAttributesReader,
+    id: &IdentityIdentifier,
+    role: &str,
+) -> Result<bool> {
+    Ok(attributes_reader
+        .get_attributes(id)
+        .await?
+        .and_then(|entry| entry.attrs().get(OCKAM_ROLE).cloned())
+        .map_or(false, |r| r == role.as_bytes()))
+}
+
 // This acts as a facade, modifying and forwarding incoming messages from legacy clients
 // to the new endpoints.   It's going to be removed once we don't need to maintain compatibility
 // with old clients anymore.
@@ -149,24 +195,106 @@ impl Worker for LegacyApiConverter {
 pub struct DirectAuthenticator {
     trust_context: String,
     attributes_writer: Arc<dyn IdentityAttributesWriter>,
+    attributes_reader: Arc<dyn IdentityAttributesReader>,
+    revocations: Option<RevocationsStorage>,
+    credential_schemas: CredentialSchemas,
 }
 
 impl DirectAuthenticator {
     pub async fn new(
         trust_context: String,
         attributes_writer: Arc<dyn IdentityAttributesWriter>,
+        attributes_reader: Arc<dyn IdentityAttributesReader>,
     ) -> Result<Self> {
         Ok(Self {
             trust_context,
             attributes_writer,
+            attributes_reader,
+            revocations: None,
+            credential_schemas: CredentialSchemas::default(),
         })
     }
 
+    /// Reject the members whose attributes would not conform to the schema of
+    /// the credentials issued to them
+    pub fn with_credential_schemas(mut self, credential_schemas: CredentialSchemas) -> Self {
+        self.credential_schemas = credential_schemas;
+        self
+    }
+
+    /// Record revoked members in the given storage, so that their
+    /// credentials are published in the authority revocation list
+    pub fn with_revocations(mut self, revocations: RevocationsStorage) -> Self {
+        self.revocations = Some(revocations);
+        self
+    }
+
+    /// Revoke the credentials issued to a member and remove its attributes
+    async fn revoke_member(
+        &self,
+        revocations: &RevocationsStorage,
+        id: &IdentityIdentifier,
+    ) -> Result<()> {
+        revocations.revoke(id).await?;
+        self.attributes_writer.delete(id).await
+    }
+
+    /// Return the members of the trust context with their attributes
+    async fn list_members(&self) -> Result<Vec<(IdentityIdentifier, AttributesEntry)>> {
+        Ok(self
+            .attributes_reader
+            .list()
+            .await?
+            .into_iter()
+            .filter(|(_, entry)| self.is_member(entry))
+            .collect())
+    }
+
+    /// Return the attributes of a member of the trust context
+    async fn show_member(&self, id: &IdentityIdentifier) -> Result<Option<AttributesEntry>> {
+        Ok(self
+            .attributes_reader
+            .get_attributes(id)
+            .await?
+            .filter(|entry| self.is_member(entry)))
+    }
+
+    /// Remove a member of the trust context
+    ///
+    /// Return false if the identity is not a member. When revocations are recorded
+    /// the credentials already issued to the member are revoked, otherwise they stay
+    /// valid until they expire.
+    async fn remove_member(&self, id: &IdentityIdentifier) -> Result<bool> {
+        if self.show_member(id).await?.is_none() {
+            return Ok(false);
+        }
+        match &self.revocations {
+            Some(revocations) => self.revoke_member(revocations, id).await?,
+            None => self.attributes_writer.delete(id).await?,
+        }
+        Ok(true)
+    }
+
+    /// Return true if the attributes were given by this trust context
+    fn is_member(&self, entry: &AttributesEntry) -> bool {
+        entry.attrs().get(TRUST_CONTEXT_ID).map(|v| v.as_slice())
+            == Some(self.trust_context.as_bytes())
+    }
+
+    /// Return true if an identity is allowed to manage the members of the trust context
+    ///
+    /// Members can be added by any identity allowed to access this service, but
+    /// only enrollers can read or remove them.
+    async fn is_enroller(&self, id: &IdentityIdentifier) -> Result<bool> {
+        is_enroller(self.attributes_reader.as_ref(), id).await
+    }
+
     async fn add_member<'a>(
         &self,
         enroller: &IdentityIdentifier,
         id: &IdentityIdentifier,
         attrs: &HashMap<CowStr<'a>, CowStr<'a>>,
+        attributes_ttl: Option<Duration>,
     ) -> Result<()> {
         let auth_attrs = attrs
             .iter()
@@ -182,10 +310,11 @@ impl DirectAuthenticator {
                 .into_iter(),
             )
             .collect();
+        let now = Timestamp::now().unwrap();
         let entry = AttributesEntry::new(
             auth_attrs,
-            Timestamp::now().unwrap(),
-            None,
+            now,
+            expiration_time(now, attributes_ttl),
             Some(enroller.clone()),
         );
         self.attributes_writer.put_attributes(id, entry).await
@@ -211,12 +340,73 @@ impl Worker for DirectAuthenticator {
                 body   = %req.has_body(),
                 "request"
             }
-            let res = match (req.method(), req.path()) {
-                (Some(Method::Post), "/") | (Some(Method::Post), "/members") => {
+            let path_segments = req.path_segments::<3>();
+            let res = match (req.method(), path_segments.as_slice()) {
+                (Some(Method::Post), [""]) | (Some(Method::Post), ["members"]) => {
                     let add: AddMember = dec.decode()?;
-                    self.add_member(&from, add.member(), add.attributes())
+                    let attributes = add.attributes().iter().map(|(k, v)| (&**k, &**v));
+                    if let Err(error) =
+                        validate_member_attributes(&self.credential_schemas, attributes)
+                    {
+                        api::bad_request(&req, &error.to_string()).to_vec()?
+                    } else {
+                        self.add_member(
+                            &from,
+                            add.member(),
+                            add.attributes(),
+                            add.attributes_ttl(),
+                        )
                         .await?;
-                    Response::ok(req.id()).to_vec()?
+                        Response::ok(req.id()).to_vec()?
+                    }
+                }
+                (Some(Method::Get), ["members"]) => {
+                    if self.is_enroller(&from).await? {
+                        Response::ok(req.id())
+                            .body(self.list_members().await?)
+                            .to_vec()?
+                    } else {
+                        api::forbidden(&req, "only enrollers can list members").to_vec()?
+                    }
+                }
+                (Some(Method::Get), ["members", id]) => {
+                    if !self.is_enroller(&from).await? {
+                        api::forbidden(&req, "only enrollers can read members").to_vec()?
+                    } else if let Ok(id) = IdentityIdentifier::try_from(id.to_string()) {
+                        match self.show_member(&id).await? {
+                            Some(entry) => Response::ok(req.id()).body(entry).to_vec()?,
+                            None => Response::not_found(req.id()).to_vec()?,
+                        }
+                    } else {
+                        api::bad_request(&req, "invalid identity identifier").to_vec()?
+                    }
+                }
+                (Some(Method::Delete), ["members", id]) => {
+                    if !self.is_enroller(&from).await? {
+                        api::forbidden(&req, "only enrollers can remove members").to_vec()?
+                    } else if let Ok(id) = IdentityIdentifier::try_from(id.to_string()) {
+                        if self.remove_member(&id).await? {
+                            Response::ok(req.id()).to_vec()?
+                        } else {
+                            Response::not_found(req.id()).to_vec()?
+                        }
+                    } else {
+                        api::bad_request(&req, "invalid identity identifier").to_vec()?
+                    }
+                }
+                (Some(Method::Post), ["revocations"]) => {
+                    let revoke: RevokeMember = dec.decode()?;
+                    match &self.revocations {
+                        Some(revocations) => {
+                            match self.revoke_member(revocations, revoke.member()).await {
+                                Ok(()) => Response::ok(req.id()).to_vec()?,
+                                Err(error) => {
+                                    api::internal_error(&req, &error.to_string()).to_vec()?
+                                }
+                            }
+                        }
+                        None => api::bad_request(&req, "revocations are not supported").to_vec()?,
+                    }
                 }
                 _ => api::unknown_path(&req).to_vec()?,
             };
@@ -230,7 +420,9 @@ impl Worker for DirectAuthenticator {
 #[derive(Clone)]
 pub struct EnrollmentTokenAuthenticator {
     trust_context: String,
-    tokens: Arc<RwLock<LruCache<[u8; 32], Token>>>,
+    tokens: EnrollmentTokens,
+    credential_schemas: CredentialSchemas,
+    max_token_duration: Duration,
 }
 
 pub struct EnrollmentTokenIssuer(EnrollmentTokenAuthenticator);
@@ -244,12 +436,13 @@ impl EnrollmentTokenAuthenticator {
     pub fn new_worker_pair(
         trust_context: String,
         attributes_writer: Arc<dyn IdentityAttributesWriter>,
+        tokens: EnrollmentTokens,
     ) -> (EnrollmentTokenIssuer, EnrollmentTokenAcceptor) {
         let base = Self {
             trust_context,
-            tokens: Arc::new(RwLock::new(LruCache::new(
-                NonZeroUsize::new(128).expect("0 < 128"),
-            ))),
+            tokens,
+            credential_schemas: CredentialSchemas::default(),
+            max_token_duration: DEFAULT_MAX_TOKEN_DURATION,
         };
         (
             EnrollmentTokenIssuer(base.clone()),
@@ -259,31 +452,37 @@ impl EnrollmentTokenAuthenticator {
 }
 
 impl EnrollmentTokenIssuer {
+    /// Refuse to issue tokens for attributes which would not conform to the
+    /// schema of the credentials issued to the enrolled members
+    pub fn with_credential_schemas(mut self, credential_schemas: CredentialSchemas) -> Self {
+        self.0.credential_schemas = credential_schemas;
+        self
+    }
+
+    /// Refuse to issue tokens valid for longer than the given duration
+    pub fn with_max_token_duration(mut self, max_token_duration: Duration) -> Self {
+        self.0.max_token_duration = max_token_duration;
+        self
+    }
+
     async fn issue_token(
         &self,
         enroller: &IdentityIdentifier,
-        attrs: HashMap<String, String>,
+        create: CreateToken<'_>,
     ) -> Result<OneTimeCode> {
-        let otc = OneTimeCode::new();
-        let tkn = Token {
-            attrs,
-            generated_by: enroller.clone(),
-            time: Instant::now(),
-        };
+        let duration = create.duration().unwrap_or(DEFAULT_TOKEN_DURATION);
+        let usage_count = create.usage_count().unwrap_or(DEFAULT_TOKEN_USAGE_COUNT);
+        let attributes_ttl = create.attributes_ttl();
         self.0
             .tokens
-            .write()
-            .map(|mut r| {
-                r.put(*otc.code(), tkn);
-                otc
-            })
-            .map_err(|_| {
-                ockam_core::Error::new(
-                    Origin::Other,
-                    Kind::Internal,
-                    "failed to get read lock on tokens table",
-                )
-            })
+            .issue(
+                enroller,
+                create.into_owned_attributes(),
+                duration,
+                usage_count,
+                attributes_ttl,
+            )
+            .await
     }
 }
 
@@ -308,9 +507,36 @@ impl Worker for EnrollmentTokenIssuer {
             }
             let res = match (req.method(), req.path()) {
                 (Some(Method::Post), "/") | (Some(Method::Post), "/tokens") => {
-                    let att: CreateToken = dec.decode()?;
-                    match self.issue_token(&from, att.into_owned_attributes()).await {
-                        Ok(otc) => Response::ok(req.id()).body(&otc).to_vec()?,
+                    let create: CreateToken = dec.decode()?;
+                    if create.usage_count() == Some(0) {
+                        api::bad_request(&req, "the usage count must be greater than 0").to_vec()?
+                    } else if create.duration().unwrap_or_default() > self.0.max_token_duration {
+                        let message = format!(
+                            "the duration must not exceed {} seconds",
+                            self.0.max_token_duration.as_secs()
+                        );
+                        api::bad_request(&req, &message).to_vec()?
+                    } else if let Err(error) = validate_member_attributes(
+                        &self.0.credential_schemas,
+                        create.attributes().iter().map(|(k, v)| (&**k, &**v)),
+                    ) {
+                        api::bad_request(&req, &error.to_string()).to_vec()?
+                    } else {
+                        match self.issue_token(&from, create).await {
+                            Ok(otc) => Response::ok(req.id()).body(&otc).to_vec()?,
+                            Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
+                        }
+                    }
+                }
+                (Some(Method::Get), "/tokens") => match self.0.tokens.list().await {
+                    Ok(tokens) => Response::ok(req.id()).body(tokens).to_vec()?,
+                    Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
+                },
+                (Some(Method::Delete), path) if path.starts_with("/tokens/") => {
+                    let id = &path["/tokens/".len()..];
+                    match self.0.tokens.revoke(id).await {
+                        Ok(true) => Response::ok(req.id()).to_vec()?,
+                        Ok(false) => Response::not_found(req.id()).to_vec()?,
                         Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
                     }
                 }
@@ -346,29 +572,20 @@ impl Worker for EnrollmentTokenAcceptor {
                 (Some(Method::Post), "/") | (Some(Method::Post), "/credential") => {
                     //TODO: move out of the worker handle_message implementation
                     let otc: OneTimeCode = dec.decode()?;
-                    let token = match self.0.tokens.write() {
-                        Ok(mut r) => {
-                            if let Some(tkn) = r.pop(otc.code()) {
-                                if tkn.time.elapsed() > MAX_TOKEN_DURATION {
-                                    Err(api::forbidden(&req, "expired token"))
-                                } else {
-                                    Ok(tkn)
-                                }
-                            } else {
-                                Err(api::forbidden(&req, "unknown token"))
-                            }
+                    let token = match self.0.tokens.redeem(&otc).await {
+                        Ok(Some(tkn)) => Ok(tkn),
+                        Ok(None) => Err(api::forbidden(&req, "unknown or expired token").to_vec()?),
+                        Err(error) => {
+                            let message = error.to_string();
+                            Err(api::internal_error(&req, &message).to_vec()?)
                         }
-                        Err(_) => Err(api::internal_error(
-                            &req,
-                            "Failed to get read lock on tokens table",
-                        )),
                     };
                     match token {
                         Ok(tkn) => {
                             //TODO: fixme:  unify use of hashmap vs btreemap
                             let trust_context = self.0.trust_context.as_bytes().to_vec();
                             let attrs = tkn
-                                .attrs
+                                .attributes()
                                 .iter()
                                 .map(|(k, v)| (k.to_string(), v.as_bytes().to_vec()))
                                 .chain(
@@ -379,16 +596,17 @@ impl Worker for EnrollmentTokenAcceptor {
                                     .into_iter(),
                                 )
                                 .collect();
+                            let now = Timestamp::now().unwrap();
                             let entry = AttributesEntry::new(
                                 attrs,
-                                Timestamp::now().unwrap(),
-                                None,
-                                Some(tkn.generated_by),
+                                now,
+                                expiration_time(now, tkn.attributes_ttl()),
+                                Some(tkn.generated_by().clone()),
                             );
                             self.1.put_attributes(&from, entry).await?;
                             Response::ok(req.id()).to_vec()?
                         }
-                        Err(err) => err.to_vec()?,
+                        Err(err) => err,
                     }
                 }
                 _ => api::unknown_path(&req).to_vec()?,
@@ -400,10 +618,22 @@ impl Worker for EnrollmentTokenAcceptor {
     }
 }
 
-struct Token {
-    attrs: HashMap<String, String>,
-    generated_by: IdentityIdentifier,
-    time: Instant,
+/// Check that the attributes given to a member conform to the schema of the
+/// credentials issued to members, if the credential schemas are validated
+fn validate_member_attributes<'a>(
+    credential_schemas: &CredentialSchemas,
+    attributes: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Result<()> {
+    let mut member_attributes = Attributes::new();
+    for (name, value) in attributes {
+        member_attributes.put(name, value.as_bytes());
+    }
+    credential_schemas.validate(Some(PROJECT_MEMBER_SCHEMA), &member_attributes)
+}
+
+/// Return the expiration time of attributes added at a given time
+fn expiration_time(added: Timestamp, ttl: Option<Duration>) -> Option<Timestamp> {
+    ttl.map(|ttl| added.add_seconds(ttl.as_secs()))
 }
 
 pub struct DirectAuthenticatorClient(RpcClient);
@@ -413,15 +643,47 @@ impl DirectAuthenticatorClient {
         DirectAuthenticatorClient(client)
     }
 
+    /// Add a member with the given attributes
+    ///
+    /// The attributes expire after `attributes_ttl`, if specified.
     pub async fn add_member(
         &self,
         id: IdentityIdentifier,
         attributes: HashMap<&str, &str>,
+        attributes_ttl: Option<Duration>,
     ) -> Result<()> {
+        let mut body = AddMember::new(id).with_attributes(attributes);
+        if let Some(ttl) = attributes_ttl {
+            body = body.with_attributes_ttl(ttl);
+        }
         self.0
-            .request_no_resp_body(
-                &Request::post("/").body(AddMember::new(id).with_attributes(attributes)),
-            )
+            .request_no_resp_body(&Request::post("/").body(body))
+            .await
+    }
+
+    /// Return the members of the trust context with their attributes
+    pub async fn list_members(&self) -> Result<Vec<(IdentityIdentifier, AttributesEntry)>> {
+        self.0.request(&Request::get("/members")).await
+    }
+
+    /// Return the attributes of a member
+    pub async fn show_member(&self, id: &IdentityIdentifier) -> Result<AttributesEntry> {
+        self.0
+            .request(&Request::get(format!("/members/{id}")))
+            .await
+    }
+
+    /// Remove a member, revoking its credentials if the authority records revocations
+    pub async fn remove_member(&self, id: &IdentityIdentifier) -> Result<()> {
+        self.0
+            .request_no_resp_body(&Request::delete(format!("/members/{id}")))
+            .await
+    }
+
+    /// Revoke the credentials issued to a member
+    pub async fn revoke_member(&self, id: IdentityIdentifier) -> Result<()> {
+        self.0
+            .request_no_resp_body(&Request::post("/revocations").body(RevokeMember::new(id)))
             .await
     }
 }
@@ -433,9 +695,39 @@ impl TokenIssuerClient {
         TokenIssuerClient(client)
     }
 
-    pub async fn create_token(&self, attributes: HashMap<&str, &str>) -> Result<OneTimeCode> {
+    /// Create a token which can be used `usage_count` times during `duration`
+    ///
+    /// The authority defaults are used for the unspecified values. The attributes
+    /// of the members enrolled with the token expire after `attributes_ttl`, if specified.
+    pub async fn create_token(
+        &self,
+        attributes: HashMap<&str, &str>,
+        duration: Option<Duration>,
+        usage_count: Option<u64>,
+        attributes_ttl: Option<Duration>,
+    ) -> Result<OneTimeCode> {
+        let mut body = CreateToken::new().with_attributes(attributes);
+        if let Some(duration) = duration {
+            body = body.with_duration(duration);
+        }
+        if let Some(usage_count) = usage_count {
+            body = body.with_usage_count(usage_count);
+        }
+        if let Some(ttl) = attributes_ttl {
+            body = body.with_attributes_ttl(ttl);
+        }
+        self.0.request(&Request::post("/").body(body)).await
+    }
+
+    /// Return the outstanding tokens
+    pub async fn list_tokens(&self) -> Result<Vec<EnrollmentToken>> {
+        self.0.request(&Request::get("/tokens")).await
+    }
+
+    /// Revoke an outstanding token
+    pub async fn revoke_token(&self, id: &str) -> Result<()> {
         self.0
-            .request(&Request::post("/").body(CreateToken::new().with_attributes(attributes)))
+            .request_no_resp_body(&Request::delete(format!("/tokens/{id}")))
             .await
     }
 }
@@ -0,0 +1,232 @@
+use crate::authenticator::direct::types::EnrollmentToken;
+use ockam::identity::{IdentityIdentifier, OneTimeCode, Storage, Timestamp};
+use ockam_core::compat::rand;
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use ockam_node::compat::asynchronous::Mutex;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Persistent set of the enrollment tokens issued by an authority
+///
+/// Tokens are stored by a hash of their code so that they survive a restart of
+/// the authority without the storage disclosing codes which can still be used.
+/// They are removed once expired, revoked, or used as many times as allowed.
+#[derive(Clone)]
+pub struct EnrollmentTokens {
+    storage: Arc<dyn Storage>,
+    // Serializes the updates of the tokens, which are read-modify-write operations
+    lock: Arc<Mutex<()>>,
+}
+
+impl EnrollmentTokens {
+    const ENROLLMENT_TOKEN_KEY: &'static str = "ENROLLMENT_TOKEN";
+
+    /// Create a new set of enrollment tokens
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self {
+            storage,
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Issue a new token which can be used `max_usage_count` times during `duration`
+    ///
+    /// The attributes given to the members enrolled with the token expire after
+    /// `attributes_ttl`, if specified.
+    pub async fn issue(
+        &self,
+        enroller: &IdentityIdentifier,
+        attributes: HashMap<String, String>,
+        duration: Duration,
+        max_usage_count: u64,
+        attributes_ttl: Option<Duration>,
+    ) -> Result<OneTimeCode> {
+        let now = now()?;
+        let otc = OneTimeCode::new();
+        let token = EnrollmentToken::new(
+            random_id(),
+            attributes,
+            enroller.clone(),
+            now,
+            now.add_seconds(duration.as_secs()),
+            max_usage_count,
+        )
+        .with_attributes_ttl(attributes_ttl);
+        self.put(&key(&otc), &token).await?;
+        Ok(otc)
+    }
+
+    /// Use a token, returning it if it is still valid
+    pub async fn redeem(&self, otc: &OneTimeCode) -> Result<Option<EnrollmentToken>> {
+        let _guard = self.lock.lock().await;
+        let code = key(otc);
+        let mut token = match self.get(&code).await? {
+            Some(token) => token,
+            None => return Ok(None),
+        };
+
+        if token.is_expired(now()?) {
+            self.storage.del(&code, Self::ENROLLMENT_TOKEN_KEY).await?;
+            return Ok(None);
+        }
+
+        token.increment_usage_count();
+        if token.is_expired(now()?) {
+            self.storage.del(&code, Self::ENROLLMENT_TOKEN_KEY).await?;
+        } else {
+            self.put(&code, &token).await?;
+        }
+        Ok(Some(token))
+    }
+
+    /// Return the outstanding tokens, removing the expired ones
+    pub async fn list(&self) -> Result<Vec<EnrollmentToken>> {
+        let _guard = self.lock.lock().await;
+        let now = now()?;
+        let mut tokens = vec![];
+        for code in self.storage.keys(Self::ENROLLMENT_TOKEN_KEY).await? {
+            match self.get(&code).await? {
+                Some(token) if token.is_expired(now) => {
+                    self.storage.del(&code, Self::ENROLLMENT_TOKEN_KEY).await?
+                }
+                Some(token) => tokens.push(token),
+                None => {}
+            }
+        }
+        Ok(tokens)
+    }
+
+    /// Delete the tokens which expired before being used as many times as allowed
+    ///
+    /// Return the number of deleted tokens.
+    pub async fn delete_expired(&self) -> Result<usize> {
+        let _guard = self.lock.lock().await;
+        let now = now()?;
+        let mut deleted = 0;
+        for code in self.storage.keys(Self::ENROLLMENT_TOKEN_KEY).await? {
+            if let Some(token) = self.get(&code).await? {
+                if token.is_expired(now) {
+                    self.storage.del(&code, Self::ENROLLMENT_TOKEN_KEY).await?;
+                    deleted += 1;
+                }
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Revoke an outstanding token
+    ///
+    /// Return false if there is no token with this identifier.
+    pub async fn revoke(&self, id: &str) -> Result<bool> {
+        let _guard = self.lock.lock().await;
+        for code in self.storage.keys(Self::ENROLLMENT_TOKEN_KEY).await? {
+            if let Some(token) = self.get(&code).await? {
+                if token.id() == id {
+                    self.storage.del(&code, Self::ENROLLMENT_TOKEN_KEY).await?;
+                    return Ok(true);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    async fn get(&self, code: &str) -> Result<Option<EnrollmentToken>> {
+        match self.storage.get(code, Self::ENROLLMENT_TOKEN_KEY).await? {
+            Some(value) => Ok(Some(minicbor::decode(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn put(&self, code: &str, token: &EnrollmentToken) -> Result<()> {
+        self.storage
+            .set(
+                code,
+                Self::ENROLLMENT_TOKEN_KEY.to_string(),
+                minicbor::to_vec(token)?,
+            )
+            .await
+    }
+}
+
+fn now() -> Result<Timestamp> {
+    Timestamp::now()
+        .ok_or_else(|| Error::new(Origin::Application, Kind::Internal, "invalid system time"))
+}
+
+/// Return the storage key of a token, so that its code is never stored
+fn key(otc: &OneTimeCode) -> String {
+    hex::encode(Sha256::digest(otc.to_string().as_bytes()))
+}
+
+/// Return a random identifier used to refer to a token without disclosing its code
+fn random_id() -> String {
+    hex::encode(rand::random::<[u8; 8]>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam::identity::InMemoryStorage;
+    use ockam_node::Context;
+
+    #[ockam_macros::test(timeout = 5_000)]
+    async fn tokens_can_be_used_a_limited_number_of_times(ctx: &mut Context) -> Result<()> {
+        let tokens = EnrollmentTokens::new(InMemoryStorage::create());
+        let enroller = IdentityIdentifier::from_key_id("enroller");
+        let otc = tokens
+            .issue(&enroller, HashMap::new(), Duration::from_secs(60), 2, None)
+            .await?;
+
+        let listed = tokens.list().await?;
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].usage_count(), 0);
+
+        assert!(tokens.redeem(&otc).await?.is_some());
+        assert_eq!(tokens.list().await?[0].usage_count(), 1);
+        assert!(tokens.redeem(&otc).await?.is_some());
+        assert!(tokens.redeem(&otc).await?.is_none());
+        assert!(tokens.list().await?.is_empty());
+
+        let otc = tokens
+            .issue(&enroller, HashMap::new(), Duration::from_secs(60), 1, None)
+            .await?;
+        let id = tokens.list().await?[0].id().to_string();
+        assert!(tokens.revoke(&id).await?);
+        assert!(!tokens.revoke(&id).await?);
+        assert!(tokens.redeem(&otc).await?.is_none());
+
+        ctx.stop().await
+    }
+
+    #[ockam_macros::test(timeout = 5_000)]
+    async fn tokens_are_stored_by_hash_and_swept_once_expired(ctx: &mut Context) -> Result<()> {
+        let storage = InMemoryStorage::create();
+        let tokens = EnrollmentTokens::new(storage.clone());
+        let enroller = IdentityIdentifier::from_key_id("enroller");
+        let otc = tokens
+            .issue(&enroller, HashMap::new(), Duration::from_secs(60), 1, None)
+            .await?;
+        tokens
+            .issue(&enroller, HashMap::new(), Duration::ZERO, 1, None)
+            .await?;
+
+        let keys = storage.keys(EnrollmentTokens::ENROLLMENT_TOKEN_KEY).await?;
+        assert_eq!(keys.len(), 2);
+        assert!(!keys.contains(&otc.to_string()));
+
+        assert_eq!(tokens.delete_expired().await?, 1);
+        assert_eq!(
+            storage
+                .keys(EnrollmentTokens::ENROLLMENT_TOKEN_KEY)
+                .await?
+                .len(),
+            1
+        );
+        assert!(tokens.redeem(&otc).await?.is_some());
+
+        ctx.stop().await
+    }
+}
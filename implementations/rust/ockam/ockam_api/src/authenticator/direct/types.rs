@@ -1,7 +1,8 @@
 use minicbor::{Decode, Encode};
-use ockam::identity::IdentityIdentifier;
+use ockam::identity::{IdentityIdentifier, Timestamp};
 use ockam_core::CowStr;
 use std::collections::HashMap;
+use std::time::Duration;
 
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
@@ -14,6 +15,7 @@ pub struct AddMember<'a> {
     #[n(0)] tag: TypeTag<2820828>,
     #[n(1)] member: IdentityIdentifier,
     #[b(2)] attributes: HashMap<CowStr<'a>, CowStr<'a>>,
+    #[n(3)] attributes_ttl: Option<u64>,
 }
 
 impl<'a> AddMember<'a> {
@@ -23,9 +25,20 @@ impl<'a> AddMember<'a> {
             tag: TypeTag,
             member,
             attributes: HashMap::new(),
+            attributes_ttl: None,
         }
     }
 
+    /// Make the attributes of the member expire after the given duration
+    pub fn with_attributes_ttl(mut self, ttl: Duration) -> Self {
+        self.attributes_ttl = Some(ttl.as_secs());
+        self
+    }
+
+    pub fn attributes_ttl(&self) -> Option<Duration> {
+        self.attributes_ttl.map(Duration::from_secs)
+    }
+
     pub fn with_attributes<S: Into<CowStr<'a>>>(mut self, attributes: HashMap<S, S>) -> Self {
         self.attributes = attributes
             .into_iter()
@@ -43,6 +56,29 @@ impl<'a> AddMember<'a> {
     }
 }
 
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RevokeMember {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4529351>,
+    #[n(1)] member: IdentityIdentifier,
+}
+
+impl RevokeMember {
+    pub fn new(member: IdentityIdentifier) -> Self {
+        RevokeMember {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            member,
+        }
+    }
+
+    pub fn member(&self) -> &IdentityIdentifier {
+        &self.member
+    }
+}
+
 #[derive(Debug, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -50,6 +86,9 @@ pub struct CreateToken<'a> {
     #[cfg(feature = "tag")]
     #[n(0)] tag: TypeTag<2502742>,
     #[b(1)] attributes: HashMap<CowStr<'a>, CowStr<'a>>,
+    #[n(2)] duration: Option<u64>,
+    #[n(3)] usage_count: Option<u64>,
+    #[n(4)] attributes_ttl: Option<u64>,
 }
 
 impl<'a> CreateToken<'a> {
@@ -59,9 +98,46 @@ impl<'a> CreateToken<'a> {
             #[cfg(feature = "tag")]
             tag: TypeTag,
             attributes: HashMap::new(),
+            duration: None,
+            usage_count: None,
+            attributes_ttl: None,
         }
     }
 
+    /// Make the token expire after the given duration
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration.as_secs());
+        self
+    }
+
+    /// Allow the token to be used this number of times
+    pub fn with_usage_count(mut self, usage_count: u64) -> Self {
+        self.usage_count = Some(usage_count);
+        self
+    }
+
+    /// Make the attributes of the members enrolled with the token expire after the given duration
+    pub fn with_attributes_ttl(mut self, ttl: Duration) -> Self {
+        self.attributes_ttl = Some(ttl.as_secs());
+        self
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration.map(Duration::from_secs)
+    }
+
+    pub fn attributes_ttl(&self) -> Option<Duration> {
+        self.attributes_ttl.map(Duration::from_secs)
+    }
+
+    pub fn usage_count(&self) -> Option<u64> {
+        self.usage_count
+    }
+
+    pub fn attributes(&self) -> &HashMap<CowStr<'a>, CowStr<'a>> {
+        &self.attributes
+    }
+
     pub fn with_attributes<S: Into<CowStr<'a>>>(mut self, attributes: HashMap<S, S>) -> Self {
         self.attributes = attributes
             .into_iter()
@@ -77,3 +153,94 @@ impl<'a> CreateToken<'a> {
             .collect()
     }
 }
+
+/// An enrollment token, as stored by an authority
+///
+/// The secret code of the token is not part of this description, so that
+/// tokens can be listed without disclosing their code.
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct EnrollmentToken {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7463916>,
+    #[n(1)] id: String,
+    #[n(2)] attributes: HashMap<String, String>,
+    #[n(3)] generated_by: IdentityIdentifier,
+    #[n(4)] created_at: Timestamp,
+    #[n(5)] expires_at: Timestamp,
+    #[n(6)] max_usage_count: u64,
+    #[n(7)] usage_count: u64,
+    #[n(8)] attributes_ttl: Option<u64>,
+}
+
+impl EnrollmentToken {
+    pub fn new(
+        id: String,
+        attributes: HashMap<String, String>,
+        generated_by: IdentityIdentifier,
+        created_at: Timestamp,
+        expires_at: Timestamp,
+        max_usage_count: u64,
+    ) -> Self {
+        EnrollmentToken {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            id,
+            attributes,
+            generated_by,
+            created_at,
+            expires_at,
+            max_usage_count,
+            usage_count: 0,
+            attributes_ttl: None,
+        }
+    }
+
+    /// Make the attributes of the members enrolled with this token expire after the given duration
+    pub fn with_attributes_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.attributes_ttl = ttl.map(|ttl| ttl.as_secs());
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn attributes(&self) -> &HashMap<String, String> {
+        &self.attributes
+    }
+
+    pub fn generated_by(&self) -> &IdentityIdentifier {
+        &self.generated_by
+    }
+
+    pub fn created_at(&self) -> Timestamp {
+        self.created_at
+    }
+
+    pub fn expires_at(&self) -> Timestamp {
+        self.expires_at
+    }
+
+    pub fn max_usage_count(&self) -> u64 {
+        self.max_usage_count
+    }
+
+    pub fn usage_count(&self) -> u64 {
+        self.usage_count
+    }
+
+    pub fn attributes_ttl(&self) -> Option<Duration> {
+        self.attributes_ttl.map(Duration::from_secs)
+    }
+
+    /// Return true if the token can't be used anymore at the given time
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        now >= self.expires_at || self.usage_count >= self.max_usage_count
+    }
+
+    pub(crate) fn increment_usage_count(&mut self) {
+        self.usage_count += 1;
+    }
+}
@@ -99,6 +99,10 @@ impl IdentityAttributesWriter for BootstrapedIdentityStore {
     async fn delete(&self, identity: &IdentityIdentifier) -> Result<()> {
         self.repository.delete(identity).await
     }
+
+    async fn delete_expired(&self) -> Result<Vec<IdentityIdentifier>> {
+        self.repository.delete_expired().await
+    }
 }
 
 #[async_trait]
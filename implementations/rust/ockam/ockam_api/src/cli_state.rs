@@ -11,6 +11,7 @@ use ockam::identity::{
     Identities, IdentitiesRepository, IdentitiesStorage, IdentitiesVault, Identity,
     IdentityIdentifier,
 };
+use ockam_abac::FileDecisionLog;
 use ockam_core::compat::sync::Arc;
 use ockam_core::env::get_env_with_default;
 use ockam_identity::LmdbStorage;
@@ -635,6 +636,19 @@ impl IdentityState {
         self.persist()
     }
 
+    /// Store the change history of the identity after one of its keys was rotated
+    pub fn update_change_history(&mut self, identity: &Identity) -> Result<()> {
+        if identity.identifier() != self.config.identifier {
+            return Err(CliStateError::Invalid(format!(
+                "Can't update identity '{}' with the change history of {}",
+                &self.name,
+                identity.identifier()
+            )));
+        }
+        self.config.change_history = identity.change_history();
+        self.persist()
+    }
+
     pub async fn get(&self, vault: Arc<dyn IdentitiesVault>) -> Result<Identity> {
         let data = self.config.change_history.export()?;
         Ok(self
@@ -971,6 +985,24 @@ impl NodeState {
         Ok(())
     }
 
+    /// Version of the authority policy bundle last applied to the node
+    pub fn policy_bundle_version(&self) -> Result<Option<u64>> {
+        let path = self.path.join("policy_bundle_version");
+        if path.exists() {
+            let version = std::fs::read_to_string(path)?
+                .parse::<u64>()
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            Ok(Some(version))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn set_policy_bundle_version(&self, version: u64) -> Result<()> {
+        std::fs::write(self.path.join("policy_bundle_version"), version.to_string())?;
+        Ok(())
+    }
+
     pub fn is_running(&self) -> bool {
         if let Ok(Some(pid)) = self.pid() {
             let mut sys = System::new();
@@ -993,6 +1025,14 @@ impl NodeState {
         Ok(LmdbStorage::new(self.path.join("policies_storage.lmdb")).await?)
     }
 
+    pub async fn enrollment_tokens_storage(&self) -> Result<LmdbStorage> {
+        Ok(LmdbStorage::new(self.path.join("enrollment_tokens.lmdb")).await?)
+    }
+
+    pub fn decision_log(&self) -> FileDecisionLog {
+        FileDecisionLog::new(self.path.join("policy_audit.log"))
+    }
+
     pub fn kill_process(&self, sigkill: bool) -> Result<()> {
         if let Some(pid) = self.pid()? {
             nix::sys::signal::kill(
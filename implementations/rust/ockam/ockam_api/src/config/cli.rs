@@ -8,11 +8,11 @@ use crate::{cli_state, multiaddr_to_route, DefaultAddress, HexByteVec};
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::FlowControls;
 use ockam_core::{Result, Route};
-use ockam_identity::credential::Credential;
+use ockam_identity::credential::{Credential, CredentialSchema};
 use ockam_identity::{
-    identities, AuthorityService, CredentialsMemoryRetriever, CredentialsRetriever, Identities,
-    Identity, IdentityIdentifier, RemoteCredentialsRetriever, RemoteCredentialsRetrieverInfo,
-    SecureChannels, TrustContext,
+    identities, AttributesScope, AuthorityService, CredentialsMemoryRetriever,
+    CredentialsRetriever, Identities, Identity, IdentityIdentifier, RemoteCredentialsRetriever,
+    RemoteCredentialsRetrieverInfo, SecureChannels, TrustContext,
 };
 use ockam_multiaddr::MultiAddr;
 use ockam_transport_tcp::TcpTransport;
@@ -79,6 +79,10 @@ pub struct TrustContextConfig {
     id: String,
     authority: Option<TrustAuthorityConfig>,
     path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    scoped_authorities: Vec<ScopedAuthorityConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    credential_schemas: Vec<CredentialSchema>,
 }
 
 impl TrustContextConfig {
@@ -87,9 +91,31 @@ impl TrustContextConfig {
             id,
             authority,
             path: None,
+            scoped_authorities: Vec::new(),
+            credential_schemas: Vec::new(),
         }
     }
 
+    /// Trust an additional authority for some attributes only
+    pub fn with_scoped_authority(mut self, authority: ScopedAuthorityConfig) -> Self {
+        self.scoped_authorities.push(authority);
+        self
+    }
+
+    pub fn scoped_authorities(&self) -> &[ScopedAuthorityConfig] {
+        &self.scoped_authorities
+    }
+
+    /// Require the credentials of this trust context to conform to a schema
+    pub fn with_credential_schema(mut self, schema: CredentialSchema) -> Self {
+        self.credential_schemas.push(schema);
+        self
+    }
+
+    pub fn credential_schemas(&self) -> &[CredentialSchema] {
+        &self.credential_schemas
+    }
+
     pub fn id(&self) -> &str {
         &self.id
     }
@@ -136,7 +162,17 @@ impl TrustContextConfig {
                 None
             };
 
-        Ok(TrustContext::new(self.id.clone(), authority))
+        let mut trust_context = TrustContext::new(self.id.clone(), authority);
+        for scoped_authority in &self.scoped_authorities {
+            trust_context = trust_context.with_scoped_authority(
+                scoped_authority.identity().await?,
+                AttributesScope::new(scoped_authority.attributes.clone()),
+            );
+        }
+        for schema in &self.credential_schemas {
+            trust_context = trust_context.with_credential_schema(schema.clone());
+        }
+        Ok(trust_context)
     }
 
     pub fn from_authority_identity(
@@ -259,6 +295,39 @@ impl TrustAuthorityConfig {
     }
 }
 
+/// An authority trusted to attest to some attributes only
+///
+/// An attribute name can be `*` for all the attributes, or end with `.*` for
+/// all the attributes of a namespace, for example `device.*`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScopedAuthorityConfig {
+    identity: String,
+    attributes: Vec<String>,
+}
+
+impl ScopedAuthorityConfig {
+    pub fn new(identity: String, attributes: Vec<String>) -> Self {
+        Self {
+            identity,
+            attributes,
+        }
+    }
+
+    pub fn attributes(&self) -> &[String] {
+        &self.attributes
+    }
+
+    pub async fn identity(&self) -> Result<Identity> {
+        identities()
+            .identities_creation()
+            .import_identity(
+                &hex::decode(&self.identity)
+                    .map_err(|_| ApiError::generic("unable to decode authority identity"))?,
+            )
+            .await
+    }
+}
+
 /// Type of credential retriever
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub enum CredentialRetrieverConfig {
@@ -292,7 +361,10 @@ impl CredentialRetrieverConfig {
                         .resolve_route(tcp_transport, flow_controls.clone())
                         .await?,
                     DefaultAddress::CREDENTIAL_ISSUER.into(),
-                );
+                )
+                .with_revocation_list_address(DefaultAddress::REVOCATION_LIST.into())
+                .with_identity_updates_address(DefaultAddress::IDENTITY_UPDATES.into())
+                .with_policy_bundles_address(DefaultAddress::POLICY_BUNDLES.into());
 
                 Ok(Arc::new(RemoteCredentialsRetriever::new(
                     secure_channels,
@@ -380,7 +452,9 @@ impl CredentialIssuerConfig {
         tcp_transport: TcpTransport,
         flow_controls: FlowControls,
     ) -> Result<Route> {
-        let Some(authority_tcp_session) = multiaddr_to_route(&self.multiaddr, &tcp_transport, &flow_controls).await else {
+        let Some(authority_tcp_session) =
+            multiaddr_to_route(&self.multiaddr, &tcp_transport, &flow_controls).await
+        else {
             let err_msg = format!("Invalid route within trust context: {}", &self.multiaddr);
             error!("{err_msg}");
             return Err(ApiError::generic(&err_msg));
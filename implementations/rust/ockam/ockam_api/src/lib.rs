@@ -13,6 +13,7 @@ pub mod nodes;
 pub mod okta;
 pub mod port_range;
 pub mod rpc_proxy;
+pub mod tracer;
 pub mod uppercase;
 pub mod vault;
 pub mod verifier;
@@ -36,10 +37,14 @@ impl DefaultAddress {
     pub const UPPERCASE_SERVICE: &'static str = "uppercase";
     pub const ECHO_SERVICE: &'static str = "echo";
     pub const HOP_SERVICE: &'static str = "hop";
+    pub const TRACER_SERVICE: &'static str = "tracer";
     pub const CREDENTIALS_SERVICE: &'static str = "credentials";
     pub const SECURE_CHANNEL_LISTENER: &'static str = "api";
     pub const DIRECT_AUTHENTICATOR: &'static str = "direct_authenticator";
     pub const CREDENTIAL_ISSUER: &'static str = "credential_issuer";
+    pub const REVOCATION_LIST: &'static str = "revocation_list";
+    pub const IDENTITY_UPDATES: &'static str = "identity_updates";
+    pub const POLICY_BUNDLES: &'static str = "policy_bundles";
     pub const ENROLLMENT_TOKEN_ISSUER: &'static str = "enrollment_token_issuer";
     pub const ENROLLMENT_TOKEN_ACCEPTOR: &'static str = "enrollment_token_acceptor";
     pub const VERIFIER: &'static str = "verifier";
@@ -53,6 +58,8 @@ pub mod actions {
     use ockam_abac::Action;
 
     pub const HANDLE_MESSAGE: Action = Action::assert_inline("handle_message");
+    pub const CONNECT: Action = Action::assert_inline("connect");
+    pub const SEND_MESSAGE: Action = Action::assert_inline("send_message");
 }
 
 pub mod resources {
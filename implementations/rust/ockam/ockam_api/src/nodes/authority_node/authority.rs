@@ -1,20 +1,28 @@
-use crate::authenticator::direct::EnrollmentTokenAuthenticator;
+use crate::authenticator::direct::{EnrollmentTokenAuthenticator, EnrollmentTokens};
 use crate::bootstrapped_identities_store::BootstrapedIdentityStore;
 use crate::echoer::Echoer;
 use crate::nodes::authority_node::authority::EnrollerCheck::{AnyMember, EnrollerOnly};
-use crate::nodes::authority_node::Configuration;
+use crate::nodes::authority_node::{
+    Configuration, ExpiredEntriesSweeper, PolicyBundleIssuer, PolicyBundlesStorage,
+};
 use crate::{actions, DefaultAddress};
 use ockam::identity::{
     Identities, IdentitiesRepository, IdentitiesStorage, IdentitiesVault, Identity,
-    IdentityAttributesWriter, SecureChannelListenerOptions, SecureChannels, TrustEveryonePolicy,
+    IdentityAttributesReader, IdentityAttributesWriter, SecureChannelListenerOptions,
+    SecureChannels, TrustEveryonePolicy,
 };
 use ockam_abac::expr::{and, eq, ident, str};
 use ockam_abac::{AbacAccessControl, Env};
 use ockam_core::compat::sync::Arc;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::{FlowControlId, FlowControlPolicy, FlowControls};
-use ockam_core::{Address, AllowAll, Error, Message, Result, Worker};
-use ockam_identity::{CredentialsIssuer, LmdbStorage};
+use ockam_core::{
+    Address, AllIncomingAccessControl, AllowAll, DenyAll, Error, GlobalKey, Message,
+    RateLimitedAccessControl, Result, TransportPeerKey, Worker,
+};
+use ockam_identity::{
+    CredentialsIssuer, LmdbStorage, RevocationListIssuer, RevocationsStorage, Storage,
+};
 use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_tcp::{TcpListenerOptions, TcpTransport};
 use ockam_vault::storage::FileStorage;
@@ -22,6 +30,18 @@ use ockam_vault::Vault;
 use std::path::Path;
 use tracing::info;
 
+/// Maximum number of enrollment tokens which can be presented per second, from each IP address
+const ENROLLMENT_TOKEN_ACCEPTOR_RATE: f64 = 1.0;
+
+/// Maximum number of enrollment tokens which can be presented in a burst, from each IP address
+const ENROLLMENT_TOKEN_ACCEPTOR_BURST: u32 = 5;
+
+/// Maximum number of enrollment tokens which can be presented per second, in total
+const ENROLLMENT_TOKEN_ACCEPTOR_GLOBAL_RATE: f64 = 50.0;
+
+/// Maximum number of enrollment tokens which can be presented in a burst, in total
+const ENROLLMENT_TOKEN_ACCEPTOR_GLOBAL_BURST: u32 = 100;
+
 /// This struct represents an Authority, which is an
 /// Identity which other identities trust to authenticate attributes
 /// An Authority is able to start a few services
@@ -29,9 +49,15 @@ use tracing::info;
 //   - a credential issuer
 //   - an enrollment token issuer
 //   - an enrollment token acceptor
+//   - a revocation list issuer
+//   - a policy bundle issuer
+//   - a sweeper deleting expired attributes and enrollment tokens
 pub struct Authority {
     identity: Identity,
     secure_channels: Arc<SecureChannels>,
+    revocations: RevocationsStorage,
+    policy_bundles: PolicyBundlesStorage,
+    enrollment_tokens: EnrollmentTokens,
 }
 
 /// Public functions to:
@@ -49,7 +75,8 @@ impl Authority {
     pub async fn create(configuration: &Configuration) -> Result<Authority> {
         info!("configuration {:?}", configuration);
         let vault = Self::create_secure_channels_vault(configuration).await?;
-        let repository = Self::create_identities_repository(configuration).await?;
+        let storage = Self::create_storage(configuration).await?;
+        let repository = Self::create_identities_repository(storage.clone(), configuration);
         let secure_channels = SecureChannels::builder()
             .with_identities_vault(vault)
             .with_identities_repository(repository)
@@ -62,9 +89,17 @@ impl Authority {
             .await?;
         info!("retrieved the authority identity {}", identity.identifier());
 
+        let credential_schemas = secure_channels.identities().credential_schemas();
+        for schema in &configuration.credential_schemas {
+            credential_schemas.register(schema.clone());
+        }
+
         Ok(Authority {
             identity,
             secure_channels,
+            revocations: RevocationsStorage::new(storage.clone()),
+            policy_bundles: PolicyBundlesStorage::new(storage.clone()),
+            enrollment_tokens: EnrollmentTokens::new(storage),
         })
     }
 
@@ -127,8 +162,11 @@ impl Authority {
         let direct = crate::authenticator::direct::DirectAuthenticator::new(
             configuration.clone().trust_context_identifier(),
             self.attributes_writer(),
+            self.attributes_reader(),
         )
-        .await?;
+        .await?
+        .with_revocations(self.revocations.clone())
+        .with_credential_schemas(self.secure_channels.identities().credential_schemas());
 
         let name = configuration.clone().authenticator_name();
         flow_controls.add_consumer(
@@ -159,7 +197,11 @@ impl Authority {
         let (issuer, acceptor) = EnrollmentTokenAuthenticator::new_worker_pair(
             configuration.trust_context_identifier(),
             self.attributes_writer(),
+            self.enrollment_tokens.clone(),
         );
+        let issuer = issuer
+            .with_credential_schemas(self.secure_channels.identities().credential_schemas())
+            .with_max_token_duration(configuration.enrollment_token_max_duration());
 
         // start an enrollment token issuer with an abac policy checking that
         // the caller is an enroller for the authority project
@@ -182,7 +224,9 @@ impl Authority {
         // start an enrollment token acceptor allowing any incoming message as long as
         // it comes through a secure channel. We accept any message since the purpose of
         // that service is to access a one-time token stating that the sender of the message
-        // is a project member
+        // is a project member. So that tokens can't be guessed, the number of tokens presented
+        // is rate limited per IP address of the transport connection they come from, since
+        // new identities and secure channels are cheap to create, and globally
         let acceptor_address: String = DefaultAddress::ENROLLMENT_TOKEN_ACCEPTOR.into();
         flow_controls.add_consumer(
             &Address::from_string(acceptor_address.clone()),
@@ -191,7 +235,18 @@ impl Authority {
         );
 
         WorkerBuilder::with_access_control(
-            Arc::new(AllowAll),
+            Arc::new(AllIncomingAccessControl::new(vec![
+                Arc::new(RateLimitedAccessControl::new(
+                    TransportPeerKey,
+                    ENROLLMENT_TOKEN_ACCEPTOR_RATE,
+                    ENROLLMENT_TOKEN_ACCEPTOR_BURST,
+                )?),
+                Arc::new(RateLimitedAccessControl::new(
+                    GlobalKey,
+                    ENROLLMENT_TOKEN_ACCEPTOR_GLOBAL_RATE,
+                    ENROLLMENT_TOKEN_ACCEPTOR_GLOBAL_BURST,
+                )?),
+            ])),
             Arc::new(AllowAll),
             acceptor_address.clone(),
             acceptor,
@@ -235,6 +290,90 @@ impl Authority {
         Ok(())
     }
 
+    /// Start the revocation list issuer service to publish the list of the
+    /// identities whose credentials have been revoked
+    pub async fn start_revocation_list_issuer(
+        &self,
+        ctx: &Context,
+        flow_controls: &FlowControls,
+        secure_channel_flow_control_id: &FlowControlId,
+        configuration: &Configuration,
+    ) -> Result<()> {
+        let issuer = RevocationListIssuer::new(
+            self.identities(),
+            self.identity.clone(),
+            self.revocations.clone(),
+        );
+
+        let address = DefaultAddress::REVOCATION_LIST.to_string();
+        flow_controls.add_consumer(
+            &Address::from_string(address.clone()),
+            secure_channel_flow_control_id,
+            FlowControlPolicy::SpawnerAllowMultipleMessages,
+        );
+
+        self.start(ctx, configuration, address.clone(), AnyMember, issuer)
+            .await?;
+
+        info!("started a revocation list issuer at '{address}'");
+        Ok(())
+    }
+
+    /// Start the policy bundle issuer service to publish the policies which
+    /// the nodes of the trust context apply
+    pub async fn start_policy_bundle_issuer(
+        &self,
+        ctx: &Context,
+        flow_controls: &FlowControls,
+        secure_channel_flow_control_id: &FlowControlId,
+        configuration: &Configuration,
+    ) -> Result<()> {
+        let issuer = PolicyBundleIssuer::new(
+            self.identities(),
+            self.identity.clone(),
+            self.attributes_reader(),
+            self.policy_bundles.clone(),
+        );
+
+        let address = DefaultAddress::POLICY_BUNDLES.to_string();
+        flow_controls.add_consumer(
+            &Address::from_string(address.clone()),
+            secure_channel_flow_control_id,
+            FlowControlPolicy::SpawnerAllowMultipleMessages,
+        );
+
+        // Publishing a bundle is restricted to policy publishers by the issuer itself
+        self.start(ctx, configuration, address.clone(), AnyMember, issuer)
+            .await?;
+
+        info!("started a policy bundle issuer at '{address}'");
+        Ok(())
+    }
+
+    /// Start the identity updates service to receive the change histories
+    /// of the members which rotated their keys
+    pub async fn start_identity_updates_service(
+        &self,
+        ctx: &Context,
+        flow_controls: &FlowControls,
+        secure_channel_flow_control_id: &FlowControlId,
+    ) -> Result<()> {
+        let address = DefaultAddress::IDENTITY_UPDATES.to_string();
+        flow_controls.add_consumer(
+            &Address::from_string(address.clone()),
+            secure_channel_flow_control_id,
+            FlowControlPolicy::SpawnerAllowMultipleMessages,
+        );
+
+        // An identity can only update itself, this is checked by the service
+        self.secure_channels
+            .start_identity_updates_service(ctx, address.clone())
+            .await?;
+
+        info!("started an identity updates service at '{address}'");
+        Ok(())
+    }
+
     /// Start the Okta service to retrieve attributes authenticated by Okta
     pub async fn start_okta(
         &self,
@@ -269,6 +408,22 @@ impl Authority {
         Ok(())
     }
 
+    /// Periodically delete the attributes and the enrollment tokens which have expired
+    ///
+    /// Expired attributes and tokens are already ignored when they are read, this
+    /// only reclaims their storage. The deletions stop with the node.
+    pub async fn start_attributes_sweeper(&self, ctx: &Context) -> Result<()> {
+        let sweeper =
+            ExpiredEntriesSweeper::new(self.attributes_writer(), self.enrollment_tokens.clone());
+        ctx.start_worker(
+            Address::random_tagged("ExpiredEntriesSweeper"),
+            sweeper,
+            DenyAll,
+            DenyAll,
+        )
+        .await
+    }
+
     /// Start an echo service
     pub async fn start_echo_service(
         &self,
@@ -305,6 +460,11 @@ impl Authority {
         self.identities_repository().as_attributes_writer().clone()
     }
 
+    /// Return the attributes reader used by the authority
+    fn attributes_reader(&self) -> Arc<dyn IdentityAttributesReader> {
+        self.identities_repository().as_attributes_reader()
+    }
+
     /// Create an identity vault backed by a FileStorage
     async fn create_secure_channels_vault(
         configuration: &Configuration,
@@ -317,15 +477,20 @@ impl Authority {
         Ok(vault)
     }
 
-    /// Create an authenticated storage backed by a Lmdb database
-    async fn create_identities_repository(
-        configuration: &Configuration,
-    ) -> Result<Arc<dyn IdentitiesRepository>> {
+    /// Create a storage backed by a Lmdb database
+    async fn create_storage(configuration: &Configuration) -> Result<Arc<dyn Storage>> {
         let storage_path = &configuration.storage_path;
         Self::create_ockam_directory_if_necessary(storage_path)?;
-        let storage = Arc::new(LmdbStorage::new(&storage_path).await?);
+        Ok(Arc::new(LmdbStorage::new(&storage_path).await?))
+    }
+
+    /// Create an authenticated storage backed by the authority storage
+    fn create_identities_repository(
+        storage: Arc<dyn Storage>,
+        configuration: &Configuration,
+    ) -> Arc<dyn IdentitiesRepository> {
         let repository = Arc::new(IdentitiesStorage::new(storage));
-        Ok(Self::bootstrap_repository(repository, configuration))
+        Self::bootstrap_repository(repository, configuration)
     }
 
     /// Create a directory to save storage files if they haven't been  created before
@@ -409,11 +574,11 @@ impl Authority {
         env.put("action.id", str(actions::HANDLE_MESSAGE.as_str()));
         env.put(
             "resource.project_id",
-            str(configuration.clone().project_identifier),
+            str(configuration.project_identifier.clone()),
         );
         env.put(
             "resource.trust_context_id",
-            str(configuration.clone().trust_context_identifier),
+            str(configuration.trust_context_identifier.clone()),
         );
         let abac = Arc::new(AbacAccessControl::new(
             self.identities_repository(),
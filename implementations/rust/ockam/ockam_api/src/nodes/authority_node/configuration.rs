@@ -1,13 +1,15 @@
+use crate::authenticator::direct::DEFAULT_MAX_TOKEN_DURATION;
 use crate::bootstrapped_identities_store::PreTrustedIdentities;
 use crate::DefaultAddress;
 use ockam::identity::credential::Timestamp;
-use ockam::identity::{AttributesEntry, Identity, IdentityIdentifier};
+use ockam::identity::{AttributesEntry, CredentialSchema, Identity, IdentityIdentifier};
 use ockam_core::compat::collections::HashMap;
 use ockam_core::compat::fmt;
 use ockam_core::compat::fmt::{Display, Formatter};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// Configuration for the Authority node
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,6 +49,15 @@ pub struct Configuration {
     /// If true don't start the token enroller service
     pub no_token_enrollment: bool,
 
+    /// Maximum duration of the enrollment tokens
+    /// The default is DEFAULT_MAX_TOKEN_DURATION
+    #[serde(default)]
+    pub enrollment_token_max_duration: Option<Duration>,
+
+    /// Schemas that the credentials issued by the authority must conform to
+    #[serde(default)]
+    pub credential_schemas: Vec<CredentialSchema>,
+
     /// optional configuration for the okta service
     pub okta: Option<OktaConfiguration>,
 }
@@ -81,6 +92,12 @@ impl Configuration {
             .clone()
             .unwrap_or(DefaultAddress::DIRECT_AUTHENTICATOR.to_string())
     }
+
+    /// Return the maximum duration of the enrollment tokens
+    pub(crate) fn enrollment_token_max_duration(&self) -> Duration {
+        self.enrollment_token_max_duration
+            .unwrap_or(DEFAULT_MAX_TOKEN_DURATION)
+    }
 }
 
 /// Configuration for the Okta service
@@ -1,7 +1,11 @@
 mod authority;
 mod configuration;
 mod node;
+mod policy_bundles;
+mod sweeper;
 
 pub use authority::*;
 pub use configuration::*;
 pub use node::*;
+pub use policy_bundles::*;
+pub use sweeper::*;
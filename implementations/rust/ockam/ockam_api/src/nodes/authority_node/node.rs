@@ -46,6 +46,28 @@ pub async fn start_node(ctx: &Context, configuration: &Configuration) -> Result<
         )
         .await?;
 
+    authority
+        .start_revocation_list_issuer(
+            ctx,
+            &flow_controls,
+            &secure_channel_flow_control_id,
+            configuration,
+        )
+        .await?;
+
+    authority
+        .start_policy_bundle_issuer(
+            ctx,
+            &flow_controls,
+            &secure_channel_flow_control_id,
+            configuration,
+        )
+        .await?;
+
+    authority
+        .start_identity_updates_service(ctx, &flow_controls, &secure_channel_flow_control_id)
+        .await?;
+
     // start the Okta service (if the optional configuration has been provided)
     authority
         .start_okta(
@@ -56,6 +78,9 @@ pub async fn start_node(ctx: &Context, configuration: &Configuration) -> Result<
         )
         .await?;
 
+    // delete the expired attributes of the members and the expired enrollment tokens
+    authority.start_attributes_sweeper(ctx).await?;
+
     // start an echo service so that the node can be queried as healthy
     authority
         .start_echo_service(ctx, &flow_controls, &secure_channel_flow_control_id)
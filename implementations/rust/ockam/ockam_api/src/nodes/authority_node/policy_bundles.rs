@@ -0,0 +1,231 @@
+use crate::authenticator::direct::is_policy_publisher;
+use crate::nodes::models::policy::PublishPolicyBundle;
+use minicbor::Decoder;
+use ockam::identity::{
+    Identities, Identity, IdentityAttributesReader, IdentitySecureChannelLocalInfo,
+    SignedPolicyBundle,
+};
+use ockam_abac::{PolicyBundle, PolicyBundleEntry, PolicyStorage};
+use ockam_core::api::{self, Method, Request, Response};
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result, Routed, Worker};
+use ockam_identity::{secure_channel_required, Storage};
+use ockam_node::{Context, RpcClient};
+use tracing::trace;
+
+/// Persistent set of the policy bundles published by an authority
+#[derive(Clone)]
+pub struct PolicyBundlesStorage {
+    storage: Arc<dyn Storage>,
+}
+
+impl PolicyBundlesStorage {
+    const POLICY_BUNDLE_KEY: &'static str = "POLICY_BUNDLE";
+
+    /// Create a new storage for policy bundles
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self { storage }
+    }
+
+    /// Sign and store a new version of the policy bundle
+    ///
+    /// Return the stored bundle.
+    pub async fn publish(
+        &self,
+        identities: &Identities,
+        issuer: &Identity,
+        policies: Vec<PolicyBundleEntry>,
+    ) -> Result<SignedPolicyBundle> {
+        let version = self.latest_version().await?.map(|v| v + 1).unwrap_or(1);
+        let bundle = PolicyBundle::new(issuer.identifier(), version, policies);
+        let signed = bundle.sign(identities, issuer).await?;
+        self.storage
+            .set(
+                &version.to_string(),
+                Self::POLICY_BUNDLE_KEY.to_string(),
+                minicbor::to_vec(&signed)?,
+            )
+            .await?;
+        Ok(signed)
+    }
+
+    /// Return a given version of the policy bundle
+    pub async fn get(&self, version: u64) -> Result<Option<SignedPolicyBundle>> {
+        match self
+            .storage
+            .get(&version.to_string(), Self::POLICY_BUNDLE_KEY)
+            .await?
+        {
+            Some(value) => Ok(Some(minicbor::decode(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Return the latest version of the policy bundle
+    pub async fn latest(&self) -> Result<Option<SignedPolicyBundle>> {
+        match self.latest_version().await? {
+            Some(version) => self.get(version).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn latest_version(&self) -> Result<Option<u64>> {
+        Ok(self
+            .storage
+            .keys(Self::POLICY_BUNDLE_KEY)
+            .await?
+            .iter()
+            .filter_map(|id| id.parse::<u64>().ok())
+            .max())
+    }
+}
+
+/// This struct runs as a Worker to publish the signed policy bundles of an authority
+///
+/// Any member can read the bundles, only the identities with the `policy-publisher`
+/// role can publish a new version.
+pub struct PolicyBundleIssuer {
+    identities: Arc<Identities>,
+    issuer: Identity,
+    attributes_reader: Arc<dyn IdentityAttributesReader>,
+    bundles: PolicyBundlesStorage,
+}
+
+impl PolicyBundleIssuer {
+    /// Create a new policy bundle issuer
+    pub fn new(
+        identities: Arc<Identities>,
+        issuer: Identity,
+        attributes_reader: Arc<dyn IdentityAttributesReader>,
+        bundles: PolicyBundlesStorage,
+    ) -> Self {
+        Self {
+            identities,
+            issuer,
+            attributes_reader,
+            bundles,
+        }
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for PolicyBundleIssuer {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        if let Ok(i) = IdentitySecureChannelLocalInfo::find_info(m.local_message()) {
+            let from = i.their_identity_id();
+            let mut dec = Decoder::new(m.as_body());
+            let req: Request = dec.decode()?;
+            trace! {
+                target: "ockam_api::nodes::authority_node::policy_bundles",
+                from   = %from,
+                id     = %req.id(),
+                method = ?req.method(),
+                path   = %req.path(),
+                body   = %req.has_body(),
+                "request"
+            }
+            let path_segments = req.path_segments::<2>();
+            let res = match (req.method(), path_segments.as_slice()) {
+                (Some(Method::Get), [""]) => Response::ok(req.id())
+                    .body(self.bundles.latest().await?)
+                    .to_vec()?,
+                (Some(Method::Get), [version]) => match version.parse::<u64>() {
+                    Ok(version) => match self.bundles.get(version).await? {
+                        Some(bundle) => Response::ok(req.id()).body(bundle).to_vec()?,
+                        None => Response::not_found(req.id()).to_vec()?,
+                    },
+                    Err(_) => api::bad_request(&req, "invalid policy bundle version").to_vec()?,
+                },
+                (Some(Method::Post), [""]) => {
+                    if is_policy_publisher(self.attributes_reader.as_ref(), &from).await? {
+                        let body: PublishPolicyBundle = dec.decode()?;
+                        let bundle = self
+                            .bundles
+                            .publish(&self.identities, &self.issuer, body.policies().to_vec())
+                            .await?;
+                        Response::ok(req.id()).body(bundle).to_vec()?
+                    } else {
+                        api::forbidden(&req, "only policy publishers can publish policies")
+                            .to_vec()?
+                    }
+                }
+                _ => api::unknown_path(&req).to_vec()?,
+            };
+            c.send(m.return_route(), res).await
+        } else {
+            secure_channel_required(c, m).await
+        }
+    }
+}
+
+/// Verify a policy bundle retrieved from an authority and apply its policies
+///
+/// When a version is pinned only that version is applied. Otherwise a bundle
+/// older than the applied version is refused. Return the applied bundle and the
+/// number of policies it changed, or `None` if its version was already applied.
+pub async fn apply_policy_bundle(
+    identities: &Identities,
+    authority: &Identity,
+    signed: &SignedPolicyBundle,
+    policies: &dyn PolicyStorage,
+    pinned_version: Option<u64>,
+    applied_version: u64,
+) -> Result<Option<(PolicyBundle, usize)>> {
+    let bundle = PolicyBundle::verify(identities, authority, signed).await?;
+    let version = bundle.version();
+    match pinned_version {
+        Some(pinned) if version != pinned => {
+            let msg =
+                format!("the policy bundle version {version} is not the pinned version {pinned}");
+            return Err(Error::new(Origin::Application, Kind::Invalid, msg));
+        }
+        None if version < applied_version => {
+            let msg = format!(
+                "the policy bundle version {version} is older than the applied version {applied_version}"
+            );
+            return Err(Error::new(Origin::Application, Kind::Invalid, msg));
+        }
+        _ => {}
+    }
+    if version == applied_version {
+        return Ok(None);
+    }
+    let changed = bundle.apply(policies).await?;
+    Ok(Some((bundle, changed)))
+}
+
+/// Client to publish policy bundles to an authority
+///
+/// The nodes retrieve the published bundles with their credentials retriever.
+pub struct PolicyBundlesPublisherClient(RpcClient);
+
+impl PolicyBundlesPublisherClient {
+    pub fn new(client: RpcClient) -> Self {
+        PolicyBundlesPublisherClient(client)
+    }
+
+    /// Publish a new version of the policy bundle, replacing the previous one
+    pub async fn publish(&self, policies: Vec<PolicyBundleEntry>) -> Result<PolicyBundle> {
+        let signed: SignedPolicyBundle = self
+            .0
+            .request(&Request::post("/").body(PublishPolicyBundle::new(policies)))
+            .await?;
+        Ok(minicbor::decode(signed.unverified_data())?)
+    }
+
+    /// Return the latest policy bundle, or a given version of it
+    pub async fn show(&self, version: Option<u64>) -> Result<Option<PolicyBundle>> {
+        let signed: Option<SignedPolicyBundle> = match version {
+            Some(version) => Some(self.0.request(&Request::get(format!("/{version}"))).await?),
+            None => self.0.request(&Request::get("/")).await?,
+        };
+        match signed {
+            Some(signed) => Ok(Some(minicbor::decode(signed.unverified_data())?)),
+            None => Ok(None),
+        }
+    }
+}
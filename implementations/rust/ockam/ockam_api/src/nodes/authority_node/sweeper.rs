@@ -0,0 +1,79 @@
+use crate::authenticator::direct::EnrollmentTokens;
+use ockam::identity::IdentityAttributesWriter;
+use ockam_core::compat::sync::Arc;
+use ockam_core::{Result, Routed, Worker};
+use ockam_node::tokio::task::JoinHandle;
+use ockam_node::{tokio, Context};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Interval between two deletions of the expired attributes and enrollment tokens
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Worker periodically deleting the attributes and the enrollment tokens which have expired
+///
+/// The deletions run in a task which is aborted when the worker is stopped, so
+/// that they don't outlive the node.
+pub struct ExpiredEntriesSweeper {
+    attributes_writer: Arc<dyn IdentityAttributesWriter>,
+    enrollment_tokens: EnrollmentTokens,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl ExpiredEntriesSweeper {
+    pub fn new(
+        attributes_writer: Arc<dyn IdentityAttributesWriter>,
+        enrollment_tokens: EnrollmentTokens,
+    ) -> Self {
+        Self {
+            attributes_writer,
+            enrollment_tokens,
+            handle: None,
+        }
+    }
+}
+
+#[ockam::worker]
+impl Worker for ExpiredEntriesSweeper {
+    type Context = Context;
+    type Message = ();
+
+    async fn initialize(&mut self, _ctx: &mut Context) -> Result<()> {
+        let attributes_writer = self.attributes_writer.clone();
+        let enrollment_tokens = self.enrollment_tokens.clone();
+        self.handle = Some(tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(SWEEP_INTERVAL).await;
+                match attributes_writer.delete_expired().await {
+                    Ok(deleted) if !deleted.is_empty() => {
+                        info!(
+                            "deleted the expired attributes of {} identities",
+                            deleted.len()
+                        )
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("failed to delete the expired attributes: {e}"),
+                }
+                match enrollment_tokens.delete_expired().await {
+                    Ok(deleted) if deleted > 0 => {
+                        info!("deleted {deleted} expired enrollment tokens")
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("failed to delete the expired enrollment tokens: {e}"),
+                }
+            }
+        }));
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, _ctx: &mut Context) -> Result<()> {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, _ctx: &mut Context, _msg: Routed<()>) -> Result<()> {
+        Ok(())
+    }
+}
@@ -24,6 +24,7 @@ pub struct NodeStatus<'a> {
     #[n(3)] pub workers: u32,
     #[n(4)] pub pid: i32,
     #[n(5)] pub transports: u32,
+    #[b(6)] pub credential_refresh_error: Option<CowStr<'a>>,
 }
 
 impl<'a> NodeStatus<'a> {
@@ -42,6 +43,16 @@ impl<'a> NodeStatus<'a> {
             workers,
             pid,
             transports,
+            credential_refresh_error: None,
         }
     }
+
+    /// Report the error returned by the last failed renewal of the node credential
+    pub fn with_credential_refresh_error(
+        mut self,
+        credential_refresh_error: Option<impl Into<CowStr<'a>>>,
+    ) -> Self {
+        self.credential_refresh_error = credential_refresh_error.map(|e| e.into());
+        self
+    }
 }
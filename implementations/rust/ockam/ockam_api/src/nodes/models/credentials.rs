@@ -40,6 +40,8 @@ pub struct PresentCredentialRequest<'a> {
     #[n(0)] tag: TypeTag<3698687>,
     #[b(1)] pub route: Cow<'a, str>,
     #[n(2)] pub oneway: bool,
+    /// Attributes to disclose when presenting a selective credential instead of a credential
+    #[n(3)] pub disclose: Option<Vec<String>>,
 }
 
 impl<'a> PresentCredentialRequest<'a> {
@@ -49,6 +51,13 @@ impl<'a> PresentCredentialRequest<'a> {
             tag: TypeTag,
             route: route.to_string().into(),
             oneway,
+            disclose: None,
         }
     }
+
+    /// Present a selective credential disclosing only the given attributes
+    pub fn with_disclosed_attributes(mut self, names: Vec<String>) -> Self {
+        self.disclose = Some(names);
+        self
+    }
 }
@@ -0,0 +1,85 @@
+use minicbor::{Decode, Encode};
+use ockam_core::flow_control::FlowControlInfo;
+use ockam_core::CowStr;
+
+#[cfg(feature = "tag")]
+use ockam_core::TypeTag;
+
+/// A Consumer of a Flow Control, with its policy
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct FlowControlConsumer<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5061729>,
+    #[b(1)] pub address: CowStr<'a>,
+    #[b(2)] pub policy: CowStr<'a>,
+}
+
+/// A Flow Control with its Spawners, Producers and Consumers
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct FlowControlStatus<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<1592648>,
+    #[b(1)] pub flow_control_id: CowStr<'a>,
+    #[b(2)] pub spawner_flow_control_id: Option<CowStr<'a>>,
+    #[b(3)] pub spawners: Vec<CowStr<'a>>,
+    #[b(4)] pub producers: Vec<CowStr<'a>>,
+    #[b(5)] pub consumers: Vec<FlowControlConsumer<'a>>,
+}
+
+impl<'a> From<&FlowControlInfo> for FlowControlStatus<'a> {
+    fn from(info: &FlowControlInfo) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            flow_control_id: info.flow_control_id().to_string().into(),
+            spawner_flow_control_id: info
+                .spawner_flow_control_id()
+                .as_ref()
+                .map(|id| id.to_string().into()),
+            spawners: info
+                .spawners()
+                .iter()
+                .map(|a| a.to_string().into())
+                .collect(),
+            producers: info
+                .producers()
+                .iter()
+                .map(|a| a.to_string().into())
+                .collect(),
+            consumers: info
+                .consumers()
+                .iter()
+                .map(|(address, policy)| FlowControlConsumer {
+                    #[cfg(feature = "tag")]
+                    tag: TypeTag,
+                    address: address.to_string().into(),
+                    policy: policy.to_string().into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Response body for listing flow controls
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct FlowControlList<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8306541>,
+    #[b(1)] pub list: Vec<FlowControlStatus<'a>>
+}
+
+impl<'a> FlowControlList<'a> {
+    pub fn new(list: Vec<FlowControlStatus<'a>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            list,
+        }
+    }
+}
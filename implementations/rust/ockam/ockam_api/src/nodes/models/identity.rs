@@ -46,3 +46,34 @@ impl<'a> ShortIdentityResponse<'a> {
         }
     }
 }
+
+/// Result of the rotation of the root key of the node identity
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RotateKeyResponse<'a> {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<3027484>,
+    #[b(1)] pub identity_id: Cow<'a, str>,
+    /// Number of secure channel peers which accepted the new change history
+    #[n(2)] pub updated_peers: u64,
+    /// True if the new change history was accepted by the trust context authority
+    #[n(3)] pub authority_updated: bool,
+}
+
+impl<'a> RotateKeyResponse<'a> {
+    pub fn new(
+        identity_id: impl Into<Cow<'a, str>>,
+        updated_peers: u64,
+        authority_updated: bool,
+    ) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity_id: identity_id.into(),
+            updated_peers,
+            authority_updated,
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use minicbor::{Decode, Encode};
-use ockam_abac::{Action, Expr};
+use ockam::identity::IdentityIdentifier;
+use ockam_abac::{Action, Decision, Explanation, Expr, PolicyBundleEntry, PolicyVersion};
 
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
@@ -11,6 +12,7 @@ pub struct Policy {
     #[cfg(feature = "tag")]
     #[n(0)] tag: TypeTag<2000111>,
     #[n(1)] expression: Expr,
+    #[n(2)] comment: Option<String>,
 }
 
 impl Policy {
@@ -19,12 +21,22 @@ impl Policy {
             #[cfg(feature = "tag")]
             tag: TypeTag,
             expression: e,
+            comment: None,
         }
     }
 
+    pub fn with_comment(mut self, comment: Option<String>) -> Self {
+        self.comment = comment;
+        self
+    }
+
     pub fn expression(&self) -> &Expr {
         &self.expression
     }
+
+    pub fn comment(&self) -> Option<&str> {
+        self.comment.as_deref()
+    }
 }
 
 #[derive(Debug, Decode, Encode)]
@@ -49,3 +61,215 @@ impl PolicyList {
         &self.expressions
     }
 }
+
+#[derive(Debug, Default, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ListDecisions {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<4398310>,
+    #[n(1)] resource: Option<String>,
+    #[n(2)] action: Option<String>,
+    #[n(3)] subject: Option<String>,
+    #[n(4)] denied_only: bool,
+    #[n(5)] limit: Option<u32>,
+}
+
+impl ListDecisions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_resource(mut self, resource: Option<String>) -> Self {
+        self.resource = resource;
+        self
+    }
+
+    pub fn with_action(mut self, action: Option<String>) -> Self {
+        self.action = action;
+        self
+    }
+
+    pub fn with_subject(mut self, subject: Option<String>) -> Self {
+        self.subject = subject;
+        self
+    }
+
+    pub fn with_denied_only(mut self, denied_only: bool) -> Self {
+        self.denied_only = denied_only;
+        self
+    }
+
+    pub fn with_limit(mut self, limit: Option<u32>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn limit(&self) -> Option<u32> {
+        self.limit
+    }
+
+    /// Return true if a decision is selected by this request filters
+    pub fn matches(&self, d: &Decision) -> bool {
+        fn is(filter: &Option<String>, value: Option<&str>) -> bool {
+            filter.as_deref().map_or(true, |f| Some(f) == value)
+        }
+        is(&self.resource, d.resource())
+            && is(&self.action, d.action())
+            && is(&self.subject, d.subject())
+            && !(self.denied_only && d.is_allowed())
+    }
+}
+
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DecisionList {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<7730521>,
+    #[n(1)] decisions: Vec<Decision>,
+}
+
+impl DecisionList {
+    pub fn new(decisions: Vec<Decision>) -> Self {
+        DecisionList {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            decisions,
+        }
+    }
+
+    pub fn decisions(&self) -> &[Decision] {
+        &self.decisions
+    }
+}
+
+/// Request to evaluate a stored policy against the attributes of an identity
+///
+/// The attributes of the resource are the ones of the worker at the given address,
+/// which is required when several workers share the same resource.
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TestPolicy {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<2688951>,
+    #[n(1)] identity: IdentityIdentifier,
+    #[n(2)] worker: Option<String>,
+}
+
+impl TestPolicy {
+    pub fn new(identity: IdentityIdentifier) -> Self {
+        TestPolicy {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            identity,
+            worker: None,
+        }
+    }
+
+    pub fn with_worker(mut self, worker: Option<String>) -> Self {
+        self.worker = worker;
+        self
+    }
+
+    pub fn identity(&self) -> &IdentityIdentifier {
+        &self.identity
+    }
+
+    pub fn worker(&self) -> Option<&str> {
+        self.worker.as_deref()
+    }
+}
+
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyExplanation {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<5104673>,
+    #[n(1)] explanation: Explanation,
+}
+
+impl PolicyExplanation {
+    pub fn new(explanation: Explanation) -> Self {
+        PolicyExplanation {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            explanation,
+        }
+    }
+
+    pub fn explanation(&self) -> &Explanation {
+        &self.explanation
+    }
+}
+
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyHistory {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8260335>,
+    #[n(1)] versions: Vec<PolicyVersion>,
+}
+
+impl PolicyHistory {
+    pub fn new(versions: Vec<PolicyVersion>) -> Self {
+        PolicyHistory {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            versions,
+        }
+    }
+
+    pub fn versions(&self) -> &[PolicyVersion] {
+        &self.versions
+    }
+}
+
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RollbackPolicy {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<1939704>,
+    #[n(1)] version: u64,
+}
+
+impl RollbackPolicy {
+    pub fn new(version: u64) -> Self {
+        RollbackPolicy {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            version,
+        }
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+}
+
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PublishPolicyBundle {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6140835>,
+    #[n(1)] policies: Vec<PolicyBundleEntry>,
+}
+
+impl PublishPolicyBundle {
+    pub fn new(policies: Vec<PolicyBundleEntry>) -> Self {
+        PublishPolicyBundle {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            policies,
+        }
+    }
+
+    pub fn policies(&self) -> &[PolicyBundleEntry] {
+        &self.policies
+    }
+}
@@ -2,13 +2,15 @@
 
 use minicbor::Decoder;
 use ockam::identity::{
-    Credentials, CredentialsServer, CredentialsServerModule, Identities, IdentitiesRepository,
-    IdentitiesVault, IdentityAttributesReader, IdentityAttributesWriter,
+    Credentials, CredentialsRefresher, CredentialsServer, CredentialsServerModule, Identities,
+    IdentitiesRepository, IdentitiesVault, IdentityAttributesReader, IdentityAttributesWriter,
+};
+use ockam::identity::{
+    Identity, IdentityIdentifier, IdentitySecureChannelLocalInfo, SecureChannels,
 };
-use ockam::identity::{Identity, IdentityIdentifier, SecureChannels};
 use ockam::{Address, Context, ForwardingService, Result, Routed, TcpTransport, Worker};
-use ockam_abac::expr::{and, eq, ident, str};
-use ockam_abac::{Action, Env, Expr, PolicyAccessControl, PolicyStorage, Resource};
+use ockam_abac::expr::{and, eq, ident, int, str};
+use ockam_abac::{Action, DecisionLog, Env, Expr, PolicyAccessControl, PolicyStorage, Resource};
 use ockam_core::api::{Error, Method, Request, Response, ResponseBuilder, Status};
 use ockam_core::compat::{
     boxed::Box,
@@ -17,7 +19,10 @@ use ockam_core::compat::{
 };
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::{FlowControlId, FlowControlPolicy, FlowControls};
-use ockam_core::{route, AllowAll, AsyncTryClone, IncomingAccessControl};
+use ockam_core::{
+    route, AllowAll, AsyncTryClone, IncomingAccessControl, OutgoingAccessControl,
+    RateLimitedAccessControl, SourceAddressKey,
+};
 use ockam_multiaddr::proto::{Project, Secure};
 use ockam_multiaddr::{MultiAddr, Protocol};
 use ockam_node::compat::asynchronous::RwLock;
@@ -38,20 +43,22 @@ use crate::config::lookup::ProjectLookup;
 use crate::error::ApiError;
 use crate::nodes::connection::Connection;
 use crate::nodes::models::base::NodeStatus;
+use crate::nodes::models::flow_controls::{FlowControlList, FlowControlStatus};
 use crate::nodes::models::transport::{TransportMode, TransportType};
 use crate::nodes::models::workers::{WorkerList, WorkerStatus};
 use crate::rpc_proxy::RpcProxyService;
 use crate::session::util::{starts_with_host_tcp, starts_with_secure};
 use crate::session::{Medic, Sessions};
 use crate::{
-    local_multiaddr_to_route, multiaddr_to_route, route_to_multiaddr, try_address_to_multiaddr,
-    DefaultAddress,
+    actions, local_multiaddr_to_route, multiaddr_to_route, route_to_multiaddr,
+    try_address_to_multiaddr, DefaultAddress,
 };
 
 pub mod message;
 
 mod credentials;
 mod forwarder;
+mod identity_updates;
 mod node_identities;
 mod node_services;
 mod policy;
@@ -64,6 +71,12 @@ use ockam_identity::TrustContext;
 
 const TARGET: &str = "ockam_api::nodemanager::service";
 
+/// Maximum number of forwarder registrations per second, for each connection
+const FORWARDING_SERVICE_RATE: f64 = 5.0;
+
+/// Maximum number of forwarder registrations in a burst, for each connection
+const FORWARDING_SERVICE_BURST: u32 = 20;
+
 pub(crate) type Alias = String;
 
 /// Generate a new alias for some user created extension
@@ -100,7 +113,16 @@ pub struct NodeManager {
     pub(crate) registry: Registry,
     sessions: Arc<Mutex<Sessions>>,
     medic: JoinHandle<Result<(), ockam_core::Error>>,
+    revocation_list_refresher: Option<JoinHandle<()>>,
+    policy_bundle_version: Option<u64>,
+    policy_bundle_refresher: Option<JoinHandle<()>>,
+    credential_refresh_fraction: Option<f64>,
+    credentials_refresher: Option<CredentialsRefresher>,
     policies: Arc<dyn PolicyStorage>,
+    decision_log: Option<Arc<dyn DecisionLog>>,
+    /// Resource and attributes of the workers of the node, keyed by worker address,
+    /// to test the policies of their resource
+    resource_environments: Mutex<BTreeMap<Address, (Resource, Env)>>,
     pub(crate) flow_controls: FlowControls,
 }
 
@@ -164,17 +186,59 @@ pub struct IdentityOverride {
 }
 
 impl NodeManager {
+    /// Keep the resource of a worker and its attributes, see [`resource_environment`],
+    /// to test the policies of the resource later on
+    pub(super) fn add_resource_environment(&self, worker: &Address, r: &Resource, env: Env) {
+        self.resource_environments
+            .lock()
+            .unwrap()
+            .insert(worker.clone(), (r.clone(), env));
+    }
+
+    /// Forget the resource and attributes of a worker once it is stopped
+    pub(super) fn remove_resource_environment(&self, worker: &Address) {
+        self.resource_environments.lock().unwrap().remove(worker);
+    }
+
+    /// Create an access control evaluating the policy of a resource and an action,
+    /// recording its decisions if the node has a decision log
+    pub(super) fn policy_access_control(
+        &self,
+        r: &Resource,
+        a: &Action,
+        env: Env,
+    ) -> PolicyAccessControl {
+        let ac = PolicyAccessControl::new(
+            self.policies.clone(),
+            self.identities_repository(),
+            r.clone(),
+            a.clone(),
+            env,
+        );
+        match &self.decision_log {
+            Some(decision_log) => ac.with_decision_log(decision_log.clone()),
+            None => ac,
+        }
+    }
+
+    /// Create the access control of a resource and an action, evaluating their policy
+    ///
+    /// The given environment contains the attributes specific to the resource,
+    /// see [`resource_environment`]. The resource identifier, the node name and
+    /// the trust context identifier are added to it, as well as the action identifier.
     async fn access_control(
         &self,
         r: &Resource,
         a: &Action,
         trust_context_id: Option<&str>,
         custom_default: Option<&Expr>,
+        env: Env,
     ) -> Result<Arc<dyn IncomingAccessControl>> {
         if let Some(tcid) = trust_context_id {
             // Populate environment with known attributes:
-            let mut env = Env::new();
+            let mut env = env;
             env.put("resource.id", str(r.as_str()));
+            env.put("resource.node", str(self.node_name.as_str()));
             env.put("action.id", str(a.as_str()));
             env.put("resource.project_id", str(tcid.to_string()));
             env.put("resource.trust_context_id", str(tcid));
@@ -199,20 +263,74 @@ impl NodeManager {
                 };
                 self.policies.set_policy(r, a, &fallback).await?
             }
-            let policies = self.policies.clone();
-            Ok(Arc::new(PolicyAccessControl::new(
-                policies,
-                self.identities_repository(),
-                r.clone(),
-                a.clone(),
-                env,
-            )))
+            Ok(Arc::new(self.policy_access_control(r, a, env)))
         } else {
             // TODO: @ac allow passing this as a cli argument
             Ok(Arc::new(AllowAll))
         }
     }
 
+    /// Return the access control checking the requests for new connections to
+    /// a resource, with the policy of the 'connect' action
+    ///
+    /// Without a policy for the 'connect' action the current policy of the
+    /// 'handle_message' action is evaluated instead.
+    pub(super) async fn connect_access_control(
+        &self,
+        r: &Resource,
+        trust_context_id: Option<&str>,
+        env: Env,
+    ) -> Result<Arc<dyn IncomingAccessControl>> {
+        let tcid = match trust_context_id {
+            Some(tcid) => tcid,
+            None => return Ok(Arc::new(AllowAll)),
+        };
+        let a = &actions::CONNECT;
+        let mut env = env;
+        env.put("resource.id", str(r.as_str()));
+        env.put("resource.node", str(self.node_name.as_str()));
+        env.put("action.id", str(a.as_str()));
+        env.put("resource.project_id", str(tcid.to_string()));
+        env.put("resource.trust_context_id", str(tcid));
+
+        Ok(Arc::new(
+            self.policy_access_control(r, a, env)
+                .with_fallback_action(actions::HANDLE_MESSAGE),
+        ))
+    }
+
+    /// Return the access control checking the recipients of the messages sent
+    /// by a resource, with the policy of the 'send_message' action
+    ///
+    /// Without a policy for the resource all recipients are allowed. Without a
+    /// trust context there is no outgoing access control.
+    pub(super) async fn outgoing_access_control(
+        &self,
+        r: &Resource,
+        trust_context_id: Option<&str>,
+        env: Env,
+    ) -> Result<Option<Arc<dyn OutgoingAccessControl>>> {
+        let tcid = match trust_context_id {
+            Some(tcid) => tcid,
+            None => return Ok(None),
+        };
+        let a = &actions::SEND_MESSAGE;
+        let mut env = env;
+        env.put("resource.id", str(r.as_str()));
+        env.put("resource.node", str(self.node_name.as_str()));
+        env.put("action.id", str(a.as_str()));
+        env.put("resource.project_id", str(tcid.to_string()));
+        env.put("resource.trust_context_id", str(tcid));
+
+        if self.policies.get_policy(r, a).await?.is_none() {
+            self.policies.set_policy(r, a, &Expr::Bool(true)).await?
+        }
+        Ok(Some(Arc::new(
+            self.policy_access_control(r, a, env)
+                .with_secure_channel_registry(self.secure_channels.secure_channel_registry()),
+        )))
+    }
+
     pub(crate) fn trust_context(&self) -> Result<&TrustContext> {
         self.trust_context
             .as_ref()
@@ -224,11 +342,35 @@ impl NodeManager {
     }
 }
 
+/// Create an environment with the attributes of a resource of a node: its type,
+/// e.g. `echoer` or `tcp-outlet`, and the address of its worker, if known
+fn resource_environment(resource_type: &str, address: Option<&Address>) -> Env {
+    let mut env = Env::new();
+    env.put("resource.type", str(resource_type));
+    if let Some(address) = address {
+        env.put("resource.address", str(address.address()));
+    }
+    env
+}
+
+/// Add a `host:port` socket address, e.g. the target of an outlet, to the
+/// attributes of a resource as `resource.host` and `resource.port`
+fn put_resource_socket_address(env: &mut Env, socket_address: &str) {
+    if let Some((host, port)) = socket_address.rsplit_once(':') {
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        env.put("resource.host", str(host));
+        if let Ok(port) = port.parse::<u16>() {
+            env.put("resource.port", int(port));
+        }
+    }
+}
+
 pub struct NodeManagerGeneralOptions {
     cli_state: CliState,
     node_name: String,
     skip_defaults: bool,
     pre_trusted_identities: Option<PreTrustedIdentities>,
+    policy_audit: bool,
 }
 
 impl NodeManagerGeneralOptions {
@@ -243,8 +385,15 @@ impl NodeManagerGeneralOptions {
             node_name,
             skip_defaults,
             pre_trusted_identities,
+            policy_audit: false,
         }
     }
+
+    /// Record the decisions of the policies of the node in a log file
+    pub fn with_policy_audit(mut self, policy_audit: bool) -> Self {
+        self.policy_audit = policy_audit;
+        self
+    }
 }
 
 pub struct NodeManagerProjectsOptions {
@@ -288,20 +437,41 @@ impl NodeManagerTransportOptions {
 
 pub struct NodeManagerTrustOptions {
     trust_context_config: Option<TrustContextConfig>,
+    credential_refresh_fraction: Option<f64>,
+    policy_bundle_version: Option<u64>,
 }
 
 impl NodeManagerTrustOptions {
     pub fn new(trust_context_config: Option<TrustContextConfig>) -> Self {
         Self {
             trust_context_config,
+            credential_refresh_fraction: None,
+            policy_bundle_version: None,
         }
     }
+
+    /// Renew the node credential once this fraction of its lifetime has elapsed
+    pub fn with_credential_refresh_fraction(mut self, credential_refresh_fraction: f64) -> Self {
+        self.credential_refresh_fraction = Some(credential_refresh_fraction);
+        self
+    }
+
+    /// Only apply this version of the policy bundle published by the authority,
+    /// instead of the latest one
+    pub fn with_policy_bundle_version(mut self, policy_bundle_version: Option<u64>) -> Self {
+        self.policy_bundle_version = policy_bundle_version;
+        self
+    }
 }
 
 pub(crate) struct ConnectResult {
     pub(crate) secure_channel: MultiAddr,
     pub(crate) suffix: MultiAddr,
     pub(crate) flow_control_id: Option<FlowControlId>,
+    /// TCP connection created by [`NodeManager::connect`], if any
+    pub(crate) tcp_connection: Option<Address>,
+    /// Secure channel created by [`NodeManager::connect`], if any
+    pub(crate) secure_channel_address: Option<Address>,
 }
 
 impl NodeManager {
@@ -341,6 +511,11 @@ impl NodeManager {
             .build();
 
         let policies: Arc<dyn PolicyStorage> = Arc::new(node_state.policies_storage().await?);
+        let decision_log: Option<Arc<dyn DecisionLog>> = if general_options.policy_audit {
+            Some(Arc::new(node_state.decision_log()))
+        } else {
+            None
+        };
 
         let identity = node_state.config.default_identity().await?;
 
@@ -371,8 +546,15 @@ impl NodeManager {
                 let ctx = ctx.async_try_clone().await?;
                 tokio::spawn(medic.start(ctx))
             },
+            revocation_list_refresher: None,
+            policy_bundle_version: trust_options.policy_bundle_version,
+            policy_bundle_refresher: None,
+            credential_refresh_fraction: trust_options.credential_refresh_fraction,
+            credentials_refresher: None,
             sessions,
             policies,
+            decision_log,
+            resource_environments: Mutex::new(BTreeMap::new()),
             flow_controls,
         };
 
@@ -389,13 +571,17 @@ impl NodeManager {
     }
 
     async fn configure_trust_context(&mut self, tc: &TrustContextConfig) -> Result<()> {
-        self.trust_context = Some(
-            tc.to_trust_context(
+        let trust_context = tc
+            .to_trust_context(
                 self.secure_channels.clone(),
                 Some(self.tcp_transport.async_try_clone().await?),
             )
-            .await?,
-        );
+            .await?;
+        let credential_schemas = self.secure_channels.identities().credential_schemas();
+        for schema in trust_context.credential_schemas() {
+            credential_schemas.register(schema.clone());
+        }
+        self.trust_context = Some(trust_context);
 
         info!("NodeManager::configure_trust_context: trust context configured");
 
@@ -414,11 +600,18 @@ impl NodeManager {
             .await?;
         self.start_hop_service_impl(ctx, DefaultAddress::HOP_SERVICE.into())
             .await?;
+        self.start_tracer_service_impl(ctx, DefaultAddress::TRACER_SERVICE.into())
+            .await?;
+        self.start_identity_updates_service_impl(ctx).await?;
 
         ForwardingService::create(
             ctx,
             DefaultAddress::FORWARDING_SERVICE,
-            AllowAll, // FIXME: @ac
+            RateLimitedAccessControl::new(
+                SourceAddressKey,
+                FORWARDING_SERVICE_RATE,
+                FORWARDING_SERVICE_BURST,
+            )?,
             AllowAll, // FIXME: @ac
         )
         .await?;
@@ -441,6 +634,10 @@ impl NodeManager {
                 false,
             )
             .await?;
+
+            self.revocation_list_refresher = self.start_revocation_list_refresher(ctx).await?;
+            self.policy_bundle_refresher = self.start_policy_bundle_refresher(ctx).await?;
+            self.credentials_refresher = self.start_credentials_refresher(ctx).await?;
         }
 
         Ok(())
@@ -472,11 +669,37 @@ impl NodeManager {
                     flow_control_id,
                     FlowControlPolicy::ProducerAllowMultiple,
                 );
+                self.flow_controls.add_consumer(
+                    &DefaultAddress::TRACER_SERVICE.into(),
+                    flow_control_id,
+                    FlowControlPolicy::ProducerAllowMultiple,
+                );
+                self.flow_controls.add_consumer(
+                    &DefaultAddress::IDENTITY_UPDATES.into(),
+                    flow_control_id,
+                    FlowControlPolicy::ProducerAllowMultiple,
+                );
             }
         }
 
         Ok(res)
     }
+
+    /// Stop the secure channel and the TCP connection created by [`NodeManager::connect`]
+    pub(crate) async fn disconnect(
+        &mut self,
+        ctx: &Context,
+        connection: &ConnectResult,
+    ) -> Result<()> {
+        if let Some(sc_address) = &connection.secure_channel_address {
+            self.delete_secure_channel(ctx, sc_address).await?;
+        }
+        if let Some(tcp_connection) = &connection.tcp_connection {
+            self.tcp_transport.disconnect(tcp_connection).await?;
+        }
+        Ok(())
+    }
+
     pub(crate) async fn connect_impl(
         &mut self,
         connection: Connection<'_>,
@@ -522,6 +745,8 @@ impl NodeManager {
                     secure_channel: try_address_to_multiaddr(&sc_address)?,
                     suffix: a,
                     flow_control_id: Some(sc_flow_control_id),
+                    tcp_connection: route.tcp_connection,
+                    secure_channel_address: Some(sc_address),
                 };
 
                 return Ok(res);
@@ -557,6 +782,8 @@ impl NodeManager {
                         secure_channel: try_address_to_multiaddr(&sc_address)?,
                         suffix: b2,
                         flow_control_id: Some(sc_flow_control_id),
+                        tcp_connection: route.tcp_connection,
+                        secure_channel_address: Some(sc_address),
                     };
 
                     Ok(res)
@@ -571,6 +798,8 @@ impl NodeManager {
                             .ok_or_else(|| ApiError::generic("invalid multiaddr"))?,
                         suffix: Default::default(),
                         flow_control_id: route.flow_control_id,
+                        tcp_connection: route.tcp_connection,
+                        secure_channel_address: None,
                     };
 
                     Ok(res)
@@ -599,6 +828,8 @@ impl NodeManager {
                 secure_channel: try_address_to_multiaddr(&sc_address)?,
                 suffix: Default::default(),
                 flow_control_id: Some(sc_flow_control_id),
+                tcp_connection: None,
+                secure_channel_address: Some(sc_address),
             };
 
             return Ok(res);
@@ -608,6 +839,8 @@ impl NodeManager {
             secure_channel: Default::default(),
             suffix: addr.clone(),
             flow_control_id: None,
+            tcp_connection: None,
+            secure_channel_address: None,
         };
 
         Ok(res)
@@ -635,11 +868,14 @@ impl NodeManager {
 impl NodeManagerWorker {
     //////// Request matching and response handling ////////
 
+    /// Handle a request, sent by the given identity if it was received through
+    /// a secure channel
     async fn handle_request(
         &mut self,
         ctx: &mut Context,
         req: &Request<'_>,
         dec: &mut Decoder<'_>,
+        sender: Option<&IdentityIdentifier>,
     ) -> Result<Vec<u8>> {
         debug! {
             target: TARGET,
@@ -663,14 +899,21 @@ impl NodeManagerWorker {
             // TODO: create, delete, destroy remote nodes
             (Get, ["node"]) => {
                 let node_manager = self.node_manager.read().await;
+                let credential_refresh_error = node_manager
+                    .credentials_refresher
+                    .as_ref()
+                    .and_then(|refresher| refresher.status().last_error);
                 Response::ok(req.id())
-                    .body(NodeStatus::new(
-                        &node_manager.node_name,
-                        "Running",
-                        ctx.list_workers().await?.len() as u32,
-                        std::process::id() as i32,
-                        node_manager.transports.len() as u32,
-                    ))
+                    .body(
+                        NodeStatus::new(
+                            &node_manager.node_name,
+                            "Running",
+                            ctx.list_workers().await?.len() as u32,
+                            std::process::id() as i32,
+                            node_manager.transports.len() as u32,
+                        )
+                        .with_credential_refresh_error(credential_refresh_error),
+                    )
                     .to_vec()?
             }
 
@@ -715,10 +958,19 @@ impl NodeManagerWorker {
                 .get_credential(req, dec, ctx)
                 .await?
                 .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
+            (Post, ["node", "credentials", "actions", "get_selective"]) => self
+                .get_selective_credential(req, dec, ctx)
+                .await?
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
             (Post, ["node", "credentials", "actions", "present"]) => {
                 self.present_credential(req, dec, ctx).await?.to_vec()?
             }
 
+            // ==*== Identity ==*==
+            (Post, ["node", "identity", "actions", "rotate_key"]) => {
+                self.rotate_identity_key(req, ctx).await?.to_vec()?
+            }
+
             // ==*== Secure channels ==*==
             // TODO: Change to RequestBuilder format
             (Get, ["node", "secure_channel"]) => {
@@ -854,11 +1106,54 @@ impl NodeManagerWorker {
                     .to_vec()?
             }
 
+            // ==*== Flow Controls ==*==
+            (Get, ["node", "flow_controls"]) => {
+                let node_manager = self.node_manager.read().await;
+                let list = node_manager
+                    .flow_controls
+                    .get_flow_controls_info()
+                    .iter()
+                    .map(FlowControlStatus::from)
+                    .collect();
+
+                Response::ok(req.id())
+                    .body(FlowControlList::new(list))
+                    .to_vec()?
+            }
+
+            (Get, ["policy_audit"]) => self
+                .node_manager
+                .read()
+                .await
+                .list_decisions(req, dec)
+                .await?
+                .to_vec()?,
+            (Get, ["policy", resource, action, "history"]) => self
+                .node_manager
+                .read()
+                .await
+                .policy_history(req, resource, action)
+                .await?
+                .to_vec()?,
+            (Post, ["policy", resource, action, "rollback"]) => self
+                .node_manager
+                .read()
+                .await
+                .rollback_policy(req, dec, resource, action, sender)
+                .await?
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
+            (Post, ["policy", resource, action, "test"]) => self
+                .node_manager
+                .read()
+                .await
+                .test_policy(req, dec, resource, action)
+                .await?
+                .either(ResponseBuilder::to_vec, ResponseBuilder::to_vec)?,
             (Post, ["policy", resource, action]) => self
                 .node_manager
                 .read()
                 .await
-                .add_policy(resource, action, req, dec)
+                .add_policy(resource, action, req, dec, sender)
                 .await?
                 .to_vec()?,
             (Get, ["policy", resource]) => self
@@ -927,6 +1222,8 @@ impl NodeManagerWorker {
 
             // ==*== Messages ==*==
             (Post, ["v0", "message"]) => self.send_message(ctx, req, dec).await?,
+            (Post, ["v0", "message", "ping"]) => self.ping_route(ctx, req, dec).await?,
+            (Post, ["v0", "message", "trace"]) => self.trace_route(ctx, req, dec).await?,
 
             // ==*== Catch-all for Unimplemented APIs ==*==
             _ => {
@@ -971,6 +1268,15 @@ impl Worker for NodeManagerWorker {
     async fn shutdown(&mut self, _: &mut Self::Context) -> Result<()> {
         let node_manager = self.node_manager.read().await;
         node_manager.medic.abort();
+        if let Some(refresher) = &node_manager.revocation_list_refresher {
+            refresher.abort();
+        }
+        if let Some(refresher) = &node_manager.policy_bundle_refresher {
+            refresher.abort();
+        }
+        if let Some(refresher) = &node_manager.credentials_refresher {
+            refresher.stop();
+        }
         Ok(())
     }
 
@@ -984,7 +1290,13 @@ impl Worker for NodeManagerWorker {
             }
         };
 
-        let r = match self.handle_request(ctx, &req, &mut dec).await {
+        let sender = IdentitySecureChannelLocalInfo::find_info(msg.local_message())
+            .ok()
+            .map(|info| info.their_identity_id());
+        let r = match self
+            .handle_request(ctx, &req, &mut dec, sender.as_ref())
+            .await
+        {
             Ok(r) => r,
             Err(err) => {
                 error! {
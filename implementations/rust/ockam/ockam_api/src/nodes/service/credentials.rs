@@ -4,16 +4,111 @@ use crate::nodes::models::credentials::{GetCredentialRequest, PresentCredentialR
 use crate::nodes::service::map_multiaddr_err;
 use either::Either;
 use minicbor::Decoder;
-use ockam::identity::Credential;
+use ockam::identity::{Credential, CredentialsRefresher, Identity, SelectiveCredential};
 use ockam::Result;
 use ockam_core::api::{Error, Request, Response, ResponseBuilder};
+use ockam_core::AsyncTryClone;
 use ockam_identity::IdentitiesVault;
 use ockam_multiaddr::MultiAddr;
+use ockam_node::tokio;
+use ockam_node::tokio::task::JoinHandle;
 use ockam_node::{Context, MessageSendReceiveOptions};
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
-use super::NodeManagerWorker;
+use super::{NodeManager, NodeManagerWorker};
+
+/// Interval between two retrievals of the trust context authority revocation list
+const REVOCATION_LIST_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+impl NodeManager {
+    /// Periodically retrieve the revocation list of the trust context authority,
+    /// so that revoked credentials are rejected by this node
+    pub(super) async fn start_revocation_list_refresher(
+        &self,
+        ctx: &Context,
+    ) -> Result<Option<JoinHandle<()>>> {
+        let authority = match self.trust_context().and_then(|tc| tc.authority()) {
+            Ok(authority) => authority.clone(),
+            Err(_) => return Ok(None),
+        };
+        let identity = self.identity();
+        let ctx = ctx.async_try_clone().await?;
+
+        Ok(Some(tokio::spawn(async move {
+            loop {
+                match authority.refresh_revocation_list(&ctx, &identity).await {
+                    Ok(true) => debug!("Refreshed the authority revocation list"),
+                    // The authority doesn't publish a revocation list
+                    Ok(false) => return,
+                    Err(e) => warn!("Failed to refresh the authority revocation list: {e}"),
+                }
+                tokio::time::sleep(REVOCATION_LIST_REFRESH_INTERVAL).await;
+            }
+        })))
+    }
+
+    /// Renew the credential of the node identity in the background, before it expires
+    pub(super) async fn start_credentials_refresher(
+        &self,
+        ctx: &Context,
+    ) -> Result<Option<CredentialsRefresher>> {
+        let authority = match self.trust_context().and_then(|tc| tc.authority()) {
+            Ok(authority) => authority.clone(),
+            Err(_) => return Ok(None),
+        };
+
+        let mut refresher =
+            CredentialsRefresher::new(authority, self.credentials_service(), self.identity())
+                .with_flow_controls(&self.flow_controls);
+        if let Some(fraction) = self.credential_refresh_fraction {
+            refresher = refresher.with_refresh_fraction(fraction)?;
+        }
+        refresher.start(ctx).await?;
+
+        Ok(Some(refresher))
+    }
+
+    /// Return the identity a credential is requested for, the node identity by default
+    async fn credential_identity(&self, request: &GetCredentialRequest) -> Result<Identity> {
+        match &request.identity_name {
+            Some(identity) => {
+                let idt_state = self.cli_state.identities.get(identity)?;
+                match idt_state.get(self.identities_vault()).await {
+                    Ok(idt) => Ok(idt),
+                    Err(_) => {
+                        let default_vault = &self.cli_state.vaults.default()?.get().await?;
+                        let vault: Arc<dyn IdentitiesVault> = Arc::new(default_vault.clone());
+                        Ok(idt_state.get(vault).await?)
+                    }
+                }
+            }
+            None => Ok(self.identity()),
+        }
+    }
+
+    /// Return a credential issued by the trust context authority for an identity
+    ///
+    /// The credential of the node identity is cached and renewed by the credentials refresher.
+    pub(super) async fn trust_context_credential(
+        &self,
+        ctx: &Context,
+        identity: &Identity,
+    ) -> Result<Credential> {
+        match &self.credentials_refresher {
+            Some(refresher) if identity.identifier() == self.identity.identifier() => {
+                refresher.credential(ctx).await
+            }
+            _ => {
+                self.trust_context()?
+                    .authority()?
+                    .credential(ctx, identity)
+                    .await
+            }
+        }
+    }
+}
 
 impl NodeManagerWorker {
     pub(super) async fn get_credential(
@@ -24,20 +119,7 @@ impl NodeManagerWorker {
     ) -> Result<Either<ResponseBuilder<Error<'_>>, ResponseBuilder<Credential>>> {
         let node_manager = self.node_manager.write().await;
         let request: GetCredentialRequest = dec.decode()?;
-
-        let identity = if let Some(identity) = &request.identity_name {
-            let idt_state = node_manager.cli_state.identities.get(identity)?;
-            match idt_state.get(node_manager.identities_vault()).await {
-                Ok(idt) => Arc::new(idt),
-                Err(_) => {
-                    let default_vault = &node_manager.cli_state.vaults.default()?.get().await?;
-                    let vault: Arc<dyn IdentitiesVault> = Arc::new(default_vault.clone());
-                    Arc::new(idt_state.get(vault).await?)
-                }
-            }
-        } else {
-            Arc::new(node_manager.identity())
-        };
+        let identity = node_manager.credential_identity(&request).await?;
 
         if let Ok(c) = node_manager
             .trust_context()?
@@ -52,6 +134,31 @@ impl NodeManagerWorker {
         }
     }
 
+    pub(super) async fn get_selective_credential(
+        &mut self,
+        req: &Request<'_>,
+        dec: &mut Decoder<'_>,
+        ctx: &Context,
+    ) -> Result<Either<ResponseBuilder<Error<'_>>, ResponseBuilder<SelectiveCredential>>> {
+        let node_manager = self.node_manager.write().await;
+        let request: GetCredentialRequest = dec.decode()?;
+        let identity = node_manager.credential_identity(&request).await?;
+
+        match node_manager
+            .trust_context()?
+            .authority()?
+            .selective_credential(ctx, &identity)
+            .await
+        {
+            Ok(c) => Ok(Either::Right(Response::ok(req.id()).body(c))),
+            Err(e) => {
+                let err = Error::default()
+                    .with_message(format!("error getting selective credential: {e}"));
+                Ok(Either::Left(Response::internal_error(req.id()).body(err)))
+            }
+        }
+    }
+
     pub(super) async fn present_credential(
         &self,
         req: &Request<'_>,
@@ -68,10 +175,34 @@ impl NodeManagerWorker {
             None => return Err(ApiError::generic("invalid credentials service route")),
         };
 
+        // Only the requested attributes of a selective credential are disclosed
+        if let Some(names) = &request.disclose {
+            if !request.oneway {
+                return Err(ApiError::generic(
+                    "selective credentials can only be presented one way",
+                ));
+            }
+            let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+            let credential = node_manager
+                .trust_context()?
+                .authority()?
+                .selective_credential(ctx, &node_manager.identity)
+                .await?
+                .disclose(&names);
+            node_manager
+                .credentials_service()
+                .present_selective_credential(
+                    ctx,
+                    route,
+                    credential,
+                    MessageSendReceiveOptions::new().with_flow_control(&node_manager.flow_controls),
+                )
+                .await?;
+            return Ok(Response::ok(req.id()));
+        }
+
         let credential = node_manager
-            .trust_context()?
-            .authority()?
-            .credential(ctx, &node_manager.identity)
+            .trust_context_credential(ctx, &node_manager.identity)
             .await?;
 
         if request.oneway {
@@ -90,7 +221,7 @@ impl NodeManagerWorker {
                 .present_credential_mutual(
                     ctx,
                     route,
-                    &[node_manager.trust_context()?.authority()?.identity()],
+                    node_manager.trust_context()?,
                     credential,
                     MessageSendReceiveOptions::new().with_flow_control(&node_manager.flow_controls),
                 )
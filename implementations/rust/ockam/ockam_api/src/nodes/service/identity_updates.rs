@@ -0,0 +1,95 @@
+use crate::nodes::models::identity::RotateKeyResponse;
+use crate::DefaultAddress;
+use ockam::identity::Identity;
+use ockam::Result;
+use ockam_core::api::{Request, Response, ResponseBuilder};
+use ockam_core::compat::join;
+use ockam_node::Context;
+
+use super::{NodeManager, NodeManagerWorker};
+
+impl NodeManager {
+    /// Start the service receiving the change histories of the identities
+    /// at the other end of the secure channels of this node
+    pub(super) async fn start_identity_updates_service_impl(&self, ctx: &Context) -> Result<()> {
+        self.secure_channels
+            .start_identity_updates_service(ctx, DefaultAddress::IDENTITY_UPDATES)
+            .await
+    }
+
+    /// Rotate the root key of the node identity and store its new change history
+    ///
+    /// Return the rotated identity, which must then be pushed to the peers of the
+    /// secure channels of the node and to the trust context authority.
+    async fn rotate_identity_key(&mut self) -> Result<Identity> {
+        let mut identity = self.identity();
+        self.secure_channels.rotate_root_key(&mut identity).await?;
+        self.cli_state
+            .identities
+            .get_by_identifier(&identity.identifier())?
+            .update_change_history(&identity)?;
+        self.identity = identity.clone();
+        Ok(identity)
+    }
+}
+
+impl NodeManagerWorker {
+    /// Rotate the root key of the node identity and push its new change history
+    /// to the peers of its secure channels and to the trust context authority
+    ///
+    /// The node manager is only locked while the key is rotated, the peers and the
+    /// authority are then updated concurrently.
+    pub(super) async fn rotate_identity_key(
+        &self,
+        req: &Request<'_>,
+        ctx: &Context,
+    ) -> Result<ResponseBuilder<RotateKeyResponse<'_>>> {
+        let (identity, secure_channels, flow_controls, authority) = {
+            let mut node_manager = self.node_manager.write().await;
+            let identity = node_manager.rotate_identity_key().await?;
+            let authority = node_manager
+                .trust_context()
+                .and_then(|tc| tc.authority())
+                .ok()
+                .cloned();
+            (
+                identity,
+                node_manager.secure_channels.clone(),
+                node_manager.flow_controls.clone(),
+                authority,
+            )
+        };
+
+        let service = DefaultAddress::IDENTITY_UPDATES.into();
+        let update_peers = secure_channels.propagate_identity_update(
+            ctx,
+            &identity,
+            &service,
+            Some(&flow_controls),
+        );
+        let update_authority = async {
+            match &authority {
+                Some(authority) => match authority.push_identity_update(ctx, &identity).await {
+                    Ok(updated) => updated,
+                    Err(e) => {
+                        warn!("Failed to push the rotated identity to the authority: {e}");
+                        false
+                    }
+                },
+                None => false,
+            }
+        };
+        let (updated_peers, authority_updated) = join!(update_peers, update_authority);
+
+        info!(
+            "Rotated the root key of {}, updated {} peer(s)",
+            identity.identifier(),
+            updated_peers
+        );
+        Ok(Response::ok(req.id()).body(RotateKeyResponse::new(
+            identity.identifier().to_string(),
+            updated_peers as u64,
+            authority_updated,
+        )))
+    }
+}
@@ -1,4 +1,5 @@
 use std::str::FromStr;
+use std::time::Duration;
 
 use minicbor::{Decode, Encode};
 
@@ -6,9 +7,11 @@ use ockam_core::Result;
 #[cfg(feature = "tag")]
 use ockam_core::TypeTag;
 use ockam_core::{CowBytes, CowStr};
-use ockam_multiaddr::MultiAddr;
+use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Secure, Service};
+use ockam_multiaddr::{MultiAddr, Protocol};
 
 use crate::error::ApiError;
+use crate::DefaultAddress;
 
 #[derive(Encode, Decode, Debug)]
 #[cfg_attr(test, derive(Clone))]
@@ -37,17 +40,188 @@ impl<'a> SendMessage<'a> {
     }
 }
 
+/// Request body to measure the round trip time to the echo service at the end of a route
+#[derive(Encode, Decode, Debug)]
+#[cfg_attr(test, derive(Clone))]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PingRoute<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] pub tag: TypeTag<2754127>,
+    #[b(1)] pub route: CowStr<'a>,
+    #[n(2)] pub count: u16,
+    #[n(3)] pub timeout: Duration,
+}
+
+impl<'a> PingRoute<'a> {
+    pub fn new(route: &MultiAddr, count: u16, timeout: Duration) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            route: route.to_string().into(),
+            count,
+            timeout,
+        }
+    }
+
+    pub fn multiaddr(&self) -> Result<MultiAddr> {
+        MultiAddr::from_str(self.route.as_ref())
+            .map_err(|_err| ApiError::generic(&format!("Invalid route: {}", self.route)))
+    }
+}
+
+/// Response body of a [`PingRoute`] request: one entry per probe, `None` if it timed out
+#[derive(Encode, Decode, Debug, Clone)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PingResult {
+    #[cfg(feature = "tag")]
+    #[n(0)] pub tag: TypeTag<3591452>,
+    #[n(1)] pub rtts: Vec<Option<Duration>>,
+}
+
+impl PingResult {
+    pub fn new(rtts: Vec<Option<Duration>>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            rtts,
+        }
+    }
+
+    pub fn received(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.rtts.iter().flatten().copied()
+    }
+}
+
+/// Request body to measure the round trip time to every hop of a route
+#[derive(Encode, Decode, Debug)]
+#[cfg_attr(test, derive(Clone))]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TraceRoute<'a> {
+    #[cfg(feature = "tag")]
+    #[n(0)] pub tag: TypeTag<4408219>,
+    #[b(1)] pub route: CowStr<'a>,
+    #[n(2)] pub timeout: Duration,
+}
+
+impl<'a> TraceRoute<'a> {
+    pub fn new(route: &MultiAddr, timeout: Duration) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            route: route.to_string().into(),
+            timeout,
+        }
+    }
+
+    pub fn multiaddr(&self) -> Result<MultiAddr> {
+        MultiAddr::from_str(self.route.as_ref())
+            .map_err(|_err| ApiError::generic(&format!("Invalid route: {}", self.route)))
+    }
+}
+
+/// Response body of a [`TraceRoute`] request
+#[derive(Encode, Decode, Debug, Clone)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TraceResult {
+    #[cfg(feature = "tag")]
+    #[n(0)] pub tag: TypeTag<1903377>,
+    #[n(1)] pub hops: Vec<TraceHop>,
+}
+
+impl TraceResult {
+    pub fn new(hops: Vec<TraceHop>) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            hops,
+        }
+    }
+}
+
+/// A single hop of a [`TraceResult`]
+#[derive(Encode, Decode, Debug, Clone)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TraceHop {
+    /// Route from the node to this hop
+    #[n(1)] pub route: String,
+    /// Round trip time to the tracer service reached through this hop, `None` if unreachable
+    #[n(2)] pub rtt: Option<Duration>,
+    /// Identifier of the node reached through this hop
+    #[n(3)] pub identifier: Option<String>,
+    /// Reason why the hop could not be reached
+    #[n(4)] pub error: Option<String>,
+}
+
+/// Split a route into the prefixes ending at each hop
+///
+/// A hop is any protocol value except network addresses (`/ip4`,
+/// `/ip6`, `/dnsaddr`), which are always followed by their `/tcp` port.
+pub fn hop_prefixes(route: &MultiAddr) -> Vec<MultiAddr> {
+    hop_ends(route)
+        .into_iter()
+        .map(|end| route.split(end).0)
+        .collect()
+}
+
+/// Build the routes of the probes sent to trace a route
+///
+/// A tracer service is reached after each hop of a probe route. Since a
+/// secure channel hides the hops it goes through, a new probe is started
+/// for every secure channel of the route: it goes through the preceding
+/// hops without tracers and traces the secure channel and the following
+/// hops. Return the route of each probe with the number of hops it traces.
+pub fn trace_probes(route: &MultiAddr) -> Result<Vec<(MultiAddr, usize)>> {
+    let mut probes: Vec<(MultiAddr, usize)> = vec![];
+    let mut start = 0;
+    for end in hop_ends(route) {
+        let is_secure = route.iter().nth(end - 1).map(|p| p.code()) == Some(Secure::CODE);
+        if is_secure || probes.is_empty() {
+            probes.push((route.split(start).0, 0));
+        }
+        if let Some((probe, hops)) = probes.last_mut() {
+            probe.try_extend(route.iter().skip(start).take(end - start))?;
+            probe.push_back(Service::new(DefaultAddress::TRACER_SERVICE))?;
+            *hops += 1;
+        }
+        start = end;
+    }
+    Ok(probes)
+}
+
+/// Positions in a route of the end of each hop
+fn hop_ends(route: &MultiAddr) -> Vec<usize> {
+    route
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| !matches!(p.code(), Ip4::CODE | Ip6::CODE | DnsAddr::CODE))
+        .map(|(i, _)| i + 1)
+        .collect()
+}
+
 mod node {
+    use std::time::{Duration, Instant};
+
     use minicbor::Decoder;
     use tracing::trace;
 
     use crate::error::ApiError;
-    use crate::local_multiaddr_to_route;
     use crate::nodes::connection::Connection;
+    use crate::nodes::service::ConnectResult;
+    use crate::tracer::TraceProbe;
+    use crate::{local_multiaddr_to_route, DefaultAddress};
     use ockam_core::api::{Request, Response, Status};
-    use ockam_core::{self, Result};
+    use ockam_core::flow_control::FlowControls;
+    use ockam_core::{self, Result, Route};
+    use ockam_multiaddr::proto::Service;
+    use ockam_multiaddr::MultiAddr;
     use ockam_node::{Context, MessageSendReceiveOptions};
 
+    use super::{hop_prefixes, trace_probes, PingResult, TraceHop, TraceResult};
     use crate::nodes::NodeManagerWorker;
 
     const TARGET: &str = "ockam_api::message";
@@ -64,12 +238,15 @@ mod node {
             let msg = req_body.message.to_vec();
             let msg_length = msg.len();
 
-            let mut node_manager = self.node_manager.write().await;
-            let connection = Connection::new(ctx, &multiaddr);
-            let connection = node_manager.connect(connection).await?;
-            let full = connection.secure_channel.try_with(&connection.suffix)?;
-            let route =
-                local_multiaddr_to_route(&full).ok_or(ApiError::generic("Invalid route"))?;
+            let (route, flow_controls) = {
+                let mut node_manager = self.node_manager.write().await;
+                let connection = Connection::new(ctx, &multiaddr);
+                let connection = node_manager.connect(connection).await?;
+                let full = connection.secure_channel.try_with(&connection.suffix)?;
+                let route =
+                    local_multiaddr_to_route(&full).ok_or(ApiError::generic("Invalid route"))?;
+                (route, node_manager.flow_controls.clone())
+            };
 
             trace!(target: TARGET, route = %req_body.route, msg_l = %msg_length, "sending message");
 
@@ -77,7 +254,7 @@ mod node {
                 .send_and_receive_extended::<Vec<u8>>(
                     route,
                     msg,
-                    MessageSendReceiveOptions::new().with_flow_control(&node_manager.flow_controls),
+                    MessageSendReceiveOptions::new().with_flow_control(&flow_controls),
                 )
                 .await;
             match res {
@@ -92,5 +269,218 @@ mod node {
                 }
             }
         }
+
+        pub(crate) async fn ping_route(
+            &mut self,
+            ctx: &mut Context,
+            req: &Request<'_>,
+            dec: &mut Decoder<'_>,
+        ) -> Result<Vec<u8>> {
+            let req_body: super::PingRoute = dec.decode()?;
+            let mut multiaddr = req_body.multiaddr()?;
+            multiaddr.push_back(Service::new(DefaultAddress::ECHO_SERVICE))?;
+
+            trace!(target: TARGET, route = %req_body.route, count = %req_body.count, "pinging route");
+
+            let (connection, route, flow_controls) = self
+                .open_probe_route(ctx, &multiaddr, req_body.timeout)
+                .await?;
+
+            let mut rtts = Vec::with_capacity(req_body.count as usize);
+            for _ in 0..req_body.count {
+                let start = Instant::now();
+                let res = ctx
+                    .send_and_receive_extended::<Vec<u8>>(
+                        route.clone(),
+                        b"ping".to_vec(),
+                        MessageSendReceiveOptions::new()
+                            .with_flow_control(&flow_controls)
+                            .with_timeout(req_body.timeout),
+                    )
+                    .await;
+                match res {
+                    Ok(_) => rtts.push(Some(start.elapsed())),
+                    Err(err) => {
+                        debug!(target: TARGET, ?err, "Ping probe failed");
+                        rtts.push(None)
+                    }
+                }
+            }
+
+            self.close_probe_route(ctx, &connection).await;
+
+            Ok(Response::ok(req.id())
+                .body(PingResult::new(rtts))
+                .to_vec()?)
+        }
+
+        pub(crate) async fn trace_route(
+            &mut self,
+            ctx: &mut Context,
+            req: &Request<'_>,
+            dec: &mut Decoder<'_>,
+        ) -> Result<Vec<u8>> {
+            let req_body: super::TraceRoute = dec.decode()?;
+            let multiaddr = req_body.multiaddr()?;
+
+            trace!(target: TARGET, route = %req_body.route, "tracing route");
+
+            let mut prefixes = hop_prefixes(&multiaddr).into_iter();
+            let mut hops = vec![];
+            for (probe, hops_count) in trace_probes(&multiaddr)? {
+                let traced = self.trace(ctx, &probe, req_body.timeout).await;
+                let prefixes = (&mut prefixes).take(hops_count);
+                match traced {
+                    Ok(hops_rtts) if hops_rtts.len() == hops_count => {
+                        for (prefix, (rtt, identifier)) in prefixes.zip(hops_rtts) {
+                            hops.push(TraceHop {
+                                route: prefix.to_string(),
+                                rtt: Some(rtt),
+                                identifier,
+                                error: None,
+                            })
+                        }
+                    }
+                    res => {
+                        let error = match res {
+                            Err(err) => err.to_string(),
+                            Ok(_) => "a hop of the route does not run a tracer".to_string(),
+                        };
+                        for prefix in prefixes {
+                            hops.push(TraceHop {
+                                route: prefix.to_string(),
+                                rtt: None,
+                                identifier: None,
+                                error: Some(error.clone()),
+                            })
+                        }
+                        // The following hops can only be reached through these ones
+                        break;
+                    }
+                }
+            }
+
+            Ok(Response::ok(req.id())
+                .body(TraceResult::new(hops))
+                .to_vec()?)
+        }
+
+        /// Send a [`TraceProbe`] along a route built by [`trace_probes`]
+        ///
+        /// Return the round trip time to each traced hop, and the identifier
+        /// of the node reached by that hop.
+        async fn trace(
+            &self,
+            ctx: &Context,
+            probe: &MultiAddr,
+            timeout: Duration,
+        ) -> Result<Vec<(Duration, Option<String>)>> {
+            let (connection, route, flow_controls) =
+                self.open_probe_route(ctx, probe, timeout).await?;
+
+            let start = Instant::now();
+            let res = ctx
+                .send_and_receive_extended::<Vec<u8>>(
+                    route,
+                    minicbor::to_vec(TraceProbe::new())?,
+                    MessageSendReceiveOptions::new()
+                        .with_flow_control(&flow_controls)
+                        .with_timeout(timeout),
+                )
+                .await;
+            let total = start.elapsed();
+
+            self.close_probe_route(ctx, &connection).await;
+
+            let probe: TraceProbe = minicbor::decode(&res?.body())?;
+            // The time spent beyond a hop is measured by the node of that hop
+            Ok(probe
+                .records
+                .into_iter()
+                .map(|r| (total.saturating_sub(r.dwell.unwrap_or(total)), r.identifier))
+                .collect())
+        }
+
+        /// Create the connections needed to send probes to `route`
+        ///
+        /// The node manager is only locked while connecting, not while
+        /// the probes are in flight.
+        async fn open_probe_route(
+            &self,
+            ctx: &Context,
+            route: &MultiAddr,
+            timeout: Duration,
+        ) -> Result<(ConnectResult, Route, FlowControls)> {
+            let mut node_manager = self.node_manager.write().await;
+            let connection = Connection::new(ctx, route).with_timeout(timeout);
+            let connection = node_manager.connect(connection).await?;
+            let full = connection
+                .secure_channel
+                .clone()
+                .try_with(&connection.suffix)?;
+            match local_multiaddr_to_route(&full) {
+                Some(route) => Ok((connection, route, node_manager.flow_controls.clone())),
+                None => {
+                    if let Err(err) = node_manager.disconnect(ctx, &connection).await {
+                        debug!(target: TARGET, ?err, "Failed to close a probe connection");
+                    }
+                    Err(ApiError::generic("Invalid route"))
+                }
+            }
+        }
+
+        /// Stop the connections created by [`Self::open_probe_route`]
+        async fn close_probe_route(&self, ctx: &Context, connection: &ConnectResult) {
+            let mut node_manager = self.node_manager.write().await;
+            if let Err(err) = node_manager.disconnect(ctx, connection).await {
+                debug!(target: TARGET, ?err, "Failed to close a probe connection");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hop_prefixes_keep_network_addresses_with_their_port() {
+        let route = MultiAddr::from_str("/ip4/127.0.0.1/tcp/4000/service/forward_to_n2/secure/api")
+            .unwrap();
+        let prefixes: Vec<String> = hop_prefixes(&route).iter().map(|p| p.to_string()).collect();
+        assert_eq!(
+            prefixes,
+            vec![
+                "/ip4/127.0.0.1/tcp/4000",
+                "/ip4/127.0.0.1/tcp/4000/service/forward_to_n2",
+                "/ip4/127.0.0.1/tcp/4000/service/forward_to_n2/secure/api",
+            ]
+        );
+    }
+
+    #[test]
+    fn trace_probes_start_at_each_secure_channel() {
+        let route = MultiAddr::from_str("/ip4/127.0.0.1/tcp/4000/service/forward_to_n2/secure/api")
+            .unwrap();
+        let probes: Vec<(String, usize)> = trace_probes(&route)
+            .unwrap()
+            .iter()
+            .map(|(p, n)| (p.to_string(), *n))
+            .collect();
+        assert_eq!(
+            probes,
+            vec![
+                (
+                    "/ip4/127.0.0.1/tcp/4000/service/tracer/service/forward_to_n2/service/tracer"
+                        .to_string(),
+                    2
+                ),
+                (
+                    "/ip4/127.0.0.1/tcp/4000/service/forward_to_n2/secure/api/service/tracer"
+                        .to_string(),
+                    1
+                ),
+            ]
+        );
     }
 }
@@ -1,5 +1,5 @@
 use crate::auth::Server;
-use crate::authenticator::direct::EnrollmentTokenAuthenticator;
+use crate::authenticator::direct::{EnrollmentTokenAuthenticator, EnrollmentTokens};
 use crate::echoer::Echoer;
 use crate::error::ApiError;
 use crate::hop::Hop;
@@ -22,6 +22,7 @@ use crate::nodes::registry::{
 };
 use crate::nodes::NodeManager;
 use crate::port_range::PortRange;
+use crate::tracer::Tracer;
 use crate::uppercase::Uppercase;
 use crate::vault::VaultService;
 use crate::{actions, resources};
@@ -30,7 +31,7 @@ use core::time::Duration;
 use minicbor::Decoder;
 use ockam::{Address, Context, Result};
 use ockam_abac::expr::{and, eq, ident, str};
-use ockam_abac::{Action, Env, Expr, PolicyAccessControl, Resource};
+use ockam_abac::{Action, Env, Expr, Resource};
 use ockam_core::api::{bad_request, Error, Request, Response, ResponseBuilder};
 use ockam_core::compat::sync::Arc;
 use ockam_core::{route, AllowAll, IncomingAccessControl};
@@ -40,7 +41,7 @@ use ockam_multiaddr::MultiAddr;
 use ockam_node::WorkerBuilder;
 use ockam_transport_tcp::TcpInletOptions;
 
-use super::NodeManagerWorker;
+use super::{resource_environment, NodeManagerWorker};
 
 impl NodeManager {
     pub(super) async fn start_vault_service_impl(
@@ -184,12 +185,14 @@ impl NodeManager {
 
         let maybe_trust_context_id = self.trust_context.as_ref().map(|c| c.id());
         let resource = Resource::assert_inline(addr.address());
+        let env = resource_environment("echoer", Some(&addr));
         let ac = self
             .access_control(
                 &resource,
                 &actions::HANDLE_MESSAGE,
                 maybe_trust_context_id,
                 None,
+                env.clone(),
             )
             .await?;
 
@@ -197,6 +200,7 @@ impl NodeManager {
             .start(ctx)
             .await
             .map(|_| ())?;
+        self.add_resource_environment(&addr, &resource, env);
 
         self.registry
             .echoer_services
@@ -205,6 +209,32 @@ impl NodeManager {
         Ok(())
     }
 
+    pub(super) async fn start_tracer_service_impl(
+        &mut self,
+        ctx: &Context,
+        addr: Address,
+    ) -> Result<()> {
+        let maybe_trust_context_id = self.trust_context.as_ref().map(|c| c.id());
+        let resource = Resource::assert_inline(addr.address());
+        let env = resource_environment("tracer", Some(&addr));
+        let ac = self
+            .access_control(
+                &resource,
+                &actions::HANDLE_MESSAGE,
+                maybe_trust_context_id,
+                None,
+                env.clone(),
+            )
+            .await?;
+
+        let tracer = Tracer::new(Some(self.identity.identifier().clone()));
+        WorkerBuilder::with_access_control(ac, Arc::new(AllowAll), addr.clone(), tracer)
+            .start(ctx)
+            .await?;
+        self.add_resource_environment(&addr, &resource, env);
+        Ok(())
+    }
+
     pub(super) async fn start_hop_service_impl(
         &mut self,
         ctx: &Context,
@@ -233,10 +263,12 @@ impl NodeManager {
         a: &Action,
         project_id: &str,
         default: &Expr,
+        env: Env,
     ) -> Result<Arc<dyn IncomingAccessControl>> {
         // Populate environment with known attributes:
-        let mut env = Env::new();
+        let mut env = env;
         env.put("resource.id", str(r.as_str()));
+        env.put("resource.node", str(self.node_name.as_str()));
         env.put("action.id", str(a.as_str()));
         env.put("resource.project_id", str(project_id));
         // Check if a policy exists for (resource, action) and if not, then
@@ -244,13 +276,7 @@ impl NodeManager {
         if self.policies.get_policy(r, a).await?.is_none() {
             self.policies.set_policy(r, a, default).await?
         }
-        Ok(Arc::new(PolicyAccessControl::new(
-            self.policies.clone(),
-            self.identities_repository(),
-            r.clone(),
-            a.clone(),
-            env,
-        )))
+        Ok(Arc::new(self.policy_access_control(r, a, env)))
     }
 
     pub(super) async fn start_credential_issuer_service_impl(
@@ -267,8 +293,9 @@ impl NodeManager {
         let action = actions::HANDLE_MESSAGE;
         let resource = Resource::new(&addr.to_string());
         let rule = eq([ident("resource.project_id"), ident("subject.project_id")]);
+        let env = resource_environment("credential_issuer", Some(&addr));
         let abac = self
-            .build_access_control(&resource, &action, project.as_str(), &rule)
+            .build_access_control(&resource, &action, project.as_str(), &rule, env.clone())
             .await?;
         let issuer =
             CredentialsIssuer::new(self.identities(), self.identity.clone(), project).await?;
@@ -276,6 +303,7 @@ impl NodeManager {
             .start(ctx)
             .await
             .map(|_| ())?;
+        self.add_resource_environment(&addr, &resource, env);
         self.registry
             .authenticator_service
             .insert(addr, AuthenticatorServiceInfo::default());
@@ -296,14 +324,21 @@ impl NodeManager {
         }
         let action = actions::HANDLE_MESSAGE;
         let resource = Resource::new(&addr.to_string());
-
+        let env = resource_environment("direct_authenticator", Some(&addr));
         let abac = self
-            .access_control(&resource, &action, Some(project.as_str()), None)
+            .access_control(
+                &resource,
+                &action,
+                Some(project.as_str()),
+                None,
+                env.clone(),
+            )
             .await?;
 
         let direct = crate::authenticator::direct::DirectAuthenticator::new(
             project.clone(),
             self.attributes_writer(),
+            self.attributes_reader(),
         )
         .await?;
 
@@ -311,6 +346,7 @@ impl NodeManager {
             .start(ctx)
             .await
             .map(|_| ())?;
+        self.add_resource_environment(&addr, &resource, env);
 
         self.registry
             .authenticator_service
@@ -346,22 +382,28 @@ impl NodeManager {
         }
         let action = actions::HANDLE_MESSAGE;
         let resource = Resource::new(&issuer_addr.to_string());
+        // Tokens are persisted, so that they can still be used after a restart of the node
+        let node_state = self.cli_state.nodes.get(&self.node_name)?;
+        let tokens_storage = Arc::new(node_state.enrollment_tokens_storage().await?);
         let (issuer, acceptor) = EnrollmentTokenAuthenticator::new_worker_pair(
             project.clone(),
             self.attributes_writer(),
+            EnrollmentTokens::new(tokens_storage),
         );
         let rule = and([
             eq([ident("resource.project_id"), ident("subject.project_id")]),
             eq([ident("subject.ockam-role"), str("enroller")]),
         ]);
+        let env = resource_environment("enrollment_token_issuer", Some(&issuer_addr));
         let abac = self
-            .build_access_control(&resource, &action, project.as_str(), &rule)
+            .build_access_control(&resource, &action, project.as_str(), &rule, env.clone())
             .await?;
         let allow_all = Arc::new(AllowAll);
         WorkerBuilder::with_access_control(abac, allow_all.clone(), issuer_addr.clone(), issuer)
             .start(ctx)
             .await
             .map(|_| ())?;
+        self.add_resource_environment(&issuer_addr, &resource, env);
         WorkerBuilder::with_access_control(
             allow_all.clone(),
             allow_all,
@@ -1,27 +1,169 @@
-use crate::nodes::models::policy::{Policy, PolicyList};
+use crate::error::ApiError;
+use crate::nodes::authority_node::apply_policy_bundle;
+use crate::nodes::models::policy::{
+    DecisionList, ListDecisions, Policy, PolicyExplanation, PolicyHistory, PolicyList,
+    RollbackPolicy, TestPolicy,
+};
 use either::Either;
 use minicbor::Decoder;
-use ockam_abac::{Action, Resource};
+use ockam::identity::IdentityIdentifier;
+use ockam_abac::expr::str;
+use ockam_abac::{AbacAccessControl, Action, Env, Resource};
 use ockam_core::api::{Error, Request, Response, ResponseBuilder};
-use ockam_core::Result;
+use ockam_core::errcode::Kind;
+use ockam_core::{Address, AsyncTryClone, Result};
+use ockam_node::tokio;
+use ockam_node::tokio::task::JoinHandle;
+use ockam_node::Context;
+use std::time::Duration;
 
 use super::NodeManager;
 
+/// Interval between two retrievals of the trust context authority policy bundle
+const POLICY_BUNDLE_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
 impl NodeManager {
+    /// Periodically retrieve the policy bundle published by the trust context
+    /// authority and apply its policies to this node
+    ///
+    /// If a version is given, only that version of the bundle is applied, see
+    /// [`apply_policy_bundle`]. Otherwise a bundle older than the last applied
+    /// one, whose version is kept with the node state, is refused.
+    /// When the authority can't be reached the node keeps its local policies.
+    pub(super) async fn start_policy_bundle_refresher(
+        &self,
+        ctx: &Context,
+    ) -> Result<Option<JoinHandle<()>>> {
+        let authority = match self.trust_context().and_then(|tc| tc.authority()) {
+            Ok(authority) => authority.clone(),
+            Err(_) => return Ok(None),
+        };
+        let identity = self.identity();
+        let identities = self.identities();
+        let policies = self.policies.clone();
+        let pinned_version = self.policy_bundle_version;
+        let node_state = self.cli_state.nodes.get(&self.node_name)?;
+        let ctx = ctx.async_try_clone().await?;
+
+        Ok(Some(tokio::spawn(async move {
+            let mut applied_version = match node_state.policy_bundle_version() {
+                Ok(version) => version.unwrap_or_default(),
+                Err(e) => {
+                    warn!("Failed to read the applied policy bundle version: {e}");
+                    0
+                }
+            };
+            loop {
+                match authority
+                    .retrieve_policy_bundle(&ctx, &identity, pinned_version)
+                    .await
+                {
+                    Ok(Some(signed)) => match apply_policy_bundle(
+                        &identities,
+                        &authority.identity(),
+                        &signed,
+                        policies.as_ref(),
+                        pinned_version,
+                        applied_version,
+                    )
+                    .await
+                    {
+                        Ok(Some((bundle, changed))) => {
+                            applied_version = bundle.version();
+                            info!(
+                                "Applied the version {applied_version} of the authority policy bundle, {changed} policies changed"
+                            );
+                            if let Err(e) = node_state.set_policy_bundle_version(applied_version) {
+                                warn!("Failed to store the applied policy bundle version: {e}")
+                            }
+                        }
+                        // The bundle was already applied
+                        Ok(None) => {}
+                        Err(e) => warn!("Refused the authority policy bundle: {e}"),
+                    },
+                    // No bundle was published yet, or the authority doesn't publish any
+                    Ok(None) => {}
+                    Err(e) => warn!(
+                        "Failed to retrieve the authority policy bundle, keeping the local policies: {e}"
+                    ),
+                }
+                tokio::time::sleep(POLICY_BUNDLE_REFRESH_INTERVAL).await;
+            }
+        })))
+    }
+
+    /// Set a policy, the author of the new version being the identity which sent
+    /// the request, if it was received through a secure channel
     pub(super) async fn add_policy(
         &self,
         resource: &str,
         action: &str,
         req: &Request<'_>,
         dec: &mut Decoder<'_>,
+        author: Option<&IdentityIdentifier>,
     ) -> Result<ResponseBuilder<()>> {
         let p: Policy = dec.decode()?;
         let r = Resource::new(resource);
         let a = Action::new(action);
-        self.policies.set_policy(&r, &a, p.expression()).await?;
+        self.policies
+            .update_policy(&r, &a, p.expression(), author, p.comment())
+            .await?;
         Ok(Response::ok(req.id()))
     }
 
+    pub(super) async fn policy_history(
+        &self,
+        req: &Request<'_>,
+        resource: &str,
+        action: &str,
+    ) -> Result<ResponseBuilder<PolicyHistory>> {
+        let r = Resource::new(resource);
+        let a = Action::new(action);
+        let versions = self.policies.policy_history(&r, &a).await?;
+        Ok(Response::ok(req.id()).body(PolicyHistory::new(versions)))
+    }
+
+    /// Roll a policy back to one of its versions, the author of the new version
+    /// being the identity which sent the request, if it was received through a
+    /// secure channel
+    pub(super) async fn rollback_policy<'a>(
+        &self,
+        req: &'a Request<'_>,
+        dec: &mut Decoder<'_>,
+        resource: &str,
+        action: &str,
+        author: Option<&IdentityIdentifier>,
+    ) -> Result<Either<ResponseBuilder<Error<'a>>, ResponseBuilder<PolicyHistory>>> {
+        let rb: RollbackPolicy = dec.decode()?;
+        let r = Resource::new(resource);
+        let a = Action::new(action);
+        match self
+            .policies
+            .rollback_policy(&r, &a, rb.version(), author)
+            .await
+        {
+            Ok(version) => Ok(Either::Right(
+                Response::ok(req.id()).body(PolicyHistory::new(vec![version])),
+            )),
+            Err(e) if e.code().kind == Kind::NotFound => {
+                let mut err = Error::new(req.path()).with_message("policy version not found");
+                if let Some(m) = req.method() {
+                    err.set_method(m)
+                }
+                Ok(Either::Left(Response::not_found(req.id()).body(err)))
+            }
+            Err(e) if e.code().kind == Kind::Invalid => {
+                let mut err = Error::new(req.path())
+                    .with_message("a policy can't be rolled back to its deletion");
+                if let Some(m) = req.method() {
+                    err.set_method(m)
+                }
+                Ok(Either::Left(Response::bad_request(req.id()).body(err)))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
     pub(super) async fn get_policy<'a>(
         &self,
         req: &'a Request<'_>,
@@ -41,6 +183,79 @@ impl NodeManager {
         }
     }
 
+    /// Evaluate the policy of a resource and action against the attributes of
+    /// an identity, without sending any message, and explain the result
+    pub(super) async fn test_policy<'a>(
+        &self,
+        req: &'a Request<'_>,
+        dec: &mut Decoder<'_>,
+        resource: &str,
+        action: &str,
+    ) -> Result<Either<ResponseBuilder<Error<'a>>, ResponseBuilder<PolicyExplanation>>> {
+        let t: TestPolicy = dec.decode()?;
+        let r = Resource::new(resource);
+        let a = Action::new(action);
+        let expr = if let Some(e) = self.policies.get_policy(&r, &a).await? {
+            e
+        } else {
+            let mut err = Error::new(req.path()).with_message("policy not found");
+            if let Some(m) = req.method() {
+                err.set_method(m)
+            }
+            return Ok(Either::Left(Response::not_found(req.id()).body(err)));
+        };
+
+        // Use the attributes of the worker of the resource, if it is running
+        let workers: Vec<(Address, Env)> = self
+            .resource_environments
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, (resource, _))| resource == &r)
+            .map(|(worker, (_, env))| (worker.clone(), env.clone()))
+            .collect();
+        let worker_env = match (t.worker(), &workers[..]) {
+            (Some(worker), _) => match workers.into_iter().find(|(w, _)| w.address() == worker) {
+                Some((_, env)) => env,
+                None => {
+                    let mut err = Error::new(req.path())
+                        .with_message("no worker of this resource at this address");
+                    if let Some(m) = req.method() {
+                        err.set_method(m)
+                    }
+                    return Ok(Either::Left(Response::not_found(req.id()).body(err)));
+                }
+            },
+            (None, []) => Env::new(),
+            (None, [(_, env)]) => env.clone(),
+            (None, _) => {
+                let mut err = Error::new(req.path()).with_message(
+                    "several workers share this resource, a worker address is required",
+                );
+                if let Some(m) = req.method() {
+                    err.set_method(m)
+                }
+                return Ok(Either::Left(Response::bad_request(req.id()).body(err)));
+            }
+        };
+
+        let mut env = worker_env;
+        env.put("resource.id", str(r.as_str()));
+        env.put("resource.node", str(self.node_name.as_str()));
+        if let Some(tc) = &self.trust_context {
+            env.put("resource.project_id", str(tc.id()));
+            env.put("resource.trust_context_id", str(tc.id()));
+        }
+        env.put("action.id", str(a.as_str()));
+
+        let explanation = AbacAccessControl::new(self.identities_repository(), expr, env)
+            .explain(t.identity())
+            .await?;
+        Ok(Either::Right(
+            Response::ok(req.id()).body(PolicyExplanation::new(explanation)),
+        ))
+    }
+
     pub(super) async fn list_policies(
         &self,
         req: &Request<'_>,
@@ -62,4 +277,108 @@ impl NodeManager {
         self.policies.del_policy(&r, &a).await?;
         Ok(Response::ok(req.id()))
     }
+
+    /// Return the most recent policy decisions selected by the request filters,
+    /// oldest first
+    pub(super) async fn list_decisions(
+        &self,
+        req: &Request<'_>,
+        dec: &mut Decoder<'_>,
+    ) -> Result<ResponseBuilder<DecisionList>> {
+        let decision_log = self.decision_log.as_ref().ok_or_else(|| {
+            ApiError::generic("The policy decisions of this node are not recorded")
+        })?;
+        let filters: ListDecisions = dec.decode()?;
+        let limit = filters.limit().map(|l| l as usize);
+        let decisions = decision_log
+            .decisions(Box::new(move |d| filters.matches(d)), limit)
+            .await?;
+        Ok(Response::ok(req.id()).body(DecisionList::new(decisions)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nodes::models::policy::{Policy, PolicyExplanation, TestPolicy};
+    use crate::nodes::models::portal::{CreateOutlet, OutletStatus};
+    use crate::nodes::NODEMANAGER_ADDR;
+    use crate::util::test::start_manager_for_tests;
+    use minicbor::{Decode, Decoder, Encode};
+    use ockam_core::api::{Request, RequestBuilder, Response, Status};
+    use ockam_core::{route, Result};
+    use ockam_node::Context;
+
+    /// Send a request to the node manager and return the status and body of the response
+    async fn request<T: Encode<()>>(
+        ctx: &mut Context,
+        req: RequestBuilder<'_>,
+        body: T,
+    ) -> Result<(Option<Status>, Vec<u8>)> {
+        let res: Vec<u8> = ctx
+            .send_and_receive(route![NODEMANAGER_ADDR], req.body(body).to_vec()?)
+            .await?;
+        let mut dec = Decoder::new(&res);
+        let header: Response = dec.decode()?;
+        Ok((header.status(), res[dec.position()..].to_vec()))
+    }
+
+    fn decode<'a, T: Decode<'a, ()>>(body: &'a [u8]) -> Result<T> {
+        Ok(minicbor::decode(body)?)
+    }
+
+    #[ockam_macros::test]
+    async fn policies_are_tested_with_the_attributes_of_a_worker(ctx: &mut Context) -> Result<()> {
+        let handle = start_manager_for_tests(ctx).await?;
+        let identity = handle.identity.identifier();
+
+        // Two outlets share the default outlet resource
+        for (tcp_addr, worker_addr) in [
+            ("127.0.0.1:5000", "outlet_a"),
+            ("127.0.0.1:6000", "outlet_b"),
+        ] {
+            let req = Request::post("/node/outlet");
+            let (status, _) =
+                request(ctx, req, CreateOutlet::new(tcp_addr, worker_addr, None)).await?;
+            assert_eq!(status, Some(Status::Ok));
+        }
+        let req = Request::post("/policy/tcp-outlet/handle_message");
+        let (status, _) = request(ctx, req, Policy::new("(= resource.port 5000)".parse()?)).await?;
+        assert_eq!(status, Some(Status::Ok));
+
+        // The worker is required to know which attributes to use
+        let path = "/policy/tcp-outlet/handle_message/test";
+        let (status, _) =
+            request(ctx, Request::post(path), TestPolicy::new(identity.clone())).await?;
+        assert_eq!(status, Some(Status::BadRequest));
+
+        for (worker, allowed) in [("outlet_a", true), ("outlet_b", false)] {
+            let test = TestPolicy::new(identity.clone()).with_worker(Some(worker.to_string()));
+            let (status, body) = request(ctx, Request::post(path), test).await?;
+            assert_eq!(status, Some(Status::Ok));
+            let res: PolicyExplanation = decode(&body)?;
+            assert_eq!(res.explanation().is_true(), allowed);
+        }
+        let test = TestPolicy::new(identity.clone()).with_worker(Some("unknown".to_string()));
+        let (status, _) = request(ctx, Request::post(path), test).await?;
+        assert_eq!(status, Some(Status::NotFound));
+
+        // Once an outlet is deleted its attributes are forgotten
+        let outlets = handle.node_manager.read().await.registry.outlets.clone();
+        let alias = outlets
+            .iter()
+            .find(|(_, info)| info.worker_addr.address() == "outlet_b")
+            .map(|(alias, _)| alias.clone())
+            .unwrap();
+        let req = Request::delete(format!("/node/outlet/{alias}"));
+        let (status, body) = request(ctx, req, ()).await?;
+        assert_eq!(status, Some(Status::Ok));
+        let _: OutletStatus = decode(&body)?;
+
+        let (status, body) = request(ctx, Request::post(path), TestPolicy::new(identity)).await?;
+        assert_eq!(status, Some(Status::Ok));
+        let res: PolicyExplanation = decode(&body)?;
+        assert!(res.explanation().is_true());
+
+        ctx.stop().await
+    }
 }
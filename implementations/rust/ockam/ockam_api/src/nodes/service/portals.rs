@@ -13,11 +13,11 @@ use ockam::compat::tokio::time::timeout;
 use ockam::identity::IdentityIdentifier;
 use ockam::{Address, AsyncTryClone, Result};
 
-use ockam_abac::Resource;
+use ockam_abac::{Env, Resource};
 use ockam_core::api::{Request, Response, ResponseBuilder};
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::FlowControlPolicy;
-use ockam_core::IncomingAccessControl;
+use ockam_core::{IncomingAccessControl, OutgoingAccessControl};
 use ockam_multiaddr::proto::{Project, Secure, Service};
 use ockam_multiaddr::{MultiAddr, Protocol};
 use ockam_node::compat::asynchronous::RwLock;
@@ -25,10 +25,12 @@ use ockam_node::Context;
 use ockam_transport_tcp::{TcpInletOptions, TcpOutletOptions};
 use std::collections::BTreeMap;
 
-use super::{NodeManager, NodeManagerWorker};
+use super::{put_resource_socket_address, resource_environment, NodeManager, NodeManagerWorker};
 
 const INLET_WORKER: &str = "inlet-worker";
 const OUTER_CHAN: &str = "outer-chan";
+const OUTGOING_ACCESS_CONTROL: &str = "outgoing-access-control";
+const RESOURCE_ENVIRONMENT: &str = "resource-environment";
 
 impl NodeManagerWorker {
     pub(super) fn get_inlets<'a>(
@@ -158,13 +160,27 @@ impl NodeManagerWorker {
             None
         };
 
+        let mut env = resource_environment(resources::INLET.as_str(), None);
+        put_resource_socket_address(&mut env, &listen_addr);
         let access_control = node_manager
-            .access_control(&resource, &actions::HANDLE_MESSAGE, project_id, None)
+            .access_control(
+                &resource,
+                &actions::HANDLE_MESSAGE,
+                project_id,
+                None,
+                env.clone(),
+            )
+            .await?;
+        let outgoing_access_control = node_manager
+            .outgoing_access_control(&resource, project_id, env.clone())
             .await?;
 
-        let options = TcpInletOptions::new()
+        let mut options = TcpInletOptions::new()
             .with_incoming_access_control(access_control.clone())
             .as_consumer(&node_manager.flow_controls);
+        if let Some(outgoing_access_control) = &outgoing_access_control {
+            options = options.with_outgoing_access_control(outgoing_access_control.clone());
+        }
 
         let res = node_manager
             .tcp_transport
@@ -173,6 +189,7 @@ impl NodeManagerWorker {
 
         Ok(match res {
             Ok((_, worker_addr)) => {
+                node_manager.add_resource_environment(&worker_addr, &resource, env.clone());
                 // TODO: Use better way to store inlets?
                 node_manager.registry.inlets.insert(
                     alias.clone(),
@@ -182,6 +199,11 @@ impl NodeManagerWorker {
                     let mut s = Session::new(without_outlet_address(rest));
                     s.data().put(INLET_WORKER, worker_addr.clone());
                     s.data().put(OUTER_CHAN, outer);
+                    s.data().put(RESOURCE_ENVIRONMENT, (resource.clone(), env));
+                    if let Some(outgoing_access_control) = outgoing_access_control {
+                        s.data()
+                            .put(OUTGOING_ACCESS_CONTROL, outgoing_access_control);
+                    }
                     let ctx = Arc::new(ctx.async_try_clone().await?);
                     let repl = replacer(
                         manager,
@@ -233,6 +255,7 @@ impl NodeManagerWorker {
         info!(%alias, "Handling request to delete inlet portal");
         if let Some(inlet_to_delete) = node_manager.registry.inlets.remove(alias) {
             debug!(%alias, "Sucessfully removed inlet from node registry");
+            node_manager.remove_resource_environment(&inlet_to_delete.worker_addr);
             let was_stopped = node_manager
                 .tcp_transport
                 .stop_inlet(inlet_to_delete.worker_addr.clone())
@@ -327,10 +350,34 @@ impl NodeManagerWorker {
             None
         };
 
+        let mut env = resource_environment(resources::OUTLET.as_str(), Some(&worker_addr));
+        put_resource_socket_address(&mut env, &tcp_addr);
         let access_control = node_manager
-            .access_control(&resource, &actions::HANDLE_MESSAGE, trust_context_id, None)
+            .access_control(
+                &resource,
+                &actions::HANDLE_MESSAGE,
+                trust_context_id,
+                None,
+                env.clone(),
+            )
             .await?;
-        let options = TcpOutletOptions::new().with_incoming_access_control(access_control);
+
+        // Requests for new connections are checked with the policy of the 'connect'
+        // action, or the current policy of the 'handle_message' action if there is none
+        let connect_access_control = node_manager
+            .connect_access_control(&resource, trust_context_id, env.clone())
+            .await?;
+        // The inlets receiving the replies of the outlet are checked once, when
+        // they connect, with the policy of the 'send_message' action
+        let outgoing_access_control = node_manager
+            .outgoing_access_control(&resource, trust_context_id, env.clone())
+            .await?;
+        let mut options = TcpOutletOptions::new()
+            .with_incoming_access_control(access_control)
+            .with_connect_access_control(connect_access_control);
+        if let Some(outgoing_access_control) = outgoing_access_control {
+            options = options.with_outgoing_access_control(outgoing_access_control);
+        }
 
         // Accept messages from the default secure channel listener
         let options = if let Some(flow_control_id) = node_manager
@@ -353,6 +400,7 @@ impl NodeManagerWorker {
 
         Ok(match res {
             Ok(_) => {
+                node_manager.add_resource_environment(&worker_addr, &resource, env);
                 // TODO: Use better way to store outlets?
                 node_manager.registry.outlets.insert(
                     alias.clone(),
@@ -393,6 +441,7 @@ impl NodeManagerWorker {
         info!(%alias, "Handling request to delete outlet portal");
         if let Some(outlet_to_delete) = node_manager.registry.outlets.remove(alias) {
             debug!(%alias, "Successfully removed outlet from node registry");
+            node_manager.remove_resource_environment(&outlet_to_delete.worker_addr);
             let was_stopped = node_manager
                 .tcp_transport
                 .stop_outlet(outlet_to_delete.worker_addr.clone())
@@ -530,19 +579,21 @@ fn replacer(
 
                 // The previous inlet worker needs to be stopped:
                 if let Some(wa) = data.get::<Address>(INLET_WORKER) {
+                    this.remove_resource_environment(&wa);
                     let _ = this.tcp_transport.stop_inlet(wa).await;
                 }
 
                 // Finally attempt to create a new inlet using the new route:
-                let wa = this
-                    .tcp_transport
-                    .create_inlet(
-                        bind,
-                        r,
-                        TcpInletOptions::new().with_incoming_access_control(access),
-                    )
-                    .await?
-                    .1;
+                let mut options = TcpInletOptions::new().with_incoming_access_control(access);
+                if let Some(outgoing_access_control) =
+                    data.get::<Arc<dyn OutgoingAccessControl>>(OUTGOING_ACCESS_CONTROL)
+                {
+                    options = options.with_outgoing_access_control(outgoing_access_control);
+                }
+                let wa = this.tcp_transport.create_inlet(bind, r, options).await?.1;
+                if let Some((resource, env)) = data.get::<(Resource, Env)>(RESOURCE_ENVIRONMENT) {
+                    this.add_resource_environment(&wa, &resource, env);
+                }
                 data.put(INLET_WORKER, wa);
 
                 Ok(without_outlet_address(rest))
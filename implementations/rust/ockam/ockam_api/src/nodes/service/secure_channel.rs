@@ -136,14 +136,10 @@ impl NodeManager {
             }
             CredentialExchangeMode::Oneway => {
                 debug!(%sc_addr, "One-way credential presentation");
+                let from_authority = provided_credential.is_none();
                 let credential = match provided_credential {
                     Some(c) => c,
-                    None => {
-                        self.trust_context()?
-                            .authority()?
-                            .credential(ctx, &identity)
-                            .await?
-                    }
+                    None => self.trust_context_credential(ctx, &identity).await?,
                 };
 
                 self.credentials_service()
@@ -155,29 +151,31 @@ impl NodeManager {
                     )
                     .await?;
                 debug!(%sc_addr, "One-way credential presentation success");
+                if from_authority {
+                    self.register_credential_presentation(&sc_addr, &identity);
+                }
             }
             CredentialExchangeMode::Mutual => {
                 debug!(%sc_addr, "Mutual credential presentation");
+                let from_authority = provided_credential.is_none();
                 let credential = match provided_credential {
                     Some(c) => c,
-                    None => {
-                        self.trust_context()?
-                            .authority()?
-                            .credential(ctx, &identity)
-                            .await?
-                    }
+                    None => self.trust_context_credential(ctx, &identity).await?,
                 };
 
                 self.credentials_service()
                     .present_credential_mutual(
                         ctx,
                         route![sc_addr.clone(), DefaultAddress::CREDENTIALS_SERVICE],
-                        &[self.trust_context()?.authority()?.identity()],
+                        self.trust_context()?,
                         credential,
                         MessageSendReceiveOptions::new().with_flow_control(&self.flow_controls),
                     )
                     .await?;
                 debug!(%sc_addr, "Mutual credential presentation success");
+                if from_authority {
+                    self.register_credential_presentation(&sc_addr, &identity);
+                }
             }
         }
 
@@ -224,13 +222,19 @@ impl NodeManager {
         );
 
         // TODO: Clean
-        // Add Echoer, Uppercase and Cred Exch as a consumer by default
+        // Add Echoer, Tracer, Uppercase, Cred Exch and Identity Updates as a consumer by default
         self.flow_controls.add_consumer(
             &DefaultAddress::ECHO_SERVICE.into(),
             &flow_control_id,
             FlowControlPolicy::SpawnerAllowMultipleMessages,
         );
 
+        self.flow_controls.add_consumer(
+            &DefaultAddress::TRACER_SERVICE.into(),
+            &flow_control_id,
+            FlowControlPolicy::SpawnerAllowMultipleMessages,
+        );
+
         self.flow_controls.add_consumer(
             &DefaultAddress::UPPERCASE_SERVICE.into(),
             &flow_control_id,
@@ -243,6 +247,12 @@ impl NodeManager {
             FlowControlPolicy::SpawnerAllowMultipleMessages,
         );
 
+        self.flow_controls.add_consumer(
+            &DefaultAddress::IDENTITY_UPDATES.into(),
+            &flow_control_id,
+            FlowControlPolicy::SpawnerAllowMultipleMessages,
+        );
+
         self.flow_controls.add_consumer(
             &KAFKA_SECURE_CHANNEL_CONTROLLER_ADDRESS.into(),
             &flow_control_id,
@@ -329,9 +339,24 @@ impl NodeManager {
         debug!(%addr, "deleting secure channel");
         self.secure_channels.stop_secure_channel(ctx, addr).await?;
         self.registry.secure_channels.remove_by_addr(addr);
+        if let Some(refresher) = &self.credentials_refresher {
+            refresher
+                .remove_presentation(&route![addr.clone(), DefaultAddress::CREDENTIALS_SERVICE]);
+        }
         Ok(())
     }
 
+    /// Present the renewed credentials of the node identity on a secure channel
+    fn register_credential_presentation(&self, sc_addr: &Address, identity: &Identity) {
+        if identity.identifier() != self.identity.identifier() {
+            return;
+        }
+        if let Some(refresher) = &self.credentials_refresher {
+            refresher
+                .add_presentation(route![sc_addr.clone(), DefaultAddress::CREDENTIALS_SERVICE]);
+        }
+    }
+
     pub(super) async fn delete_secure_channel_listener_impl(
         &mut self,
         addr: &Address,
@@ -64,7 +64,10 @@ impl Worker for RpcProxyService {
         // Send the message on its onward_route
         ctx.forward(msg).await?;
 
-        let response = child_ctx.receive::<Any>().await?;
+        let response = child_ctx.receive::<Any>().await;
+        // The child address is only used for this request
+        self.flow_controls.cleanup_address(&child_address);
+        let response = response?;
         let response = response.into_local_message();
         let local_info = response.local_info().to_vec();
 
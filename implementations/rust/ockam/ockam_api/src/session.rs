@@ -8,10 +8,11 @@ use ockam_core::compat::sync::{Arc, Mutex};
 use ockam_core::flow_control::{FlowControlPolicy, FlowControls};
 use ockam_core::{Address, AllowAll, Decodable, DenyAll, Encodable, Error, Routed, LOCAL};
 use ockam_multiaddr::MultiAddr;
+use ockam_node::simulation::{Clock, SystemClock};
 use ockam_node::tokio;
 use ockam_node::tokio::sync::mpsc;
 use ockam_node::tokio::task::JoinSet;
-use ockam_node::tokio::time::{timeout, Duration};
+use ockam_node::tokio::time::Duration;
 use ockam_node::Context;
 use sessions::{Key, Ping, Status};
 use tracing as log;
@@ -24,6 +25,7 @@ const DELAY: Duration = Duration::from_secs(3);
 #[derive(Debug)]
 pub struct Medic {
     delay: Duration,
+    clock: Arc<dyn Clock>,
     sessions: Arc<Mutex<Sessions>>,
     pings: JoinSet<(Key, Result<(), Error>)>,
     replacements: JoinSet<(Key, Result<MultiAddr, Error>)>,
@@ -41,6 +43,7 @@ impl Medic {
     pub fn new(flow_controls: FlowControls) -> Self {
         Self {
             delay: DELAY,
+            clock: Arc::new(SystemClock),
             sessions: Arc::new(Mutex::new(Sessions::new())),
             pings: JoinSet::new(),
             replacements: JoinSet::new(),
@@ -48,6 +51,13 @@ impl Medic {
         }
     }
 
+    /// Use the given clock to wait between two session checks
+    #[cfg(test)]
+    fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     pub fn sessions(&self) -> Arc<Mutex<Sessions>> {
         self.sessions.clone()
     }
@@ -143,7 +153,11 @@ impl Medic {
                 }
             }
 
-            let _ = timeout(self.delay, self.get_results(&mut rx)).await;
+            let delay = self.clock.sleep(self.delay);
+            tokio::select! {
+                _ = delay => {}
+                _ = self.get_results(&mut rx) => {}
+            }
         }
     }
 
@@ -243,3 +257,47 @@ impl Worker for Collector {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_node::simulation::VirtualClock;
+
+    #[ockam_macros::test(timeout = 5_000)]
+    async fn unresponsive_session_is_replaced_on_clock_time(
+        ctx: &mut Context,
+    ) -> ockam_core::Result<()> {
+        let clock = VirtualClock::new();
+        let medic = Medic::new(FlowControls::default()).with_clock(Arc::new(clock.clone()));
+
+        let (tx, mut replaced) = mpsc::channel(1);
+        let mut session = Session::new("/service/unreachable".parse().unwrap());
+        session.set_replacer(Box::new(move |addr| {
+            let tx = tx.clone();
+            Box::pin(async move {
+                let _ = tx.send(()).await;
+                Ok(addr)
+            })
+        }));
+        medic.sessions().lock().unwrap().add(session);
+
+        let medic_ctx = ctx
+            .new_detached(Address::random_local(), AllowAll, AllowAll)
+            .await?;
+        ctx.runtime().spawn(medic.start(medic_ctx));
+
+        // A ping is sent on every check, the session is replaced once
+        // MAX_FAILURES pings are left unanswered
+        for _ in 0..MAX_FAILURES {
+            ctx.sleep(Duration::from_millis(100)).await;
+            assert!(replaced.try_recv().is_err());
+            clock.advance(DELAY);
+        }
+        tokio::time::timeout(Duration::from_secs(1), replaced.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        ctx.stop().await
+    }
+}
@@ -0,0 +1,162 @@
+use std::time::{Duration, Instant};
+
+use minicbor::{Decode, Encode};
+use ockam::identity::IdentityIdentifier;
+use ockam::{Any, Context, Result, Routed, Worker};
+#[cfg(feature = "tag")]
+use ockam_core::TypeTag;
+use ockam_core::{Decodable, Encodable};
+use tracing as log;
+
+/// Probe sent along a route to measure the latency of each of its hops
+///
+/// A [`Tracer`] is reached after every hop of the route. On the way to
+/// the destination each tracer appends a [`TraceRecord`] to the probe,
+/// the last one sends the probe back and on the way back each tracer
+/// completes its own record with the time the probe spent beyond it.
+#[derive(Encode, Decode, Debug, Clone, Default)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TraceProbe {
+    #[cfg(feature = "tag")]
+    #[n(0)] pub tag: TypeTag<6072481>,
+    #[n(1)] pub records: Vec<TraceRecord>,
+    #[n(2)] pub returning: bool,
+}
+
+/// What a [`Tracer`] appends to a [`TraceProbe`]
+#[derive(Encode, Decode, Debug, Clone)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TraceRecord {
+    /// Identifier of the node running the tracer
+    #[n(1)] pub identifier: Option<String>,
+    /// Time of arrival of the probe, in microseconds since the tracer started
+    #[n(2)] pub arrived_at: u64,
+    /// Time between the arrival of the probe and its return to the tracer
+    #[n(3)] pub dwell: Option<Duration>,
+}
+
+impl TraceProbe {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn decode_payload(payload: &[u8]) -> Result<Self> {
+        let bytes = <Vec<u8> as Decodable>::decode(payload)?;
+        Ok(minicbor::decode(&bytes)?)
+    }
+
+    pub fn encode_payload(&self) -> Result<Vec<u8>> {
+        Encodable::encode(&minicbor::to_vec(self)?)
+    }
+}
+
+/// A worker appending a [`TraceRecord`] to the probes going through it
+///
+/// Arrival times are measured on a monotonic clock local to the tracer,
+/// which is also the one completing the record when the probe returns.
+pub struct Tracer {
+    identifier: Option<IdentityIdentifier>,
+    started_at: Instant,
+}
+
+impl Tracer {
+    pub fn new(identifier: Option<IdentityIdentifier>) -> Self {
+        Self {
+            identifier,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Microseconds elapsed since this tracer started
+    fn now_micros(&self) -> u64 {
+        self.started_at.elapsed().as_micros() as u64
+    }
+}
+
+#[ockam::worker]
+impl Worker for Tracer {
+    type Context = Context;
+    type Message = Any;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        let mut message = msg.into_local_message();
+        let transport_message = message.transport_mut();
+        let mut probe = TraceProbe::decode_payload(&transport_message.payload)?;
+        let now = self.now_micros();
+
+        // Remove my address from the onward_route
+        transport_message.onward_route.step()?;
+
+        if probe.returning {
+            // The last record without a dwell time is the one of this tracer
+            match probe.records.iter_mut().rev().find(|r| r.dwell.is_none()) {
+                Some(record) => {
+                    record.dwell =
+                        Some(Duration::from_micros(now.saturating_sub(record.arrived_at)))
+                }
+                None => {
+                    log::warn!("dropping a returning trace probe without a record");
+                    return Ok(());
+                }
+            }
+        } else {
+            probe.records.push(TraceRecord {
+                identifier: self.identifier.as_ref().map(|i| i.to_string()),
+                arrived_at: now,
+                dwell: None,
+            });
+
+            // The route ends here, send the probe back
+            if transport_message.onward_route.is_empty() {
+                if let Some(record) = probe.records.last_mut() {
+                    record.dwell = Some(Duration::ZERO);
+                }
+                probe.returning = true;
+                transport_message.onward_route = transport_message.return_route.clone();
+                transport_message.return_route = ctx.address().into();
+                transport_message.payload = probe.encode_payload()?;
+                return ctx.forward(message).await;
+            }
+        }
+
+        // Insert my address at the beginning return_route
+        transport_message
+            .return_route
+            .modify()
+            .prepend(ctx.address());
+        transport_message.payload = probe.encode_payload()?;
+
+        // Send the probe on its onward_route
+        ctx.forward(message).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::{route, AllowAll};
+
+    #[ockam_macros::test(timeout = 5_000)]
+    async fn every_tracer_appends_a_record(ctx: &mut Context) -> Result<()> {
+        for address in ["tracer_1", "tracer_2", "tracer_3"] {
+            ctx.start_worker(address, Tracer::new(None), AllowAll, AllowAll)
+                .await?;
+        }
+
+        let bytes = minicbor::to_vec(TraceProbe::new())?;
+        let reply: Vec<u8> = ctx
+            .send_and_receive(route!["tracer_1", "tracer_2", "tracer_3"], bytes)
+            .await?;
+        let probe: TraceProbe = minicbor::decode(&reply)?;
+
+        assert!(probe.returning);
+        assert_eq!(probe.records.len(), 3);
+        let dwells: Vec<Duration> = probe.records.iter().map(|r| r.dwell.unwrap()).collect();
+        assert_eq!(dwells[2], Duration::ZERO);
+        assert!(dwells[0] >= dwells[1]);
+
+        ctx.stop().await
+    }
+}
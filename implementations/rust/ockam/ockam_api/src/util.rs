@@ -45,6 +45,8 @@ pub fn local_multiaddr_to_route(ma: &MultiAddr) -> Option<Route> {
 pub struct MultiAddrToRouteResult {
     pub flow_control_id: Option<FlowControlId>,
     pub route: Route,
+    /// Address of the TCP connection created for the route, if any
+    pub tcp_connection: Option<Address>,
 }
 
 pub async fn multiaddr_to_route(
@@ -56,6 +58,7 @@ pub async fn multiaddr_to_route(
     let mut it = ma.iter().peekable();
 
     let mut flow_control_id = None;
+    let mut tcp_connection = None;
     let mut number_of_tcp_hops = 0;
 
     while let Some(p) = it.next() {
@@ -80,6 +83,7 @@ pub async fn multiaddr_to_route(
 
                 let addr = tcp.connect(socket_addr.to_string(), options).await.ok()?;
                 number_of_tcp_hops += 1;
+                tcp_connection = Some(addr.clone());
                 rb = rb.append(addr)
             }
             Ip6::CODE => {
@@ -102,6 +106,7 @@ pub async fn multiaddr_to_route(
 
                 let addr = tcp.connect(socket_addr.to_string(), options).await.ok()?;
                 number_of_tcp_hops += 1;
+                tcp_connection = Some(addr.clone());
                 rb = rb.append(addr)
             }
             DnsAddr::CODE => {
@@ -123,6 +128,7 @@ pub async fn multiaddr_to_route(
                             .await
                             .ok()?;
                         number_of_tcp_hops += 1;
+                        tcp_connection = Some(addr.clone());
                         rb = rb.append(addr);
                         let _ = it.next();
                         continue;
@@ -151,6 +157,7 @@ pub async fn multiaddr_to_route(
     Some(MultiAddrToRouteResult {
         flow_control_id,
         route: rb.into(),
+        tcp_connection,
     })
 }
 
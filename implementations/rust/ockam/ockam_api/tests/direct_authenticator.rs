@@ -0,0 +1,244 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use ockam::identity::credential::{AttributeSchema, AttributeType, CredentialSchema, Timestamp};
+use ockam::identity::{
+    AttributesEntry, InMemoryStorage, RevocationsStorage, PROJECT_MEMBER_SCHEMA,
+};
+use ockam::route;
+use ockam_api::authenticator::direct::{
+    DirectAuthenticator, DirectAuthenticatorClient, EnrollmentTokenAuthenticator, EnrollmentTokens,
+    TokenIssuerClient,
+};
+use ockam_core::compat::rand::random_string;
+use ockam_core::{AllowAll, Result};
+use ockam_identity::{
+    Identity, SecureChannelListenerOptions, SecureChannelOptions, SecureChannels,
+};
+use ockam_node::{Context, RpcClient};
+
+#[ockam_macros::test]
+async fn enrollers_manage_members(ctx: &mut Context) -> Result<()> {
+    let setup = Setup::create(ctx).await?;
+    let enroller = setup.client(ctx, &setup.enroller).await?;
+    let outsider = setup.client(ctx, &setup.outsider).await?;
+    let member = setup.member.identifier();
+
+    // Any identity allowed to access the service can add members
+    outsider
+        .add_member(member.clone(), HashMap::from([("role", "user")]), None)
+        .await?;
+
+    // Only enrollers can read them
+    assert!(outsider.list_members().await.is_err());
+    assert!(outsider.show_member(&member).await.is_err());
+    let members = enroller.list_members().await?;
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0].0, member);
+    let entry = enroller.show_member(&member).await?;
+    assert_eq!(
+        entry.attrs().get("role").map(|v| v.as_slice()),
+        Some(b"user".as_slice())
+    );
+    assert_eq!(entry.attested_by(), Some(setup.outsider.identifier()));
+
+    // Only enrollers can remove them, which revokes their credentials
+    assert!(outsider.remove_member(&member).await.is_err());
+    assert!(setup.revocations.list().await?.is_empty());
+    enroller.remove_member(&member).await?;
+    assert!(enroller.show_member(&member).await.is_err());
+    assert!(enroller.list_members().await?.is_empty());
+    let revoked = setup.revocations.list().await?;
+    assert_eq!(revoked.len(), 1);
+    assert_eq!(revoked[0].subject(), &member);
+
+    // A removed member is not found anymore
+    assert!(enroller.remove_member(&member).await.is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn identities_which_are_not_members_cannot_be_removed(ctx: &mut Context) -> Result<()> {
+    let setup = Setup::create(ctx).await?;
+    let enroller = setup.client(ctx, &setup.enroller).await?;
+
+    // The enroller has attributes but they were not given by the trust context
+    assert!(enroller
+        .remove_member(&setup.enroller.identifier())
+        .await
+        .is_err());
+    assert!(enroller.list_members().await?.is_empty());
+    assert!(setup.revocations.list().await?.is_empty());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn members_attributes_must_conform_to_the_member_schema(ctx: &mut Context) -> Result<()> {
+    let setup = Setup::create(ctx).await?;
+    let enroller = setup.client(ctx, &setup.enroller).await?;
+    let member = setup.member.identifier();
+
+    setup
+        .secure_channels
+        .identities()
+        .credential_schemas()
+        .register(
+            CredentialSchema::new(PROJECT_MEMBER_SCHEMA, "member").with_attribute(
+                AttributeSchema::new("role", AttributeType::Utf8)
+                    .required()
+                    .with_allowed_values(vec!["user".to_string()]),
+            ),
+        );
+
+    // Members which would not get a valid credential are rejected
+    assert!(enroller
+        .add_member(member.clone(), HashMap::from([("role", "admin")]), None)
+        .await
+        .is_err());
+    assert!(enroller
+        .add_member(member.clone(), HashMap::from([("team", "blue")]), None)
+        .await
+        .is_err());
+    assert!(enroller.list_members().await?.is_empty());
+
+    enroller
+        .add_member(member.clone(), HashMap::from([("role", "user")]), None)
+        .await?;
+    assert_eq!(enroller.list_members().await?.len(), 1);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn token_durations_are_limited_by_the_configured_maximum(ctx: &mut Context) -> Result<()> {
+    let setup = Setup::create(ctx).await?;
+    let repository = setup.secure_channels.identities().repository();
+    let (issuer, _) = EnrollmentTokenAuthenticator::new_worker_pair(
+        "project42".into(),
+        repository.as_attributes_writer(),
+        EnrollmentTokens::new(InMemoryStorage::create()),
+    );
+    let issuer_address = random_string();
+    ctx.start_worker(
+        &issuer_address,
+        issuer.with_max_token_duration(Duration::from_secs(3600)),
+        AllowAll,
+        AllowAll,
+    )
+    .await?;
+
+    let channel = setup
+        .secure_channels
+        .create_secure_channel(
+            ctx,
+            &setup.enroller,
+            &setup.listener,
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    let client = TokenIssuerClient::new(
+        RpcClient::new(route![channel.address(), &issuer_address], ctx).await?,
+    );
+
+    // Tokens can be valid for longer than the default duration, up to the maximum
+    client
+        .create_token(
+            HashMap::new(),
+            Some(Duration::from_secs(3600)),
+            Some(10),
+            None,
+        )
+        .await?;
+    assert!(client
+        .create_token(HashMap::new(), Some(Duration::from_secs(3601)), None, None)
+        .await
+        .is_err());
+    assert_eq!(client.list_tokens().await?.len(), 1);
+
+    ctx.stop().await
+}
+
+struct Setup {
+    secure_channels: Arc<SecureChannels>,
+    revocations: RevocationsStorage,
+    listener: String,
+    authenticator: String,
+    enroller: Identity,
+    outsider: Identity,
+    member: Identity,
+}
+
+impl Setup {
+    async fn create(ctx: &Context) -> Result<Self> {
+        let secure_channels = SecureChannels::builder().build();
+        let identities_creation = secure_channels.identities().identities_creation();
+        let authority = identities_creation.create_identity().await?;
+        let enroller = identities_creation.create_identity().await?;
+        let outsider = identities_creation.create_identity().await?;
+        let member = identities_creation.create_identity().await?;
+
+        let repository = secure_channels.identities().repository();
+        repository
+            .put_attributes(
+                &enroller.identifier(),
+                AttributesEntry::new(
+                    BTreeMap::from([("ockam-role".to_string(), b"enroller".to_vec())]),
+                    Timestamp::now().unwrap(),
+                    None,
+                    None,
+                ),
+            )
+            .await?;
+
+        let listener = random_string();
+        secure_channels
+            .create_secure_channel_listener(
+                ctx,
+                &authority,
+                &listener,
+                SecureChannelListenerOptions::new(),
+            )
+            .await?;
+
+        let revocations = RevocationsStorage::new(InMemoryStorage::create());
+        let authenticator = random_string();
+        let worker = DirectAuthenticator::new(
+            "project42".into(),
+            repository.as_attributes_writer(),
+            repository.as_attributes_reader(),
+        )
+        .await?
+        .with_revocations(revocations.clone())
+        .with_credential_schemas(secure_channels.identities().credential_schemas());
+        ctx.start_worker(&authenticator, worker, AllowAll, AllowAll)
+            .await?;
+
+        Ok(Self {
+            secure_channels,
+            revocations,
+            listener,
+            authenticator,
+            enroller,
+            outsider,
+            member,
+        })
+    }
+
+    async fn client(
+        &self,
+        ctx: &Context,
+        identity: &Identity,
+    ) -> Result<DirectAuthenticatorClient> {
+        let channel = self
+            .secure_channels
+            .create_secure_channel(ctx, identity, &self.listener, SecureChannelOptions::new())
+            .await?;
+        let route = route![channel.address(), &self.authenticator];
+        Ok(DirectAuthenticatorClient::new(
+            RpcClient::new(route, ctx).await?,
+        ))
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use minicbor::Decoder;
+use ockam::identity::credential::Timestamp;
+use ockam::identity::{AttributesEntry, InMemoryStorage};
+use ockam::route;
+use ockam_abac::mem::Memory;
+use ockam_abac::{Action, Expr, PolicyBundleEntry, PolicyStorage, Resource};
+use ockam_api::nodes::authority_node::{
+    apply_policy_bundle, PolicyBundleIssuer, PolicyBundlesPublisherClient, PolicyBundlesStorage,
+};
+use ockam_api::nodes::models::policy::PublishPolicyBundle;
+use ockam_core::api::{Request, Response, Status};
+use ockam_core::flow_control::FlowControls;
+use ockam_core::{AllowAll, Result};
+use ockam_identity::{
+    AuthorityService, RemoteCredentialsRetriever, RemoteCredentialsRetrieverInfo,
+    SecureChannelListenerOptions, SecureChannelOptions, SecureChannels,
+};
+use ockam_node::{Context, RpcClient};
+
+#[ockam_macros::test]
+async fn policy_bundles_are_published_and_applied(ctx: &mut Context) -> Result<()> {
+    let secure_channels = SecureChannels::builder().build();
+    let identities = secure_channels.identities();
+    let identities_creation = identities.identities_creation();
+    let authority = identities_creation.create_identity().await?;
+    let publisher = identities_creation.create_identity().await?;
+    let member = identities_creation.create_identity().await?;
+
+    let repository = identities.repository();
+    repository
+        .put_attributes(
+            &publisher.identifier(),
+            AttributesEntry::new(
+                BTreeMap::from([("ockam-role".to_string(), b"policy-publisher".to_vec())]),
+                Timestamp::now().unwrap(),
+                None,
+                None,
+            ),
+        )
+        .await?;
+
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &authority,
+            "authority_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+    let issuer = PolicyBundleIssuer::new(
+        identities.clone(),
+        authority.clone(),
+        repository.as_attributes_reader(),
+        PolicyBundlesStorage::new(InMemoryStorage::create()),
+    );
+    ctx.start_worker("policy_bundles", issuer, AllowAll, AllowAll)
+        .await?;
+
+    // Only the policy publishers can publish a bundle
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &member,
+            route!["authority_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    let req = Request::post("/").body(PublishPolicyBundle::new(vec![]));
+    let res: Vec<u8> = ctx
+        .send_and_receive(route![channel, "policy_bundles"], req.to_vec()?)
+        .await?;
+    let res: Response = Decoder::new(&res).decode()?;
+    assert_eq!(res.status(), Some(Status::Forbidden));
+
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &publisher,
+            route!["authority_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    let client = PolicyBundlesPublisherClient::new(
+        RpcClient::new(route![channel, "policy_bundles"], ctx).await?,
+    );
+    let (r, a) = (Resource::new("tcp-outlet"), Action::new("handle_message"));
+    let entry = |expr: &str| -> Result<Vec<PolicyBundleEntry>> {
+        Ok(vec![PolicyBundleEntry::new(
+            r.clone(),
+            a.clone(),
+            expr.parse()?,
+        )])
+    };
+    let published = client.publish(entry("(= subject.role \"admin\")")?).await?;
+    assert_eq!(published.version(), 1);
+
+    // The published bundle is served to the members
+    let retriever = RemoteCredentialsRetriever::new(
+        secure_channels.clone(),
+        RemoteCredentialsRetrieverInfo::new(
+            authority.clone(),
+            route!["authority_listener"],
+            "credential_issuer".into(),
+        )
+        .with_policy_bundles_address("policy_bundles".into()),
+        FlowControls::default(),
+    );
+    let authority_service = AuthorityService::new(
+        identities.credentials(),
+        authority.clone(),
+        Some(Arc::new(retriever)),
+    );
+    let first = authority_service
+        .retrieve_policy_bundle(ctx, &member, None)
+        .await?
+        .unwrap();
+
+    // and applied by the nodes, once
+    let policies = Memory::new();
+    let (bundle, changed) =
+        apply_policy_bundle(&identities, &authority, &first, &policies, None, 0)
+            .await?
+            .unwrap();
+    assert_eq!((bundle.version(), changed), (1, 1));
+    assert!(
+        apply_policy_bundle(&identities, &authority, &first, &policies, None, 1)
+            .await?
+            .is_none()
+    );
+
+    client.publish(entry("(= subject.role \"user\")")?).await?;
+    let second = authority_service
+        .retrieve_policy_bundle(ctx, &member, None)
+        .await?
+        .unwrap();
+    let (bundle, _) = apply_policy_bundle(&identities, &authority, &second, &policies, None, 1)
+        .await?
+        .unwrap();
+    assert_eq!(bundle.version(), 2);
+    let user = "(= subject.role \"user\")".parse::<Expr>()?.to_string();
+    let current = policies.get_policy(&r, &a).await?;
+    assert_eq!(current.map(|e| e.to_string()), Some(user.clone()));
+
+    // A downgrade is refused
+    assert!(
+        apply_policy_bundle(&identities, &authority, &first, &policies, None, 2)
+            .await
+            .is_err()
+    );
+    let current = policies.get_policy(&r, &a).await?;
+    assert_eq!(current.map(|e| e.to_string()), Some(user));
+
+    // With a pinned version, only that version is applied
+    let pinned = authority_service
+        .retrieve_policy_bundle(ctx, &member, Some(1))
+        .await?
+        .unwrap();
+    assert!(
+        apply_policy_bundle(&identities, &authority, &second, &policies, Some(1), 2)
+            .await
+            .is_err()
+    );
+    let (bundle, _) = apply_policy_bundle(&identities, &authority, &pinned, &policies, Some(1), 2)
+        .await?
+        .unwrap();
+    assert_eq!(bundle.version(), 1);
+
+    // A bundle signed by another authority is refused
+    assert!(
+        apply_policy_bundle(&identities, &member, &second, &policies, None, 0)
+            .await
+            .is_err()
+    );
+
+    ctx.stop().await
+}
@@ -0,0 +1,86 @@
+use ockam::route;
+use ockam_abac::{AbacAccessControl, Env, Expr};
+use ockam_api::echoer::Echoer;
+use ockam_core::{AllowAll, Result};
+use ockam_identity::secure_channels::secure_channels;
+use ockam_identity::{SecureChannelListenerOptions, SecureChannelOptions};
+use ockam_node::{Context, MessageReceiveOptions};
+use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
+
+#[ockam_macros::test]
+async fn channel_and_transport_facts_are_bound(ctx: &mut Context) -> Result<()> {
+    let server_secure_channels = secure_channels();
+    let client_secure_channels = secure_channels();
+    let server = server_secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let client = client_secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+
+    let repository = server_secure_channels.identities().repository();
+    let responder_only: Expr =
+        r#"(and (= channel.initiator false) (= transport.peer_ip "127.0.0.1"))"#.parse()?;
+    let initiator_only: Expr = "(= channel.initiator true)".parse()?;
+    ctx.start_worker(
+        "echoer_responder",
+        Echoer,
+        AbacAccessControl::new(repository.clone(), responder_only, Env::new()),
+        AllowAll,
+    )
+    .await?;
+    ctx.start_worker(
+        "echoer_initiator",
+        Echoer,
+        AbacAccessControl::new(repository, initiator_only, Env::new()),
+        AllowAll,
+    )
+    .await?;
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let (socket_addr, _) = tcp.listen("127.0.0.1:0", TcpListenerOptions::new()).await?;
+    server_secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &server,
+            "listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+
+    let connection = tcp
+        .connect(socket_addr.to_string(), TcpConnectionOptions::new())
+        .await?;
+    let channel = client_secure_channels
+        .create_secure_channel(
+            ctx,
+            &client,
+            route![connection, "listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    // The server is the responder of the channel and the client connects from localhost
+    ctx.send(
+        route![channel.clone(), "echoer_responder"],
+        "hello".to_string(),
+    )
+    .await?;
+    let reply = ctx
+        .receive_extended::<String>(MessageReceiveOptions::new().with_timeout_secs(1))
+        .await?;
+    assert_eq!(reply.body(), "hello");
+
+    ctx.send(route![channel, "echoer_initiator"], "hello".to_string())
+        .await?;
+    let reply = ctx
+        .receive_extended::<String>(MessageReceiveOptions::new().with_timeout_secs(1))
+        .await;
+    assert!(reply.is_err());
+
+    ctx.stop().await
+}
@@ -0,0 +1,280 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ockam::identity::credential::Timestamp;
+use ockam::route;
+use ockam_abac::mem::Memory;
+use ockam_abac::{Env, Expr, PolicyAccessControl, PolicyStorage};
+use ockam_api::{actions, resources};
+use ockam_core::{AllowAll, Result};
+use ockam_identity::secure_channels::secure_channels;
+use ockam_identity::{
+    identities, AttributesEntry, SecureChannelListenerOptions, SecureChannelOptions,
+};
+use ockam_node::Context;
+use ockam_transport_tcp::{TcpInletOptions, TcpOutletOptions, TcpTransport};
+use tokio::io::AsyncReadExt;
+use tokio::net::{TcpListener, TcpStream};
+
+/// Return an outgoing access control evaluating the 'send_message' policy of a resource
+fn send_message_access_control(
+    policies: Arc<Memory>,
+    resource: ockam_abac::Resource,
+) -> PolicyAccessControl {
+    PolicyAccessControl::new(
+        policies,
+        identities().repository(),
+        resource,
+        actions::SEND_MESSAGE,
+        Env::new(),
+    )
+    .with_secure_channel_registry(secure_channels().secure_channel_registry())
+}
+
+#[ockam_macros::test]
+async fn outlet_connections_are_checked_with_the_current_handle_message_policy(
+    ctx: &mut Context,
+) -> Result<()> {
+    let policies = Arc::new(Memory::new());
+    policies
+        .set_policy(
+            &resources::OUTLET,
+            &actions::HANDLE_MESSAGE,
+            &Expr::Bool(false),
+        )
+        .await?;
+
+    // There is no policy for the 'connect' action
+    let connect_access_control = PolicyAccessControl::new(
+        policies.clone(),
+        identities().repository(),
+        resources::OUTLET,
+        actions::CONNECT,
+        Env::new(),
+    )
+    .with_fallback_action(actions::HANDLE_MESSAGE);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let tcp = TcpTransport::create(ctx).await?;
+    tcp.create_outlet(
+        "outlet",
+        listener.local_addr().unwrap().to_string(),
+        TcpOutletOptions::new()
+            .with_incoming_access_control(Arc::new(AllowAll))
+            .with_connect_access_control(Arc::new(connect_access_control)),
+    )
+    .await?;
+    let (inlet_addr, _) = tcp
+        .create_inlet("127.0.0.1:0", route!["outlet"], TcpInletOptions::new())
+        .await?;
+
+    // The connection is denied, the outlet never connects to the listener
+    let _denied = TcpStream::connect(inlet_addr).await.unwrap();
+    let accepted = tokio::time::timeout(Duration::from_secs(1), listener.accept()).await;
+    assert!(accepted.is_err());
+
+    // Updating the 'handle_message' policy applies to the next connections
+    policies
+        .set_policy(
+            &resources::OUTLET,
+            &actions::HANDLE_MESSAGE,
+            &Expr::Bool(true),
+        )
+        .await?;
+    let _allowed = TcpStream::connect(inlet_addr).await.unwrap();
+    let accepted = tokio::time::timeout(Duration::from_secs(1), listener.accept()).await;
+    assert!(accepted.is_ok());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn outlets_do_not_connect_to_denied_inlets(ctx: &mut Context) -> Result<()> {
+    let policies = Arc::new(Memory::new());
+    policies
+        .set_policy(
+            &resources::OUTLET,
+            &actions::SEND_MESSAGE,
+            &Expr::Bool(false),
+        )
+        .await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let tcp = TcpTransport::create(ctx).await?;
+    tcp.create_outlet(
+        "outlet",
+        listener.local_addr().unwrap().to_string(),
+        TcpOutletOptions::new().with_outgoing_access_control(Arc::new(
+            send_message_access_control(policies.clone(), resources::OUTLET),
+        )),
+    )
+    .await?;
+    let (inlet_addr, _) = tcp
+        .create_inlet("127.0.0.1:0", route!["outlet"], TcpInletOptions::new())
+        .await?;
+
+    // The inlet is denied when the portal connects, the outlet doesn't connect
+    let _denied = TcpStream::connect(inlet_addr).await.unwrap();
+    let accepted = tokio::time::timeout(Duration::from_secs(1), listener.accept()).await;
+    assert!(accepted.is_err());
+
+    policies
+        .set_policy(
+            &resources::OUTLET,
+            &actions::SEND_MESSAGE,
+            &Expr::Bool(true),
+        )
+        .await?;
+    let _allowed = TcpStream::connect(inlet_addr).await.unwrap();
+    let accepted = tokio::time::timeout(Duration::from_secs(1), listener.accept()).await;
+    assert!(accepted.is_ok());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn inlets_to_denied_outlets_are_closed(ctx: &mut Context) -> Result<()> {
+    let policies = Arc::new(Memory::new());
+    policies
+        .set_policy(
+            &resources::INLET,
+            &actions::SEND_MESSAGE,
+            &Expr::Bool(false),
+        )
+        .await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let tcp = TcpTransport::create(ctx).await?;
+    tcp.create_outlet(
+        "outlet",
+        listener.local_addr().unwrap().to_string(),
+        TcpOutletOptions::new(),
+    )
+    .await?;
+    let (inlet_addr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_outgoing_access_control(Arc::new(
+                send_message_access_control(policies, resources::INLET),
+            )),
+        )
+        .await?;
+
+    // The outlet is denied when the portal connects, the inlet connection is closed
+    let mut stream = TcpStream::connect(inlet_addr).await.unwrap();
+    let mut buffer = [0u8; 8];
+    let read = tokio::time::timeout(Duration::from_secs(2), stream.read(&mut buffer))
+        .await
+        .unwrap();
+    assert!(matches!(read, Ok(0) | Err(_)));
+    let accepted = tokio::time::timeout(Duration::from_millis(500), listener.accept()).await;
+    assert!(accepted.is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn outlets_check_the_attributes_of_the_inlets_secure_channel_peer(
+    ctx: &mut Context,
+) -> Result<()> {
+    let server_channels = secure_channels();
+    let client_channels = secure_channels();
+    let server = server_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let admin = client_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let user = client_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+
+    // The outlet node knows the roles of the identities of the inlet nodes
+    let repository = server_channels.identities().repository();
+    for (identity, role) in [(&admin, "admin"), (&user, "user")] {
+        repository
+            .put_attributes(
+                &identity.identifier(),
+                AttributesEntry::new(
+                    BTreeMap::from([("role".to_string(), role.as_bytes().to_vec())]),
+                    Timestamp::now().unwrap(),
+                    None,
+                    None,
+                ),
+            )
+            .await?;
+    }
+    let policies = Arc::new(Memory::new());
+    policies
+        .set_policy(
+            &resources::OUTLET,
+            &actions::SEND_MESSAGE,
+            &r#"(= subject.role "admin")"#.parse()?,
+        )
+        .await?;
+
+    server_channels
+        .create_secure_channel_listener(
+            ctx,
+            &server,
+            "listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let tcp = TcpTransport::create(ctx).await?;
+    let outgoing_access_control = PolicyAccessControl::new(
+        policies,
+        repository,
+        resources::OUTLET,
+        actions::SEND_MESSAGE,
+        Env::new(),
+    )
+    .with_secure_channel_registry(server_channels.secure_channel_registry());
+    tcp.create_outlet(
+        "outlet",
+        listener.local_addr().unwrap().to_string(),
+        TcpOutletOptions::new().with_outgoing_access_control(Arc::new(outgoing_access_control)),
+    )
+    .await?;
+
+    // Each inlet reaches the outlet through its own secure channel
+    let mut inlets = Vec::new();
+    for identity in [&admin, &user] {
+        let channel = client_channels
+            .create_secure_channel(
+                ctx,
+                identity,
+                route!["listener"],
+                SecureChannelOptions::new(),
+            )
+            .await?;
+        let (inlet_addr, _) = tcp
+            .create_inlet(
+                "127.0.0.1:0",
+                route![channel, "outlet"],
+                TcpInletOptions::new(),
+            )
+            .await?;
+        inlets.push(inlet_addr);
+    }
+
+    // The role of the peer of the channel carrying the replies of the outlet is checked
+    let _denied = TcpStream::connect(inlets[1]).await.unwrap();
+    let accepted = tokio::time::timeout(Duration::from_secs(1), listener.accept()).await;
+    assert!(accepted.is_err());
+
+    let _allowed = TcpStream::connect(inlets[0]).await.unwrap();
+    let accepted = tokio::time::timeout(Duration::from_secs(1), listener.accept()).await;
+    assert!(accepted.is_ok());
+
+    ctx.stop().await
+}
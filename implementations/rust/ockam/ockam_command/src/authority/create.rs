@@ -14,10 +14,11 @@ use ockam_api::nodes::models::transport::{CreateTransportJson, TransportMode, Tr
 use ockam_api::DefaultAddress;
 use ockam_core::compat::collections::HashMap;
 use ockam_core::compat::fmt;
-use ockam_identity::{AttributesEntry, IdentityIdentifier};
+use ockam_identity::{AttributesEntry, CredentialSchema, IdentityIdentifier};
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::path::PathBuf;
+use std::time::Duration;
 use tracing::error;
 
 /// Create a node
@@ -53,6 +54,11 @@ pub struct CreateCommand {
     #[arg(long, value_name = "BOOL", default_value_t = false)]
     no_token_enrollment: bool,
 
+    /// Maximum number of seconds for which an enrollment token can be valid.
+    /// The default is 30 days
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    enrollment_token_max_duration: Option<u64>,
+
     /// List of the trusted identities, and corresponding attributes to be preload in the attributes storage.
     /// Format: {"identifier1": {"attribute1": "value1", "attribute2": "value12"}, ...}
     #[arg(group = "trusted", long, value_name = "JSON_OBJECT", value_parser=parse_trusted_identities)]
@@ -63,6 +69,12 @@ pub struct CreateCommand {
     #[arg(group = "trusted", long, value_name = "PATH")]
     reload_from_trusted_identities_file: Option<PathBuf>,
 
+    /// Path of a file containing the schemas that the issued credentials must conform to,
+    /// encoded as a JSON array.
+    /// Format: [{"id": 1, "name": "member", "attributes": [{"name": "role", "type": "utf8", "required": true, "allowed_values": ["admin", "member"]}]}]
+    #[arg(long, value_name = "PATH")]
+    credential_schemas: Option<PathBuf>,
+
     /// Okta: URL used for accessing the Okta API (optional)
     #[arg(long, value_name = "URL", default_value = None)]
     tenant_base_url: Option<String>,
@@ -125,6 +137,11 @@ async fn spawn_background_node(opts: &CommandGlobalOpts, cmd: &CreateCommand) ->
         args.push("--no-token-enrollment".to_string());
     }
 
+    if let Some(enrollment_token_max_duration) = cmd.enrollment_token_max_duration {
+        args.push("--enrollment-token-max-duration".to_string());
+        args.push(enrollment_token_max_duration.to_string());
+    }
+
     if let Some(trusted_identities) = &cmd.trusted_identities {
         args.push("--trusted-identities".to_string());
         args.push(trusted_identities.to_string());
@@ -139,6 +156,11 @@ async fn spawn_background_node(opts: &CommandGlobalOpts, cmd: &CreateCommand) ->
         );
     }
 
+    if let Some(credential_schemas) = &cmd.credential_schemas {
+        args.push("--credential-schemas".to_string());
+        args.push(credential_schemas.to_string_lossy().to_string());
+    }
+
     if let Some(tenant_base_url) = &cmd.tenant_base_url {
         args.push("--tenant-base-url".to_string());
         args.push(tenant_base_url.clone());
@@ -209,6 +231,22 @@ impl CreateCommand {
             )),
         }
     }
+
+    /// Return the credential schemas declared in the credential schemas file, if any
+    pub(crate) fn credential_schemas(&self) -> Result<Vec<CredentialSchema>> {
+        match &self.credential_schemas {
+            Some(path) => {
+                let contents = std::fs::read_to_string(path)?;
+                serde_json::from_str(&contents).map_err(|e| {
+                    crate::Error::new(
+                        exitcode::CONFIG,
+                        anyhow!("Cannot parse the credential schemas: {e}"),
+                    )
+                })
+            }
+            None => Ok(vec![]),
+        }
+    }
 }
 
 /// Given a Context start a node in a new OS process
@@ -300,6 +338,7 @@ async fn start_authority_node(
 
     let trusted_identities =
         &command.trusted_identities(&command.project_identifier.clone(), &identity.identifier())?;
+    let credential_schemas = command.credential_schemas()?;
 
     let configuration = authority_node::Configuration {
         identity,
@@ -313,6 +352,10 @@ async fn start_authority_node(
         trusted_identities: trusted_identities.clone(),
         no_direct_authentication: command.no_direct_authentication,
         no_token_enrollment: command.no_token_enrollment,
+        enrollment_token_max_duration: command
+            .enrollment_token_max_duration
+            .map(Duration::from_secs),
+        credential_schemas,
         okta: okta_configuration,
     };
     authority_node::start_node(&ctx, &configuration).await?;
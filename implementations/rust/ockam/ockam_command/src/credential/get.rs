@@ -16,6 +16,10 @@ pub struct GetCommand {
 
     #[arg(long = "identity", value_name = "IDENTITY")]
     identity: Option<String>,
+
+    /// Get a credential whose attributes can be disclosed one by one
+    #[arg(long, conflicts_with = "overwrite")]
+    pub selective: bool,
 }
 
 impl GetCommand {
@@ -34,10 +38,15 @@ async fn run_impl(
     cmd: GetCommand,
 ) -> crate::Result<()> {
     let mut rpc = Rpc::background(ctx, &opts, &cmd.node_opts.api_node)?;
-    rpc.request(api::credentials::get_credential(
-        cmd.overwrite,
-        cmd.identity,
-    ))
-    .await?;
+    if cmd.selective {
+        rpc.request(api::credentials::get_selective_credential(cmd.identity))
+            .await?;
+    } else {
+        rpc.request(api::credentials::get_credential(
+            cmd.overwrite,
+            cmd.identity,
+        ))
+        .await?;
+    }
     Ok(())
 }
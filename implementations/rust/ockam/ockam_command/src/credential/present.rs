@@ -18,6 +18,10 @@ pub struct PresentCommand {
 
     #[arg(short, long)]
     pub oneway: bool,
+
+    /// Present a selective credential disclosing only this attribute, can be repeated
+    #[arg(long = "disclose", value_name = "ATTRIBUTE", requires = "oneway")]
+    pub disclose: Vec<String>,
 }
 
 impl PresentCommand {
@@ -39,7 +43,10 @@ async fn run_impl(
     cmd: PresentCommand,
 ) -> crate::Result<()> {
     let mut rpc = Rpc::background(ctx, &opts, &cmd.node_opts.api_node)?;
-    rpc.request(api::credentials::present_credential(&cmd.to, cmd.oneway))
-        .await?;
+    let disclose = (!cmd.disclose.is_empty()).then_some(cmd.disclose);
+    rpc.request(api::credentials::present_credential(
+        &cmd.to, cmd.oneway, disclose,
+    ))
+    .await?;
     Ok(())
 }
@@ -2,6 +2,7 @@ mod create;
 mod default;
 mod delete;
 mod list;
+mod rotate_key;
 mod show;
 
 use colorful::Colorful;
@@ -9,6 +10,7 @@ pub(crate) use create::CreateCommand;
 pub(crate) use delete::DeleteCommand;
 pub(crate) use list::ListCommand;
 use ockam_api::cli_state::CliState;
+pub(crate) use rotate_key::RotateKeyCommand;
 pub(crate) use show::ShowCommand;
 
 use crate::util::OckamConfig;
@@ -39,6 +41,7 @@ pub enum IdentitySubcommand {
     List(ListCommand),
     Default(DefaultCommand),
     Delete(DeleteCommand),
+    RotateKey(RotateKeyCommand),
 }
 
 impl IdentityCommand {
@@ -49,6 +52,7 @@ impl IdentityCommand {
             IdentitySubcommand::List(c) => c.run(options),
             IdentitySubcommand::Delete(c) => c.run(options),
             IdentitySubcommand::Default(c) => c.run(options),
+            IdentitySubcommand::RotateKey(c) => c.run(options),
         }
     }
 }
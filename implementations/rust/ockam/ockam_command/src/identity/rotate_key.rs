@@ -0,0 +1,92 @@
+use crate::identity::default_identity_name;
+use crate::util::output::Output;
+use crate::util::{api, node_rpc, parse_node_name, Rpc};
+use crate::{docs, CommandGlobalOpts, Result};
+use clap::Args;
+use core::fmt::Write;
+use ockam::identity::IdentitiesVault;
+use ockam::Context;
+use ockam_api::nodes::models::identity::RotateKeyResponse;
+use std::sync::Arc;
+
+const LONG_ABOUT: &str = include_str!("./static/rotate_key/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/rotate_key/after_long_help.txt");
+
+/// Rotate the root key of an identity
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RotateKeyCommand {
+    #[arg(default_value_t = default_identity_name())]
+    name: String,
+
+    /// Vault name storing the identity key
+    #[arg(long, conflicts_with = "node")]
+    vault: Option<String>,
+
+    /// Rotate the key of the identity of this running node instead
+    #[arg(long, value_name = "NODE")]
+    node: Option<String>,
+}
+
+impl RotateKeyCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self))
+    }
+}
+
+async fn run_impl(
+    ctx: Context,
+    (opts, cmd): (CommandGlobalOpts, RotateKeyCommand),
+) -> crate::Result<()> {
+    match &cmd.node {
+        Some(node_name) => {
+            let node_name = parse_node_name(node_name)?;
+            let mut rpc = Rpc::background(&ctx, &opts, &node_name)?;
+            rpc.request(api::rotate_identity_key()).await?;
+            let res = rpc.parse_response::<RotateKeyResponse>()?;
+            rpc.print_response(res)?;
+        }
+        None => {
+            let vault: Arc<dyn IdentitiesVault> = match &cmd.vault {
+                Some(vault) => Arc::new(opts.state.vaults.get(vault)?.get().await?),
+                None => Arc::new(opts.state.vaults.default()?.get().await?),
+            };
+            let mut state = opts.state.identities.get(&cmd.name)?;
+            let mut identity = state.get(vault.clone()).await?;
+            let identities = state.make_identities(vault).await?;
+            identities
+                .identities_keys()
+                .rotate_root_key(&mut identity)
+                .await?;
+            identities
+                .repository()
+                .update_known_identity(&identity)
+                .await?;
+            state.update_change_history(&identity)?;
+
+            opts.shell
+                .stdout()
+                .plain(format!(
+                    "Root key rotated for identity: {}",
+                    identity.identifier()
+                ))
+                .machine(identity.identifier())
+                .json(&serde_json::json!({ "identity": { "identifier": &identity.identifier() } }))
+                .write_line()?;
+        }
+    }
+    Ok(())
+}
+
+impl Output for RotateKeyResponse<'_> {
+    fn output(&self) -> Result<String> {
+        let mut w = String::new();
+        writeln!(w, "Root key rotated for identity: {}", self.identity_id)?;
+        writeln!(w, "Secure channel peers updated: {}", self.updated_peers)?;
+        write!(w, "Authority updated: {}", self.authority_updated)?;
+        Ok(w)
+    }
+}
@@ -1,8 +1,13 @@
 use crate::CommandGlobalOpts;
 use clap::{Args, Subcommand};
+pub use ping::PingCommand;
 pub use send::SendCommand;
+pub use trace::TraceCommand;
 
+mod ping;
 mod send;
+mod trace;
+mod util;
 
 /// Send and Receive Messages
 #[derive(Clone, Debug, Args)]
@@ -16,12 +21,18 @@ pub struct MessageCommand {
 pub enum MessageSubcommand {
     #[command(display_order = 800)]
     Send(SendCommand),
+    #[command(display_order = 801)]
+    Ping(PingCommand),
+    #[command(display_order = 802)]
+    Trace(TraceCommand),
 }
 
 impl MessageCommand {
     pub fn run(self, options: CommandGlobalOpts) {
         match self.subcommand {
             MessageSubcommand::Send(c) => c.run(options),
+            MessageSubcommand::Ping(c) => c.run(options),
+            MessageSubcommand::Trace(c) => c.run(options),
         }
     }
 }
@@ -0,0 +1,93 @@
+use clap::Args;
+
+use core::time::Duration;
+use ockam::Context;
+use ockam_api::nodes::service::message::{PingResult, PingRoute};
+use ockam_core::api::{Request, RequestBuilder};
+use ockam_multiaddr::MultiAddr;
+
+use super::util::RouteRequest;
+use crate::util::api::{CloudOpts, TrustContextOpts};
+use crate::util::node_rpc;
+use crate::Result;
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/ping/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/ping/after_long_help.txt");
+
+/// Measure the round trip time to a node
+#[derive(Clone, Debug, Args)]
+#[command(
+arg_required_else_help = true,
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct PingCommand {
+    /// The node to send the probes from
+    #[arg(short, long, value_name = "NODE")]
+    from: Option<String>,
+
+    /// The route to the node to ping
+    #[arg(short, long, value_name = "ROUTE")]
+    pub to: MultiAddr,
+
+    /// Number of probes to send
+    #[arg(short, long, value_name = "COUNT", default_value = "4")]
+    pub count: u16,
+
+    /// Timeout of each probe, in seconds
+    #[arg(long, value_name = "TIMEOUT", default_value = "5")]
+    pub timeout: u64,
+
+    #[command(flatten)]
+    cloud_opts: CloudOpts,
+
+    #[command(flatten)]
+    pub trust_context_opts: TrustContextOpts,
+}
+
+impl PingCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self))
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, PingCommand)) -> Result<()> {
+    let timeout = Duration::from_secs(cmd.timeout);
+    let request = RouteRequest {
+        from: cmd.from.as_deref(),
+        to: &cmd.to,
+        cloud_opts: &cmd.cloud_opts,
+        trust_context_opts: &cmd.trust_context_opts,
+        timeout: timeout * (cmd.count as u32 + 1),
+    };
+    let res: PingResult = request
+        .send(&ctx, &opts, |to| req(to, cmd.count, timeout))
+        .await?;
+
+    for (seq, rtt) in res.rtts.iter().enumerate() {
+        match rtt {
+            Some(rtt) => println!("{}: seq={} time={:?}", cmd.to, seq + 1, rtt),
+            None => println!("{}: seq={} timeout", cmd.to, seq + 1),
+        }
+    }
+    let received: Vec<Duration> = res.received().collect();
+    println!(
+        "{} probes sent, {} received",
+        res.rtts.len(),
+        received.len()
+    );
+    if let (Some(min), Some(max)) = (received.iter().min(), received.iter().max()) {
+        let avg = received.iter().sum::<Duration>() / received.len() as u32;
+        println!("rtt min/avg/max = {min:?}/{avg:?}/{max:?}");
+    }
+    Ok(())
+}
+
+pub(crate) fn req<'a>(
+    to: &MultiAddr,
+    count: u16,
+    timeout: Duration,
+) -> RequestBuilder<'a, PingRoute<'a>> {
+    Request::post("v0/message/ping").body(PingRoute::new(to, count, timeout))
+}
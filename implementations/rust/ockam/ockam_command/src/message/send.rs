@@ -2,15 +2,14 @@ use anyhow::Context as _;
 use clap::Args;
 
 use core::time::Duration;
-use ockam::{Context, TcpTransport};
-use ockam_api::nodes::models::secure_channel::CredentialExchangeMode;
+use ockam::Context;
 use ockam_api::nodes::service::message::SendMessage;
 use ockam_core::api::{Request, RequestBuilder};
 use ockam_multiaddr::MultiAddr;
 
-use crate::node::util::{delete_embedded_node, start_embedded_node_with_vault_and_identity};
+use super::util::RouteRequest;
 use crate::util::api::{CloudOpts, TrustContextOpts};
-use crate::util::{clean_nodes_multiaddr, extract_address_value, node_rpc, RpcBuilder};
+use crate::util::node_rpc;
 use crate::Result;
 use crate::{docs, CommandGlobalOpts};
 
@@ -52,63 +51,24 @@ impl SendCommand {
     }
 }
 
-async fn rpc(mut ctx: Context, (opts, cmd): (CommandGlobalOpts, SendCommand)) -> Result<()> {
-    async fn go(ctx: &mut Context, opts: &CommandGlobalOpts, cmd: SendCommand) -> Result<()> {
-        // Setup environment depending on whether we are sending the message from an embedded node or a background node
-        let (api_node, tcp) = if let Some(node) = &cmd.from {
-            let api_node = extract_address_value(node)?;
-            let tcp = TcpTransport::create(ctx).await?;
-            (api_node, Some(tcp))
-        } else {
-            let api_node = start_embedded_node_with_vault_and_identity(
-                ctx,
-                opts,
-                None,
-                Some(cmd.cloud_opts.identity.clone()),
-                Some(&cmd.trust_context_opts),
-            )
-            .await?;
-            (api_node, None)
-        };
-
-        // Process `--to` Multiaddr
-        let (to, meta) =
-            clean_nodes_multiaddr(&cmd.to, &opts.state).context("Argument '--to' is invalid")?;
-
-        // Replace `/project/<name>` occurrences with their respective secure channel addresses
-        let projects_sc = crate::project::util::get_projects_secure_channels_from_config_lookup(
-            ctx,
-            opts,
-            &meta,
-            &cmd.cloud_opts.route(),
-            &api_node,
-            tcp.as_ref(),
-            CredentialExchangeMode::Oneway,
-        )
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, SendCommand)) -> Result<()> {
+    let request = RouteRequest {
+        from: cmd.from.as_deref(),
+        to: &cmd.to,
+        cloud_opts: &cmd.cloud_opts,
+        trust_context_opts: &cmd.trust_context_opts,
+        timeout: Duration::from_secs(cmd.timeout),
+    };
+    let res: Vec<u8> = request
+        .send(&ctx, &opts, |to| req(to, &cmd.message))
         .await?;
-        let to = crate::project::util::clean_projects_multiaddr(to, projects_sc)?;
-        // Send request
-        let mut rpc = RpcBuilder::new(ctx, opts, &api_node)
-            .tcp(tcp.as_ref())?
-            .build();
-        rpc.request_with_timeout(req(&to, &cmd.message), Duration::from_secs(cmd.timeout))
-            .await?;
-        let res = rpc.parse_response::<Vec<u8>>()?;
-        println!(
-            "{}",
-            String::from_utf8(res).context("Received content is not a valid utf8 string")?
-        );
-
-        // only delete node in case 'from' is empty and embedded node was started before
-        if cmd.from.is_none() {
-            delete_embedded_node(opts, rpc.node_name()).await;
-        }
-
-        Ok(())
-    }
-    go(&mut ctx, &opts, cmd).await
+    println!(
+        "{}",
+        String::from_utf8(res).context("Received content is not a valid utf8 string")?
+    );
+    Ok(())
 }
 
-pub(crate) fn req<'a>(to: &'a MultiAddr, message: &'a str) -> RequestBuilder<'a, SendMessage<'a>> {
+pub(crate) fn req<'a>(to: &MultiAddr, message: &'a str) -> RequestBuilder<'a, SendMessage<'a>> {
     Request::post("v0/message").body(SendMessage::new(to, message.as_bytes()))
 }
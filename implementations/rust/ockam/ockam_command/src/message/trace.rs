@@ -0,0 +1,81 @@
+use clap::Args;
+
+use core::time::Duration;
+use ockam::Context;
+use ockam_api::nodes::service::message::{TraceResult, TraceRoute};
+use ockam_core::api::{Request, RequestBuilder};
+use ockam_multiaddr::proto::Secure;
+use ockam_multiaddr::{MultiAddr, Protocol};
+
+use super::util::RouteRequest;
+use crate::util::api::{CloudOpts, TrustContextOpts};
+use crate::util::node_rpc;
+use crate::Result;
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/trace/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/trace/after_long_help.txt");
+
+/// Measure the round trip time to every hop of a route
+#[derive(Clone, Debug, Args)]
+#[command(
+arg_required_else_help = true,
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct TraceCommand {
+    /// The node to send the probes from
+    #[arg(short, long, value_name = "NODE")]
+    from: Option<String>,
+
+    /// The route to trace
+    #[arg(short, long, value_name = "ROUTE")]
+    pub to: MultiAddr,
+
+    /// Timeout of each probe, in seconds
+    #[arg(long, value_name = "TIMEOUT", default_value = "5")]
+    pub timeout: u64,
+
+    #[command(flatten)]
+    cloud_opts: CloudOpts,
+
+    #[command(flatten)]
+    pub trust_context_opts: TrustContextOpts,
+}
+
+impl TraceCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self))
+    }
+}
+
+async fn rpc(ctx: Context, (opts, cmd): (CommandGlobalOpts, TraceCommand)) -> Result<()> {
+    let timeout = Duration::from_secs(cmd.timeout);
+    // A probe is sent for the route and for each secure channel of the route
+    let probes_count = cmd.to.iter().filter(|p| p.code() == Secure::CODE).count() as u32 + 1;
+    let request = RouteRequest {
+        from: cmd.from.as_deref(),
+        to: &cmd.to,
+        cloud_opts: &cmd.cloud_opts,
+        trust_context_opts: &cmd.trust_context_opts,
+        timeout: timeout * (2 * probes_count + 1),
+    };
+    let res: TraceResult = request.send(&ctx, &opts, |to| req(to, timeout)).await?;
+
+    for (i, hop) in res.hops.iter().enumerate() {
+        let rtt = match (&hop.rtt, &hop.error) {
+            (Some(rtt), _) => format!("{rtt:?}"),
+            (None, Some(error)) => format!("unreachable ({error})"),
+            (None, None) => "unreachable".to_string(),
+        };
+        match &hop.identifier {
+            Some(identifier) => println!("{}  {}  {}  {}", i + 1, hop.route, rtt, identifier),
+            None => println!("{}  {}  {}", i + 1, hop.route, rtt),
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn req<'a>(to: &MultiAddr, timeout: Duration) -> RequestBuilder<'a, TraceRoute<'a>> {
+    Request::post("v0/message/trace").body(TraceRoute::new(to, timeout))
+}
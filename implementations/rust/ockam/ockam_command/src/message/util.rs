@@ -0,0 +1,87 @@
+use anyhow::Context as _;
+use core::time::Duration;
+use minicbor::{Decode, Encode};
+use ockam::{Context, TcpTransport};
+use ockam_api::nodes::models::secure_channel::CredentialExchangeMode;
+use ockam_core::api::RequestBuilder;
+use ockam_multiaddr::MultiAddr;
+
+use crate::node::util::{delete_embedded_node, start_embedded_node_with_vault_and_identity};
+use crate::util::api::{CloudOpts, TrustContextOpts};
+use crate::util::{clean_nodes_multiaddr, extract_address_value, RpcBuilder};
+use crate::CommandGlobalOpts;
+use crate::Result;
+
+/// Options of the commands sending a request about a route to a node
+pub(super) struct RouteRequest<'a> {
+    /// The node sending messages along the route, an embedded node is started if missing
+    pub from: Option<&'a str>,
+    /// The route, which can contain `/node` and `/project` occurrences
+    pub to: &'a MultiAddr,
+    pub cloud_opts: &'a CloudOpts,
+    pub trust_context_opts: &'a TrustContextOpts,
+    pub timeout: Duration,
+}
+
+impl<'a> RouteRequest<'a> {
+    /// Send the request built by `req` for the resolved route and return the response body
+    pub(super) async fn send<B, T, F>(
+        self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        req: F,
+    ) -> Result<T>
+    where
+        B: Encode<()>,
+        T: for<'b> Decode<'b, ()>,
+        F: FnOnce(&MultiAddr) -> RequestBuilder<'a, B>,
+    {
+        // Setup environment depending on whether we are sending the request to an embedded node or a background node
+        let (api_node, tcp) = if let Some(node) = self.from {
+            let api_node = extract_address_value(node)?;
+            let tcp = TcpTransport::create(ctx).await?;
+            (api_node, Some(tcp))
+        } else {
+            let api_node = start_embedded_node_with_vault_and_identity(
+                ctx,
+                opts,
+                None,
+                Some(self.cloud_opts.identity.clone()),
+                Some(self.trust_context_opts),
+            )
+            .await?;
+            (api_node, None)
+        };
+
+        // Process `--to` Multiaddr
+        let (to, meta) =
+            clean_nodes_multiaddr(self.to, &opts.state).context("Argument '--to' is invalid")?;
+
+        // Replace `/project/<name>` occurrences with their respective secure channel addresses
+        let projects_sc = crate::project::util::get_projects_secure_channels_from_config_lookup(
+            ctx,
+            opts,
+            &meta,
+            &self.cloud_opts.route(),
+            &api_node,
+            tcp.as_ref(),
+            CredentialExchangeMode::Oneway,
+        )
+        .await?;
+        let to = crate::project::util::clean_projects_multiaddr(to, projects_sc)?;
+
+        // Send request
+        let mut rpc = RpcBuilder::new(ctx, opts, &api_node)
+            .tcp(tcp.as_ref())?
+            .build();
+        let res = rpc.request_with_timeout(req(&to), self.timeout).await;
+        let res = res.and_then(|_| rpc.parse_response::<T>());
+
+        // only delete node in case 'from' is empty and embedded node was started before
+        if self.from.is_none() {
+            delete_embedded_node(opts, rpc.node_name()).await;
+        }
+
+        res
+    }
+}
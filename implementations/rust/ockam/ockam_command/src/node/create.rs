@@ -108,6 +108,19 @@ pub struct CreateCommand {
     #[arg(long = "credential", value_name = "CREDENTIAL_NAME")]
     pub credential: Option<String>,
 
+    /// Renew the node credential once this fraction of its lifetime has elapsed
+    #[arg(long, value_name = "FRACTION")]
+    pub credential_refresh_fraction: Option<f64>,
+
+    /// Apply this version of the policy bundle published by the trust context
+    /// authority, instead of the latest one
+    #[arg(long, value_name = "VERSION")]
+    pub policy_bundle_version: Option<u64>,
+
+    /// Record the policy decisions of the node, shown by `ockam policy audit`
+    #[arg(long)]
+    pub policy_audit: bool,
+
     #[command(flatten)]
     pub trust_context_opts: TrustContextOpts,
 }
@@ -128,6 +141,9 @@ impl Default for CreateCommand {
             reload_from_trusted_identities_file: None,
             authority_identity: None,
             credential: None,
+            credential_refresh_fraction: None,
+            policy_bundle_version: None,
+            policy_audit: false,
             trust_context_opts: TrustContextOpts::default(),
         }
     }
@@ -262,6 +278,12 @@ async fn run_foreground_node(
     let projects = cfg.inner().lookup().projects().collect();
     let pre_trusted_identities = load_pre_trusted_identities(&cmd)?;
 
+    let mut trust_options = NodeManagerTrustOptions::new(trust_context_config);
+    if let Some(fraction) = cmd.credential_refresh_fraction {
+        trust_options = trust_options.with_credential_refresh_fraction(fraction);
+    }
+    trust_options = trust_options.with_policy_bundle_version(cmd.policy_bundle_version);
+
     let node_man = NodeManager::create(
         &ctx,
         NodeManagerGeneralOptions::new(
@@ -269,7 +291,8 @@ async fn run_foreground_node(
             cmd.node_name.clone(),
             cmd.launch_config.is_some(),
             pre_trusted_identities,
-        ),
+        )
+        .with_policy_audit(cmd.policy_audit),
         NodeManagerProjectsOptions::new(projects),
         NodeManagerTransportOptions::new(
             ApiTransport {
@@ -281,7 +304,7 @@ async fn run_foreground_node(
             },
             tcp.async_try_clone().await?,
         ),
-        NodeManagerTrustOptions::new(trust_context_config),
+        trust_options,
     )
     .await?;
     let node_manager_worker = NodeManagerWorker::new(node_man);
@@ -474,6 +497,9 @@ async fn spawn_background_node(
             .as_ref()
             .map(|tc| tc.path().unwrap()),
         cmd.trust_context_opts.project.as_ref(),
+        cmd.credential_refresh_fraction,
+        cmd.policy_bundle_version,
+        cmd.policy_audit,
     )?;
 
     Ok(())
@@ -522,6 +548,8 @@ async fn start_authority_node(
             trusted_identities,
             no_direct_authentication: true,
             no_token_enrollment: true,
+            enrollment_token_max_duration: None,
+            credential_schemas: vec![],
             okta: None,
         };
         authority_node::start_node(&ctx, &configuration).await?;
@@ -5,6 +5,7 @@ use crate::{docs, CommandGlobalOpts, Result};
 use clap::Args;
 use colorful::Colorful;
 use ockam::TcpTransport;
+use ockam_api::nodes::models::flow_controls::FlowControlList;
 use ockam_api::nodes::models::portal::{InletList, OutletList};
 use ockam_api::nodes::models::services::ServiceList;
 use ockam_api::nodes::models::transport::TransportList;
@@ -31,6 +32,10 @@ pub struct ShowCommand {
     /// Name of the node.
     #[arg(default_value_t = default_node_name(), value_parser = node_name_parser)]
     node_name: String,
+
+    /// Show the flow controls of the node, with their spawners, producers and consumers
+    #[arg(long)]
+    flow_controls: bool,
 }
 
 impl ShowCommand {
@@ -49,9 +54,36 @@ async fn run_impl(
     let mut rpc = RpcBuilder::new(&ctx, &opts, node_name).tcp(&tcp)?.build();
     let is_default = check_default(&opts, node_name);
     print_query_status(&mut rpc, node_name, false, is_default).await?;
+
+    if cmd.flow_controls && is_node_up(&mut rpc, false).await? {
+        let mut rpc = rpc.clone();
+        rpc.request(api::list_flow_controls()).await?;
+        let flow_controls = rpc.parse_response::<FlowControlList>()?;
+        print_flow_controls(&flow_controls);
+    }
     Ok(())
 }
 
+fn print_flow_controls(flow_controls: &FlowControlList) {
+    println!("  Flow Controls:");
+    for e in &flow_controls.list {
+        println!("    Flow Control:");
+        println!("      Id: {}", e.flow_control_id);
+        if let Some(id) = &e.spawner_flow_control_id {
+            println!("      Spawner Flow Control Id: {id}");
+        }
+        for address in &e.spawners {
+            println!("      Spawner: {address}");
+        }
+        for address in &e.producers {
+            println!("      Producer: {address}");
+        }
+        for consumer in &e.consumers {
+            println!("      Consumer: {} ({})", consumer.address, consumer.policy);
+        }
+    }
+}
+
 // TODO: This function should be replaced with a better system of
 // printing the node state in the future but for now we can just tell
 // clippy to stop complaining about it.
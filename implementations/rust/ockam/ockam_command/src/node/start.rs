@@ -69,6 +69,9 @@ async fn run_impl(
         None,               // Credential
         None,               // Trust Context
         None,               // Project Name
+        None,               // Credential refresh fraction
+        None,               // Policy bundle version
+        false,              // Policy audit
     )?;
 
     // Print node status
@@ -210,6 +210,9 @@ pub fn spawn_node(
     credential: Option<&String>,
     trust_context: Option<&PathBuf>,
     project_name: Option<&String>,
+    credential_refresh_fraction: Option<f64>,
+    policy_bundle_version: Option<u64>,
+    policy_audit: bool,
 ) -> crate::Result<()> {
     let mut args = vec![
         match verbose {
@@ -282,6 +285,20 @@ pub fn spawn_node(
         args.push(project_name.to_string());
     }
 
+    if let Some(fraction) = credential_refresh_fraction {
+        args.push("--credential-refresh-fraction".to_string());
+        args.push(fraction.to_string());
+    }
+
+    if let Some(version) = policy_bundle_version {
+        args.push("--policy-bundle-version".to_string());
+        args.push(version.to_string());
+    }
+
+    if policy_audit {
+        args.push("--policy-audit".to_string());
+    }
+
     args.push(name.to_owned());
 
     run_ockam(opts, name, args)
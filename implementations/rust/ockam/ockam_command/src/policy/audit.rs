@@ -0,0 +1,71 @@
+use crate::util::{extract_address_value, node_rpc, Rpc};
+use crate::{CommandGlobalOpts, Result};
+use clap::Args;
+use ockam::Context;
+use ockam_api::nodes::models::policy::{DecisionList, ListDecisions};
+use ockam_core::api::Request;
+
+/// Show the most recent policy decisions of a node created with `--policy-audit`
+#[derive(Clone, Debug, Args)]
+pub struct AuditCommand {
+    /// Node whose policy decisions are shown.
+    #[arg(long, display_order = 900, id = "NODE")]
+    at: String,
+
+    /// Only show the decisions about this resource.
+    #[arg(short, long)]
+    resource: Option<String>,
+
+    /// Only show the decisions about this action.
+    #[arg(short, long)]
+    action: Option<String>,
+
+    /// Only show the decisions about this identity identifier.
+    #[arg(short, long)]
+    subject: Option<String>,
+
+    /// Only show the decisions denying access.
+    #[arg(long)]
+    denied: bool,
+
+    /// Maximum number of decisions to show.
+    #[arg(short, long)]
+    limit: Option<u32>,
+}
+
+impl AuditCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self));
+    }
+}
+
+async fn rpc(mut ctx: Context, (opts, cmd): (CommandGlobalOpts, AuditCommand)) -> Result<()> {
+    run_impl(&mut ctx, opts, cmd).await
+}
+
+async fn run_impl(ctx: &mut Context, opts: CommandGlobalOpts, cmd: AuditCommand) -> Result<()> {
+    let node = extract_address_value(&cmd.at)?;
+    let bdy = ListDecisions::new()
+        .with_resource(cmd.resource)
+        .with_action(cmd.action)
+        .with_subject(cmd.subject)
+        .with_denied_only(cmd.denied)
+        .with_limit(cmd.limit);
+    let req = Request::get("/policy_audit").body(bdy);
+    let mut rpc = Rpc::background(ctx, &opts, &node)?;
+    rpc.request(req).await?;
+    let list: DecisionList = rpc.parse_response()?;
+    for d in list.decisions() {
+        println!(
+            "{} {} {}/{} subject={} policy={}: {}",
+            d.timestamp(),
+            if d.is_allowed() { "allow" } else { "deny" },
+            d.resource().unwrap_or("-"),
+            d.action().unwrap_or("-"),
+            d.subject().unwrap_or("-"),
+            d.policy_hash().unwrap_or("-"),
+            d.reason()
+        )
+    }
+    Ok(())
+}
@@ -21,6 +21,10 @@ pub struct CreateCommand {
 
     #[arg(short, long)]
     expression: Expr,
+
+    /// Comment describing the change, kept in the policy history.
+    #[arg(long)]
+    comment: Option<String>,
 }
 
 impl CreateCommand {
@@ -35,7 +39,7 @@ async fn rpc(mut ctx: Context, (opts, cmd): (CommandGlobalOpts, CreateCommand))
 
 async fn run_impl(ctx: &mut Context, opts: CommandGlobalOpts, cmd: CreateCommand) -> Result<()> {
     let node = extract_address_value(&cmd.at)?;
-    let bdy = Policy::new(cmd.expression);
+    let bdy = Policy::new(cmd.expression).with_comment(cmd.comment);
     let req = Request::post(policy_path(&cmd.resource, &cmd.action)).body(bdy);
     let mut rpc = Rpc::background(ctx, &opts, &node)?;
     rpc.request(req).await?;
@@ -0,0 +1,54 @@
+use crate::policy::policy_path;
+use crate::util::{extract_address_value, node_rpc, Rpc};
+use crate::{CommandGlobalOpts, Result};
+use clap::Args;
+use ockam::Context;
+use ockam_abac::{Action, Resource};
+use ockam_api::nodes::models::policy::PolicyHistory;
+use ockam_core::api::Request;
+
+/// Show the versions of a policy, oldest first
+#[derive(Clone, Debug, Args)]
+pub struct HistoryCommand {
+    /// Node on which the policy is stored.
+    #[arg(long, display_order = 900, id = "NODE")]
+    at: String,
+
+    #[arg(short, long)]
+    resource: Resource,
+
+    #[arg(short, long, default_value = "handle_message")]
+    action: Action,
+}
+
+impl HistoryCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self));
+    }
+}
+
+async fn rpc(mut ctx: Context, (opts, cmd): (CommandGlobalOpts, HistoryCommand)) -> Result<()> {
+    run_impl(&mut ctx, opts, cmd).await
+}
+
+async fn run_impl(ctx: &mut Context, opts: CommandGlobalOpts, cmd: HistoryCommand) -> Result<()> {
+    let node = extract_address_value(&cmd.at)?;
+    let path = format!("{}/history", policy_path(&cmd.resource, &cmd.action));
+    let mut rpc = Rpc::background(ctx, &opts, &node)?;
+    rpc.request(Request::get(path)).await?;
+    let history: PolicyHistory = rpc.parse_response()?;
+    for v in history.versions() {
+        println!(
+            "{} {} author={} comment={:?}: {}{}",
+            v.version(),
+            v.timestamp(),
+            v.author()
+                .map(|a| a.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            v.comment().unwrap_or_default(),
+            if v.is_deleted() { "(deleted) " } else { "" },
+            v.expression()
+        )
+    }
+    Ok(())
+}
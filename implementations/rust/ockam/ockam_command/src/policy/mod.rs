@@ -1,10 +1,18 @@
+mod audit;
 mod create;
 mod delete;
+mod history;
 mod list;
+mod rollback;
 mod show;
+mod test;
+use crate::policy::audit::AuditCommand;
 use crate::policy::delete::DeleteCommand;
+use crate::policy::history::HistoryCommand;
 use crate::policy::list::ListCommand;
+use crate::policy::rollback::RollbackCommand;
 use crate::policy::show::ShowCommand;
+use crate::policy::test::TestCommand;
 use crate::{policy::create::CreateCommand, util::Rpc};
 use crate::{CommandGlobalOpts, Result};
 use clap::{Args, Subcommand};
@@ -28,6 +36,10 @@ pub enum PolicySubcommand {
     Show(ShowCommand),
     Delete(DeleteCommand),
     List(ListCommand),
+    Audit(AuditCommand),
+    Test(TestCommand),
+    History(HistoryCommand),
+    Rollback(RollbackCommand),
 }
 
 impl PolicyCommand {
@@ -37,6 +49,10 @@ impl PolicyCommand {
             PolicySubcommand::Show(c) => c.run(opts),
             PolicySubcommand::Delete(c) => c.run(opts),
             PolicySubcommand::List(c) => c.run(opts),
+            PolicySubcommand::Audit(c) => c.run(opts),
+            PolicySubcommand::Test(c) => c.run(opts),
+            PolicySubcommand::History(c) => c.run(opts),
+            PolicySubcommand::Rollback(c) => c.run(opts),
         }
     }
 }
@@ -0,0 +1,49 @@
+use crate::policy::policy_path;
+use crate::util::{extract_address_value, node_rpc, Rpc};
+use crate::{CommandGlobalOpts, Result};
+use clap::Args;
+use ockam::Context;
+use ockam_abac::{Action, Resource};
+use ockam_api::nodes::models::policy::{PolicyHistory, RollbackPolicy};
+use ockam_core::api::Request;
+
+/// Set a policy back to one of its previous versions
+#[derive(Clone, Debug, Args)]
+pub struct RollbackCommand {
+    /// Node on which the policy is stored.
+    #[arg(long, display_order = 900, id = "NODE")]
+    at: String,
+
+    #[arg(short, long)]
+    resource: Resource,
+
+    #[arg(short, long, default_value = "handle_message")]
+    action: Action,
+
+    /// Version to roll back to, as shown by `ockam policy history`.
+    #[arg(long)]
+    version: u64,
+}
+
+impl RollbackCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self));
+    }
+}
+
+async fn rpc(mut ctx: Context, (opts, cmd): (CommandGlobalOpts, RollbackCommand)) -> Result<()> {
+    run_impl(&mut ctx, opts, cmd).await
+}
+
+async fn run_impl(ctx: &mut Context, opts: CommandGlobalOpts, cmd: RollbackCommand) -> Result<()> {
+    let node = extract_address_value(&cmd.at)?;
+    let path = format!("{}/rollback", policy_path(&cmd.resource, &cmd.action));
+    let req = Request::post(path).body(RollbackPolicy::new(cmd.version));
+    let mut rpc = Rpc::background(ctx, &opts, &node)?;
+    rpc.request(req).await?;
+    let history: PolicyHistory = rpc.parse_response()?;
+    for v in history.versions() {
+        println!("{}: {}", v.version(), v.expression())
+    }
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use crate::policy::policy_path;
+use crate::util::{extract_address_value, node_rpc, Rpc};
+use crate::{CommandGlobalOpts, Result};
+use clap::Args;
+use ockam::identity::IdentityIdentifier;
+use ockam::Context;
+use ockam_abac::{Action, Resource};
+use ockam_api::nodes::models::policy::{PolicyExplanation, TestPolicy};
+use ockam_core::api::Request;
+
+/// Evaluate a policy against the attributes of an identity, without sending
+/// any message, and explain the result
+///
+/// The attributes of the messages, such as the `channel.*` and `transport.*`
+/// attributes, are only known when a message is received and are left unbound.
+#[derive(Clone, Debug, Args)]
+pub struct TestCommand {
+    /// Node on which the policy is stored.
+    #[arg(long, display_order = 900, id = "NODE")]
+    at: String,
+
+    #[arg(short, long)]
+    resource: Resource,
+
+    #[arg(short, long, default_value = "handle_message")]
+    action: Action,
+
+    /// Identifier of the identity accessing the resource.
+    #[arg(short, long)]
+    identity: IdentityIdentifier,
+
+    /// Address of the worker whose resource attributes are used, required
+    /// when several workers share the resource, e.g. several TCP outlets.
+    #[arg(short, long)]
+    worker: Option<String>,
+}
+
+impl TestCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(rpc, (options, self));
+    }
+}
+
+async fn rpc(mut ctx: Context, (opts, cmd): (CommandGlobalOpts, TestCommand)) -> Result<()> {
+    run_impl(&mut ctx, opts, cmd).await
+}
+
+async fn run_impl(ctx: &mut Context, opts: CommandGlobalOpts, cmd: TestCommand) -> Result<()> {
+    let node = extract_address_value(&cmd.at)?;
+    let path = format!("{}/test", policy_path(&cmd.resource, &cmd.action));
+    let req = Request::post(path).body(TestPolicy::new(cmd.identity).with_worker(cmd.worker));
+    let mut rpc = Rpc::background(ctx, &opts, &node)?;
+    rpc.request(req).await?;
+    let res: PolicyExplanation = rpc.parse_response()?;
+    let explanation = res.explanation();
+    print!("{explanation}");
+    let unbound = explanation.unbound();
+    if !unbound.is_empty() {
+        println!("runtime-only (unbound): {}", unbound.join(" "))
+    }
+    if explanation.is_true() {
+        println!("access allowed")
+    } else {
+        println!("access denied")
+    }
+    Ok(())
+}
@@ -6,7 +6,7 @@ use std::collections::HashMap;
 use std::time::Duration;
 
 use anyhow::{anyhow, Context as _};
-use ockam::identity::IdentityIdentifier;
+use ockam::identity::{IdentityIdentifier, OneTimeCode};
 use ockam::Context;
 use ockam_api::authenticator::direct::{DirectAuthenticatorClient, TokenIssuerClient};
 use ockam_api::config::lookup::{ConfigLookup, ProjectAuthority, ProjectLookup};
@@ -44,6 +44,10 @@ pub struct EnrollCommand {
     /// Attributes in `key=value` format to be attached to the member
     #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
     attributes: Vec<String>,
+
+    /// Number of seconds after which the attributes of the member expire
+    #[arg(long, value_name = "SECONDS")]
+    attributes_ttl: Option<u64>,
 }
 
 impl EnrollCommand {
@@ -55,15 +59,24 @@ impl EnrollCommand {
     }
 
     fn attributes(&self) -> Result<HashMap<&str, &str>> {
-        let mut attributes = HashMap::new();
-        for attr in &self.attributes {
-            let mut parts = attr.splitn(2, '=');
-            let key = parts.next().context("key expected")?;
-            let value = parts.next().context("value expected)")?;
-            attributes.insert(key, value);
-        }
-        Ok(attributes)
+        parse_attributes(&self.attributes)
+    }
+
+    fn attributes_ttl(&self) -> Option<Duration> {
+        self.attributes_ttl.map(Duration::from_secs)
+    }
+}
+
+/// Parse attributes given in the `key=value` format
+pub(crate) fn parse_attributes(attributes: &[String]) -> Result<HashMap<&str, &str>> {
+    let mut parsed = HashMap::new();
+    for attr in attributes {
+        let mut parts = attr.splitn(2, '=');
+        let key = parts.next().context("key expected")?;
+        let value = parts.next().context("value expected)")?;
+        parsed.insert(key, value);
     }
+    Ok(parsed)
 }
 
 struct Runner {
@@ -81,103 +94,46 @@ impl Runner {
         let node_name =
             start_embedded_node(&self.ctx, &self.opts, Some(&self.cmd.trust_opts)).await?;
 
-        let map = self.opts.config.lookup();
-        let mut project: Option<ProjectLookup> = None;
-        let mut trust_context: Option<TrustContextConfig> = None;
-
-        let (base_addr, _flow_control_id) =
-            if let Some(tc) = self.cmd.trust_opts.trust_context.as_ref() {
-                trust_context = Some(tc.clone());
-                let cred_retr = tc.authority()?.own_credential()?;
-                let addr = match cred_retr {
-                    ockam_api::config::cli::CredentialRetrieverConfig::FromCredentialIssuer(c) => {
-                        &c.multiaddr
-                    }
-                    _ => {
-                        return Err(anyhow!(
-                            "Trust context must be configured with a credential issuer"
-                        )
-                        .into());
-                    }
-                };
-                let (sc_addr, sc_flow_control_id) = create_secure_channel_to_authority(
-                    &self.ctx,
-                    &self.opts,
-                    &node_name,
-                    tc.authority()?.identity().await?.identifier().clone(),
-                    addr,
-                    Some(self.cmd.cloud_opts.identity.clone()),
-                )
-                .await?;
-
-                (sc_addr, Some(sc_flow_control_id))
-            } else if let (Some(p), Some(a)) = get_project(&self.cmd.to, &map)? {
-                let (sc_addr, sc_flow_control_id) = create_secure_channel_to_authority(
-                    &self.ctx,
-                    &self.opts,
-                    &node_name,
-                    a.identity_id().clone(),
-                    a.address(),
-                    Some(self.cmd.cloud_opts.identity.clone()),
-                )
-                .await?;
-
-                project = Some(p);
-                (sc_addr, Some(sc_flow_control_id))
-            } else {
-                (self.cmd.to.clone(), None)
-            };
+        let authority = AuthorityConnection::create(
+            &self.ctx,
+            &self.opts,
+            &node_name,
+            &self.cmd.cloud_opts,
+            &self.cmd.trust_opts,
+            &self.cmd.to,
+        )
+        .await?;
         // If an identity identifier is given add it as a member, otherwise
         // request an enrollment token that a future member can use to get a
         // credential.
         if let Some(id) = &self.cmd.member {
-            let direct_authenticator_route = {
-                let service = MultiAddr::try_from(
-                    format!("/service/{}", DefaultAddress::DIRECT_AUTHENTICATOR).as_str(),
-                )?;
-                let mut addr = base_addr.clone();
-                for proto in service.iter() {
-                    addr.push_back_value(&proto)?;
-                }
-                ockam_api::local_multiaddr_to_route(&addr)
-                    .context(format!("Invalid MultiAddr {addr}"))?
-            };
             let client = DirectAuthenticatorClient::new(
-                RpcClient::new(
-                    route![DefaultAddress::RPC_PROXY, direct_authenticator_route],
-                    &self.ctx,
-                )
-                .await?
-                .with_timeout(Duration::from_secs(ORCHESTRATOR_RESTART_TIMEOUT)),
+                authority
+                    .rpc_client(&self.ctx, DefaultAddress::DIRECT_AUTHENTICATOR)
+                    .await?,
             );
             client
-                .add_member(id.clone(), self.cmd.attributes()?)
+                .add_member(
+                    id.clone(),
+                    self.cmd.attributes()?,
+                    self.cmd.attributes_ttl(),
+                )
                 .await?
         } else {
-            let token_issuer_route = {
-                let service = MultiAddr::try_from(
-                    format!("/service/{}", DefaultAddress::ENROLLMENT_TOKEN_ISSUER).as_str(),
-                )?;
-                let mut addr = base_addr.clone();
-                for proto in service.iter() {
-                    addr.push_back_value(&proto)?;
-                }
-                ockam_api::local_multiaddr_to_route(&addr)
-                    .context(format!("Invalid MultiAddr {addr}"))?
-            };
             let client = TokenIssuerClient::new(
-                RpcClient::new(
-                    route![DefaultAddress::RPC_PROXY, token_issuer_route],
-                    &self.ctx,
-                )
-                .await?
-                .with_timeout(Duration::from_secs(ORCHESTRATOR_RESTART_TIMEOUT)),
+                authority
+                    .rpc_client(&self.ctx, DefaultAddress::ENROLLMENT_TOKEN_ISSUER)
+                    .await?,
             );
-            let token = client.create_token(self.cmd.attributes()?).await?;
-
-            let ticket = EnrollmentTicket::new(token, project, trust_context);
-            let ticket_serialized = hex::encode(serde_json::to_vec(&ticket)?);
-            print!("{}", ticket_serialized)
+            let token = client
+                .create_token(
+                    self.cmd.attributes()?,
+                    None,
+                    None,
+                    self.cmd.attributes_ttl(),
+                )
+                .await?;
+            print!("{}", authority.ticket(token)?)
         }
 
         delete_embedded_node(&self.opts, &node_name).await;
@@ -185,6 +141,100 @@ impl Runner {
     }
 }
 
+/// Secure channel to the authority of a project or of a trust context
+pub(crate) struct AuthorityConnection {
+    base_addr: MultiAddr,
+    project: Option<ProjectLookup>,
+    trust_context: Option<TrustContextConfig>,
+}
+
+impl AuthorityConnection {
+    /// Create a secure channel from a node to the authority of the given trust
+    /// context if any, or else to the authority of the project at the `to` address
+    pub(crate) async fn create(
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        node_name: &str,
+        cloud_opts: &CloudOpts,
+        trust_opts: &TrustContextOpts,
+        to: &MultiAddr,
+    ) -> Result<Self> {
+        let map = opts.config.lookup();
+        let mut project: Option<ProjectLookup> = None;
+        let mut trust_context: Option<TrustContextConfig> = None;
+
+        let base_addr = if let Some(tc) = trust_opts.trust_context.as_ref() {
+            trust_context = Some(tc.clone());
+            let cred_retr = tc.authority()?.own_credential()?;
+            let addr = match cred_retr {
+                ockam_api::config::cli::CredentialRetrieverConfig::FromCredentialIssuer(c) => {
+                    &c.multiaddr
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "Trust context must be configured with a credential issuer"
+                    )
+                    .into());
+                }
+            };
+            let (sc_addr, _sc_flow_control_id) = create_secure_channel_to_authority(
+                ctx,
+                opts,
+                node_name,
+                tc.authority()?.identity().await?.identifier().clone(),
+                addr,
+                Some(cloud_opts.identity.clone()),
+            )
+            .await?;
+            sc_addr
+        } else if let (Some(p), Some(a)) = get_project(to, &map)? {
+            let (sc_addr, _sc_flow_control_id) = create_secure_channel_to_authority(
+                ctx,
+                opts,
+                node_name,
+                a.identity_id().clone(),
+                a.address(),
+                Some(cloud_opts.identity.clone()),
+            )
+            .await?;
+            project = Some(p);
+            sc_addr
+        } else {
+            to.clone()
+        };
+
+        Ok(Self {
+            base_addr,
+            project,
+            trust_context,
+        })
+    }
+
+    /// Return a client for a service of the authority
+    pub(crate) async fn rpc_client(&self, ctx: &Context, service: &str) -> Result<RpcClient> {
+        let route = {
+            let service = MultiAddr::try_from(format!("/service/{service}").as_str())?;
+            let mut addr = self.base_addr.clone();
+            for proto in service.iter() {
+                addr.push_back_value(&proto)?;
+            }
+            ockam_api::local_multiaddr_to_route(&addr)
+                .context(format!("Invalid MultiAddr {addr}"))?
+        };
+        Ok(
+            RpcClient::new(route![DefaultAddress::RPC_PROXY, route], ctx)
+                .await?
+                .with_timeout(Duration::from_secs(ORCHESTRATOR_RESTART_TIMEOUT)),
+        )
+    }
+
+    /// Return the serialized enrollment ticket for a token issued by the authority
+    pub(crate) fn ticket(&self, token: OneTimeCode) -> Result<String> {
+        let ticket = EnrollmentTicket::new(token, self.project.clone(), self.trust_context.clone());
+        Ok(hex::encode(serde_json::to_vec(&ticket)?))
+    }
+}
+
 /// Get the project authority from the first address protocol.
 ///
 /// If the first protocol is a `/project`, look up the project's config.
@@ -0,0 +1,115 @@
+use clap::{Args, Subcommand};
+
+use ockam::identity::{AttributesEntry, IdentityIdentifier, Timestamp};
+use ockam::Context;
+use ockam_api::authenticator::direct::DirectAuthenticatorClient;
+use ockam_api::DefaultAddress;
+use ockam_multiaddr::MultiAddr;
+
+use crate::node::util::{delete_embedded_node, start_embedded_node};
+use crate::project::enroll::AuthorityConnection;
+use crate::util::api::{CloudOpts, TrustContextOpts};
+use crate::util::node_rpc;
+use crate::{CommandGlobalOpts, Result};
+
+/// Manage the members of a project
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, subcommand_required = true)]
+pub struct MemberCommand {
+    #[command(subcommand)]
+    subcommand: MemberSubcommand,
+
+    /// Orchestrator address to resolve projects present in the `to` argument
+    #[command(flatten)]
+    cloud_opts: CloudOpts,
+
+    #[command(flatten)]
+    trust_opts: TrustContextOpts,
+
+    #[arg(long, short, global = true, default_value = "/project/default")]
+    to: MultiAddr,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum MemberSubcommand {
+    /// List the members of the project with their attributes
+    List,
+    /// Show the attributes of a member
+    Show {
+        /// Identifier of the member
+        member: IdentityIdentifier,
+    },
+    /// Remove a member from the project
+    Remove {
+        /// Identifier of the member
+        member: IdentityIdentifier,
+    },
+}
+
+impl MemberCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self));
+    }
+}
+
+async fn run_impl(ctx: Context, (opts, cmd): (CommandGlobalOpts, MemberCommand)) -> Result<()> {
+    let node_name = start_embedded_node(&ctx, &opts, Some(&cmd.trust_opts)).await?;
+    let result = run_command(&ctx, &opts, &node_name, cmd).await;
+    delete_embedded_node(&opts, &node_name).await;
+    result
+}
+
+async fn run_command(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+    cmd: MemberCommand,
+) -> Result<()> {
+    let authority = AuthorityConnection::create(
+        ctx,
+        opts,
+        node_name,
+        &cmd.cloud_opts,
+        &cmd.trust_opts,
+        &cmd.to,
+    )
+    .await?;
+    let client = DirectAuthenticatorClient::new(
+        authority
+            .rpc_client(ctx, DefaultAddress::DIRECT_AUTHENTICATOR)
+            .await?,
+    );
+
+    match cmd.subcommand {
+        MemberSubcommand::List => {
+            for (member, entry) in client.list_members().await? {
+                println!("{member}  {}", format_attributes(&entry));
+            }
+        }
+        MemberSubcommand::Show { member } => {
+            let entry = client.show_member(&member).await?;
+            println!("{member}  {}", format_attributes(&entry));
+        }
+        MemberSubcommand::Remove { member } => {
+            client.remove_member(&member).await?;
+            println!("Removed member {member}");
+        }
+    }
+    Ok(())
+}
+
+fn format_attributes(entry: &AttributesEntry) -> String {
+    let attributes: Vec<String> = entry
+        .attrs()
+        .iter()
+        .map(|(k, v)| format!("{k}={}", String::from_utf8_lossy(v)))
+        .collect();
+    match (entry.expires(), Timestamp::now()) {
+        (Some(expires), Some(now)) => format!(
+            "[{}]  expires in {}s",
+            attributes.join(", "),
+            expires.unix_time().saturating_sub(now.unix_time())
+        ),
+        _ => format!("[{}]", attributes.join(", ")),
+    }
+}
@@ -5,7 +5,10 @@ mod delete;
 mod enroll;
 mod info;
 mod list;
+mod member;
+mod policies;
 mod show;
+mod ticket;
 pub mod util;
 
 pub use info::ProjectInfo;
@@ -20,7 +23,10 @@ pub use delete::DeleteCommand;
 pub use enroll::EnrollCommand;
 pub use info::InfoCommand;
 pub use list::ListCommand;
+pub use member::MemberCommand;
+pub use policies::PoliciesCommand;
 pub use show::ShowCommand;
+pub use ticket::TicketCommand;
 
 use crate::project::auth::AuthCommand;
 use crate::CommandGlobalOpts;
@@ -43,6 +49,9 @@ pub enum ProjectSubcommand {
     Enroll(EnrollCommand),
     Addon(AddonCommand),
     Authenticate(AuthCommand),
+    Ticket(TicketCommand),
+    Member(MemberCommand),
+    Policies(PoliciesCommand),
 }
 
 impl ProjectCommand {
@@ -56,6 +65,9 @@ impl ProjectCommand {
             ProjectSubcommand::Information(c) => c.run(options),
             ProjectSubcommand::Addon(c) => c.run(options),
             ProjectSubcommand::Authenticate(c) => c.run(options),
+            ProjectSubcommand::Ticket(c) => c.run(options),
+            ProjectSubcommand::Member(c) => c.run(options),
+            ProjectSubcommand::Policies(c) => c.run(options),
         }
     }
 }
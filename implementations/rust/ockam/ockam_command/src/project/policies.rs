@@ -0,0 +1,141 @@
+use anyhow::{anyhow, Context as _};
+use clap::{Args, Subcommand};
+use std::path::{Path, PathBuf};
+
+use ockam::Context;
+use ockam_abac::{Action, Expr, PolicyBundle, PolicyBundleEntry, Resource};
+use ockam_api::nodes::authority_node::PolicyBundlesPublisherClient;
+use ockam_api::DefaultAddress;
+use ockam_multiaddr::MultiAddr;
+
+use crate::node::util::{delete_embedded_node, start_embedded_node};
+use crate::project::enroll::AuthorityConnection;
+use crate::util::api::{CloudOpts, TrustContextOpts};
+use crate::util::node_rpc;
+use crate::{CommandGlobalOpts, Result};
+
+/// Manage the policies distributed by the project authority to the project nodes
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, subcommand_required = true)]
+pub struct PoliciesCommand {
+    #[command(subcommand)]
+    subcommand: PoliciesSubcommand,
+
+    /// Orchestrator address to resolve projects present in the `to` argument
+    #[command(flatten)]
+    cloud_opts: CloudOpts,
+
+    #[command(flatten)]
+    trust_opts: TrustContextOpts,
+
+    #[arg(long, short, global = true, default_value = "/project/default")]
+    to: MultiAddr,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum PoliciesSubcommand {
+    /// Publish a new version of the policy bundle
+    ///
+    /// Only the members with the `policy-publisher` role can publish bundles.
+    /// Each non-empty line of the file contains a resource, an action and a policy
+    /// expression separated by whitespace. Lines starting with '#' are ignored.
+    Publish {
+        /// File containing the policies of the bundle
+        file: PathBuf,
+    },
+    /// Show the latest policy bundle, or a given version of it
+    Show {
+        #[arg(long)]
+        version: Option<u64>,
+    },
+}
+
+impl PoliciesCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self));
+    }
+}
+
+async fn run_impl(ctx: Context, (opts, cmd): (CommandGlobalOpts, PoliciesCommand)) -> Result<()> {
+    let node_name = start_embedded_node(&ctx, &opts, Some(&cmd.trust_opts)).await?;
+    let result = run_command(&ctx, &opts, &node_name, cmd).await;
+    delete_embedded_node(&opts, &node_name).await;
+    result
+}
+
+async fn run_command(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+    cmd: PoliciesCommand,
+) -> Result<()> {
+    let authority = AuthorityConnection::create(
+        ctx,
+        opts,
+        node_name,
+        &cmd.cloud_opts,
+        &cmd.trust_opts,
+        &cmd.to,
+    )
+    .await?;
+    let client = PolicyBundlesPublisherClient::new(
+        authority
+            .rpc_client(ctx, DefaultAddress::POLICY_BUNDLES)
+            .await?,
+    );
+
+    match cmd.subcommand {
+        PoliciesSubcommand::Publish { file } => {
+            let policies = read_policies(&file)?;
+            let bundle = client.publish(policies).await?;
+            println!(
+                "Published the version {} of the policy bundle",
+                bundle.version()
+            );
+        }
+        PoliciesSubcommand::Show { version } => match client.show(version).await? {
+            Some(bundle) => print_bundle(&bundle),
+            None => println!("No policy bundle was published"),
+        },
+    }
+    Ok(())
+}
+
+fn read_policies(file: &Path) -> Result<Vec<PolicyBundleEntry>> {
+    let content = std::fs::read_to_string(file).context(format!("failed to read {:?}", file))?;
+    let mut policies = Vec::new();
+    for (n, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.splitn(3, char::is_whitespace);
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(resource), Some(action), Some(expression)) => {
+                let expression: Expr = expression
+                    .trim()
+                    .parse()
+                    .map_err(|e| anyhow!("invalid expression on line {}: {e}", n + 1))?;
+                policies.push(PolicyBundleEntry::new(
+                    Resource::new(resource),
+                    Action::new(action),
+                    expression,
+                ));
+            }
+            _ => return Err(anyhow!("invalid policy on line {}", n + 1).into()),
+        }
+    }
+    Ok(policies)
+}
+
+fn print_bundle(bundle: &PolicyBundle) {
+    println!(
+        "Version {} published by {} at {}",
+        bundle.version(),
+        bundle.issuer(),
+        bundle.created()
+    );
+    for p in bundle.policies() {
+        println!("{} {} {}", p.resource(), p.action(), p.expression());
+    }
+}
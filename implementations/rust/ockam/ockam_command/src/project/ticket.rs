@@ -0,0 +1,141 @@
+use clap::{Args, Subcommand};
+use std::time::Duration;
+
+use ockam::identity::Timestamp;
+use ockam::Context;
+use ockam_api::authenticator::direct::TokenIssuerClient;
+use ockam_api::DefaultAddress;
+use ockam_multiaddr::MultiAddr;
+
+use crate::node::util::{delete_embedded_node, start_embedded_node};
+use crate::project::enroll::{parse_attributes, AuthorityConnection};
+use crate::util::api::{CloudOpts, TrustContextOpts};
+use crate::util::node_rpc;
+use crate::{CommandGlobalOpts, Result};
+
+/// Manage the enrollment tickets of a project
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, subcommand_required = true)]
+pub struct TicketCommand {
+    #[command(subcommand)]
+    subcommand: TicketSubcommand,
+
+    /// Orchestrator address to resolve projects present in the `to` argument
+    #[command(flatten)]
+    cloud_opts: CloudOpts,
+
+    #[command(flatten)]
+    trust_opts: TrustContextOpts,
+
+    #[arg(long, short, global = true, default_value = "/project/default")]
+    to: MultiAddr,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum TicketSubcommand {
+    /// Create an enrollment ticket
+    Create {
+        /// Attributes in `key=value` format to be attached to the members using the ticket
+        #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
+        attributes: Vec<String>,
+
+        /// Number of seconds after which the ticket expires, 600 by default.
+        /// The authority rejects durations longer than its configured maximum
+        #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+        expires_in: Option<u64>,
+
+        /// Number of times the ticket can be used
+        #[arg(long, value_name = "COUNT", value_parser = clap::value_parser!(u64).range(1..))]
+        usage_count: Option<u64>,
+
+        /// Number of seconds after which the attributes of the members using the ticket expire
+        #[arg(long, value_name = "SECONDS")]
+        attributes_ttl: Option<u64>,
+    },
+    /// List the outstanding enrollment tickets
+    List,
+    /// Revoke an outstanding enrollment ticket
+    Revoke {
+        /// Identifier of the ticket, as displayed by the list command
+        id: String,
+    },
+}
+
+impl TicketCommand {
+    pub fn run(self, options: CommandGlobalOpts) {
+        node_rpc(run_impl, (options, self));
+    }
+}
+
+async fn run_impl(ctx: Context, (opts, cmd): (CommandGlobalOpts, TicketCommand)) -> Result<()> {
+    let node_name = start_embedded_node(&ctx, &opts, Some(&cmd.trust_opts)).await?;
+    let result = run_command(&ctx, &opts, &node_name, cmd).await;
+    delete_embedded_node(&opts, &node_name).await;
+    result
+}
+
+async fn run_command(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+    cmd: TicketCommand,
+) -> Result<()> {
+    let authority = AuthorityConnection::create(
+        ctx,
+        opts,
+        node_name,
+        &cmd.cloud_opts,
+        &cmd.trust_opts,
+        &cmd.to,
+    )
+    .await?;
+    let client = TokenIssuerClient::new(
+        authority
+            .rpc_client(ctx, DefaultAddress::ENROLLMENT_TOKEN_ISSUER)
+            .await?,
+    );
+
+    match cmd.subcommand {
+        TicketSubcommand::Create {
+            attributes,
+            expires_in,
+            usage_count,
+            attributes_ttl,
+        } => {
+            let token = client
+                .create_token(
+                    parse_attributes(&attributes)?,
+                    expires_in.map(Duration::from_secs),
+                    usage_count,
+                    attributes_ttl.map(Duration::from_secs),
+                )
+                .await?;
+            print!("{}", authority.ticket(token)?)
+        }
+        TicketSubcommand::List => {
+            let now = Timestamp::now().map_or(0, |now| now.unix_time());
+            for token in client.list_tokens().await? {
+                let mut attributes: Vec<String> = token
+                    .attributes()
+                    .iter()
+                    .map(|(k, v)| format!("{k}={v}"))
+                    .collect();
+                attributes.sort();
+                println!(
+                    "{}  used {}/{}  expires in {}s  generated by {}  attributes [{}]",
+                    token.id(),
+                    token.usage_count(),
+                    token.max_usage_count(),
+                    token.expires_at().unix_time().saturating_sub(now),
+                    token.generated_by(),
+                    attributes.join(", ")
+                );
+            }
+        }
+        TicketSubcommand::Revoke { id } => {
+            client.revoke_token(&id).await?;
+            println!("Revoked enrollment ticket {id}");
+        }
+    }
+    Ok(())
+}
@@ -83,7 +83,10 @@ async fn get_node_status(
         .is_ok()
     {
         let resp = rpc.parse_response::<NodeStatus>()?;
-        node_status = resp.status.to_string();
+        node_status = match resp.credential_refresh_error {
+            Some(error) => format!("{} (credential renewal failing: {error})", resp.status),
+            None => resp.status.to_string(),
+        };
     }
 
     Ok(node_status)
@@ -8,6 +8,9 @@ use crate::{
 use anyhow::anyhow;
 use clap::Args;
 use ockam::Context;
+use ockam_api::config::cli::ScopedAuthorityConfig;
+use ockam_identity::CredentialSchema;
+use std::path::PathBuf;
 
 #[derive(Clone, Debug, Args)]
 #[command(arg_required_else_help = false)]
@@ -19,6 +22,19 @@ pub struct CreateCommand {
     /// Create a trust context from a credential
     #[arg(long)]
     credential: Option<String>,
+
+    /// Trust an additional authority for some attributes only, in the
+    /// `IDENTITY:ATTRIBUTE,ATTRIBUTE` format, where `IDENTITY` is the
+    /// hex-encoded identity of the authority and an attribute can end with
+    /// `.*` to trust all the attributes of a namespace
+    #[arg(long = "scoped-authority", value_name = "IDENTITY:ATTRIBUTES", value_parser = parse_scoped_authority)]
+    scoped_authorities: Vec<ScopedAuthorityConfig>,
+
+    /// Path of a file containing the schemas that the credentials of this trust context must
+    /// conform to, encoded as a JSON array.
+    /// Format: [{"id": 1, "name": "member", "attributes": [{"name": "role", "type": "utf8", "required": true}]}]
+    #[arg(long, value_name = "PATH")]
+    credential_schemas: Option<PathBuf>,
 }
 
 impl CreateCommand {
@@ -36,7 +52,15 @@ async fn run_impl(
         .use_default_trust_context(false)
         .build();
 
-    if let Some(tcc) = tcc {
+    if let Some(mut tcc) = tcc {
+        for scoped_authority in cmd.scoped_authorities {
+            tcc = tcc.with_scoped_authority(scoped_authority);
+        }
+        if let Some(path) = &cmd.credential_schemas {
+            for schema in parse_credential_schemas(path)? {
+                tcc = tcc.with_credential_schema(schema);
+            }
+        }
         opts.state.trust_contexts.create(&cmd.name, tcc.clone())?;
 
         let auth = if let Ok(auth) = tcc.authority() {
@@ -51,10 +75,14 @@ Trust Context:
     Name: {}
     ID: {}
     Authority: {}
+    Scoped authorities: {}
+    Credential schemas: {}
 "#,
             cmd.name,
             tcc.id(),
-            auth
+            auth,
+            tcc.scoped_authorities().len(),
+            tcc.credential_schemas().len()
         );
 
         opts.shell
@@ -68,3 +96,24 @@ Trust Context:
 
     Ok(())
 }
+
+fn parse_scoped_authority(input: &str) -> crate::Result<ScopedAuthorityConfig> {
+    let (identity, attributes) = input
+        .split_once(':')
+        .ok_or_else(|| anyhow!("expected IDENTITY:ATTRIBUTES, got {input}"))?;
+    let attributes: Vec<String> = attributes
+        .split(',')
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .collect();
+    if attributes.is_empty() {
+        return Err(anyhow!("no attributes given for the scoped authority {identity}").into());
+    }
+    Ok(ScopedAuthorityConfig::new(identity.to_string(), attributes))
+}
+
+fn parse_credential_schemas(path: &PathBuf) -> crate::Result<Vec<CredentialSchema>> {
+    let contents = std::fs::read_to_string(path)?;
+    serde_json::from_str(&contents)
+        .map_err(|e| anyhow!("Cannot parse the credential schemas: {e}").into())
+}
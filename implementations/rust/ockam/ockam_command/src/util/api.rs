@@ -86,6 +86,16 @@ pub(crate) fn list_workers() -> RequestBuilder<'static, ()> {
     Request::get("/node/workers")
 }
 
+/// Construct a request builder to list all flow controls on the given node
+pub(crate) fn list_flow_controls() -> RequestBuilder<'static, ()> {
+    Request::get("/node/flow_controls")
+}
+
+/// Construct a request to rotate the root key of the identity of the given node
+pub(crate) fn rotate_identity_key() -> RequestBuilder<'static, ()> {
+    Request::post("/node/identity/actions/rotate_key")
+}
+
 pub(crate) fn delete_secure_channel(
     addr: &Address,
 ) -> RequestBuilder<'static, models::secure_channel::DeleteSecureChannelRequest<'static>> {
@@ -212,8 +222,12 @@ pub(crate) mod credentials {
     pub(crate) fn present_credential(
         to: &MultiAddr,
         oneway: bool,
+        disclose: Option<Vec<String>>,
     ) -> RequestBuilder<PresentCredentialRequest> {
-        let b = PresentCredentialRequest::new(to, oneway);
+        let mut b = PresentCredentialRequest::new(to, oneway);
+        if let Some(names) = disclose {
+            b = b.with_disclosed_attributes(names);
+        }
         Request::post("/node/credentials/actions/present").body(b)
     }
 
@@ -224,6 +238,13 @@ pub(crate) mod credentials {
         let b = GetCredentialRequest::new(overwrite, identity_name);
         Request::post("/node/credentials/actions/get").body(b)
     }
+
+    pub(crate) fn get_selective_credential<'r>(
+        identity_name: Option<String>,
+    ) -> RequestBuilder<'r, GetCredentialRequest> {
+        let b = GetCredentialRequest::new(false, identity_name);
+        Request::post("/node/credentials/actions/get_selective").body(b)
+    }
 }
 
 /// Return the path of a service given its name
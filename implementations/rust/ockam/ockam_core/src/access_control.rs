@@ -70,6 +70,8 @@ mod any;
 mod deny_all;
 mod local;
 mod onward;
+#[cfg(feature = "std")]
+mod rate_limit;
 mod source;
 
 pub use all::*;
@@ -78,4 +80,6 @@ pub use any::*;
 pub use deny_all::*;
 pub use local::*;
 pub use onward::*;
+#[cfg(feature = "std")]
+pub use rate_limit::*;
 pub use source::*;
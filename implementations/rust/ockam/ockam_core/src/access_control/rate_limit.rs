@@ -0,0 +1,227 @@
+use crate::compat::boxed::Box;
+use crate::compat::collections::BTreeMap;
+use crate::compat::sync::Mutex;
+use crate::compat::vec::Vec;
+use crate::errcode::{Kind, Origin};
+use crate::{async_trait, Error, IncomingAccessControl, RelayMessage, Result, TransportPeerInfo};
+use core::fmt::Debug;
+use std::time::{Duration, Instant};
+
+/// Extract the key under which a message is rate limited
+///
+/// Messages without a key are all rate limited together, in a shared bucket.
+pub trait RateLimitKey: Debug + Send + Sync + 'static {
+    /// Return the key of a message, if any
+    fn key(&self, relay_msg: &RelayMessage) -> Option<Vec<u8>>;
+}
+
+/// Rate limit messages per source address
+#[derive(Debug)]
+pub struct SourceAddressKey;
+
+impl RateLimitKey for SourceAddressKey {
+    fn key(&self, relay_msg: &RelayMessage) -> Option<Vec<u8>> {
+        Some(relay_msg.source().to_string().into_bytes())
+    }
+}
+
+/// Rate limit messages per IP address of the peer of the transport connection
+/// they were received from
+///
+/// This address is kept when messages are forwarded by secure channels, so
+/// that a peer can't get new buckets by creating new identities or channels.
+/// The messages which were not received from a transport connection are rate
+/// limited together, in a shared bucket.
+#[derive(Debug)]
+pub struct TransportPeerKey;
+
+impl RateLimitKey for TransportPeerKey {
+    fn key(&self, relay_msg: &RelayMessage) -> Option<Vec<u8>> {
+        TransportPeerInfo::find_info(relay_msg.local_message()).map(|p| p.ip().as_bytes().to_vec())
+    }
+}
+
+/// Rate limit all messages together, in a single bucket
+#[derive(Debug)]
+pub struct GlobalKey;
+
+impl RateLimitKey for GlobalKey {
+    fn key(&self, _relay_msg: &RelayMessage) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// A token bucket
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// An Access Control type that allows a maximum rate of messages per key
+///
+/// Each key gets a bucket of `burst` tokens, refilled at `rate` tokens per
+/// second. A message is allowed if a token can be taken from its bucket.
+/// This access control is usually combined with other ones, with
+/// [`AllIncomingAccessControl`](crate::AllIncomingAccessControl), to protect
+/// publicly reachable workers from being flooded.
+#[derive(Debug)]
+pub struct RateLimitedAccessControl<K> {
+    key: K,
+    rate: f64,
+    burst: f64,
+    max_keys: usize,
+    buckets: Mutex<BTreeMap<Option<Vec<u8>>, Bucket>>,
+}
+
+impl<K: RateLimitKey> RateLimitedAccessControl<K> {
+    /// Default maximum number of keys tracked at the same time
+    pub const DEFAULT_MAX_KEYS: usize = 10_000;
+
+    /// Allow `rate` messages per second, with bursts of up to `burst` messages, per key
+    pub fn new(key: K, rate: f64, burst: u32) -> Result<Self> {
+        if rate.is_nan() || rate <= 0.0 || burst == 0 {
+            return Err(Error::new(
+                Origin::Core,
+                Kind::Invalid,
+                "the rate and burst of a rate limit must be positive",
+            ));
+        }
+        Ok(Self {
+            key,
+            rate,
+            burst: burst as f64,
+            max_keys: Self::DEFAULT_MAX_KEYS,
+            buckets: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    /// Set the maximum number of keys tracked at the same time
+    ///
+    /// When this number is reached, the keys whose buckets are full again are
+    /// forgotten. Messages with a new key are denied while no key can be forgotten.
+    pub fn with_max_keys(mut self, max_keys: usize) -> Self {
+        self.max_keys = max_keys;
+        self
+    }
+
+    fn take_token(&self, key: Option<Vec<u8>>, now: Instant) -> bool {
+        let mut buckets = self.buckets.lock().unwrap();
+        if !buckets.contains_key(&key) && buckets.len() >= self.max_keys {
+            buckets.retain(|_, b| self.refill(b, now) < self.burst);
+            if buckets.len() >= self.max_keys {
+                return false;
+            }
+        }
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        if self.refill(bucket, now) >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Add the tokens accumulated since the last update of a bucket and
+    /// return the number of available tokens
+    fn refill(&self, bucket: &mut Bucket, now: Instant) -> f64 {
+        let elapsed = now
+            .checked_duration_since(bucket.updated)
+            .unwrap_or(Duration::ZERO);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * self.rate).min(self.burst);
+        bucket.updated = now;
+        bucket.tokens
+    }
+}
+
+#[async_trait]
+impl<K: RateLimitKey> IncomingAccessControl for RateLimitedAccessControl<K> {
+    async fn is_authorized(&self, relay_msg: &RelayMessage) -> Result<bool> {
+        if self.take_token(self.key.key(relay_msg), Instant::now()) {
+            crate::allow()
+        } else {
+            debug!(
+                "rate limit exceeded for a message from {} to {}",
+                relay_msg.source(),
+                relay_msg.destination()
+            );
+            crate::deny()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{route, LocalMessage, TransportMessage};
+
+    #[test]
+    fn test_burst_and_refill() -> Result<()> {
+        let ac = RateLimitedAccessControl::new(SourceAddressKey, 2.0, 3)?;
+        let start = Instant::now();
+        let a = Some(b"a".to_vec());
+        let b = Some(b"b".to_vec());
+
+        for _ in 0..3 {
+            assert!(ac.take_token(a.clone(), start));
+        }
+        assert!(!ac.take_token(a.clone(), start));
+
+        // Other keys have their own bucket
+        assert!(ac.take_token(b, start));
+
+        // 2 tokens per second
+        let later = start + Duration::from_millis(500);
+        assert!(ac.take_token(a.clone(), later));
+        assert!(!ac.take_token(a.clone(), later));
+
+        // The bucket never holds more than the burst
+        let much_later = start + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert!(ac.take_token(a.clone(), much_later));
+        }
+        assert!(!ac.take_token(a, much_later));
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_keys() -> Result<()> {
+        let ac = RateLimitedAccessControl::new(SourceAddressKey, 1.0, 1)?.with_max_keys(2);
+        let start = Instant::now();
+
+        assert!(ac.take_token(Some(b"a".to_vec()), start));
+        assert!(ac.take_token(Some(b"b".to_vec()), start));
+        // Both buckets are empty, no key can be forgotten
+        assert!(!ac.take_token(Some(b"c".to_vec()), start));
+
+        // Once the buckets are full again, their keys are forgotten
+        let later = start + Duration::from_secs(1);
+        assert!(ac.take_token(Some(b"c".to_vec()), later));
+        assert_eq!(ac.buckets.lock().unwrap().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transport_peer_key() {
+        let message = |local_info| {
+            let transport_message = TransportMessage::v1(route![], route![], vec![]);
+            RelayMessage::new(
+                "source".into(),
+                "destination".into(),
+                LocalMessage::new(transport_message, local_info),
+            )
+        };
+        let from_peer = message(vec![TransportPeerInfo::new("10.0.0.1").to_local_info()]);
+        assert_eq!(TransportPeerKey.key(&from_peer), Some(b"10.0.0.1".to_vec()));
+        assert_eq!(TransportPeerKey.key(&message(vec![])), None);
+    }
+
+    #[test]
+    fn test_invalid_rate() {
+        assert!(RateLimitedAccessControl::new(SourceAddressKey, 0.0, 1).is_err());
+        assert!(RateLimitedAccessControl::new(SourceAddressKey, 1.0, 0).is_err());
+    }
+}
@@ -326,7 +326,7 @@ pub mod fmt {
     pub type Vec<T> = heapless::Vec<T, 64>;
 }
 
-/// Provides `future::poll_once`
+/// Provides `future::poll_once` and `future::join_all`
 pub mod future {
     use crate::{
         errcode::{Kind, Origin},
@@ -334,6 +334,9 @@ pub mod future {
     };
     use futures_util::future::{Future, FutureExt};
 
+    #[cfg(feature = "alloc")]
+    pub use futures_util::future::join_all;
+
     /// Polls a future just once and returns the Result
     ///
     /// This is only used for some tests and it is hoped that we can
@@ -5,8 +5,7 @@ use crate::compat::vec::Vec;
 use crate::flow_control::{FlowControlId, FlowControlPolicy};
 use crate::Address;
 
-// TODO: Consider integrating this into Routing for better UX + to allow removing
-// entries from that storage
+// TODO: Consider integrating this into Routing for better UX
 /// Storage for all Flow Control-related data
 #[derive(Clone, Debug, Default)]
 pub struct FlowControls {
@@ -122,6 +121,140 @@ impl FlowControls {
             .map(|x| x.0.clone())
             .collect()
     }
+
+    /// Remove all Flow Control entries of the given [`Address`]
+    ///
+    /// Workers and Processors registered as Consumers, Producers or Spawners
+    /// should call this when they stop, so that their entries do not accumulate.
+    /// When the last Producer or Spawner of a [`FlowControlId`] is removed,
+    /// the Consumers of that [`FlowControlId`] are removed as well.
+    pub fn cleanup_address(&self, address: &Address) {
+        // All the locks are held together, so that a Producer or a Spawner added
+        // concurrently is either seen by the check below or added after the cleanup.
+        // Other functions never hold more than one of these locks at a time.
+        let mut consumers = self.consumers.write().unwrap();
+        let mut producers = self.producers.write().unwrap();
+        let mut producers_additional_addresses =
+            self.producers_additional_addresses.write().unwrap();
+        let mut spawners = self.spawners.write().unwrap();
+
+        let mut removed_flow_control_ids = Vec::new();
+        if let Some(producer_info) = producers.remove(address) {
+            removed_flow_control_ids.push(producer_info.flow_control_id);
+        }
+        producers_additional_addresses.retain(|additional_address, producer_address| {
+            additional_address != address && producer_address != address
+        });
+        if let Some(flow_control_id) = spawners.remove(address) {
+            removed_flow_control_ids.push(flow_control_id);
+        }
+
+        // Keep the Consumers of the FlowControlIds which still have a Producer or a Spawner
+        removed_flow_control_ids.retain(|flow_control_id| {
+            !producers
+                .values()
+                .any(|x| &x.flow_control_id == flow_control_id)
+                && !spawners.values().any(|x| x == flow_control_id)
+        });
+
+        for flow_control_id in removed_flow_control_ids {
+            consumers.remove(&flow_control_id);
+        }
+        for flow_control_consumers in consumers.values_mut() {
+            flow_control_consumers.0.remove(address);
+        }
+        consumers.retain(|_, x| !x.0.is_empty());
+    }
+
+    /// Get all known Flow Controls with their Spawners, Producers and Consumers
+    pub fn get_flow_controls_info(&self) -> Vec<FlowControlInfo> {
+        let mut flow_controls: BTreeMap<FlowControlId, FlowControlInfo> = BTreeMap::new();
+
+        let spawners = self.spawners.read().unwrap();
+        for (address, flow_control_id) in spawners.iter() {
+            flow_controls
+                .entry(flow_control_id.clone())
+                .or_insert_with(|| FlowControlInfo::new(flow_control_id))
+                .spawners
+                .push(address.clone());
+        }
+        drop(spawners);
+
+        let producers = self.producers.read().unwrap();
+        for (address, producer_info) in producers.iter() {
+            let info = flow_controls
+                .entry(producer_info.flow_control_id.clone())
+                .or_insert_with(|| FlowControlInfo::new(&producer_info.flow_control_id));
+            info.producers.push(address.clone());
+            if producer_info.spawner_flow_control_id.is_some() {
+                info.spawner_flow_control_id = producer_info.spawner_flow_control_id.clone();
+            }
+        }
+        drop(producers);
+
+        let consumers = self.consumers.read().unwrap();
+        for (flow_control_id, flow_control_consumers) in consumers.iter() {
+            flow_controls
+                .entry(flow_control_id.clone())
+                .or_insert_with(|| FlowControlInfo::new(flow_control_id))
+                .consumers
+                .extend(
+                    flow_control_consumers
+                        .0
+                        .iter()
+                        .map(|(address, policy)| (address.clone(), *policy)),
+                );
+        }
+
+        flow_controls.into_values().collect()
+    }
+}
+
+/// Spawners, Producers and Consumers of a Flow Control
+#[derive(Clone, Debug)]
+pub struct FlowControlInfo {
+    flow_control_id: FlowControlId,
+    spawner_flow_control_id: Option<FlowControlId>,
+    spawners: Vec<Address>,
+    producers: Vec<Address>,
+    consumers: Vec<(Address, FlowControlPolicy)>,
+}
+
+impl FlowControlInfo {
+    fn new(flow_control_id: &FlowControlId) -> Self {
+        Self {
+            flow_control_id: flow_control_id.clone(),
+            spawner_flow_control_id: None,
+            spawners: Vec::new(),
+            producers: Vec::new(),
+            consumers: Vec::new(),
+        }
+    }
+
+    /// [`FlowControlId`]
+    pub fn flow_control_id(&self) -> &FlowControlId {
+        &self.flow_control_id
+    }
+
+    /// [`FlowControlId`] of the Spawner which spawned the Producers, if any
+    pub fn spawner_flow_control_id(&self) -> &Option<FlowControlId> {
+        &self.spawner_flow_control_id
+    }
+
+    /// Spawners [`Address`]es
+    pub fn spawners(&self) -> &[Address] {
+        &self.spawners
+    }
+
+    /// Producers [`Address`]es
+    pub fn producers(&self) -> &[Address] {
+        &self.producers
+    }
+
+    /// Consumers [`Address`]es with their [`FlowControlPolicy`]
+    pub fn consumers(&self) -> &[(Address, FlowControlPolicy)] {
+        &self.consumers
+    }
 }
 
 /// Known Consumers for the given [`FlowControlId`]
@@ -146,3 +279,147 @@ impl ProducerInfo {
         &self.spawner_flow_control_id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cleanup_address() {
+        let flow_controls = FlowControls::default();
+        let spawner_id = flow_controls.generate_id();
+        let producer_id = flow_controls.generate_id();
+        let spawner = Address::random_local();
+        let producer = Address::random_local();
+        let additional = Address::random_local();
+        let consumer = Address::random_local();
+
+        flow_controls.add_spawner(&spawner, &spawner_id);
+        flow_controls.add_producer(
+            &producer,
+            &producer_id,
+            Some(&spawner_id),
+            vec![additional.clone()],
+        );
+        flow_controls.add_consumer(
+            &consumer,
+            &producer_id,
+            FlowControlPolicy::ProducerAllowMultiple,
+        );
+
+        let info = flow_controls.get_flow_controls_info();
+        assert_eq!(info.len(), 2);
+        let producer_info = info
+            .iter()
+            .find(|x| x.flow_control_id() == &producer_id)
+            .unwrap();
+        assert_eq!(producer_info.producers(), core::slice::from_ref(&producer));
+        assert_eq!(
+            producer_info.spawner_flow_control_id(),
+            &Some(spawner_id.clone())
+        );
+        assert_eq!(producer_info.consumers()[0].0, consumer);
+
+        flow_controls.cleanup_address(&producer);
+        assert!(flow_controls
+            .find_flow_control_with_producer_address(&additional)
+            .is_none());
+        flow_controls.cleanup_address(&consumer);
+        assert!(flow_controls
+            .get_flow_controls_with_consumer(&consumer)
+            .is_empty());
+
+        // Only the spawner is left
+        let info = flow_controls.get_flow_controls_info();
+        assert_eq!(info.len(), 1);
+        assert_eq!(info[0].spawners(), core::slice::from_ref(&spawner));
+
+        flow_controls.cleanup_address(&spawner);
+        assert!(flow_controls.get_flow_controls_info().is_empty());
+    }
+
+    #[test]
+    fn test_cleanup_last_producer_removes_consumers() {
+        let flow_controls = FlowControls::default();
+        let flow_control_id = flow_controls.generate_id();
+        let producer1 = Address::random_local();
+        let producer2 = Address::random_local();
+        let consumer = Address::random_local();
+
+        flow_controls.add_producer(&producer1, &flow_control_id, None, vec![]);
+        flow_controls.add_producer(&producer2, &flow_control_id, None, vec![]);
+        flow_controls.add_consumer(
+            &consumer,
+            &flow_control_id,
+            FlowControlPolicy::ProducerAllowMultiple,
+        );
+
+        // Another Producer is still using that FlowControlId
+        flow_controls.cleanup_address(&producer1);
+        assert_eq!(
+            flow_controls.get_flow_controls_with_consumer(&consumer),
+            vec![flow_control_id.clone()]
+        );
+
+        flow_controls.cleanup_address(&producer2);
+        assert!(flow_controls
+            .get_flow_controls_with_consumer(&consumer)
+            .is_empty());
+        assert!(flow_controls.get_flow_controls_info().is_empty());
+    }
+
+    #[test]
+    fn test_cleanup_spawner_removes_consumers() {
+        let flow_controls = FlowControls::default();
+        let flow_control_id = flow_controls.generate_id();
+        let spawner = Address::random_local();
+        let consumer = Address::random_local();
+
+        flow_controls.add_spawner(&spawner, &flow_control_id);
+        flow_controls.add_consumer(
+            &consumer,
+            &flow_control_id,
+            FlowControlPolicy::SpawnerAllowMultipleMessages,
+        );
+
+        flow_controls.cleanup_address(&spawner);
+        assert!(flow_controls.get_flow_controls_info().is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_cleanup_concurrent_with_add_producer() {
+        for _ in 0..100 {
+            let flow_controls = FlowControls::default();
+            let flow_control_id = flow_controls.generate_id();
+            let producer1 = Address::random_local();
+            let producer2 = Address::random_local();
+            let consumer = Address::random_local();
+
+            flow_controls.add_producer(&producer1, &flow_control_id, None, vec![]);
+
+            let handle = {
+                let flow_controls = flow_controls.clone();
+                let flow_control_id = flow_control_id.clone();
+                let producer2 = producer2.clone();
+                let consumer = consumer.clone();
+                std::thread::spawn(move || {
+                    flow_controls.add_producer(&producer2, &flow_control_id, None, vec![]);
+                    flow_controls.add_consumer(
+                        &consumer,
+                        &flow_control_id,
+                        FlowControlPolicy::ProducerAllowMultiple,
+                    );
+                })
+            };
+            flow_controls.cleanup_address(&producer1);
+            handle.join().unwrap();
+
+            // The Consumer added along with the new Producer is never removed
+            assert_eq!(
+                flow_controls.get_flow_controls_with_consumer(&consumer),
+                vec![flow_control_id]
+            );
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use core::fmt;
+
 /// Policy according to which a Consumer wants to receive messages from a Producer or Spawner
 #[derive(Copy, Clone, Debug)]
 pub enum FlowControlPolicy {
@@ -8,3 +10,17 @@ pub enum FlowControlPolicy {
     /// Spawner is allowed to send any number of messages to the Consumer
     SpawnerAllowMultipleMessages,
 }
+
+impl fmt::Display for FlowControlPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowControlPolicy::ProducerAllowMultiple => f.write_str("producer_allow_multiple"),
+            FlowControlPolicy::SpawnerAllowOnlyOneMessage => {
+                f.write_str("spawner_allow_only_one_message")
+            }
+            FlowControlPolicy::SpawnerAllowMultipleMessages => {
+                f.write_str("spawner_allow_multiple_messages")
+            }
+        }
+    }
+}
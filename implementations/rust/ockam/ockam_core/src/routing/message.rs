@@ -3,8 +3,14 @@
 mod local_message;
 pub use local_message::*;
 
+mod priority;
+pub use priority::*;
+
 mod relay_message;
 pub use relay_message::*;
 
 mod transport_message;
 pub use transport_message::*;
+
+mod transport_peer;
+pub use transport_peer::*;
@@ -0,0 +1,81 @@
+use crate::{LocalInfo, LocalMessage};
+
+/// [`MessagePriority`] LocalInfo unique Identifier
+pub const MESSAGE_PRIORITY_IDENTIFIER: &str = "MESSAGE_PRIORITY";
+
+/// Scheduling class of a message, used by transports multiplexing
+/// several routes over a single connection
+///
+/// Messages without a priority are treated as [`MessagePriority::Control`].
+/// Workers producing large amounts of data (e.g. TCP portals) should
+/// mark their messages as [`MessagePriority::Bulk`] so that transports
+/// can send control messages ahead of them. Workers wrapping messages
+/// into new ones (e.g. secure channels) should propagate the priority.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MessagePriority {
+    /// Latency sensitive messages (API requests, handshakes, ...)
+    Control,
+    /// Throughput oriented messages
+    Bulk,
+}
+
+impl Default for MessagePriority {
+    fn default() -> Self {
+        MessagePriority::Control
+    }
+}
+
+impl MessagePriority {
+    /// Encode this priority to a general [`LocalInfo`]
+    pub fn to_local_info(&self) -> LocalInfo {
+        let data = match self {
+            MessagePriority::Control => 0,
+            MessagePriority::Bulk => 1,
+        };
+        LocalInfo::new(MESSAGE_PRIORITY_IDENTIFIER.into(), vec![data])
+    }
+
+    /// Try to decode a priority from a general [`LocalInfo`]
+    pub fn from_local_info(value: &LocalInfo) -> Option<Self> {
+        if value.type_identifier() != MESSAGE_PRIORITY_IDENTIFIER {
+            return None;
+        }
+
+        match value.data() {
+            [0] => Some(MessagePriority::Control),
+            [1] => Some(MessagePriority::Bulk),
+            _ => None,
+        }
+    }
+
+    /// Find the priority of a [`LocalMessage`], if any
+    pub fn find_info(local_msg: &LocalMessage) -> Option<Self> {
+        Self::find_info_from_list(local_msg.local_info())
+    }
+
+    /// Find a priority in a list of general [`LocalInfo`]
+    pub fn find_info_from_list(local_info: &[LocalInfo]) -> Option<Self> {
+        local_info.iter().find_map(Self::from_local_info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{route, TransportMessage};
+
+    #[test]
+    fn priority_roundtrip() {
+        let msg = LocalMessage::new(
+            TransportMessage::v1(route![], route![], vec![]),
+            vec![MessagePriority::Bulk.to_local_info()],
+        );
+        assert_eq!(
+            MessagePriority::find_info(&msg),
+            Some(MessagePriority::Bulk)
+        );
+
+        let msg = LocalMessage::new(TransportMessage::v1(route![], route![], vec![]), vec![]);
+        assert_eq!(MessagePriority::find_info(&msg), None);
+    }
+}
@@ -0,0 +1,56 @@
+use crate::compat::string::String;
+use crate::{LocalInfo, LocalMessage};
+
+/// [`TransportPeerInfo`] LocalInfo unique Identifier
+pub const TRANSPORT_PEER_IDENTIFIER: &str = "TRANSPORT_PEER";
+
+/// Remote end of the transport connection a message was received from
+///
+/// Transports mark the messages they receive with this information.
+/// Workers wrapping messages into new ones (e.g. secure channels) should
+/// propagate it, so that access controls can check where a message came from.
+/// Note that for routes going through relays this is the address of the
+/// last hop, not of the message sender.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TransportPeerInfo {
+    ip: String,
+}
+
+impl TransportPeerInfo {
+    /// Create a new `TransportPeerInfo` for a peer IP address
+    pub fn new(ip: impl Into<String>) -> Self {
+        Self { ip: ip.into() }
+    }
+
+    /// IP address of the peer
+    pub fn ip(&self) -> &str {
+        &self.ip
+    }
+
+    /// Encode this information to a general [`LocalInfo`]
+    pub fn to_local_info(&self) -> LocalInfo {
+        LocalInfo::new(
+            TRANSPORT_PEER_IDENTIFIER.into(),
+            self.ip.as_bytes().to_vec(),
+        )
+    }
+
+    /// Try to decode the peer information from a general [`LocalInfo`]
+    pub fn from_local_info(value: &LocalInfo) -> Option<Self> {
+        if value.type_identifier() != TRANSPORT_PEER_IDENTIFIER {
+            return None;
+        }
+
+        core::str::from_utf8(value.data()).ok().map(Self::new)
+    }
+
+    /// Find the peer information of a [`LocalMessage`], if any
+    pub fn find_info(local_msg: &LocalMessage) -> Option<Self> {
+        Self::find_info_from_list(local_msg.local_info())
+    }
+
+    /// Find the peer information in a list of general [`LocalInfo`]
+    pub fn find_info_from_list(local_info: &[LocalInfo]) -> Option<Self> {
+        local_info.iter().find_map(Self::from_local_info)
+    }
+}
@@ -6,7 +6,8 @@ use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
 
 use crate::credential::{
-    Attributes, CredentialData, SchemaId, Timestamp, Verified, MAX_CREDENTIAL_VALIDITY,
+    Attributes, CredentialData, CredentialSchema, SchemaId, Timestamp, Verified,
+    MAX_CREDENTIAL_VALIDITY,
 };
 use crate::identity::identity_change::IdentityChangeConstants;
 use crate::identity::IdentityIdentifier;
@@ -17,6 +18,7 @@ use crate::TypeTag;
 /// Convenience structure to create [`Credential`]s.
 pub struct CredentialBuilder {
     pub(crate) schema: Option<SchemaId>,
+    pub(crate) schema_definition: Option<CredentialSchema>,
     pub(crate) attrs: Attributes,
     pub(crate) subject: IdentityIdentifier,
     pub(crate) issuer: IdentityIdentifier,
@@ -30,6 +32,7 @@ impl CredentialBuilder {
     ) -> CredentialBuilder {
         Self {
             schema: None,
+            schema_definition: None,
             attrs: Attributes::default(),
             subject,
             issuer,
@@ -62,6 +65,14 @@ impl CredentialBuilder {
         self
     }
 
+    /// Set the schema of the credential, its attributes are validated against it
+    /// when the credential data is built.
+    pub fn with_schema_definition(mut self, schema: &CredentialSchema) -> Self {
+        self.schema = Some(schema.id());
+        self.schema_definition = Some(schema.clone());
+        self
+    }
+
     /// Set validity duration of the credential.
     ///
     /// # Panics
@@ -77,7 +88,12 @@ impl CredentialBuilder {
     }
 
     /// Return a verified credential data, with a created timestamp
+    ///
+    /// Fail if the attributes don't conform to the schema definition, if one was given.
     pub fn build(self) -> Result<CredentialData<Verified>> {
+        if let Some(schema) = &self.schema_definition {
+            schema.validate(&self.attrs)?;
+        }
         let key_label = IdentityChangeConstants::ROOT_LABEL;
         let now = Timestamp::now()
             .ok_or_else(|| Error::new(Origin::Core, Kind::Internal, "invalid system time"))?;
@@ -332,7 +332,8 @@ impl Timestamp {
             .map(|d| Timestamp(d.as_secs()))
     }
 
-    pub(crate) fn add_seconds(&self, seconds: u64) -> Self {
+    /// Return the timestamp which is a number of seconds after this one
+    pub fn add_seconds(&self, seconds: u64) -> Self {
         Timestamp(self.0.saturating_add(seconds))
     }
 
@@ -360,8 +361,11 @@ impl From<Timestamp> for u64 {
 }
 
 /// A schema identifier allows discriminate sets of credential attributes.
-#[derive(Debug, Clone, Copy, Encode, Decode, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(
+    Debug, Clone, Copy, Encode, Decode, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
 #[cbor(transparent)]
+#[serde(transparent)]
 pub struct SchemaId(#[n(0)] pub u64);
 
 impl From<SchemaId> for u64 {
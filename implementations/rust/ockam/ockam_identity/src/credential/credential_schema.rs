@@ -0,0 +1,285 @@
+use crate::credential::{Attributes, SchemaId};
+use crate::{LEGACY_ID, TRUST_CONTEXT_ID};
+use core::str::{from_utf8, FromStr};
+use minicbor::{Decode, Encode};
+use ockam_core::compat::{string::String, vec::Vec};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "tag")]
+use crate::TypeTag;
+
+/// Declaration of the attributes that the credentials of a given schema can contain
+///
+/// Attributes are validated when a credential is built, issued and verified.
+/// The attributes identifying the trust context are always accepted since they
+/// are added by the credentials issuer.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CredentialSchema {
+    #[cfg(feature = "tag")]
+    #[serde(skip)]
+    #[n(0)] tag: TypeTag<2417760>,
+    #[n(1)] id: SchemaId,
+    #[n(2)] name: String,
+    #[n(3)] attributes: Vec<AttributeSchema>,
+    /// If true, attributes which are not declared by the schema are accepted
+    #[serde(default)]
+    #[n(4)] additional_attributes: bool,
+}
+
+impl CredentialSchema {
+    /// Create a new schema without any attribute
+    pub fn new(id: SchemaId, name: &str) -> Self {
+        Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            id,
+            name: name.into(),
+            attributes: Vec::new(),
+            additional_attributes: false,
+        }
+    }
+
+    /// Declare an attribute
+    pub fn with_attribute(mut self, attribute: AttributeSchema) -> Self {
+        self.attributes.retain(|a| a.name != attribute.name);
+        self.attributes.push(attribute);
+        self
+    }
+
+    /// Accept the attributes which are not declared by the schema
+    pub fn with_additional_attributes(mut self, additional_attributes: bool) -> Self {
+        self.additional_attributes = additional_attributes;
+        self
+    }
+
+    /// Return the schema identifier
+    pub fn id(&self) -> SchemaId {
+        self.id
+    }
+
+    /// Return the schema name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the declared attributes
+    pub fn attributes(&self) -> &[AttributeSchema] {
+        &self.attributes
+    }
+
+    /// Return true if undeclared attributes are accepted
+    pub fn additional_attributes(&self) -> bool {
+        self.additional_attributes
+    }
+
+    /// Check that a complete set of attributes conforms to this schema
+    pub fn validate(&self, attributes: &Attributes) -> Result<()> {
+        for declared in self.attributes.iter().filter(|a| a.required) {
+            if attributes.get(&declared.name).is_none() {
+                return Err(invalid(format!(
+                    "missing required attribute '{}' for schema {}",
+                    declared.name, self.id
+                )));
+            }
+        }
+        self.validate_disclosed(attributes)
+    }
+
+    /// Check that some attributes conform to this schema, without requiring
+    /// all the required attributes to be present
+    ///
+    /// This is used for credentials only disclosing a subset of their attributes.
+    pub fn validate_disclosed(&self, attributes: &Attributes) -> Result<()> {
+        for (name, value) in attributes.iter() {
+            match self.attributes.iter().find(|a| &a.name == name) {
+                Some(declared) => declared.validate(value)?,
+                None if self.additional_attributes
+                    || name == TRUST_CONTEXT_ID
+                    || name == LEGACY_ID => {}
+                None => {
+                    return Err(invalid(format!(
+                        "attribute '{}' is not declared by schema {}",
+                        name, self.id
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Declaration of a credential attribute
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AttributeSchema {
+    #[n(1)] name: String,
+    #[serde(rename = "type")]
+    #[n(2)] attribute_type: AttributeType,
+    #[serde(default)]
+    #[n(3)] required: bool,
+    /// If not empty, the only values accepted for this attribute
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[n(4)] allowed_values: Vec<String>,
+}
+
+impl AttributeSchema {
+    /// Declare an optional attribute
+    pub fn new(name: &str, attribute_type: AttributeType) -> Self {
+        Self {
+            name: name.into(),
+            attribute_type,
+            required: false,
+            allowed_values: Vec::new(),
+        }
+    }
+
+    /// Make the attribute required
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Restrict the values accepted for the attribute
+    pub fn with_allowed_values(mut self, allowed_values: Vec<String>) -> Self {
+        self.allowed_values = allowed_values;
+        self
+    }
+
+    /// Return the attribute name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the attribute type
+    pub fn attribute_type(&self) -> AttributeType {
+        self.attribute_type
+    }
+
+    /// Return true if the attribute is required
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    /// Return the values accepted for the attribute, any value is accepted if empty
+    pub fn allowed_values(&self) -> &[String] {
+        &self.allowed_values
+    }
+
+    fn validate(&self, value: &[u8]) -> Result<()> {
+        if self.attribute_type == AttributeType::Bytes {
+            return Ok(());
+        }
+        let text = from_utf8(value).map_err(|_| {
+            invalid(format!(
+                "the value of attribute '{}' is not valid UTF-8",
+                self.name
+            ))
+        })?;
+        let valid_type = match self.attribute_type {
+            AttributeType::Integer => i64::from_str(text).is_ok(),
+            AttributeType::Boolean => text == "true" || text == "false",
+            AttributeType::Utf8 | AttributeType::Bytes => true,
+        };
+        if !valid_type {
+            return Err(invalid(format!(
+                "the value of attribute '{}' is not a valid {}",
+                self.name,
+                self.attribute_type.as_str()
+            )));
+        }
+        if !self.allowed_values.is_empty() && !self.allowed_values.iter().any(|v| v == text) {
+            return Err(invalid(format!(
+                "the value '{}' is not allowed for attribute '{}'",
+                text, self.name
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Type of the value of a credential attribute
+///
+/// Values are always transported as bytes. Except for `Bytes`, they
+/// must be the UTF-8 representation of a value of the given type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum AttributeType {
+    /// Any UTF-8 string
+    #[n(0)] Utf8,
+    /// A signed 64 bits integer in decimal notation, for example `-42`
+    #[n(1)] Integer,
+    /// Either `true` or `false`
+    #[n(2)] Boolean,
+    /// Arbitrary bytes, which are not validated
+    #[n(3)] Bytes,
+}
+
+impl AttributeType {
+    /// Return the name of the type
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttributeType::Utf8 => "utf8",
+            AttributeType::Integer => "integer",
+            AttributeType::Boolean => "boolean",
+            AttributeType::Bytes => "bytes",
+        }
+    }
+}
+
+fn invalid(message: String) -> Error {
+    Error::new(Origin::Identity, Kind::Invalid, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_validation() {
+        let schema = CredentialSchema::new(SchemaId(42), "employee")
+            .with_attribute(
+                AttributeSchema::new("role", AttributeType::Utf8)
+                    .required()
+                    .with_allowed_values(vec!["admin".into(), "member".into()]),
+            )
+            .with_attribute(AttributeSchema::new("level", AttributeType::Integer))
+            .with_attribute(AttributeSchema::new("active", AttributeType::Boolean));
+
+        let mut attributes = Attributes::new();
+        attributes
+            .put("role", b"admin")
+            .put(TRUST_CONTEXT_ID, b"ctx");
+        assert!(schema.validate(&attributes).is_ok());
+
+        attributes.put("level", b"3").put("active", b"true");
+        assert!(schema.validate(&attributes).is_ok());
+
+        let mut missing = Attributes::new();
+        missing.put("level", b"3");
+        assert!(schema.validate(&missing).is_err());
+        assert!(schema.validate_disclosed(&missing).is_ok());
+
+        let mut wrong_type = attributes.clone();
+        wrong_type.put("level", b"high");
+        assert!(schema.validate(&wrong_type).is_err());
+
+        let mut not_allowed = attributes.clone();
+        not_allowed.put("role", b"root");
+        assert!(schema.validate(&not_allowed).is_err());
+
+        let mut undeclared = attributes.clone();
+        undeclared.put("team", b"blue");
+        assert!(schema.validate(&undeclared).is_err());
+        assert!(schema
+            .with_additional_attributes(true)
+            .validate(&undeclared)
+            .is_ok());
+    }
+}
@@ -2,9 +2,17 @@
 mod credential;
 mod credential_builder;
 mod credential_data;
+mod credential_schema;
 mod one_time_code;
+mod policy_bundle;
+mod revocation_list;
+mod selective_disclosure;
 
 pub use credential::*;
 pub use credential_builder::*;
 pub use credential_data::*;
+pub use credential_schema::*;
 pub use one_time_code::*;
+pub use policy_bundle::*;
+pub use revocation_list::*;
+pub use selective_disclosure::*;
@@ -0,0 +1,46 @@
+use minicbor::{Decode, Encode};
+use ockam_core::compat::vec::Vec;
+
+#[cfg(feature = "tag")]
+use crate::TypeTag;
+
+/// Policy bundle data + signature for that data
+///
+/// A policy bundle is a set of access control policies published by an
+/// authority for the nodes of its trust context. Its data is opaque to
+/// this crate: it is encoded, signed and interpreted by the policy engine.
+#[derive(Clone, Debug, Decode, Encode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SignedPolicyBundle {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<3170562>,
+    /// CBOR-encoded policy bundle.
+    #[cbor(with = "minicbor::bytes")]
+    #[b(1)] pub data: Vec<u8>,
+    /// Cryptographic signature of the policy bundle data.
+    #[cbor(with = "minicbor::bytes")]
+    #[b(2)] pub signature: Vec<u8>,
+}
+
+impl SignedPolicyBundle {
+    /// Create a signed policy bundle from its data and the signature of that data
+    pub fn new(data: Vec<u8>, signature: Vec<u8>) -> Self {
+        SignedPolicyBundle {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data,
+            signature,
+        }
+    }
+
+    /// Return the signature of a policy bundle
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+
+    /// Return the serialized data of a policy bundle
+    pub fn unverified_data(&self) -> &[u8] {
+        &self.data
+    }
+}
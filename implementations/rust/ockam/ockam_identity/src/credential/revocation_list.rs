@@ -0,0 +1,182 @@
+use crate::credential::Timestamp;
+use crate::identity::IdentityIdentifier;
+use minicbor::{Decode, Encode};
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+#[cfg(feature = "tag")]
+use crate::TypeTag;
+
+/// Prefix of the bytes signed by the issuer of a [`RevocationList`]
+///
+/// It makes sure that the signature of a revocation list can't be mistaken for the
+/// signature of another kind of data, like a [`CredentialData`](crate::CredentialData).
+const SIGNATURE_DOMAIN: &[u8] = b"ockam:revocation_list:v1:";
+
+/// Revocation list data + signature for that data
+///
+/// A revocation list is signed by an authority. Credentials issued by
+/// that authority to a revoked subject before the revocation time must
+/// be rejected, even if they have not expired yet.
+#[derive(Clone, Debug, Decode, Encode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RevocationList {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6403874>,
+    /// CBOR-encoded [`RevocationListData`].
+    #[cbor(with = "minicbor::bytes")]
+    #[b(1)] pub data: Vec<u8>,
+    /// Cryptographic signature of the revocation list data.
+    #[cbor(with = "minicbor::bytes")]
+    #[b(2)] pub signature: Vec<u8>,
+}
+
+impl RevocationList {
+    /// Return the signature of a revocation list
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+
+    /// Return the serialized data of a revocation list
+    pub fn unverified_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Return the bytes signed by the issuer for the serialized revocation list data
+    pub(crate) fn signed_data(data: &[u8]) -> Vec<u8> {
+        [SIGNATURE_DOMAIN, data].concat()
+    }
+
+    pub(crate) fn new(data: Vec<u8>, signature: Vec<u8>) -> Self {
+        RevocationList {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data,
+            signature,
+        }
+    }
+}
+
+/// Subjects revoked by an authority at a given time
+#[derive(Clone, Debug, Decode, Encode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RevocationListData {
+    /// The authority which revoked the subjects.
+    #[n(1)] issuer: IdentityIdentifier,
+    /// The time when this list was created.
+    /// A list only replaces the lists of the same issuer created before it.
+    #[n(2)] created: Timestamp,
+    /// The revoked subjects.
+    #[n(3)] revoked: Vec<RevokedSubject>,
+}
+
+impl RevocationListData {
+    /// Create a new revocation list
+    pub fn new(
+        issuer: IdentityIdentifier,
+        created: Timestamp,
+        revoked: Vec<RevokedSubject>,
+    ) -> Self {
+        Self {
+            issuer,
+            created,
+            revoked,
+        }
+    }
+
+    /// The authority which revoked the subjects
+    pub fn issuer(&self) -> &IdentityIdentifier {
+        &self.issuer
+    }
+
+    /// The time when this list was created
+    pub fn created(&self) -> Timestamp {
+        self.created
+    }
+
+    /// The revoked subjects
+    pub fn revoked(&self) -> &[RevokedSubject] {
+        &self.revoked
+    }
+
+    /// Return the time when a subject was revoked, if it was revoked
+    pub fn revoked_at(&self, subject: &IdentityIdentifier) -> Option<Timestamp> {
+        self.revoked
+            .iter()
+            .find(|r| &r.subject == subject)
+            .map(|r| r.revoked_at)
+    }
+
+    /// Return true if a credential created at the given time for the
+    /// given subject is revoked by this list
+    pub fn is_revoked(&self, subject: &IdentityIdentifier, created: Timestamp) -> bool {
+        matches!(self.revoked_at(subject), Some(revoked_at) if created <= revoked_at)
+    }
+}
+
+impl TryFrom<&[u8]> for RevocationListData {
+    type Error = minicbor::decode::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        minicbor::decode(value)
+    }
+}
+
+/// A subject whose credentials are revoked
+#[derive(Clone, Debug, Decode, Encode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RevokedSubject {
+    /// The identity whose credentials are revoked.
+    #[n(1)] subject: IdentityIdentifier,
+    /// Credentials created up to this time are revoked.
+    #[n(2)] revoked_at: Timestamp,
+}
+
+impl RevokedSubject {
+    /// Revoke the credentials created for a subject up to a given time
+    pub fn new(subject: IdentityIdentifier, revoked_at: Timestamp) -> Self {
+        Self {
+            subject,
+            revoked_at,
+        }
+    }
+
+    /// The revoked identity
+    pub fn subject(&self) -> &IdentityIdentifier {
+        &self.subject
+    }
+
+    /// Credentials created up to this time are revoked
+    pub fn revoked_at(&self) -> Timestamp {
+        self.revoked_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn credentials_created_before_revocation_are_revoked() {
+        let revoked = IdentityIdentifier::from_key_id("revoked");
+        let other = IdentityIdentifier::from_key_id("other");
+        let now = Timestamp::now().unwrap();
+        let list = RevocationListData::new(
+            IdentityIdentifier::from_key_id("authority"),
+            now.add_seconds(100),
+            vec![RevokedSubject::new(revoked.clone(), now.add_seconds(50))],
+        );
+
+        assert!(list.is_revoked(&revoked, now));
+        assert!(list.is_revoked(&revoked, now.add_seconds(50)));
+        assert!(!list.is_revoked(&revoked, now.add_seconds(51)));
+        assert!(!list.is_revoked(&other, now));
+
+        let decoded =
+            RevocationListData::try_from(minicbor::to_vec(&list).unwrap().as_slice()).unwrap();
+        assert_eq!(decoded, list);
+    }
+}
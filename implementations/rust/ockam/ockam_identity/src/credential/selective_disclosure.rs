@@ -0,0 +1,242 @@
+use crate::credential::{Attributes, CredentialData, SchemaId, Timestamp, Verified};
+use crate::identity::IdentityIdentifier;
+use core::marker::PhantomData;
+use minicbor::bytes::{ByteArray, ByteVec};
+use minicbor::{Decode, Encode};
+use ockam_core::compat::rand;
+use ockam_core::compat::rand::RngCore;
+use ockam_core::compat::{string::String, vec::Vec};
+use ockam_core::Result;
+
+#[cfg(feature = "tag")]
+use crate::TypeTag;
+
+/// Prefix of the bytes signed by the issuer of a [`SelectiveCredential`]
+///
+/// It makes sure that the signature of committed data can't be mistaken for the
+/// signature of another kind of data, like a [`CredentialData`].
+const SIGNATURE_DOMAIN: &[u8] = b"ockam:selective_credential:v1:";
+
+/// Credential whose attributes can be disclosed one by one
+///
+/// The issuer signs a salted hash of each attribute instead of the attributes themselves.
+/// The holder keeps the salted attributes, the disclosures, and only presents the ones
+/// a given peer needs with [`SelectiveCredential::disclose`]. The verifier checks the
+/// issuer signature and that each presented disclosure matches one of the signed hashes.
+#[derive(Clone, Debug, Decode, Encode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SelectiveCredential {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<8329143>,
+    /// CBOR-encoded [`CommittedCredentialData`].
+    #[cbor(with = "minicbor::bytes")]
+    #[b(1)] pub data: Vec<u8>,
+    /// Cryptographic signature of the committed data.
+    #[cbor(with = "minicbor::bytes")]
+    #[b(2)] pub signature: Vec<u8>,
+    /// Disclosed attributes.
+    #[n(3)] pub disclosures: Vec<Disclosure>,
+}
+
+impl SelectiveCredential {
+    pub(crate) fn new(data: Vec<u8>, signature: Vec<u8>, disclosures: Vec<Disclosure>) -> Self {
+        SelectiveCredential {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            data,
+            signature,
+            disclosures,
+        }
+    }
+
+    /// Return the signature of a credential
+    pub fn signature(&self) -> &[u8] {
+        &self.signature
+    }
+
+    /// Return the serialized committed data of a credential
+    pub fn unverified_data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Return the bytes signed by the issuer
+    pub(crate) fn signed_data(&self) -> Vec<u8> {
+        CommittedCredentialData::signed_data(&self.data)
+    }
+
+    /// Return the disclosed attributes
+    pub fn disclosures(&self) -> &[Disclosure] {
+        &self.disclosures
+    }
+
+    /// Return the names of the disclosed attributes
+    pub fn attribute_names(&self) -> Vec<&str> {
+        self.disclosures.iter().map(|d| d.name()).collect()
+    }
+
+    /// Return a copy of this credential disclosing only the given attributes
+    ///
+    /// Names which are not disclosed by this credential are ignored.
+    pub fn disclose(&self, names: &[&str]) -> SelectiveCredential {
+        SelectiveCredential::new(
+            self.data.clone(),
+            self.signature.clone(),
+            self.disclosures
+                .iter()
+                .filter(|d| names.contains(&d.name()))
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+/// An attribute with the random salt used to commit to it
+#[derive(Clone, Debug, Decode, Encode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct Disclosure {
+    #[n(1)] salt: ByteArray<16>,
+    #[n(2)] name: String,
+    #[n(3)] value: ByteVec,
+}
+
+impl Disclosure {
+    /// Create a disclosure for an attribute with a random salt
+    pub fn new(name: &str, value: &[u8]) -> Self {
+        let mut salt = [0; 16];
+        rand::thread_rng().fill_bytes(&mut salt);
+        Disclosure {
+            salt: salt.into(),
+            name: name.into(),
+            value: value.to_vec().into(),
+        }
+    }
+
+    /// Return the name of the attribute
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the value of the attribute
+    pub fn value(&self) -> &[u8] {
+        &self.value
+    }
+
+    /// Return the bytes which are hashed to commit to this disclosure
+    pub(crate) fn to_commitment_input(&self) -> Result<Vec<u8>> {
+        Ok(minicbor::to_vec(self)?)
+    }
+}
+
+/// Credential data where the attributes are replaced by their salted hashes
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CommittedCredentialData {
+    /// A schema identifier to allow distinguishing sets of attributes.
+    #[n(1)] pub(crate) schema: Option<SchemaId>,
+    /// SHA-256 hashes of the CBOR-encoded disclosures, sorted so that
+    /// they don't reveal the order of the attributes.
+    #[n(2)] pub(crate) commitments: Vec<ByteArray<32>>,
+    /// The subject this credential is issued for.
+    #[n(3)] pub(crate) subject: IdentityIdentifier,
+    /// The entity that signed this credential.
+    #[n(4)] pub(crate) issuer: IdentityIdentifier,
+    /// The label of the issuer's public key.
+    #[n(5)] pub(crate) issuer_key_label: String,
+    /// The time when this credential was created.
+    #[n(6)] pub(crate) created: Timestamp,
+    /// The time this credential expires.
+    #[n(7)] pub(crate) expires: Timestamp,
+}
+
+impl CommittedCredentialData {
+    /// Create committed data from credential data and the hashes of its disclosures
+    pub(crate) fn new(data: &CredentialData<Verified>, mut commitments: Vec<[u8; 32]>) -> Self {
+        commitments.sort();
+        CommittedCredentialData {
+            schema: data.schema,
+            commitments: commitments.into_iter().map(ByteArray::from).collect(),
+            subject: data.subject.clone(),
+            issuer: data.issuer.clone(),
+            issuer_key_label: data.issuer_key_label.clone(),
+            created: data.created,
+            expires: data.expires,
+        }
+    }
+
+    /// Return the bytes signed by the issuer for the serialized committed data
+    pub(crate) fn signed_data(data: &[u8]) -> Vec<u8> {
+        [SIGNATURE_DOMAIN, data].concat()
+    }
+
+    /// Return true if a disclosure hash was committed to by the issuer
+    pub(crate) fn contains(&self, commitment: &[u8; 32]) -> bool {
+        self.commitments.iter().any(|c| &**c == commitment)
+    }
+
+    /// Return the issuer of the committed data when unverified
+    pub fn unverified_issuer(&self) -> &IdentityIdentifier {
+        &self.issuer
+    }
+
+    /// Return the subject of the committed data when unverified
+    pub fn unverified_subject(&self) -> &IdentityIdentifier {
+        &self.subject
+    }
+
+    /// Return credential data containing only the given attributes, which must have been
+    /// checked against the commitments
+    pub(crate) fn with_attributes<T>(self, disclosures: &[Disclosure]) -> CredentialData<T> {
+        let mut attributes = Attributes::new();
+        for disclosure in disclosures {
+            attributes.put(disclosure.name(), disclosure.value());
+        }
+        CredentialData {
+            schema: self.schema,
+            attributes,
+            subject: self.subject,
+            issuer: self.issuer,
+            issuer_key_label: self.issuer_key_label,
+            created: self.created,
+            expires: self.expires,
+            status: None::<PhantomData<T>>,
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for CommittedCredentialData {
+    type Error = minicbor::decode::Error;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        minicbor::decode(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disclose_keeps_only_the_requested_attributes() {
+        let disclosures = vec![
+            Disclosure::new("role", b"member"),
+            Disclosure::new("team", b"blue"),
+        ];
+        let credential = SelectiveCredential::new(vec![1], vec![2], disclosures.clone());
+
+        let disclosed = credential.disclose(&["team", "unknown"]);
+        assert_eq!(disclosed.attribute_names(), vec!["team"]);
+        assert_eq!(disclosed.unverified_data(), credential.unverified_data());
+        assert_eq!(disclosed.signature(), credential.signature());
+
+        // salts are random so the same attribute gets a different commitment every time
+        assert_ne!(
+            disclosures[0].to_commitment_input().unwrap(),
+            Disclosure::new("role", b"member")
+                .to_commitment_input()
+                .unwrap()
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use crate::credentials::credentials_retriever::CredentialsRetriever;
-use crate::{Credential, Credentials, Identity, IdentityError};
+use crate::{
+    Credential, Credentials, Identity, IdentityError, SelectiveCredential, SignedPolicyBundle,
+};
 use ockam_core::compat::sync::Arc;
-use ockam_core::Result;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
 use ockam_node::Context;
 
 /// An AuthorityService represents an authority which issued credentials
@@ -48,4 +51,92 @@ impl AuthorityService {
             .await?;
         Ok(credential)
     }
+
+    /// Retrieve a selective credential for an identity within this authority
+    pub async fn selective_credential(
+        &self,
+        ctx: &Context,
+        for_identity: &Identity,
+    ) -> Result<SelectiveCredential> {
+        let retriever = self
+            .own_credential
+            .clone()
+            .ok_or(IdentityError::UnknownAuthority)?;
+        let credential = retriever
+            .retrieve_selective(ctx, for_identity)
+            .await?
+            .ok_or_else(|| {
+                Error::new(
+                    Origin::Application,
+                    Kind::Unsupported,
+                    "the authority doesn't issue selective credentials",
+                )
+            })?;
+
+        self.credentials
+            .verify_selective_credential(
+                &for_identity.identifier(),
+                &[self.identity.clone()],
+                credential.clone(),
+            )
+            .await?;
+        Ok(credential)
+    }
+
+    /// Retrieve the latest revocation list of this authority and use it to verify credentials
+    ///
+    /// Return false if the authority doesn't publish a revocation list.
+    pub async fn refresh_revocation_list(
+        &self,
+        ctx: &Context,
+        for_identity: &Identity,
+    ) -> Result<bool> {
+        let retriever = self
+            .own_credential
+            .clone()
+            .ok_or(IdentityError::UnknownAuthority)?;
+        let revocation_list = match retriever
+            .retrieve_revocation_list(ctx, for_identity)
+            .await?
+        {
+            Some(revocation_list) => revocation_list,
+            None => return Ok(false),
+        };
+
+        self.credentials
+            .receive_revocation_list(&[self.identity.clone()], revocation_list)
+            .await?;
+        Ok(true)
+    }
+
+    /// Send the updated change history of an identity to this authority
+    ///
+    /// Return false if the authority doesn't accept identity updates.
+    pub async fn push_identity_update(&self, ctx: &Context, identity: &Identity) -> Result<bool> {
+        let retriever = self
+            .own_credential
+            .clone()
+            .ok_or(IdentityError::UnknownAuthority)?;
+        retriever.push_identity_update(ctx, identity).await
+    }
+
+    /// Retrieve the policy bundle published by this authority, the latest
+    /// one unless a version is given
+    ///
+    /// Return None if the authority doesn't publish policy bundles. The
+    /// signature of the bundle must be verified by the caller.
+    pub async fn retrieve_policy_bundle(
+        &self,
+        ctx: &Context,
+        for_identity: &Identity,
+        version: Option<u64>,
+    ) -> Result<Option<SignedPolicyBundle>> {
+        let retriever = self
+            .own_credential
+            .clone()
+            .ok_or(IdentityError::UnknownAuthority)?;
+        retriever
+            .retrieve_policy_bundle(ctx, for_identity, version)
+            .await
+    }
 }
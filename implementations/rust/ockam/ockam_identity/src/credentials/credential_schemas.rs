@@ -0,0 +1,85 @@
+use crate::credential::{Attributes, CredentialSchema, SchemaId};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+/// Registry of the declared credential schemas
+///
+/// Credentials are validated against their schema when they are issued and verified
+/// by every component sharing the same [`Identities`](crate::Identities).
+///
+/// The validation is enabled once a schema is registered. From then on, credentials
+/// without a schema or referring to a schema which is not registered are rejected.
+#[derive(Clone, Default)]
+pub struct CredentialSchemas {
+    schemas: Arc<RwLock<BTreeMap<SchemaId, CredentialSchema>>>,
+}
+
+impl CredentialSchemas {
+    /// Declare a schema, replacing a previous schema with the same identifier
+    pub fn register(&self, schema: CredentialSchema) {
+        self.schemas.write().unwrap().insert(schema.id(), schema);
+    }
+
+    /// Remove a schema
+    ///
+    /// Return false if there was no schema with this identifier.
+    pub fn unregister(&self, id: SchemaId) -> bool {
+        self.schemas.write().unwrap().remove(&id).is_some()
+    }
+
+    /// Return a registered schema
+    pub fn get(&self, id: SchemaId) -> Option<CredentialSchema> {
+        self.schemas.read().unwrap().get(&id).cloned()
+    }
+
+    /// Return all the registered schemas
+    pub fn list(&self) -> Vec<CredentialSchema> {
+        self.schemas.read().unwrap().values().cloned().collect()
+    }
+
+    /// Return true if credentials are validated, which is the case once a schema is registered
+    pub fn is_validation_enabled(&self) -> bool {
+        !self.schemas.read().unwrap().is_empty()
+    }
+
+    /// Check that the attributes of a credential conform to its schema
+    pub fn validate(&self, id: Option<SchemaId>, attributes: &Attributes) -> Result<()> {
+        match self.schema(id)? {
+            Some(schema) => schema.validate(attributes),
+            None => Ok(()),
+        }
+    }
+
+    /// Check that the disclosed attributes of a credential conform to its schema
+    pub fn validate_disclosed(&self, id: Option<SchemaId>, attributes: &Attributes) -> Result<()> {
+        match self.schema(id)? {
+            Some(schema) => schema.validate_disclosed(attributes),
+            None => Ok(()),
+        }
+    }
+
+    /// Return the schema used to validate a credential, if the validation is enabled
+    fn schema(&self, id: Option<SchemaId>) -> Result<Option<CredentialSchema>> {
+        if !self.is_validation_enabled() {
+            return Ok(None);
+        }
+        match id {
+            Some(id) => match self.get(id) {
+                Some(schema) => Ok(Some(schema)),
+                None => Err(Error::new(
+                    Origin::Identity,
+                    Kind::Invalid,
+                    format!("unknown credential schema {id}"),
+                )),
+            },
+            None => Err(Error::new(
+                Origin::Identity,
+                Kind::Invalid,
+                "missing credential schema",
+            )),
+        }
+    }
+}
@@ -1,8 +1,15 @@
-use crate::credential::{Credential, CredentialData, Timestamp, Verified};
-use crate::identities::{AttributesEntry, Identities};
+use crate::credential::{
+    CommittedCredentialData, Credential, CredentialData, Disclosure, RevocationList,
+    RevocationListData, SelectiveCredential, Timestamp, Unverified, Verified,
+};
+use crate::identities::{AttributesEntry, Identities, IssuedAttributes};
 use crate::identity::{Identity, IdentityError, IdentityIdentifier};
+use crate::TrustContext;
 use async_trait::async_trait;
 use ockam_core::compat::boxed::Box;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::vault::SignatureVec;
 use ockam_core::{Error, Result};
@@ -32,6 +39,86 @@ pub trait Credentials: Send + Sync + 'static {
         authorities: &[Identity],
         credential: Credential,
     ) -> Result<()>;
+
+    /// Verify and store a credential sent by a specific identity, keeping only
+    /// the attributes that its issuer is trusted to attest to in the trust context
+    ///
+    /// By default only the credentials issued by the main authority of the trust context are accepted.
+    async fn receive_trust_context_credential(
+        &self,
+        sender: &IdentityIdentifier,
+        trust_context: &TrustContext,
+        credential: Credential,
+    ) -> Result<()> {
+        self.receive_presented_credential(
+            sender,
+            &[trust_context.authority()?.identity()],
+            credential,
+        )
+        .await
+    }
+
+    /// Verify a credential sent by a specific identity and merge its attributes with the
+    /// current attributes of that identity, keeping only the attributes that its issuer is
+    /// trusted to attest to in the trust context
+    ///
+    /// Nothing is stored, so that the resulting attributes can be checked before they are
+    /// stored with [`IdentityAttributesWriter::put_attributes`](crate::IdentityAttributesWriter).
+    async fn verify_trust_context_credential(
+        &self,
+        sender: &IdentityIdentifier,
+        trust_context: &TrustContext,
+        current: Option<AttributesEntry>,
+        credential: Credential,
+    ) -> Result<AttributesEntry>;
+
+    /// Issue a credential whose attributes can be disclosed selectively, by having the
+    /// issuer sign a salted hash of each attribute
+    async fn issue_selective_credential(
+        &self,
+        issuer: &Identity,
+        credential_data: CredentialData<Verified>,
+    ) -> Result<SelectiveCredential>;
+
+    /// Verify that a selective credential has been signed by one of the authorities,
+    /// returning only the attributes it discloses
+    async fn verify_selective_credential(
+        &self,
+        subject: &IdentityIdentifier,
+        authorities: &[Identity],
+        credential: SelectiveCredential,
+    ) -> Result<CredentialData<Verified>>;
+
+    /// Verify and store the attributes disclosed by a selective credential sent by a
+    /// specific identity, keeping only the attributes that its issuer is trusted to attest to
+    async fn receive_selective_credential(
+        &self,
+        sender: &IdentityIdentifier,
+        trust_context: &TrustContext,
+        credential: SelectiveCredential,
+    ) -> Result<()>;
+
+    /// Issue a revocation list by having the issuer sign the serialized revocation list data
+    async fn issue_revocation_list(
+        &self,
+        issuer: &Identity,
+        revocation_list_data: RevocationListData,
+    ) -> Result<RevocationList>;
+
+    /// Verify that a revocation list has been signed by one of the authorities
+    async fn verify_revocation_list(
+        &self,
+        authorities: &[Identity],
+        revocation_list: RevocationList,
+    ) -> Result<RevocationListData>;
+
+    /// Verify and cache a revocation list, so that the credentials it revokes
+    /// are rejected, and remove the attributes attested for the revoked subjects
+    async fn receive_revocation_list(
+        &self,
+        authorities: &[Identity],
+        revocation_list: RevocationList,
+    ) -> Result<()>;
 }
 
 #[async_trait]
@@ -43,38 +130,17 @@ impl Credentials for Identities {
         credential: Credential,
     ) -> Result<CredentialData<Verified>> {
         let credential_data = CredentialData::try_from(credential.data.as_slice())?;
+        self.verify_credential_data(
+            subject,
+            authorities,
+            &credential_data,
+            credential.unverified_data(),
+            credential.signature(),
+        )
+        .await?;
+        self.credential_schemas
+            .validate(credential_data.schema, &credential_data.attributes)?;
 
-        let issuer = authorities
-            .iter()
-            .find(|&x| x.identifier() == credential_data.issuer);
-        let issuer = match issuer {
-            Some(i) => i,
-            None => return Err(IdentityError::UnknownAuthority.into()),
-        };
-
-        let now = Timestamp::now()
-            .ok_or_else(|| Error::new(Origin::Application, Kind::Invalid, "invalid system time"))?;
-
-        credential_data.verify(subject, &issuer.identifier(), now)?;
-
-        let sig = ockam_core::vault::Signature::new(credential.signature().to_vec());
-
-        if !self
-            .identities_keys()
-            .verify_signature(
-                issuer,
-                &sig,
-                credential.unverified_data(),
-                Some(credential_data.clone().unverified_key_label()),
-            )
-            .await?
-        {
-            return Err(Error::new(
-                Origin::Application,
-                Kind::Invalid,
-                "invalid signature",
-            ));
-        }
         Ok(credential_data.into_verified())
     }
 
@@ -84,6 +150,8 @@ impl Credentials for Identities {
         issuer: &Identity,
         credential_data: CredentialData<Verified>,
     ) -> Result<Credential> {
+        self.credential_schemas
+            .validate(credential_data.schema, &credential_data.attributes)?;
         let bytes = minicbor::to_vec(credential_data)?;
         let sig = self
             .identities_keys()
@@ -116,4 +184,348 @@ impl Credentials for Identities {
 
         Ok(())
     }
+
+    async fn receive_trust_context_credential(
+        &self,
+        sender: &IdentityIdentifier,
+        trust_context: &TrustContext,
+        credential: Credential,
+    ) -> Result<()> {
+        let current = self.identities_repository.get_attributes(sender).await?;
+        let attributes = self
+            .verify_trust_context_credential(sender, trust_context, current, credential)
+            .await?;
+        self.identities_repository
+            .put_attributes(sender, attributes)
+            .await
+    }
+
+    async fn verify_trust_context_credential(
+        &self,
+        sender: &IdentityIdentifier,
+        trust_context: &TrustContext,
+        current: Option<AttributesEntry>,
+        credential: Credential,
+    ) -> Result<AttributesEntry> {
+        let credential_data = self
+            .verify_credential(sender, &trust_context.authorities(), credential)
+            .await?;
+        merge_trust_context_attributes(trust_context, current, credential_data)
+    }
+
+    async fn issue_selective_credential(
+        &self,
+        issuer: &Identity,
+        credential_data: CredentialData<Verified>,
+    ) -> Result<SelectiveCredential> {
+        self.credential_schemas
+            .validate(credential_data.schema, &credential_data.attributes)?;
+        let disclosures: Vec<Disclosure> = credential_data
+            .attributes
+            .iter()
+            .map(|(name, value)| Disclosure::new(name, value))
+            .collect();
+        let mut commitments = Vec::with_capacity(disclosures.len());
+        for disclosure in &disclosures {
+            commitments.push(
+                self.vault
+                    .sha256(&disclosure.to_commitment_input()?)
+                    .await?,
+            );
+        }
+
+        let bytes = minicbor::to_vec(CommittedCredentialData::new(&credential_data, commitments))?;
+        let sig = self
+            .identities_keys()
+            .create_signature(issuer, &CommittedCredentialData::signed_data(&bytes), None)
+            .await?;
+        Ok(SelectiveCredential::new(
+            bytes,
+            SignatureVec::from(sig),
+            disclosures,
+        ))
+    }
+
+    async fn verify_selective_credential(
+        &self,
+        subject: &IdentityIdentifier,
+        authorities: &[Identity],
+        credential: SelectiveCredential,
+    ) -> Result<CredentialData<Verified>> {
+        let committed_data = CommittedCredentialData::try_from(credential.unverified_data())?;
+
+        let mut names = Vec::with_capacity(credential.disclosures().len());
+        for disclosure in credential.disclosures() {
+            if names.contains(&disclosure.name()) {
+                return Err(Error::new(
+                    Origin::Application,
+                    Kind::Invalid,
+                    "duplicate disclosure",
+                ));
+            }
+            names.push(disclosure.name());
+
+            let commitment = self
+                .vault
+                .sha256(&disclosure.to_commitment_input()?)
+                .await?;
+            if !committed_data.contains(&commitment) {
+                return Err(Error::new(
+                    Origin::Application,
+                    Kind::Invalid,
+                    "invalid disclosure",
+                ));
+            }
+        }
+
+        let credential_data: CredentialData<Unverified> =
+            committed_data.with_attributes(credential.disclosures());
+        self.verify_credential_data(
+            subject,
+            authorities,
+            &credential_data,
+            &credential.signed_data(),
+            credential.signature(),
+        )
+        .await?;
+        self.credential_schemas
+            .validate_disclosed(credential_data.schema, &credential_data.attributes)?;
+
+        Ok(credential_data.into_verified())
+    }
+
+    async fn receive_selective_credential(
+        &self,
+        sender: &IdentityIdentifier,
+        trust_context: &TrustContext,
+        credential: SelectiveCredential,
+    ) -> Result<()> {
+        let credential_data = self
+            .verify_selective_credential(sender, &trust_context.authorities(), credential)
+            .await?;
+        let current = self.identities_repository.get_attributes(sender).await?;
+        let attributes = merge_trust_context_attributes(trust_context, current, credential_data)?;
+        self.identities_repository
+            .put_attributes(sender, attributes)
+            .await
+    }
+
+    async fn issue_revocation_list(
+        &self,
+        issuer: &Identity,
+        revocation_list_data: RevocationListData,
+    ) -> Result<RevocationList> {
+        let bytes = minicbor::to_vec(revocation_list_data)?;
+        let sig = self
+            .identities_keys()
+            .create_signature(issuer, &RevocationList::signed_data(&bytes), None)
+            .await?;
+        Ok(RevocationList::new(bytes, SignatureVec::from(sig)))
+    }
+
+    async fn verify_revocation_list(
+        &self,
+        authorities: &[Identity],
+        revocation_list: RevocationList,
+    ) -> Result<RevocationListData> {
+        let revocation_list_data = RevocationListData::try_from(revocation_list.unverified_data())?;
+
+        let issuer = match authorities
+            .iter()
+            .find(|&x| &x.identifier() == revocation_list_data.issuer())
+        {
+            Some(i) => i,
+            None => return Err(IdentityError::UnknownAuthority.into()),
+        };
+
+        let sig = ockam_core::vault::Signature::new(revocation_list.signature().to_vec());
+
+        if !self
+            .identities_keys()
+            .verify_signature(
+                issuer,
+                &sig,
+                &RevocationList::signed_data(revocation_list.unverified_data()),
+                None,
+            )
+            .await?
+        {
+            return Err(Error::new(
+                Origin::Application,
+                Kind::Invalid,
+                "invalid signature",
+            ));
+        }
+        Ok(revocation_list_data)
+    }
+
+    async fn receive_revocation_list(
+        &self,
+        authorities: &[Identity],
+        revocation_list: RevocationList,
+    ) -> Result<()> {
+        let revocation_list_data = self
+            .verify_revocation_list(authorities, revocation_list)
+            .await?;
+
+        if !self.revocation_lists.update(revocation_list_data.clone()) {
+            // A more recent list of the same authority was already received
+            return Ok(());
+        }
+
+        // Attributes added from a credential which is now revoked must not be used anymore
+        let issuer = revocation_list_data.issuer();
+        for revoked in revocation_list_data.revoked() {
+            let entry = match self
+                .identities_repository
+                .get_attributes(revoked.subject())
+                .await?
+            {
+                Some(entry) => entry,
+                None => continue,
+            };
+            if entry.issued().is_some() {
+                // Only the attributes of the revoking issuer are removed
+                match entry
+                    .clone()
+                    .without_revoked_issuer(issuer, revoked.revoked_at())
+                {
+                    Some(remaining) if remaining == entry => (),
+                    Some(remaining) => {
+                        self.identities_repository
+                            .put_attributes(revoked.subject(), remaining)
+                            .await?
+                    }
+                    None => self.identities_repository.delete(revoked.subject()).await?,
+                }
+            } else if entry.attested_by().as_ref() == Some(issuer)
+                && entry.added() <= revoked.revoked_at()
+            {
+                self.identities_repository.delete(revoked.subject()).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Identities {
+    /// Check the validity and the signature of some credential data
+    async fn verify_credential_data(
+        &self,
+        subject: &IdentityIdentifier,
+        authorities: &[Identity],
+        credential_data: &CredentialData<Unverified>,
+        signed_data: &[u8],
+        signature: &[u8],
+    ) -> Result<()> {
+        let issuer = authorities
+            .iter()
+            .find(|&x| x.identifier() == credential_data.issuer);
+        let issuer = match issuer {
+            Some(i) => i,
+            None => return Err(IdentityError::UnknownAuthority.into()),
+        };
+
+        let now = Timestamp::now()
+            .ok_or_else(|| Error::new(Origin::Application, Kind::Invalid, "invalid system time"))?;
+
+        credential_data.verify(subject, &issuer.identifier(), now)?;
+
+        let sig = ockam_core::vault::Signature::new(signature.to_vec());
+
+        if !self
+            .identities_keys()
+            .verify_signature(
+                issuer,
+                &sig,
+                signed_data,
+                Some(credential_data.unverified_key_label()),
+            )
+            .await?
+        {
+            return Err(Error::new(
+                Origin::Application,
+                Kind::Invalid,
+                "invalid signature",
+            ));
+        }
+
+        if self.revocation_lists.is_revoked(
+            &credential_data.issuer,
+            &credential_data.subject,
+            credential_data.created,
+        ) {
+            return Err(Error::new(
+                Origin::Application,
+                Kind::Invalid,
+                "revoked credential",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Merge the attributes of a verified credential with the current attributes of its subject,
+/// keeping only the attributes that its issuer is trusted to attest to in the trust context
+///
+/// The attributes are recorded per issuer: a new credential replaces the attributes
+/// previously attested by its issuer only. When several authorities attest to the same
+/// attribute, the value of the main authority of the trust context is used.
+fn merge_trust_context_attributes(
+    trust_context: &TrustContext,
+    current: Option<AttributesEntry>,
+    credential_data: CredentialData<Verified>,
+) -> Result<AttributesEntry> {
+    let now = Timestamp::now()
+        .ok_or_else(|| Error::new(Origin::Core, Kind::Internal, "invalid system time"))?;
+    let issuer = credential_data.issuer.clone();
+    let attributes: BTreeMap<String, Vec<u8>> = credential_data
+        .attributes
+        .as_map_vec_u8()
+        .into_iter()
+        .filter(|(name, value)| trust_context.is_trusted_for(&issuer, name, value))
+        .collect();
+
+    let mut issued = match current {
+        Some(current) => match (current.issued(), current.attested_by(), current.expires()) {
+            (Some(issued), _, _) => issued.clone(),
+            // Entries stored before the attributes were recorded per issuer
+            (None, Some(attested_by), Some(expires)) => BTreeMap::from([(
+                attested_by.to_string(),
+                IssuedAttributes::new(current.attrs().clone(), current.added(), expires),
+            )]),
+            (None, _, _) => BTreeMap::new(),
+        },
+        None => BTreeMap::new(),
+    };
+    issued.insert(
+        issuer.to_string(),
+        IssuedAttributes::new(attributes, credential_data.created, credential_data.expires),
+    );
+
+    // Forget the attributes which expired or whose issuer is not trusted anymore
+    let authorities: Vec<String> = trust_context
+        .authorities()
+        .iter()
+        .map(|a| a.identifier().to_string())
+        .collect();
+    issued.retain(|id, attributes| attributes.expires() > now && authorities.contains(id));
+
+    let main_authority = trust_context
+        .authority()
+        .ok()
+        .map(|a| a.identity().identifier().to_string());
+    let mut attributes = BTreeMap::new();
+    let (main, scoped): (Vec<_>, Vec<_>) = issued
+        .iter()
+        .partition(|(id, _)| Some(*id) == main_authority.as_ref());
+    for (_, issued_attributes) in scoped.into_iter().chain(main) {
+        attributes.extend(issued_attributes.attrs().clone());
+    }
+    // The attributes of each authority expire separately, see `AttributesEntry::without_expired_issuers`
+    let expires = issued.values().map(|a| a.expires()).max();
+
+    Ok(AttributesEntry::new(attributes, now, expires, Some(issuer)).with_issued(issued))
 }
@@ -7,7 +7,9 @@ use ockam_core::compat::vec::Vec;
 use ockam_core::{api, Result, Route, Routed, Worker};
 use ockam_node::{Context, RpcClient};
 
-use crate::credential::Credential;
+use crate::credential::{
+    Credential, SelectiveCredential, Timestamp, Verified, MAX_CREDENTIAL_VALIDITY,
+};
 use crate::identity::{Identity, IdentityIdentifier};
 use crate::{CredentialData, Identities, IdentitySecureChannelLocalInfo, PROJECT_MEMBER_SCHEMA};
 use ockam_core::api::{Method, Request, Response};
@@ -42,7 +44,11 @@ impl CredentialsIssuer {
         })
     }
 
-    async fn issue_credential(&self, from: &IdentityIdentifier) -> Result<Option<Credential>> {
+    /// Return the data of a credential attesting the attributes of an identity, if it has some
+    async fn credential_data(
+        &self,
+        from: &IdentityIdentifier,
+    ) -> Result<Option<CredentialData<Verified>>> {
         match self
             .identities
             .repository()
@@ -61,16 +67,48 @@ impl CredentialsIssuer {
                     )
                     .with_attribute(LEGACY_ID, self.trust_context.as_bytes()) // TODO: DEPRECATE - Removing PROJECT_ID attribute in favor of TRUST_CONTEXT_ID
                     .with_attribute(TRUST_CONTEXT_ID, self.trust_context.as_bytes());
-                Ok(Some(
-                    self.identities
-                        .credentials()
-                        .issue_credential(&self.issuer, crd.build()?)
-                        .await?,
-                ))
+                // A credential must not outlive the attributes it attests
+                let crd = match (entry.expires(), Timestamp::now()) {
+                    (Some(expires), Some(now)) => match expires.elapsed(now) {
+                        Some(remaining) if remaining < MAX_CREDENTIAL_VALIDITY => {
+                            crd.valid_for(remaining)
+                        }
+                        _ => crd,
+                    },
+                    _ => crd,
+                };
+                Ok(Some(crd.build()?))
             }
             None => Ok(None),
         }
     }
+
+    async fn issue_credential(&self, from: &IdentityIdentifier) -> Result<Option<Credential>> {
+        match self.credential_data(from).await? {
+            Some(data) => Ok(Some(
+                self.identities
+                    .credentials()
+                    .issue_credential(&self.issuer, data)
+                    .await?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    async fn issue_selective_credential(
+        &self,
+        from: &IdentityIdentifier,
+    ) -> Result<Option<SelectiveCredential>> {
+        match self.credential_data(from).await? {
+            Some(data) => Ok(Some(
+                self.identities
+                    .credentials()
+                    .issue_selective_credential(&self.issuer, data)
+                    .await?,
+            )),
+            None => Ok(None),
+        }
+    }
 }
 
 #[ockam_core::worker]
@@ -104,6 +142,13 @@ impl Worker for CredentialsIssuer {
                         Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
                     }
                 }
+                (Some(Method::Post), "/selective_credential") => {
+                    match self.issue_selective_credential(&from).await {
+                        Ok(Some(crd)) => Response::ok(req.id()).body(crd).to_vec()?,
+                        Ok(None) => api::forbidden(&req, "unauthorized member").to_vec()?,
+                        Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
+                    }
+                }
                 _ => api::unknown_path(&req).to_vec()?,
             };
             c.send(m.return_route(), res).await
@@ -144,6 +189,13 @@ impl CredentialsIssuerClient {
         self.client.request(&Request::post("/")).await
     }
 
+    /// Return a selective credential for the identity which initiated the secure channel
+    pub async fn selective_credential(&self) -> Result<SelectiveCredential> {
+        self.client
+            .request(&Request::post("/selective_credential"))
+            .await
+    }
+
     /// Specify the flow controls to use for the RpcClient
     pub fn with_flow_controls(self, flow_controls: &FlowControls) -> Self {
         Self {
@@ -0,0 +1,313 @@
+use crate::credential::{Credential, CredentialData, Timestamp, Unverified};
+use crate::credentials::{AuthorityService, CredentialsServer};
+use crate::identity::Identity;
+use core::time::Duration;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::flow_control::FlowControls;
+use ockam_core::{AsyncTryClone, Error, Result, Route};
+use ockam_node::tokio;
+use ockam_node::tokio::task::JoinHandle;
+use ockam_node::{Context, MessageSendReceiveOptions};
+use tracing::{debug, info, warn};
+
+/// Delay before retrying a failed renewal, or checking again for a credential
+/// when none was retrieved yet
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+/// Maximum delay between two renewal attempts when renewals keep failing
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(600);
+
+/// Status of the renewals of a [`CredentialsRefresher`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CredentialsRefresherStatus {
+    /// Time of the last successful renewal
+    pub last_refresh: Option<Timestamp>,
+    /// Expiration time of the current credential
+    pub expires: Option<Timestamp>,
+    /// Error returned by the last renewal, if it failed
+    pub last_error: Option<String>,
+    /// Number of renewals which failed since the last successful one
+    pub failures: u32,
+}
+
+#[derive(Default)]
+struct RefresherState {
+    credential: Option<Credential>,
+    refresh_at: Option<Timestamp>,
+    presentations: Vec<Route>,
+    status: CredentialsRefresherStatus,
+}
+
+/// This struct renews the credential of an identity before it expires
+///
+/// The credential is retrieved from the trust context authority once a
+/// fraction of its lifetime has elapsed. The fresh credential is then
+/// presented again on every route registered with
+/// [`CredentialsRefresher::add_presentation`], so that established
+/// secure channels keep being authorized.
+#[derive(Clone)]
+pub struct CredentialsRefresher {
+    authority: AuthorityService,
+    credentials_server: Arc<dyn CredentialsServer>,
+    identity: Identity,
+    flow_controls: Option<FlowControls>,
+    refresh_fraction: f64,
+    state: Arc<Mutex<RefresherState>>,
+    handle: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl CredentialsRefresher {
+    /// Fraction of the credential lifetime after which it is renewed, by default
+    pub const DEFAULT_REFRESH_FRACTION: f64 = 0.5;
+
+    /// Create a new credentials refresher for an identity
+    pub fn new(
+        authority: AuthorityService,
+        credentials_server: Arc<dyn CredentialsServer>,
+        identity: Identity,
+    ) -> Self {
+        Self {
+            authority,
+            credentials_server,
+            identity,
+            flow_controls: None,
+            refresh_fraction: Self::DEFAULT_REFRESH_FRACTION,
+            state: Default::default(),
+            handle: Default::default(),
+        }
+    }
+
+    /// Set the fraction of the credential lifetime after which it is renewed
+    ///
+    /// The fraction must be greater than 0 and at most 1.
+    pub fn with_refresh_fraction(mut self, refresh_fraction: f64) -> Result<Self> {
+        if refresh_fraction.is_nan() || refresh_fraction <= 0.0 || refresh_fraction > 1.0 {
+            return Err(Error::new(
+                Origin::Identity,
+                Kind::Invalid,
+                "the credential refresh fraction must be in ]0, 1]",
+            ));
+        }
+        self.refresh_fraction = refresh_fraction;
+        Ok(self)
+    }
+
+    /// Specify the flow controls used to present credentials
+    pub fn with_flow_controls(mut self, flow_controls: &FlowControls) -> Self {
+        self.flow_controls = Some(flow_controls.clone());
+        self
+    }
+
+    /// Return the current credential, retrieving a new one from the authority
+    /// if none was retrieved yet or if it is due for renewal
+    ///
+    /// If the renewal fails, the current credential is returned as long as it has not expired.
+    pub async fn credential(&self, ctx: &Context) -> Result<Credential> {
+        {
+            let state = self.state.lock().unwrap();
+            if let (Some(credential), Some(refresh_at)) = (&state.credential, state.refresh_at) {
+                if Timestamp::now().map_or(false, |now| now < refresh_at) {
+                    return Ok(credential.clone());
+                }
+            }
+        }
+        match self.refresh(ctx).await {
+            Ok(credential) => Ok(credential),
+            Err(e) => {
+                let state = self.state.lock().unwrap();
+                match (&state.credential, state.status.expires, Timestamp::now()) {
+                    (Some(credential), Some(expires), Some(now)) if now < expires => {
+                        Ok(credential.clone())
+                    }
+                    _ => Err(e),
+                }
+            }
+        }
+    }
+
+    /// Retrieve a new credential from the authority
+    ///
+    /// The renewal fails if the retrieved credential was not created after the current one.
+    pub async fn refresh(&self, ctx: &Context) -> Result<Credential> {
+        let result = self
+            .authority
+            .credential(ctx, &self.identity)
+            .await
+            .and_then(|credential| self.check_newer(credential));
+
+        let mut state = self.state.lock().unwrap();
+        match &result {
+            Ok(credential) => {
+                let (refresh_at, expires) = self.refresh_time(credential)?;
+                state.credential = Some(credential.clone());
+                state.refresh_at = Some(refresh_at);
+                state.status = CredentialsRefresherStatus {
+                    last_refresh: Timestamp::now(),
+                    expires: Some(expires),
+                    last_error: None,
+                    failures: 0,
+                };
+            }
+            Err(e) => {
+                state.status.last_error = Some(e.to_string());
+                state.status.failures += 1;
+            }
+        }
+        result
+    }
+
+    /// Register a route to a credentials service, usually going through a
+    /// secure channel, on which renewed credentials must be presented
+    pub fn add_presentation(&self, route: Route) {
+        let mut state = self.state.lock().unwrap();
+        if !state.presentations.contains(&route) {
+            state.presentations.push(route);
+        }
+    }
+
+    /// Stop presenting renewed credentials on a route
+    pub fn remove_presentation(&self, route: &Route) {
+        self.state
+            .lock()
+            .unwrap()
+            .presentations
+            .retain(|r| r != route);
+    }
+
+    /// Return the status of the credential renewals
+    pub fn status(&self) -> CredentialsRefresherStatus {
+        self.state.lock().unwrap().status.clone()
+    }
+
+    /// Start renewing the credential in the background
+    pub async fn start(&self, ctx: &Context) -> Result<()> {
+        let ctx = ctx.async_try_clone().await?;
+        let refresher = self.clone();
+        let handle = tokio::spawn(async move { refresher.run(ctx).await });
+        if let Some(previous) = self.handle.lock().unwrap().replace(handle) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    /// Stop renewing the credential
+    pub fn stop(&self) {
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+
+    async fn run(self, ctx: Context) {
+        loop {
+            tokio::time::sleep(self.next_refresh_delay()).await;
+
+            if !self.is_due() {
+                continue;
+            }
+
+            match self.refresh(&ctx).await {
+                Ok(credential) => {
+                    info!("Renewed the credential of {}", self.identity.identifier());
+                    self.present(&ctx, credential).await;
+                }
+                Err(e) => warn!(
+                    "Failed to renew the credential of {}: {}",
+                    self.identity.identifier(),
+                    e
+                ),
+            }
+        }
+    }
+
+    /// Present a renewed credential on all the registered routes
+    ///
+    /// Routes on which the presentation fails, typically because the secure
+    /// channel was closed, are not used anymore.
+    async fn present(&self, ctx: &Context, credential: Credential) {
+        let presentations = self.state.lock().unwrap().presentations.clone();
+        for route in presentations {
+            let options = match &self.flow_controls {
+                Some(flow_controls) => {
+                    MessageSendReceiveOptions::new().with_flow_control(flow_controls)
+                }
+                None => MessageSendReceiveOptions::new(),
+            };
+            if let Err(e) = self
+                .credentials_server
+                .present_credential(ctx, route.clone(), credential.clone(), options)
+                .await
+            {
+                debug!(
+                    "Failed to present the renewed credential on {}: {}",
+                    route, e
+                );
+                self.remove_presentation(&route);
+            }
+        }
+    }
+
+    /// Return true if the current credential must be renewed
+    ///
+    /// There is nothing to renew as long as no credential was requested.
+    fn is_due(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        if state.credential.is_none() {
+            return false;
+        }
+        if state.status.failures > 0 {
+            return true;
+        }
+        match (state.refresh_at, Timestamp::now()) {
+            (Some(refresh_at), Some(now)) => refresh_at <= now,
+            _ => true,
+        }
+    }
+
+    /// Return the delay before the next renewal attempt
+    ///
+    /// The delay doubles after each failed renewal, up to [`MAX_RETRY_DELAY`].
+    fn next_refresh_delay(&self) -> Duration {
+        let state = self.state.lock().unwrap();
+        if state.status.failures > 0 {
+            let factor = 1u32 << (state.status.failures - 1).min(16);
+            return RETRY_DELAY.saturating_mul(factor).min(MAX_RETRY_DELAY);
+        }
+        match (state.refresh_at, Timestamp::now()) {
+            // A credential which is already due is not renewed more often than
+            // failed renewals are retried
+            (Some(refresh_at), Some(now)) => refresh_at
+                .elapsed(now)
+                .filter(|delay| !delay.is_zero())
+                .unwrap_or(RETRY_DELAY),
+            _ => RETRY_DELAY,
+        }
+    }
+
+    /// Return an error if a retrieved credential is not newer than the current one
+    fn check_newer(&self, credential: Credential) -> Result<Credential> {
+        let current = match &self.state.lock().unwrap().credential {
+            Some(current) => CredentialData::<Unverified>::try_from(current.unverified_data())?,
+            None => return Ok(credential),
+        };
+        let retrieved = CredentialData::<Unverified>::try_from(credential.unverified_data())?;
+        if retrieved.created <= current.created {
+            return Err(Error::new(
+                Origin::Identity,
+                Kind::Invalid,
+                "the retrieved credential is not newer than the current one",
+            ));
+        }
+        Ok(credential)
+    }
+
+    /// Return the time when a credential must be renewed, and its expiration time
+    fn refresh_time(&self, credential: &Credential) -> Result<(Timestamp, Timestamp)> {
+        let data: CredentialData<Unverified> =
+            CredentialData::try_from(credential.unverified_data())?;
+        let lifetime = data.expires.elapsed(data.created).unwrap_or_default();
+        let refresh_after = (lifetime.as_secs() as f64 * self.refresh_fraction) as u64;
+        Ok((data.created.add_seconds(refresh_after), data.expires))
+    }
+}
@@ -1,13 +1,14 @@
 use crate::{
-    Credential, CredentialsIssuerClient, Identity, SecureChannelOptions, SecureChannels,
-    TrustMultiIdentifiersPolicy,
+    push_identity_update, Credential, CredentialsIssuerClient, Identity, PolicyBundlesClient,
+    RevocationList, RevocationListIssuerClient, SecureChannelOptions, SecureChannels,
+    SelectiveCredential, SignedPolicyBundle, TrustMultiIdentifiersPolicy,
 };
 use core::time::Duration;
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::FlowControls;
 use ockam_core::{async_trait, route, Address, Result, Route};
-use ockam_node::Context;
+use ockam_node::{Context, MessageSendReceiveOptions};
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
@@ -16,6 +17,43 @@ use tracing::debug;
 pub trait CredentialsRetriever: Send + Sync + 'static {
     /// Retrieve a credential for an identity
     async fn retrieve(&self, ctx: &Context, for_identity: &Identity) -> Result<Credential>;
+
+    /// Retrieve a selective credential for an identity, if the issuer issues them
+    async fn retrieve_selective(
+        &self,
+        _ctx: &Context,
+        _for_identity: &Identity,
+    ) -> Result<Option<SelectiveCredential>> {
+        Ok(None)
+    }
+
+    /// Retrieve the latest revocation list of the issuer, if it publishes one
+    async fn retrieve_revocation_list(
+        &self,
+        _ctx: &Context,
+        _for_identity: &Identity,
+    ) -> Result<Option<RevocationList>> {
+        Ok(None)
+    }
+
+    /// Send the updated change history of an identity to the issuer
+    ///
+    /// Return false if the issuer doesn't accept identity updates.
+    async fn push_identity_update(&self, _ctx: &Context, _identity: &Identity) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Retrieve the policy bundle published by the issuer, if it publishes one
+    ///
+    /// The latest bundle is returned unless a version is given.
+    async fn retrieve_policy_bundle(
+        &self,
+        _ctx: &Context,
+        _for_identity: &Identity,
+        _version: Option<u64>,
+    ) -> Result<Option<SignedPolicyBundle>> {
+        Ok(None)
+    }
 }
 
 /// Credentials retriever that retrieves a credential from memory
@@ -58,13 +96,13 @@ impl RemoteCredentialsRetriever {
             flow_controls,
         }
     }
-}
-
-#[async_trait]
-impl CredentialsRetriever for RemoteCredentialsRetriever {
-    async fn retrieve(&self, ctx: &Context, for_identity: &Identity) -> Result<Credential> {
-        debug!("Getting credential from : {}", &self.issuer.route);
 
+    /// Create a secure channel to the issuer node
+    async fn create_secure_channel(
+        &self,
+        ctx: &Context,
+        for_identity: &Identity,
+    ) -> Result<Address> {
         let allowed = vec![self.issuer.identity.identifier()];
         debug!("Create secure channel to authority");
 
@@ -86,6 +124,17 @@ impl CredentialsRetriever for RemoteCredentialsRetriever {
 
         debug!("Created secure channel to project authority");
 
+        Ok(sc)
+    }
+}
+
+#[async_trait]
+impl CredentialsRetriever for RemoteCredentialsRetriever {
+    async fn retrieve(&self, ctx: &Context, for_identity: &Identity) -> Result<Credential> {
+        debug!("Getting credential from : {}", &self.issuer.route);
+
+        let sc = self.create_secure_channel(ctx, for_identity).await?;
+
         let client =
             CredentialsIssuerClient::new(route![sc, self.issuer.service_address.clone()], ctx)
                 .await?
@@ -94,6 +143,110 @@ impl CredentialsRetriever for RemoteCredentialsRetriever {
         let credential = client.credential().await?;
         Ok(credential)
     }
+
+    async fn retrieve_selective(
+        &self,
+        ctx: &Context,
+        for_identity: &Identity,
+    ) -> Result<Option<SelectiveCredential>> {
+        debug!("Getting selective credential from : {}", &self.issuer.route);
+
+        let sc = self.create_secure_channel(ctx, for_identity).await?;
+
+        let result = match CredentialsIssuerClient::new(
+            route![sc.clone(), self.issuer.service_address.clone()],
+            ctx,
+        )
+        .await
+        {
+            Ok(client) => {
+                client
+                    .with_flow_controls(&self.flow_controls)
+                    .selective_credential()
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        self.secure_channels.stop_secure_channel(ctx, &sc).await?;
+        result.map(Some)
+    }
+
+    async fn retrieve_revocation_list(
+        &self,
+        ctx: &Context,
+        for_identity: &Identity,
+    ) -> Result<Option<RevocationList>> {
+        let revocation_list_address = match &self.issuer.revocation_list_address {
+            Some(address) => address.clone(),
+            None => return Ok(None),
+        };
+        debug!("Getting revocation list from : {}", &self.issuer.route);
+
+        let sc = self.create_secure_channel(ctx, for_identity).await?;
+
+        let result =
+            match RevocationListIssuerClient::new(route![sc.clone(), revocation_list_address], ctx)
+                .await
+            {
+                Ok(client) => {
+                    client
+                        .with_flow_controls(&self.flow_controls)
+                        .revocation_list()
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+        self.secure_channels.stop_secure_channel(ctx, &sc).await?;
+        result
+    }
+
+    async fn push_identity_update(&self, ctx: &Context, identity: &Identity) -> Result<bool> {
+        let identity_updates_address = match &self.issuer.identity_updates_address {
+            Some(address) => address.clone(),
+            None => return Ok(false),
+        };
+        debug!("Pushing identity update to : {}", &self.issuer.route);
+
+        let sc = self.create_secure_channel(ctx, identity).await?;
+        let result = push_identity_update(
+            ctx,
+            route![sc.clone(), identity_updates_address],
+            identity,
+            MessageSendReceiveOptions::new().with_flow_control(&self.flow_controls),
+        )
+        .await;
+        self.secure_channels.stop_secure_channel(ctx, &sc).await?;
+        result.map(|_| true)
+    }
+
+    async fn retrieve_policy_bundle(
+        &self,
+        ctx: &Context,
+        for_identity: &Identity,
+        version: Option<u64>,
+    ) -> Result<Option<SignedPolicyBundle>> {
+        let policy_bundles_address = match &self.issuer.policy_bundles_address {
+            Some(address) => address.clone(),
+            None => return Ok(None),
+        };
+        debug!("Getting policy bundle from : {}", &self.issuer.route);
+
+        let sc = self.create_secure_channel(ctx, for_identity).await?;
+
+        let result =
+            match PolicyBundlesClient::new(route![sc.clone(), policy_bundles_address], ctx).await {
+                Ok(client) => {
+                    let client = client.with_flow_controls(&self.flow_controls);
+                    match version {
+                        Some(version) => client.version(version).await.map(Some),
+                        None => client.latest().await,
+                    }
+                }
+                Err(e) => Err(e),
+            };
+        self.secure_channels.stop_secure_channel(ctx, &sc).await?;
+        result
+    }
 }
 
 /// Information necessary to connect to a remote credential retriever
@@ -105,6 +258,15 @@ pub struct RemoteCredentialsRetrieverInfo {
     pub route: Route,
     /// Address of the credentials service on the remote node
     pub service_address: Address,
+    /// Address of the revocation list service on the remote node, if any
+    #[serde(default)]
+    pub revocation_list_address: Option<Address>,
+    /// Address of the identity updates service on the remote node, if any
+    #[serde(default)]
+    pub identity_updates_address: Option<Address>,
+    /// Address of the policy bundles service on the remote node, if any
+    #[serde(default)]
+    pub policy_bundles_address: Option<Address>,
 }
 
 impl RemoteCredentialsRetrieverInfo {
@@ -114,6 +276,27 @@ impl RemoteCredentialsRetrieverInfo {
             identity,
             route,
             service_address,
+            revocation_list_address: None,
+            identity_updates_address: None,
+            policy_bundles_address: None,
         }
     }
+
+    /// Set the address of the revocation list service on the remote node
+    pub fn with_revocation_list_address(mut self, revocation_list_address: Address) -> Self {
+        self.revocation_list_address = Some(revocation_list_address);
+        self
+    }
+
+    /// Set the address of the identity updates service on the remote node
+    pub fn with_identity_updates_address(mut self, identity_updates_address: Address) -> Self {
+        self.identity_updates_address = Some(identity_updates_address);
+        self
+    }
+
+    /// Set the address of the policy bundles service on the remote node
+    pub fn with_policy_bundles_address(mut self, policy_bundles_address: Address) -> Self {
+        self.policy_bundles_address = Some(policy_bundles_address);
+        self
+    }
 }
@@ -1,4 +1,4 @@
-use crate::credential::Credential;
+use crate::credential::{Credential, SelectiveCredential};
 use crate::credentials::credentials_server_worker::CredentialsServerWorker;
 use crate::credentials::Credentials;
 use crate::identity::Identity;
@@ -21,11 +21,12 @@ pub trait CredentialsServer: Send + Sync {
     /// Present credential to other party, route shall use secure channel. Other party is expected
     /// to present its credential in response, otherwise this call errors.
     ///
+    /// The credential of the other party must be issued by an authority of the trust context.
     async fn present_credential_mutual(
         &self,
         ctx: &Context,
         route: Route,
-        authorities: &[Identity],
+        trust_context: &TrustContext,
         credential: Credential,
         options: MessageSendReceiveOptions,
     ) -> Result<()>;
@@ -39,6 +40,18 @@ pub trait CredentialsServer: Send + Sync {
         options: MessageSendReceiveOptions,
     ) -> Result<()>;
 
+    /// Present some attributes of a selective credential to other party,
+    /// route shall use secure channel
+    ///
+    /// Use [`SelectiveCredential::disclose`] to only reveal the attributes the other party needs.
+    async fn present_selective_credential(
+        &self,
+        ctx: &Context,
+        route: Route,
+        credential: SelectiveCredential,
+        options: MessageSendReceiveOptions,
+    ) -> Result<()>;
+
     /// Start this service as a worker
     async fn start(
         &self,
@@ -63,7 +76,7 @@ impl CredentialsServer for CredentialsServerModule {
         &self,
         ctx: &Context,
         route: Route,
-        authorities: &[Identity],
+        trust_context: &TrustContext,
         credential: Credential,
         options: MessageSendReceiveOptions,
     ) -> Result<()> {
@@ -104,7 +117,7 @@ impl CredentialsServer for CredentialsServerModule {
 
         let credential: Credential = dec.decode()?;
         self.credentials
-            .receive_presented_credential(&their_id, authorities, credential)
+            .receive_trust_context_credential(&their_id, trust_context, credential)
             .await?;
 
         Ok(())
@@ -139,6 +152,36 @@ impl CredentialsServer for CredentialsServerModule {
         }
     }
 
+    /// Present some attributes of a selective credential to other party,
+    /// route shall use secure channel
+    async fn present_selective_credential(
+        &self,
+        ctx: &Context,
+        route: Route,
+        credential: SelectiveCredential,
+        options: MessageSendReceiveOptions,
+    ) -> Result<()> {
+        let buf = request(
+            ctx,
+            "credential",
+            None,
+            route,
+            Request::post("actions/present_selective").body(credential),
+            options,
+        )
+        .await?;
+
+        let res: Response = minicbor::decode(&buf)?;
+        match res.status() {
+            Some(Status::Ok) => Ok(()),
+            _ => Err(Error::new(
+                Origin::Application,
+                Kind::Invalid,
+                "credential presentation failed",
+            )),
+        }
+    }
+
     /// Start worker that will be available to receive others attributes and put them into storage,
     /// after successful verification
     async fn start(
@@ -8,7 +8,7 @@ use ockam_core::compat::{string::ToString, sync::Arc, vec::Vec};
 use ockam_core::{Result, Routed, Worker};
 use ockam_node::Context;
 
-use crate::credential::Credential;
+use crate::credential::{Credential, SelectiveCredential};
 use crate::credentials::Credentials;
 use crate::identity::IdentityIdentifier;
 use crate::secure_channel::IdentitySecureChannelLocalInfo;
@@ -79,11 +79,7 @@ impl CredentialsServerWorker {
 
                 let res = self
                     .credentials
-                    .receive_presented_credential(
-                        &sender,
-                        &[self.trust_context.authority()?.identity()],
-                        credential,
-                    )
+                    .receive_trust_context_credential(&sender, &self.trust_context, credential)
                     .await;
 
                 match res {
@@ -100,6 +96,32 @@ impl CredentialsServerWorker {
                     }
                 }
             }
+            (Post, ["actions", "present_selective"]) => {
+                debug!(
+                    "Received selective credential presentation request from {}",
+                    sender
+                );
+                let credential: SelectiveCredential = dec.decode()?;
+
+                let res = self
+                    .credentials
+                    .receive_selective_credential(&sender, &self.trust_context, credential)
+                    .await;
+
+                match res {
+                    Ok(()) => {
+                        debug!("Selective credential presentation request processed successfully with {}", sender);
+                        Response::ok(req.id()).to_vec()?
+                    }
+                    Err(err) => {
+                        debug!(
+                            "Selective credential presentation request processing error: {} for {}",
+                            err, sender
+                        );
+                        Self::bad_request(req.id(), req.path(), &err.to_string()).to_vec()?
+                    }
+                }
+            }
             (Post, ["actions", "present_mutual"]) => {
                 debug!(
                     "Received mutual credential presentation request from {}",
@@ -110,11 +132,7 @@ impl CredentialsServerWorker {
                 info!("presented credential {}", credential);
                 let res = self
                     .credentials
-                    .receive_presented_credential(
-                        &sender,
-                        &[self.trust_context.authority()?.identity()],
-                        credential,
-                    )
+                    .receive_trust_context_credential(&sender, &self.trust_context, credential)
                     .await;
 
                 if let Err(err) = res {
@@ -128,11 +146,11 @@ impl CredentialsServerWorker {
                         "Mutual credential presentation request processed successfully with {}",
                         sender
                     );
-                    let credential = self
-                        .trust_context
-                        .authority()?
-                        .credential(ctx, &self.identity)
-                        .await;
+                    // There is no credential to present back without a main authority
+                    let credential = match self.trust_context.authority() {
+                        Ok(authority) => authority.credential(ctx, &self.identity).await,
+                        Err(e) => Err(e),
+                    };
                     match credential.as_ref() {
                         Ok(p) if self.present_back => {
                             info!("Mutual credential presentation request processed successfully with {}. Responding with own credential...", sender);
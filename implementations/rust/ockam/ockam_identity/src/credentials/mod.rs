@@ -1,15 +1,27 @@
 mod authority_service;
+mod credential_schemas;
 #[allow(clippy::module_inception)]
 mod credentials;
 mod credentials_issuer;
+#[cfg(feature = "std")]
+mod credentials_refresher;
 mod credentials_retriever;
 mod credentials_server;
 mod credentials_server_worker;
+mod policy_bundles_client;
+mod revocation_list_issuer;
+mod revocation_lists;
 mod trust_context;
 
 pub use authority_service::*;
+pub use credential_schemas::*;
 pub use credentials::*;
 pub use credentials_issuer::*;
+#[cfg(feature = "std")]
+pub use credentials_refresher::*;
 pub use credentials_retriever::*;
 pub use credentials_server::*;
+pub use policy_bundles_client::*;
+pub use revocation_list_issuer::*;
+pub use revocation_lists::*;
 pub use trust_context::*;
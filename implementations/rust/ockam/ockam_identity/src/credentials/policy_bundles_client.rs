@@ -0,0 +1,39 @@
+use crate::credential::SignedPolicyBundle;
+use ockam_core::api::Request;
+use ockam_core::flow_control::FlowControls;
+use ockam_core::{Result, Route};
+use ockam_node::{Context, RpcClient};
+
+/// Client for the policy bundles service of an authority
+pub struct PolicyBundlesClient {
+    client: RpcClient,
+}
+
+impl PolicyBundlesClient {
+    /// Create a new policy bundles client
+    /// The route needs to be a secure channel
+    pub async fn new(route: Route, ctx: &Context) -> Result<Self> {
+        Ok(PolicyBundlesClient {
+            client: RpcClient::new(route, ctx).await?,
+        })
+    }
+
+    /// Return the latest policy bundle published by the authority, if any
+    pub async fn latest(&self) -> Result<Option<SignedPolicyBundle>> {
+        self.client.request(&Request::get("/")).await
+    }
+
+    /// Return a given version of the policy bundle published by the authority
+    pub async fn version(&self, version: u64) -> Result<SignedPolicyBundle> {
+        self.client
+            .request(&Request::get(format!("/{version}")))
+            .await
+    }
+
+    /// Specify the flow controls to use for the RpcClient
+    pub fn with_flow_controls(self, flow_controls: &FlowControls) -> Self {
+        Self {
+            client: self.client.with_flow_controls(flow_controls),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::alloc::string::ToString;
+use minicbor::Decoder;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{api, Error, Result, Route, Routed, Worker};
+use ockam_node::{Context, RpcClient};
+
+use crate::credential::{RevocationList, RevocationListData, RevokedSubject, Timestamp};
+use crate::identities::Storage;
+use crate::identity::{Identity, IdentityIdentifier};
+use crate::{secure_channel_required, Identities, IdentitySecureChannelLocalInfo};
+use ockam_core::api::{Method, Request, Response};
+use ockam_core::flow_control::FlowControls;
+use tracing::trace;
+
+/// Persistent set of the subjects revoked by an authority
+#[derive(Clone)]
+pub struct RevocationsStorage {
+    storage: Arc<dyn Storage>,
+}
+
+impl RevocationsStorage {
+    const REVOCATION_KEY: &'static str = "REVOCATION";
+
+    /// Create a new storage for revocations
+    pub fn new(storage: Arc<dyn Storage>) -> Self {
+        Self { storage }
+    }
+
+    /// Revoke all the credentials issued to a subject until now
+    ///
+    /// Return the revocation time.
+    pub async fn revoke(&self, subject: &IdentityIdentifier) -> Result<Timestamp> {
+        let now = Timestamp::now()
+            .ok_or_else(|| Error::new(Origin::Core, Kind::Internal, "invalid system time"))?;
+        self.storage
+            .set(
+                &subject.to_string(),
+                Self::REVOCATION_KEY.to_string(),
+                minicbor::to_vec(now)?,
+            )
+            .await?;
+        Ok(now)
+    }
+
+    /// Return the revoked subjects
+    pub async fn list(&self) -> Result<Vec<RevokedSubject>> {
+        let mut revoked = Vec::new();
+        for id in self.storage.keys(Self::REVOCATION_KEY).await? {
+            if let Some(value) = self.storage.get(&id, Self::REVOCATION_KEY).await? {
+                let revoked_at: Timestamp = minicbor::decode(&value)?;
+                revoked.push(RevokedSubject::new(
+                    IdentityIdentifier::try_from(id)?,
+                    revoked_at,
+                ));
+            }
+        }
+        Ok(revoked)
+    }
+}
+
+/// This struct runs as a Worker to publish the signed revocation list of an authority
+pub struct RevocationListIssuer {
+    identities: Arc<Identities>,
+    issuer: Identity,
+    revocations: RevocationsStorage,
+}
+
+impl RevocationListIssuer {
+    /// Create a new revocation list issuer
+    pub fn new(
+        identities: Arc<Identities>,
+        issuer: Identity,
+        revocations: RevocationsStorage,
+    ) -> Self {
+        Self {
+            identities,
+            issuer,
+            revocations,
+        }
+    }
+
+    async fn issue_revocation_list(&self) -> Result<RevocationList> {
+        let now = Timestamp::now()
+            .ok_or_else(|| Error::new(Origin::Core, Kind::Internal, "invalid system time"))?;
+        let data = RevocationListData::new(
+            self.issuer.identifier(),
+            now,
+            self.revocations.list().await?,
+        );
+        self.identities
+            .credentials()
+            .issue_revocation_list(&self.issuer, data)
+            .await
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for RevocationListIssuer {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        if let Ok(i) = IdentitySecureChannelLocalInfo::find_info(m.local_message()) {
+            let from = i.their_identity_id();
+            let mut dec = Decoder::new(m.as_body());
+            let req: Request = dec.decode()?;
+            trace! {
+                target: "ockam_identity::credentials::revocation_list_issuer",
+                from   = %from,
+                id     = %req.id(),
+                method = ?req.method(),
+                path   = %req.path(),
+                body   = %req.has_body(),
+                "request"
+            }
+            let res = match (req.method(), req.path()) {
+                (Some(Method::Get), "/") | (Some(Method::Get), "/revocations") => {
+                    match self.issue_revocation_list().await {
+                        Ok(list) => Response::ok(req.id()).body(list).to_vec()?,
+                        Err(error) => api::internal_error(&req, &error.to_string()).to_vec()?,
+                    }
+                }
+                _ => api::unknown_path(&req).to_vec()?,
+            };
+            c.send(m.return_route(), res).await
+        } else {
+            secure_channel_required(c, m).await
+        }
+    }
+}
+
+/// Client for a revocation list issuer
+pub struct RevocationListIssuerClient {
+    client: RpcClient,
+}
+
+impl RevocationListIssuerClient {
+    /// Create a new revocation list issuer client
+    /// The route needs to be a secure channel
+    pub async fn new(route: Route, ctx: &Context) -> Result<Self> {
+        Ok(RevocationListIssuerClient {
+            client: RpcClient::new(route, ctx).await?,
+        })
+    }
+
+    /// Return the current revocation list of the authority, if it publishes one
+    pub async fn revocation_list(&self) -> Result<Option<RevocationList>> {
+        self.client.request_optional(&Request::get("/")).await
+    }
+
+    /// Specify the flow controls to use for the RpcClient
+    pub fn with_flow_controls(self, flow_controls: &FlowControls) -> Self {
+        Self {
+            client: self.client.with_flow_controls(flow_controls),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::credential::{RevocationListData, Timestamp};
+use crate::identity::IdentityIdentifier;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::{Arc, RwLock};
+
+/// Cache of the latest verified revocation list of each authority
+///
+/// This cache is consulted when verifying credentials, so that a
+/// credential revoked by its issuer is rejected by every component
+/// sharing the same [`Identities`](crate::Identities).
+#[derive(Clone, Default)]
+pub struct RevocationLists {
+    lists: Arc<RwLock<BTreeMap<IdentityIdentifier, RevocationListData>>>,
+}
+
+impl RevocationLists {
+    /// Return the cached revocation list of an issuer
+    pub fn get(&self, issuer: &IdentityIdentifier) -> Option<RevocationListData> {
+        self.lists.read().unwrap().get(issuer).cloned()
+    }
+
+    /// Return true if a credential created at the given time by the given
+    /// issuer for the given subject is revoked
+    pub fn is_revoked(
+        &self,
+        issuer: &IdentityIdentifier,
+        subject: &IdentityIdentifier,
+        created: Timestamp,
+    ) -> bool {
+        self.lists
+            .read()
+            .unwrap()
+            .get(issuer)
+            .map(|list| list.is_revoked(subject, created))
+            .unwrap_or(false)
+    }
+
+    /// Store a verified revocation list, unless a more recent list of the
+    /// same issuer is already cached
+    ///
+    /// Return true if the list was stored.
+    pub(crate) fn update(&self, list: RevocationListData) -> bool {
+        let mut lists = self.lists.write().unwrap();
+        match lists.get(list.issuer()) {
+            Some(current) if current.created() > list.created() => false,
+            _ => {
+                lists.insert(list.issuer().clone(), list);
+                true
+            }
+        }
+    }
+}
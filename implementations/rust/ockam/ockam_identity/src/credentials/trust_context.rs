@@ -1,21 +1,57 @@
-use crate::{AuthorityService, IdentityError};
-use ockam_core::compat::string::String;
+use crate::credential::CredentialSchema;
+use crate::identity::{Identity, IdentityIdentifier};
+use crate::{AuthorityService, IdentityError, LEGACY_ID, TRUST_CONTEXT_ID};
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 
 /// A trust context defines which authorities are trusted to attest to which attributes, within a context.
-/// Our first implementation assumes that there is only one authority and it is trusted to attest to all attributes within this context.
+///
+/// The main authority is trusted to attest to all the attributes within this context, and is used
+/// to retrieve the credentials of the local identity. Additional authorities can be trusted to attest
+/// to a subset of the attributes only, see [`TrustContext::with_scoped_authority`].
 #[derive(Clone)]
 pub struct TrustContext {
     /// This is the ID of the trust context; which is primarily used for ABAC policies
     id: String,
     /// Authority capable of retrieving credentials
     authority: Option<AuthorityService>,
+    /// Authorities only trusted for some attributes
+    scoped_authorities: Vec<ScopedAuthority>,
+    /// Schemas that the credentials of this trust context must conform to
+    credential_schemas: Vec<CredentialSchema>,
 }
 
 impl TrustContext {
     /// Create a new Trust Context
     pub fn new(id: String, authority: Option<AuthorityService>) -> Self {
-        Self { id, authority }
+        Self {
+            id,
+            authority,
+            scoped_authorities: Vec::new(),
+            credential_schemas: Vec::new(),
+        }
+    }
+
+    /// Declare a schema that the credentials of this trust context must conform to
+    ///
+    /// The schemas must be registered by the identities verifying credentials within
+    /// this trust context, see [`CredentialSchemas`](crate::CredentialSchemas).
+    pub fn with_credential_schema(mut self, schema: CredentialSchema) -> Self {
+        self.credential_schemas.push(schema);
+        self
+    }
+
+    /// Return the schemas that the credentials of this trust context must conform to
+    pub fn credential_schemas(&self) -> &[CredentialSchema] {
+        &self.credential_schemas
+    }
+
+    /// Trust an additional authority to attest to the attributes of the given scope
+    pub fn with_scoped_authority(mut self, identity: Identity, scope: AttributesScope) -> Self {
+        self.scoped_authorities
+            .push(ScopedAuthority::new(identity, scope));
+        self
     }
 
     /// Return the ID of the Trust Context
@@ -29,4 +65,131 @@ impl TrustContext {
             .as_ref()
             .ok_or_else(|| IdentityError::UnknownAuthority.into())
     }
+
+    /// Return the authorities only trusted for some attributes
+    pub fn scoped_authorities(&self) -> &[ScopedAuthority] {
+        &self.scoped_authorities
+    }
+
+    /// Return the identities of all the authorities of the Trust Context
+    pub fn authorities(&self) -> Vec<Identity> {
+        self.authority
+            .iter()
+            .map(|a| a.identity())
+            .chain(self.scoped_authorities.iter().map(|a| a.identity().clone()))
+            .collect()
+    }
+
+    /// Return true if an authority is trusted to attest to an attribute with the given value
+    ///
+    /// Every authority of the trust context can attest to the attributes identifying
+    /// the trust context, as long as their value is the ID of this trust context.
+    pub fn is_trusted_for(
+        &self,
+        authority: &IdentityIdentifier,
+        attribute: &str,
+        value: &[u8],
+    ) -> bool {
+        if let Some(main) = &self.authority {
+            if &main.identity().identifier() == authority {
+                return true;
+            }
+        }
+        self.scoped_authorities.iter().any(|a| {
+            &a.identity().identifier() == authority
+                && if attribute == TRUST_CONTEXT_ID || attribute == LEGACY_ID {
+                    value == self.id.as_bytes()
+                } else {
+                    a.scope().allows(attribute)
+                }
+        })
+    }
+}
+
+/// An authority which is only trusted to attest to some attributes
+#[derive(Clone)]
+pub struct ScopedAuthority {
+    identity: Identity,
+    scope: AttributesScope,
+}
+
+impl ScopedAuthority {
+    /// Create a new scoped authority
+    pub fn new(identity: Identity, scope: AttributesScope) -> Self {
+        Self { identity, scope }
+    }
+
+    /// Return the identity of the authority
+    pub fn identity(&self) -> &Identity {
+        &self.identity
+    }
+
+    /// Return the attributes the authority can attest to
+    pub fn scope(&self) -> &AttributesScope {
+        &self.scope
+    }
+}
+
+/// Names of the attributes an authority is trusted to attest to
+///
+/// A name can be:
+///  - `*` to allow all the attributes
+///  - a prefix followed by `.*`, for example `device.*`, to allow all the attributes in a namespace
+///  - the exact name of an attribute
+///
+/// The attributes identifying the trust context are not part of a scope, see
+/// [`TrustContext::is_trusted_for`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AttributesScope {
+    names: Vec<String>,
+}
+
+impl AttributesScope {
+    /// Create a new scope from a list of attribute names
+    pub fn new(names: Vec<String>) -> Self {
+        Self { names }
+    }
+
+    /// Create a scope allowing all attributes
+    pub fn all() -> Self {
+        Self::new(vec!["*".to_string()])
+    }
+
+    /// Return the attribute names of this scope
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Return true if an attribute is part of this scope
+    pub fn allows(&self, attribute: &str) -> bool {
+        self.names.iter().any(|name| {
+            if name == "*" || name == attribute {
+                return true;
+            }
+            match name.strip_suffix(".*") {
+                Some(namespace) => attribute
+                    .strip_prefix(namespace)
+                    .map_or(false, |rest| rest.starts_with('.')),
+                None => false,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_scope() {
+        let scope = AttributesScope::new(vec!["role".to_string(), "device.*".to_string()]);
+        assert!(scope.allows("role"));
+        assert!(scope.allows("device.id"));
+        assert!(scope.allows("device.os.version"));
+        assert!(!scope.allows("device"));
+        assert!(!scope.allows("devices.id"));
+        assert!(!scope.allows("roles"));
+        assert!(!AttributesScope::default().allows("role"));
+        assert!(AttributesScope::all().allows("role"));
+    }
 }
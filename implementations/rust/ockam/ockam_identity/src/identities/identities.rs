@@ -1,9 +1,12 @@
 use crate::identities::{IdentitiesKeys, IdentitiesRepository, IdentitiesVault};
+use crate::identity::{Identity, IdentityError, IdentityHistoryComparison};
 use crate::{
-    Credentials, CredentialsServer, CredentialsServerModule, IdentitiesBuilder, IdentitiesCreation,
-    IdentitiesStorage,
+    CredentialSchemas, Credentials, CredentialsServer, CredentialsServerModule, IdentitiesBuilder,
+    IdentitiesCreation, IdentitiesStorage, RevocationLists,
 };
 use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
 use ockam_vault::Vault;
 
 /// This struct supports all the services related to identities
@@ -11,6 +14,8 @@ use ockam_vault::Vault;
 pub struct Identities {
     pub(crate) vault: Arc<dyn IdentitiesVault>,
     pub(crate) identities_repository: Arc<dyn IdentitiesRepository>,
+    pub(crate) revocation_lists: RevocationLists,
+    pub(crate) credential_schemas: CredentialSchemas,
 }
 
 impl Identities {
@@ -24,6 +29,16 @@ impl Identities {
         self.identities_repository.clone()
     }
 
+    /// Return the cached revocation lists used to verify credentials
+    pub fn revocation_lists(&self) -> RevocationLists {
+        self.revocation_lists.clone()
+    }
+
+    /// Return the registry of the credential schemas used to validate credentials
+    pub fn credential_schemas(&self) -> CredentialSchemas {
+        self.credential_schemas.clone()
+    }
+
     /// Return the identities keys management service
     pub fn identities_keys(&self) -> Arc<IdentitiesKeys> {
         Arc::new(IdentitiesKeys::new(self.vault.clone()))
@@ -43,6 +58,39 @@ impl Identities {
     pub fn credentials_server(&self) -> Arc<dyn CredentialsServer> {
         Arc::new(CredentialsServerModule::new(self.credentials()))
     }
+
+    /// Verify the change history of an identity and store it if it is newer than the known one
+    ///
+    /// Return true if the stored identity was updated. An older history is ignored,
+    /// while a history conflicting with the known one, or the history of an unknown
+    /// identity, is rejected.
+    pub async fn update_identity(&self, identity: &Identity) -> Result<bool> {
+        self.identities_keys().verify_changes(identity).await?;
+        let known = self
+            .identities_repository
+            .get_identity(&identity.identifier())
+            .await?
+            .ok_or_else(|| {
+                Error::new(
+                    Origin::Identity,
+                    Kind::NotFound,
+                    format!("unknown identity {}", identity.identifier()),
+                )
+            })?;
+        match identity.compare(&known) {
+            IdentityHistoryComparison::Newer => {}
+            IdentityHistoryComparison::Equal | IdentityHistoryComparison::Older => {
+                return Ok(false)
+            }
+            IdentityHistoryComparison::Conflict => {
+                return Err(IdentityError::ConsistencyError.into())
+            }
+        }
+        self.identities_repository
+            .update_known_identity(identity)
+            .await?;
+        Ok(true)
+    }
 }
 
 impl Identities {
@@ -54,6 +102,8 @@ impl Identities {
         Identities {
             vault,
             identities_repository,
+            revocation_lists: RevocationLists::default(),
+            credential_schemas: CredentialSchemas::default(),
         }
     }
 
@@ -1,15 +1,15 @@
 use crate::alloc::string::ToString;
 use crate::identities::IdentitiesVault;
-use crate::identity::IdentityChange::{CreateKey, RotateKey};
+use crate::identity::IdentityChange::{AddDeviceKey, CreateKey, RevokeDeviceKey, RotateKey};
 use crate::identity::IdentityError::InvalidInternalState;
 use crate::identity::{
-    ChangeIdentifier, CreateKeyChangeData, Identity, IdentityChangeConstants,
-    IdentityChangeHistory, IdentityError, IdentitySignedChange, KeyAttributes, RotateKeyChangeData,
-    Signature, SignatureType,
+    AddDeviceKeyChangeData, ChangeIdentifier, CreateKeyChangeData, Identity, IdentityChange,
+    IdentityChangeConstants, IdentityChangeHistory, IdentityError, IdentitySignedChange,
+    KeyAttributes, RevokeDeviceKeyChangeData, RotateKeyChangeData, Signature, SignatureType,
 };
 use ockam_core::compat::string::String;
 use ockam_core::compat::sync::Arc;
-use ockam_core::vault::KeyId;
+use ockam_core::vault::{KeyId, PublicKey};
 use ockam_core::{Encodable, Result};
 
 /// This module supports the key operations related to identities
@@ -156,6 +156,105 @@ impl IdentitiesKeys {
 
         identity.add_change(change)
     }
+
+    /// Generate a key on this device, to be added to an `Identity` by the holder of its root key
+    pub async fn create_device_key(&self) -> Result<PublicKey> {
+        let key_attributes = KeyAttributes::default_with_label(String::new());
+        let secret = self
+            .vault
+            .secret_generate(key_attributes.secret_attributes())
+            .await?;
+        self.vault.secret_public_key_get(&secret).await
+    }
+
+    /// Allow another device to act as this `Identity` with its own key
+    ///
+    /// The change is signed with the root key, the private key of the device
+    /// is never needed on this device.
+    pub async fn add_device_key(
+        &self,
+        identity: &mut Identity,
+        label: String,
+        public_key: PublicKey,
+    ) -> Result<()> {
+        // A label can only be used once, including by keys which are not device keys
+        if label == IdentityChangeConstants::ROOT_LABEL
+            || IdentityChangeHistory::find_last_key_change(identity.changes().as_ref(), &label)
+                .is_ok()
+        {
+            return Err(InvalidInternalState.into());
+        }
+
+        let prev_change_id = identity.change_history.get_last_change_id()?;
+        let key_attributes = KeyAttributes::default_with_label(label);
+        let data = AddDeviceKeyChangeData::new(prev_change_id, key_attributes, public_key);
+        let change = self
+            .make_root_signed_change(identity, AddDeviceKey(data))
+            .await?;
+
+        identity.add_change(change)
+    }
+
+    /// Revoke the key of a device, which can't act as this `Identity` anymore
+    pub async fn revoke_device_key(&self, identity: &mut Identity, label: &str) -> Result<()> {
+        let public_key = identity
+            .get_device_public_keys()
+            .remove(label)
+            .ok_or(InvalidInternalState)?;
+
+        let prev_change_id = identity.change_history.get_last_change_id()?;
+        let data = RevokeDeviceKeyChangeData::new(prev_change_id, label.to_string(), public_key);
+        let change = self
+            .make_root_signed_change(identity, RevokeDeviceKey(data))
+            .await?;
+
+        identity.add_change(change)
+    }
+
+    /// Sign some data as an `Identity`, with its root key if it is stored in this vault,
+    /// or with the key of this device otherwise
+    pub async fn create_identity_signature(
+        &self,
+        identity: &Identity,
+        data: &[u8],
+    ) -> Result<ockam_core::vault::Signature> {
+        let mut public_keys = vec![identity.get_root_public_key()?];
+        public_keys.extend(identity.get_device_public_keys().into_values());
+
+        for public_key in public_keys {
+            let key_id = self
+                .vault
+                .compute_key_id_for_public_key(&public_key)
+                .await?;
+            if self.vault.secret_attributes_get(&key_id).await.is_ok() {
+                return self.vault.sign(&key_id, data).await;
+            }
+        }
+        Err(InvalidInternalState.into())
+    }
+
+    /// Verify a signature created with the root key of an `Identity` or with
+    /// one of its device keys which is not revoked
+    pub async fn verify_identity_signature(
+        &self,
+        identity: &Identity,
+        signature: &ockam_core::vault::Signature,
+        data: &[u8],
+    ) -> Result<bool> {
+        if self
+            .vault
+            .verify(signature, &identity.get_root_public_key()?, data)
+            .await?
+        {
+            return Ok(true);
+        }
+        for public_key in identity.get_device_public_keys().values() {
+            if self.vault.verify(signature, public_key, data).await? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
 }
 
 /// Private  functions
@@ -307,6 +406,30 @@ impl IdentitiesKeys {
         Ok(signed_change)
     }
 
+    /// Create a change which is only signed by the root key
+    async fn make_root_signed_change(
+        &self,
+        identity: &Identity,
+        change_block: IdentityChange,
+    ) -> Result<IdentitySignedChange> {
+        let change_block_binary = change_block
+            .encode()
+            .map_err(|_| IdentityError::BareError)?;
+
+        let change_id = self.vault.sha256(&change_block_binary).await?;
+        let change_id = ChangeIdentifier::from_hash(change_id);
+
+        let root_key = self.get_root_secret_key(identity).await?;
+        let root_signature = self.vault.sign(&root_key, change_id.as_ref()).await?;
+        let root_signature = Signature::new(SignatureType::RootSign, root_signature);
+
+        Ok(IdentitySignedChange::new(
+            change_id,
+            change_block,
+            vec![root_signature],
+        ))
+    }
+
     /// Get [`Secret`] key. Key is uniquely identified by label in [`KeyAttributes`]
     async fn get_root_secret_key(&self, identity: &Identity) -> Result<KeyId> {
         self.get_labelled_key(identity, IdentityChangeConstants::ROOT_LABEL)
@@ -369,6 +492,37 @@ impl IdentitiesKeys {
                     root_sign: 1,
                 }
             }
+            AddDeviceKey(data) => {
+                // The label of a device must not have been used before
+                let label = data.key_attributes().label();
+                if label == IdentityChangeConstants::ROOT_LABEL
+                    || IdentityChangeHistory::find_last_key_change(existing_changes, label).is_ok()
+                {
+                    return Err(IdentityError::IdentityVerificationFailed.into());
+                }
+
+                // Should only have root signature
+                SignaturesCheck {
+                    self_sign: 0,
+                    prev_sign: 0,
+                    root_sign: 1,
+                }
+            }
+            RevokeDeviceKey(data) => {
+                // Only a device key which is currently valid can be revoked
+                let device_keys =
+                    IdentityChangeHistory::get_device_public_keys_static(existing_changes);
+                if device_keys.get(data.label()) != Some(data.public_key()) {
+                    return Err(IdentityError::IdentityVerificationFailed.into());
+                }
+
+                // Should only have root signature
+                SignaturesCheck {
+                    self_sign: 0,
+                    prev_sign: 0,
+                    root_sign: 1,
+                }
+            }
         };
 
         for signature in new_change.signatures() {
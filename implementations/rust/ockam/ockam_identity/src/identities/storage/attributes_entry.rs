@@ -14,6 +14,44 @@ pub struct AttributesEntry {
     #[n(2)] added: Timestamp,
     #[n(3)] expires: Option<Timestamp>,
     #[n(4)] attested_by: Option<IdentityIdentifier>,
+    #[serde(default)]
+    #[b(5)] issued: Option<BTreeMap<String, IssuedAttributes>>,
+}
+
+/// Attributes attested by one of the authorities of a trust context
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct IssuedAttributes {
+    #[b(1)] attrs: BTreeMap<String, Vec<u8>>,
+    #[n(2)] expires: Timestamp,
+    #[n(3)] created: Timestamp,
+}
+
+impl IssuedAttributes {
+    /// Constructor
+    pub fn new(attrs: BTreeMap<String, Vec<u8>>, created: Timestamp, expires: Timestamp) -> Self {
+        Self {
+            attrs,
+            expires,
+            created,
+        }
+    }
+
+    /// The attested attributes
+    pub fn attrs(&self) -> &BTreeMap<String, Vec<u8>> {
+        &self.attrs
+    }
+
+    /// Expiration time of the attributes
+    pub fn expires(&self) -> Timestamp {
+        self.expires
+    }
+
+    /// Creation time of the credential attesting the attributes
+    pub fn created(&self) -> Timestamp {
+        self.created
+    }
 }
 
 impl AttributesEntry {
@@ -33,9 +71,17 @@ impl AttributesEntry {
             added,
             expires,
             attested_by,
+            issued: None,
         }
     }
 
+    /// Record the attributes attested by each authority of a trust context,
+    /// keyed by the identifier of the authority
+    pub fn with_issued(mut self, issued: BTreeMap<String, IssuedAttributes>) -> Self {
+        self.issued = Some(issued);
+        self
+    }
+
     /// The entry attributes
     pub fn attrs(&self) -> &BTreeMap<String, Vec<u8>> {
         &self.attrs
@@ -55,4 +101,80 @@ impl AttributesEntry {
     pub fn attested_by(&self) -> Option<IdentityIdentifier> {
         self.attested_by.to_owned()
     }
+
+    /// The attributes attested by each authority of a trust context, if they were recorded
+    pub fn issued(&self) -> Option<&BTreeMap<String, IssuedAttributes>> {
+        self.issued.as_ref()
+    }
+
+    /// Return true if the entry has expired at the given time
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        matches!(self.expires, Some(expires) if expires <= now)
+    }
+
+    /// Return true if the attributes of some of the authorities of a trust context
+    /// have expired at the given time
+    pub fn has_expired_issuers(&self, now: Timestamp) -> bool {
+        self.issued
+            .iter()
+            .flat_map(|issued| issued.values())
+            .any(|attributes| attributes.expires() <= now)
+    }
+
+    /// Remove the attributes of the authorities which expired at the given time.
+    /// Return None if no attributes are left
+    pub fn without_expired_issuers(self, now: Timestamp) -> Option<Self> {
+        self.retain_issued(|_, attributes| attributes.expires() > now)
+    }
+
+    /// Remove the attributes of an authority which were attested by a credential
+    /// created before the given revocation time. Return None if no attributes are left
+    pub fn without_revoked_issuer(
+        self,
+        issuer: &IdentityIdentifier,
+        revoked_at: Timestamp,
+    ) -> Option<Self> {
+        let issuer = issuer.to_string();
+        self.retain_issued(|id, attributes| id != &issuer || attributes.created() > revoked_at)
+    }
+
+    /// Keep the attributes of the authorities matching a predicate and derive the entry
+    /// attributes from what is left.
+    ///
+    /// The current value of an attribute is kept while one of the remaining authorities
+    /// attests to it, so that the precedence used when the attributes were merged still holds.
+    fn retain_issued(mut self, f: impl Fn(&String, &IssuedAttributes) -> bool) -> Option<Self> {
+        let mut issued = match self.issued.take() {
+            Some(issued) => issued,
+            None => return Some(self),
+        };
+        issued.retain(|id, attributes| f(id, attributes));
+        if issued.is_empty() {
+            return None;
+        }
+
+        let mut attrs = BTreeMap::new();
+        for attributes in issued.values() {
+            for (name, value) in attributes.attrs() {
+                if !attrs.contains_key(name) || self.attrs.get(name) == Some(value) {
+                    attrs.insert(name.clone(), value.clone());
+                }
+            }
+        }
+        let still_attested = self
+            .attested_by
+            .as_ref()
+            .map(|id| issued.contains_key(&id.to_string()))
+            .unwrap_or(false);
+        if !still_attested {
+            self.attested_by = issued
+                .keys()
+                .next()
+                .and_then(|id| IdentityIdentifier::try_from(id.as_str()).ok());
+        }
+        self.attrs = attrs;
+        self.expires = issued.values().map(|a| a.expires()).max();
+        self.issued = Some(issued);
+        Some(self)
+    }
 }
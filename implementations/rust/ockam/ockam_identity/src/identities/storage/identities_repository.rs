@@ -69,6 +69,14 @@ pub trait IdentityAttributesWriter: Send + Sync + 'static {
 
     /// Remove all attributes for a given identity identifier
     async fn delete(&self, identity: &IdentityIdentifier) -> Result<()>;
+
+    /// Remove the attributes which have expired
+    /// Return the identities whose attributes were removed
+    ///
+    /// Expired attributes are ignored when they are read, so by default nothing is removed.
+    async fn delete_expired(&self) -> Result<Vec<IdentityIdentifier>> {
+        Ok(vec![])
+    }
 }
 
 /// Trait implementing write access to identities
@@ -141,17 +149,28 @@ impl IdentityAttributesReader for IdentitiesStorage {
 
         let entry: AttributesEntry = minicbor::decode(&entry)?;
 
-        let now = Timestamp::now().ok_or_else(|| {
-            ockam_core::Error::new(Origin::Core, Kind::Internal, "invalid system time")
-        })?;
-        match entry.expires() {
-            Some(exp) if exp <= now => {
-                self.storage
-                    .del(&id, IdentityChangeConstants::ATTRIBUTES_KEY)
-                    .await?;
-                Ok(None)
+        let now = now()?;
+        if entry.is_expired(now) {
+            self.storage
+                .del(&id, IdentityChangeConstants::ATTRIBUTES_KEY)
+                .await?;
+            Ok(None)
+        } else if entry.has_expired_issuers(now) {
+            // The attributes of the other authorities of a trust context are still valid
+            match entry.without_expired_issuers(now) {
+                Some(entry) => {
+                    self.put_attributes(identity_id, entry.clone()).await?;
+                    Ok(Some(entry))
+                }
+                None => {
+                    self.storage
+                        .del(&id, IdentityChangeConstants::ATTRIBUTES_KEY)
+                        .await?;
+                    Ok(None)
+                }
             }
-            _ => Ok(Some(entry)),
+        } else {
+            Ok(Some(entry))
         }
     }
 
@@ -224,6 +243,33 @@ impl IdentityAttributesWriter for IdentitiesStorage {
             )
             .await
     }
+
+    async fn delete_expired(&self) -> Result<Vec<IdentityIdentifier>> {
+        let now = now()?;
+        let mut deleted = Vec::new();
+        for id in self
+            .storage
+            .keys(IdentityChangeConstants::ATTRIBUTES_KEY)
+            .await?
+        {
+            let entry = match self
+                .storage
+                .get(&id, IdentityChangeConstants::ATTRIBUTES_KEY)
+                .await?
+            {
+                Some(entry) => entry,
+                None => continue,
+            };
+            let entry: AttributesEntry = minicbor::decode(&entry)?;
+            if entry.is_expired(now) {
+                self.storage
+                    .del(&id, IdentityChangeConstants::ATTRIBUTES_KEY)
+                    .await?;
+                deleted.push(IdentityIdentifier::try_from(id)?);
+            }
+        }
+        Ok(deleted)
+    }
 }
 
 #[async_trait]
@@ -269,3 +315,8 @@ impl IdentitiesReader for IdentitiesStorage {
         }
     }
 }
+
+fn now() -> Result<Timestamp> {
+    Timestamp::now()
+        .ok_or_else(|| ockam_core::Error::new(Origin::Core, Kind::Internal, "invalid system time"))
+}
@@ -4,6 +4,7 @@ use crate::identity::identity_change_history::IdentityChangeHistory;
 use crate::identity::identity_identifier::IdentityIdentifier;
 use crate::IdentityHistoryComparison;
 use core::fmt::{Display, Formatter};
+use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::fmt;
 use ockam_core::compat::string::String;
 use ockam_core::compat::vec::Vec;
@@ -57,6 +58,11 @@ impl Identity {
         self.change_history.get_root_public_key()
     }
 
+    /// Return the keys of the other devices currently allowed to act as this identity
+    pub fn get_device_public_keys(&self) -> BTreeMap<String, PublicKey> {
+        self.change_history.get_device_public_keys()
+    }
+
     pub(crate) fn get_public_key(&self, key_label: Option<&str>) -> Result<PublicKey> {
         let key = match key_label {
             Some(label) => self.get_labelled_public_key(label)?,
@@ -0,0 +1,105 @@
+use crate::identity::identity_change::ChangeIdentifier;
+use crate::identity::identity_change::KeyAttributes;
+use core::fmt;
+use ockam_core::compat::string::String;
+use ockam_core::vault::PublicKey;
+use serde::{Deserialize, Serialize};
+
+/// AddDeviceKeyChangeData
+///
+/// A device key is generated by another device than the one holding the root key.
+/// It is only signed with the root key, so that its private key never has to be shared.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AddDeviceKeyChangeData {
+    prev_change_id: ChangeIdentifier,
+    key_attributes: KeyAttributes,
+    public_key: PublicKey,
+}
+
+impl AddDeviceKeyChangeData {
+    /// Return key attributes, the label is the device name
+    pub fn key_attributes(&self) -> &KeyAttributes {
+        &self.key_attributes
+    }
+    /// Return public key
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+    /// Previous change identifier, used to create a chain
+    pub fn prev_change_id(&self) -> &ChangeIdentifier {
+        &self.prev_change_id
+    }
+}
+
+impl AddDeviceKeyChangeData {
+    /// Create AddDeviceKeyChangeData
+    pub fn new(
+        prev_change_id: ChangeIdentifier,
+        key_attributes: KeyAttributes,
+        public_key: PublicKey,
+    ) -> Self {
+        Self {
+            prev_change_id,
+            key_attributes,
+            public_key,
+        }
+    }
+}
+
+impl fmt::Display for AddDeviceKeyChangeData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "prev_change_id:{} key attibutes:{} public key:{}",
+            self.prev_change_id(),
+            self.key_attributes(),
+            self.public_key()
+        )
+    }
+}
+
+/// RevokeDeviceKeyChangeData
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RevokeDeviceKeyChangeData {
+    prev_change_id: ChangeIdentifier,
+    label: String,
+    public_key: PublicKey,
+}
+
+impl RevokeDeviceKeyChangeData {
+    /// Return the label of the revoked device key
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+    /// Return the revoked public key
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+    /// Previous change identifier, used to create a chain
+    pub fn prev_change_id(&self) -> &ChangeIdentifier {
+        &self.prev_change_id
+    }
+}
+
+impl RevokeDeviceKeyChangeData {
+    /// Create RevokeDeviceKeyChangeData
+    pub fn new(prev_change_id: ChangeIdentifier, label: String, public_key: PublicKey) -> Self {
+        Self {
+            prev_change_id,
+            label,
+            public_key,
+        }
+    }
+}
+
+impl fmt::Display for RevokeDeviceKeyChangeData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "prev_change_id:{} label:{} public key:{}",
+            self.prev_change_id(),
+            self.label(),
+            self.public_key()
+        )
+    }
+}
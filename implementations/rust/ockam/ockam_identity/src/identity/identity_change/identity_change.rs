@@ -1,5 +1,7 @@
-use crate::identity::identity_change::CreateKeyChangeData;
 use crate::identity::identity_change::RotateKeyChangeData;
+use crate::identity::identity_change::{
+    AddDeviceKeyChangeData, CreateKeyChangeData, RevokeDeviceKeyChangeData,
+};
 use crate::identity::identity_change::{ChangeIdentifier, Signature};
 use core::fmt;
 use ockam_core::compat::vec::Vec;
@@ -14,6 +16,10 @@ pub enum IdentityChange {
     CreateKey(CreateKeyChangeData),
     /// Rotate key
     RotateKey(RotateKeyChangeData),
+    /// Add the key of another device
+    AddDeviceKey(AddDeviceKeyChangeData),
+    /// Revoke the key of another device
+    RevokeDeviceKey(RevokeDeviceKeyChangeData),
 }
 
 impl fmt::Display for IdentityChange {
@@ -21,6 +27,8 @@ impl fmt::Display for IdentityChange {
         match self {
             IdentityChange::CreateKey(data) => write!(f, " CreateKey:{}", data),
             IdentityChange::RotateKey(data) => write!(f, " RotateKey:{}", data),
+            IdentityChange::AddDeviceKey(data) => write!(f, " AddDeviceKey:{}", data),
+            IdentityChange::RevokeDeviceKey(data) => write!(f, " RevokeDeviceKey:{}", data),
         }
     }
 }
@@ -34,6 +42,8 @@ impl IdentityChange {
         match self {
             IdentityChange::CreateKey(data) => data.key_attributes().label(),
             IdentityChange::RotateKey(data) => data.key_attributes().label(),
+            IdentityChange::AddDeviceKey(data) => data.key_attributes().label(),
+            IdentityChange::RevokeDeviceKey(data) => data.label(),
         }
     }
 
@@ -41,6 +51,8 @@ impl IdentityChange {
         Ok(match self {
             IdentityChange::CreateKey(data) => data.public_key(),
             IdentityChange::RotateKey(data) => data.public_key(),
+            IdentityChange::AddDeviceKey(data) => data.public_key(),
+            IdentityChange::RevokeDeviceKey(data) => data.public_key(),
         }
         .clone())
     }
@@ -49,6 +61,8 @@ impl IdentityChange {
         match self {
             IdentityChange::CreateKey(data) => data.prev_change_id(),
             IdentityChange::RotateKey(data) => data.prev_change_id(),
+            IdentityChange::AddDeviceKey(data) => data.prev_change_id(),
+            IdentityChange::RevokeDeviceKey(data) => data.prev_change_id(),
         }
     }
 }
@@ -1,5 +1,6 @@
 mod change_identifier;
 mod create_key;
+mod device_key;
 #[allow(clippy::module_inception)]
 mod identity_change;
 pub(crate) mod identity_change_constants;
@@ -9,6 +10,7 @@ mod signature;
 
 pub use change_identifier::*;
 pub use create_key::*;
+pub use device_key::*;
 pub use identity_change::*;
 pub use identity_change_constants::*;
 pub use key_attributes::*;
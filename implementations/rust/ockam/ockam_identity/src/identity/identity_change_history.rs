@@ -1,5 +1,5 @@
 //! Identity history
-use crate::identity::identity_change::IdentityChange::CreateKey;
+use crate::identity::identity_change::IdentityChange::{AddDeviceKey, CreateKey, RevokeDeviceKey};
 use crate::identity::identity_change::{
     ChangeIdentifier, IdentityChangeConstants, IdentitySignedChange,
 };
@@ -7,6 +7,7 @@ use crate::identity::IdentityError;
 use core::cmp::Ordering;
 use core::fmt;
 use minicbor::{Decode, Encode};
+use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::string::String;
 use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
@@ -159,6 +160,11 @@ impl IdentityChangeHistory {
         self.get_public_key(IdentityChangeConstants::ROOT_LABEL)
     }
 
+    /// Get the device keys which have been added and not revoked, by device label
+    pub fn get_device_public_keys(&self) -> BTreeMap<String, PublicKey> {
+        Self::get_device_public_keys_static(self.as_ref())
+    }
+
     /// Check consistency of changes that are being added
     pub fn check_entire_consistency(&self) -> Result<()> {
         if !Self::check_consistency(&[], &self.0) {
@@ -213,9 +219,34 @@ impl IdentityChangeHistory {
         label: &str,
     ) -> Result<PublicKey> {
         let change = Self::find_last_key_change(changes, label)?;
+        // A revoked device key can't be used anymore
+        if let RevokeDeviceKey(_) = change.change() {
+            return Err(IdentityError::InvalidInternalState.into());
+        }
         change.change().public_key()
     }
 
+    pub(crate) fn get_device_public_keys_static(
+        changes: &[IdentitySignedChange],
+    ) -> BTreeMap<String, PublicKey> {
+        let mut keys = BTreeMap::new();
+        for change in changes {
+            match change.change() {
+                AddDeviceKey(data) => {
+                    keys.insert(
+                        data.key_attributes().label().into(),
+                        data.public_key().clone(),
+                    );
+                }
+                RevokeDeviceKey(data) => {
+                    keys.remove(data.label());
+                }
+                _ => {}
+            }
+        }
+        keys
+    }
+
     /// Check consistency of changes that are been added
     pub(crate) fn check_consistency(
         existing_changes: &[IdentitySignedChange],
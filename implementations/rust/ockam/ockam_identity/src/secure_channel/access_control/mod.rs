@@ -1,5 +1,9 @@
 mod credential_access_control;
 mod identity_access_control;
+#[cfg(feature = "std")]
+mod rate_limit;
 
 pub use credential_access_control::*;
 pub use identity_access_control::*;
+#[cfg(feature = "std")]
+pub use rate_limit::*;
@@ -0,0 +1,47 @@
+use crate::secure_channel::local_info::IdentitySecureChannelLocalInfo;
+use ockam_core::compat::string::ToString;
+use ockam_core::compat::vec::Vec;
+use ockam_core::{RateLimitKey, RelayMessage};
+
+/// Rate limit messages per identity of their sender
+///
+/// The messages which didn't come through a secure channel are rate limited
+/// together, in a shared bucket.
+#[derive(Debug)]
+pub struct IdentityRateLimitKey;
+
+impl RateLimitKey for IdentityRateLimitKey {
+    fn key(&self, relay_msg: &RelayMessage) -> Option<Vec<u8>> {
+        IdentitySecureChannelLocalInfo::find_info(relay_msg.local_message())
+            .ok()
+            .map(|info| info.their_identity_id().to_string().into_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity::IdentityIdentifier;
+    use ockam_core::{route, LocalMessage, Result, TransportMessage};
+
+    #[test]
+    fn test_identity_key() -> Result<()> {
+        let message = |local_info| {
+            let transport_message = TransportMessage::v1(route![], route![], vec![]);
+            RelayMessage::new(
+                "source".into(),
+                "destination".into(),
+                LocalMessage::new(transport_message, local_info),
+            )
+        };
+        let identifier = IdentityIdentifier::from_key_id("alice");
+        let local_info = IdentitySecureChannelLocalInfo::mark(vec![], identifier.clone(), false)?;
+
+        assert_eq!(
+            IdentityRateLimitKey.key(&message(local_info)),
+            Some(identifier.to_string().into_bytes())
+        );
+        assert_eq!(IdentityRateLimitKey.key(&message(vec![])), None);
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use crate::secure_channel::decryptor::Decryptor;
 use crate::secure_channel::encryptor::Encryptor;
 use crate::secure_channel::{Addresses, Role};
-use crate::{Identity, IdentityIdentifier, SecureChannels, TrustPolicy};
+use crate::{Credential, Identity, IdentityIdentifier, SecureChannels, TrustPolicy};
 use alloc::vec::Vec;
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::sync::Arc;
@@ -19,6 +19,7 @@ pub(crate) struct KeyExchangeState {
 
     remote_backwards_compatibility_address: Option<Address>,
     trust_policy: Arc<dyn TrustPolicy>,
+    credentials: Vec<Credential>,
 }
 
 pub(crate) struct IdentityExchangeState {
@@ -32,12 +33,15 @@ pub(crate) struct IdentityExchangeState {
     pub(crate) auth_hash: [u8; 32],
     pub(crate) identity_sent: bool,
     pub(crate) trust_policy: Arc<dyn TrustPolicy>,
+    /// Credentials presented to the other side with our identity
+    pub(crate) credentials: Vec<Credential>,
     pub(crate) remote_backwards_compatibility_address: Option<Address>,
 }
 
 pub(crate) struct InitializedState {
     //for debug purposes only
     pub(crate) role: &'static str,
+    pub(crate) is_initiator: bool,
     pub(crate) addresses: Addresses,
     pub(crate) decryptor: Decryptor,
     pub(crate) their_identity_id: IdentityIdentifier,
@@ -57,6 +61,7 @@ impl KeyExchangeState {
             remote_route: self.remote_route,
             addresses: self.addresses,
             trust_policy: self.trust_policy,
+            credentials: self.credentials,
             remote_backwards_compatibility_address: self.remote_backwards_compatibility_address,
             encryptor: Some(encryptor),
             decryptor,
@@ -73,6 +78,7 @@ impl IdentityExchangeState {
     ) -> InitializedState {
         InitializedState {
             role: self.role.str(),
+            is_initiator: self.role.is_initiator(),
             addresses: self.addresses,
             decryptor: self.decryptor,
             their_identity_id,
@@ -98,6 +104,7 @@ impl State {
         key_exchanger: Box<dyn KeyExchanger>,
         remote_route: Route,
         trust_policy: Arc<dyn TrustPolicy>,
+        credentials: Vec<Credential>,
         remote_backwards_compatibility_address: Option<Address>,
         initial_responder_payload: Option<Vec<u8>>,
     ) -> Self {
@@ -109,6 +116,7 @@ impl State {
             remote_route,
             key_exchanger,
             trust_policy,
+            credentials,
             remote_backwards_compatibility_address,
             initial_responder_payload,
             initialization_run: true,
@@ -9,20 +9,22 @@ use crate::secure_channel::{
     Addresses, AuthenticationConfirmation, CreateResponderChannelMessage, Role,
 };
 use crate::{
-    to_symmetric_vault, to_xx_vault, DecryptionRequest, DecryptionResponse, Identity,
-    IdentityError, IdentityIdentifier, IdentitySecureChannelLocalInfo, SecureChannelRegistryEntry,
-    SecureChannelTrustInfo, SecureChannels, TrustPolicy,
+    to_symmetric_vault, to_xx_vault, Credential, DecryptionRequest, DecryptionResponse, Identity,
+    IdentityError, IdentityHistoryComparison, IdentityIdentifier, IdentitySecureChannelLocalInfo,
+    SecureChannelRegistryEntry, SecureChannelTrustInfo, SecureChannels, TrustPolicy,
 };
 use core::time::Duration;
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
+use ockam_core::flow_control::FlowControls;
 use ockam_core::vault::Signature;
 use ockam_core::Result;
 use ockam_core::{
     async_trait, route, Address, AllowAll, AllowOnwardAddress, AllowSourceAddress, Any, Decodable,
-    DenyAll, Encodable, LocalMessage, LocalOnwardOnly, LocalSourceOnly, Mailbox, Mailboxes,
-    NewKeyExchanger, OutgoingAccessControl, Route, Routed, TransportMessage, Worker,
+    DenyAll, Encodable, LocalInfo, LocalMessage, LocalOnwardOnly, LocalSourceOnly, Mailbox,
+    Mailboxes, NewKeyExchanger, OutgoingAccessControl, Route, Routed, TransportMessage,
+    TransportPeerInfo, Worker,
 };
 use ockam_key_exchange_xx::XXNewKeyExchanger;
 use ockam_node::{Context, MessageReceiveOptions, WorkerBuilder};
@@ -30,6 +32,8 @@ use tracing::{debug, info, warn};
 
 pub(crate) struct DecryptorWorker {
     state: Option<State>,
+    addresses: Addresses,
+    flow_controls: Vec<FlowControls>,
 }
 
 impl DecryptorWorker {
@@ -41,7 +45,9 @@ impl DecryptorWorker {
         remote_route: Route,
         addresses: Addresses,
         trust_policy: Arc<dyn TrustPolicy>,
+        credentials: Vec<Credential>,
         decryptor_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        flow_controls: Vec<FlowControls>,
         timeout: Duration,
     ) -> Result<Address> {
         let mut completion_callback_ctx = ctx
@@ -67,9 +73,12 @@ impl DecryptorWorker {
                 Box::new(key_exchanger),
                 remote_route,
                 trust_policy,
+                credentials,
                 None,
                 None,
             )),
+            addresses: addresses.clone(),
+            flow_controls,
         };
 
         WorkerBuilder::with_mailboxes(mailboxes, worker)
@@ -92,13 +101,16 @@ impl DecryptorWorker {
 }
 
 impl DecryptorWorker {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn create_responder(
         ctx: &Context,
         secure_channels: Arc<SecureChannels>,
         addresses: Addresses,
         identity: Identity,
         trust_policy: Arc<dyn TrustPolicy>,
+        credentials: Vec<Credential>,
         decryptor_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        flow_controls: Vec<FlowControls>,
         msg: Routed<CreateResponderChannelMessage>,
     ) -> Result<()> {
         // Route to the decryptor on the other side
@@ -127,9 +139,12 @@ impl DecryptorWorker {
                 Box::new(key_exchanger),
                 remote_route,
                 trust_policy,
+                credentials,
                 Some(remote_backwards_compatibility_address),
                 Some(body.payload().to_vec()),
             )),
+            addresses: addresses.clone(),
+            flow_controls,
         };
 
         WorkerBuilder::with_mailboxes(mailboxes, worker)
@@ -395,7 +410,7 @@ impl IdentityExchangeState {
         }
         let body = IdentityChannelMessage::decode(&body.payload)?;
 
-        let (identity, signature) = body.consume();
+        let (identity, signature, credentials) = body.consume();
         debug!(
             "Received Authentication request {}",
             &self.addresses.decryptor_remote
@@ -408,15 +423,10 @@ impl IdentityExchangeState {
             .await?;
         let their_identity_id = their_identity.identifier();
 
-        // Verify responder posses their Identity key
+        // Verify responder posses their Identity key, or the key of one of their devices
         let verified = identities
             .identities_keys()
-            .verify_signature(
-                &their_identity,
-                &Signature::new(signature),
-                &self.auth_hash,
-                None,
-            )
+            .verify_identity_signature(&their_identity, &Signature::new(signature), &self.auth_hash)
             .await?;
 
         if !verified {
@@ -434,8 +444,13 @@ impl IdentityExchangeState {
             their_identity_id
         );
 
-        // Check our TrustPolicy
-        let trust_info = SecureChannelTrustInfo::new(their_identity_id.clone());
+        // Check our TrustPolicy, with the credentials presented by the other side
+        let mut their_credentials = Vec::with_capacity(credentials.len());
+        for credential in credentials {
+            their_credentials.push(minicbor::decode::<Credential>(&credential)?);
+        }
+        let trust_info = SecureChannelTrustInfo::new(their_identity_id.clone())
+            .with_credentials(their_credentials);
         let trusted = self.trust_policy.check(&trust_info).await?;
         if !trusted {
             // TODO: Shutdown? Communicate error?
@@ -450,24 +465,59 @@ impl IdentityExchangeState {
     }
 
     async fn send_identity(&mut self, ctx: &mut Context, first_sender: bool) -> Result<()> {
-        // Prove we posses our Identity key
+        // Present the latest change history of our Identity, in case one of its keys
+        // was rotated after this channel, or its listener, was created
+        if let Some(latest) = self
+            .secure_channels
+            .identities()
+            .repository()
+            .get_identity(&self.identity.identifier())
+            .await?
+        {
+            if latest.compare(&self.identity) == IdentityHistoryComparison::Newer {
+                self.identity = latest;
+            }
+        }
+
+        // Prove we posses our Identity key, or the key of this device
         let signature = self
             .secure_channels
             .identities()
             .identities_keys()
-            .create_signature(&self.identity, &self.auth_hash, None)
+            .create_identity_signature(&self.identity, &self.auth_hash)
             .await?;
 
         let exported = self.identity.export()?;
-        let auth_msg = if first_sender {
-            IdentityChannelMessage::Request {
-                identity: exported,
-                signature: signature.as_ref().to_vec(),
+        let signature = signature.as_ref().to_vec();
+        let auth_msg = if self.credentials.is_empty() {
+            if first_sender {
+                IdentityChannelMessage::Request {
+                    identity: exported,
+                    signature,
+                }
+            } else {
+                IdentityChannelMessage::Response {
+                    identity: exported,
+                    signature,
+                }
             }
         } else {
-            IdentityChannelMessage::Response {
-                identity: exported,
-                signature: signature.as_ref().to_vec(),
+            let mut credentials = Vec::with_capacity(self.credentials.len());
+            for credential in &self.credentials {
+                credentials.push(minicbor::to_vec(credential)?);
+            }
+            if first_sender {
+                IdentityChannelMessage::RequestWithCredentials {
+                    identity: exported,
+                    signature,
+                    credentials,
+                }
+            } else {
+                IdentityChannelMessage::ResponseWithCredentials {
+                    identity: exported,
+                    signature,
+                    credentials,
+                }
             }
         };
 
@@ -545,6 +595,12 @@ impl InitializedState {
             self.role, &self.addresses.decryptor_remote
         );
 
+        // Keep the transport peer of the encrypted message, to propagate it
+        let transport_peer: Vec<LocalInfo> = TransportPeerInfo::find_info(msg.local_message())
+            .map(|peer| peer.to_local_info())
+            .into_iter()
+            .collect();
+
         // Decode raw payload binary
         let payload = Vec::<u8>::decode(&msg.into_transport_message().payload)?;
 
@@ -569,8 +625,11 @@ impl InitializedState {
 
         // Mark message LocalInfo with IdentitySecureChannelLocalInfo,
         // replacing any pre-existing entries
-        let local_info =
-            IdentitySecureChannelLocalInfo::mark(vec![], self.their_identity_id.clone())?;
+        let local_info = IdentitySecureChannelLocalInfo::mark(
+            transport_peer,
+            self.their_identity_id.clone(),
+            self.is_initiator,
+        )?;
 
         let msg = LocalMessage::new(transport_message, local_info);
 
@@ -623,6 +682,15 @@ impl Worker for DecryptorWorker {
         Ok(())
     }
 
+    async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+        for flow_controls in &self.flow_controls {
+            flow_controls.cleanup_address(&self.addresses.decryptor_remote);
+            flow_controls.cleanup_address(&self.addresses.decryptor_internal);
+        }
+
+        Ok(())
+    }
+
     async fn handle_message(
         &mut self,
         ctx: &mut Self::Context,
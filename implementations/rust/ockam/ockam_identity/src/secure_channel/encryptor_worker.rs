@@ -3,8 +3,9 @@ use crate::secure_channel::addresses::Addresses;
 use crate::secure_channel::api::{EncryptionRequest, EncryptionResponse};
 use crate::secure_channel::encryptor::Encryptor;
 use ockam_core::compat::boxed::Box;
-use ockam_core::{async_trait, Address, Decodable, Encodable, Route};
-use ockam_core::{Any, Result, Routed, TransportMessage, Worker};
+use ockam_core::compat::vec::Vec;
+use ockam_core::{async_trait, route, Address, Decodable, Encodable, LocalMessage, Route};
+use ockam_core::{Any, MessagePriority, Result, Routed, TransportMessage, Worker};
 use ockam_node::Context;
 use tracing::debug;
 
@@ -76,6 +77,7 @@ impl EncryptorWorker {
 
         let mut onward_route = msg.onward_route();
         let return_route = msg.return_route();
+        let priority = MessagePriority::find_info(msg.local_message());
 
         // Remove our address
         let _ = onward_route.step();
@@ -96,14 +98,43 @@ impl EncryptorWorker {
         let encrypted_payload = self.encryptor.encrypt(&msg.encode()?).await?;
 
         // Send the message to the decryptor on the other side
-        ctx.send_from_address(
+        match priority {
+            // Keep the priority of the plaintext message so that transports can schedule it
+            Some(priority) => {
+                self.forward_with_priority(ctx, encrypted_payload, priority)
+                    .await?
+            }
+            None => {
+                ctx.send_from_address(
+                    self.remote_route.clone(),
+                    encrypted_payload,
+                    self.addresses.encryptor.clone(),
+                )
+                .await?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl EncryptorWorker {
+    async fn forward_with_priority(
+        &self,
+        ctx: &Context,
+        encrypted_payload: Vec<u8>,
+        priority: MessagePriority,
+    ) -> Result<()> {
+        let msg = TransportMessage::v1(
             self.remote_route.clone(),
-            encrypted_payload,
+            route![self.addresses.encryptor.clone()],
+            encrypted_payload.encode()?,
+        );
+        ctx.forward_from_address(
+            LocalMessage::new(msg, vec![priority.to_local_info()]),
             self.addresses.encryptor.clone(),
         )
-        .await?;
-
-        Ok(())
+        .await
     }
 }
 
@@ -66,6 +66,14 @@ impl Worker for IdentityChannelListener {
     type Message = CreateResponderChannelMessage;
     type Context = Context;
 
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        for flow_controls in self.options.flow_controls() {
+            flow_controls.cleanup_address(&ctx.address());
+        }
+
+        Ok(())
+    }
+
     async fn handle_message(
         &mut self,
         ctx: &mut Self::Context,
@@ -98,7 +106,9 @@ impl Worker for IdentityChannelListener {
             addresses,
             self.identity.clone(),
             self.options.trust_policy.clone(),
+            self.options.credentials.clone(),
             access_control.decryptor_outgoing_access_control,
+            access_control.flow_controls,
             msg,
         )
         .await
@@ -10,6 +10,7 @@ pub const IDENTITY_SECURE_CHANNEL_IDENTIFIER: &str = "IDENTITY_SECURE_CHANNEL_ID
 #[derive(Serialize, Deserialize)]
 pub struct IdentitySecureChannelLocalInfo {
     their_identity_id: IdentityIdentifier,
+    is_initiator: bool,
 }
 
 impl IdentitySecureChannelLocalInfo {
@@ -57,6 +58,11 @@ impl IdentitySecureChannelLocalInfo {
     pub fn their_identity_id(&self) -> IdentityIdentifier {
         self.their_identity_id.clone()
     }
+
+    /// Return true if the secure channel was initiated by this side of the channel
+    pub fn is_initiator(&self) -> bool {
+        self.is_initiator
+    }
 }
 
 impl IdentitySecureChannelLocalInfo {
@@ -65,12 +71,19 @@ impl IdentitySecureChannelLocalInfo {
     pub fn mark(
         mut local_info: Vec<LocalInfo>,
         their_identity_id: IdentityIdentifier,
+        is_initiator: bool,
     ) -> Result<Vec<LocalInfo>> {
         // strip out any pre-existing IdentitySecureChannelLocalInfo
         local_info.retain(|x| x.type_identifier() != IDENTITY_SECURE_CHANNEL_IDENTIFIER);
 
         // mark the vector
-        local_info.push(Self { their_identity_id }.to_local_info()?);
+        local_info.push(
+            Self {
+                their_identity_id,
+                is_initiator,
+            }
+            .to_local_info()?,
+        );
 
         Ok(local_info)
     }
@@ -13,19 +13,41 @@ pub(crate) enum IdentityChannelMessage {
         identity: Vec<u8>,
         signature: Vec<u8>,
     },
+    // Only sent when credentials are presented, so that peers which don't
+    // support credentials in the identity exchange can still create channels
+    RequestWithCredentials {
+        identity: Vec<u8>,
+        signature: Vec<u8>,
+        credentials: Vec<Vec<u8>>,
+    },
+    ResponseWithCredentials {
+        identity: Vec<u8>,
+        signature: Vec<u8>,
+        credentials: Vec<Vec<u8>>,
+    },
 }
 
 impl IdentityChannelMessage {
-    pub fn consume(self) -> (Vec<u8>, Vec<u8>) {
+    pub fn consume(self) -> (Vec<u8>, Vec<u8>, Vec<Vec<u8>>) {
         match self {
             IdentityChannelMessage::Request {
                 identity,
                 signature,
-            } => (identity, signature),
+            } => (identity, signature, Vec::new()),
             IdentityChannelMessage::Response {
                 identity,
                 signature,
-            } => (identity, signature),
+            } => (identity, signature, Vec::new()),
+            IdentityChannelMessage::RequestWithCredentials {
+                identity,
+                signature,
+                credentials,
+            } => (identity, signature, credentials),
+            IdentityChannelMessage::ResponseWithCredentials {
+                identity,
+                signature,
+                credentials,
+            } => (identity, signature, credentials),
         }
     }
 }
@@ -1,6 +1,7 @@
 use crate::secure_channel::Addresses;
-use crate::{IdentityError, TrustEveryonePolicy, TrustPolicy};
+use crate::{Credential, IdentityError, TrustEveryonePolicy, TrustPolicy};
 use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
 use ockam_core::flow_control::{
     FlowControlId, FlowControlOutgoingAccessControl, FlowControlPolicy, FlowControls,
 };
@@ -11,10 +12,13 @@ pub struct SecureChannelOptions {
     pub(crate) consumer_flow_control: Option<FlowControls>,
     pub(crate) producer_flow_control: Option<(FlowControls, FlowControlId)>,
     pub(crate) trust_policy: Arc<dyn TrustPolicy>,
+    pub(crate) credentials: Vec<Credential>,
 }
 
 pub(crate) struct SecureChannelAccessControl {
     pub(crate) decryptor_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    // FlowControls where the decryptor is registered, to be cleaned up when it stops
+    pub(crate) flow_controls: Vec<FlowControls>,
 }
 
 impl SecureChannelOptions {
@@ -27,6 +31,7 @@ impl SecureChannelOptions {
             consumer_flow_control: None,
             producer_flow_control: None,
             trust_policy: Arc::new(TrustEveryonePolicy),
+            credentials: Vec::new(),
         }
     }
 
@@ -43,6 +48,7 @@ impl SecureChannelOptions {
             consumer_flow_control: None,
             producer_flow_control: Some((flow_controls.clone(), flow_control_id.clone())),
             trust_policy: Arc::new(TrustEveryonePolicy),
+            credentials: Vec::new(),
         }
     }
 
@@ -52,6 +58,17 @@ impl SecureChannelOptions {
         self
     }
 
+    /// Present a credential to the listener, so that it can be checked by its trust policy
+    ///
+    /// The credential is sent, encrypted, with our identity once the key exchange is
+    /// completed. The listener receives it last: if its trust policy rejects the
+    /// credential, our end of the channel is still created but the listener never
+    /// registers its end, so that the messages sent on the channel are not delivered.
+    pub fn with_credential(mut self, credential: Credential) -> Self {
+        self.credentials.push(credential);
+        self
+    }
+
     pub(crate) fn setup_flow_control(&self, addresses: &Addresses, next: &Address) -> Result<()> {
         match &self.consumer_flow_control {
             Some(flow_controls) => {
@@ -93,13 +110,23 @@ impl SecureChannelOptions {
 
                 SecureChannelAccessControl {
                     decryptor_outgoing_access_control: Arc::new(ac),
+                    flow_controls: self.flow_controls(),
                 }
             }
             None => SecureChannelAccessControl {
                 decryptor_outgoing_access_control: Arc::new(AllowAll),
+                flow_controls: self.flow_controls(),
             },
         }
     }
+
+    fn flow_controls(&self) -> Vec<FlowControls> {
+        self.consumer_flow_control
+            .iter()
+            .chain(self.producer_flow_control.iter().map(|(x, _)| x))
+            .cloned()
+            .collect()
+    }
 }
 
 pub(crate) struct CiphertextFlowControlInfo {
@@ -117,6 +144,7 @@ pub struct SecureChannelListenerOptions {
     pub(crate) consumer_flow_control: Option<CiphertextFlowControl>,
     pub(crate) channels_producer_flow_control: Option<(FlowControls, FlowControlId)>,
     pub(crate) trust_policy: Arc<dyn TrustPolicy>,
+    pub(crate) credentials: Vec<Credential>,
 }
 
 impl SecureChannelListenerOptions {
@@ -129,6 +157,7 @@ impl SecureChannelListenerOptions {
             consumer_flow_control: None,
             channels_producer_flow_control: None,
             trust_policy: Arc::new(TrustEveryonePolicy),
+            credentials: Vec::new(),
         }
     }
 
@@ -173,6 +202,7 @@ impl SecureChannelListenerOptions {
             consumer_flow_control: None,
             channels_producer_flow_control: Some((flow_controls.clone(), flow_control_id.clone())),
             trust_policy: Arc::new(TrustEveryonePolicy),
+            credentials: Vec::new(),
         }
     }
 
@@ -182,6 +212,16 @@ impl SecureChannelListenerOptions {
         self
     }
 
+    /// Present a credential to the initiators, so that it can be checked by their trust policy
+    ///
+    /// The credential is sent, encrypted, with our identity once the key exchange is
+    /// completed. An initiator whose trust policy rejects the credential does not
+    /// complete the channel creation.
+    pub fn with_credential(mut self, credential: Credential) -> Self {
+        self.credentials.push(credential);
+        self
+    }
+
     pub(crate) fn setup_flow_control(
         &self,
         addresses: &Addresses,
@@ -235,12 +275,23 @@ impl SecureChannelListenerOptions {
 
                 Ok(SecureChannelAccessControl {
                     decryptor_outgoing_access_control: Arc::new(ac),
+                    flow_controls: self.flow_controls(),
                 })
             }
             (None, None) => Ok(SecureChannelAccessControl {
                 decryptor_outgoing_access_control: Arc::new(AllowAll),
+                flow_controls: self.flow_controls(),
             }),
             _ => Err(IdentityError::FlowControlsInconsistency.into()),
         }
     }
+
+    pub(crate) fn flow_controls(&self) -> Vec<FlowControls> {
+        self.consumer_flow_control
+            .iter()
+            .map(|x| &x.flow_controls)
+            .chain(self.channels_producer_flow_control.iter().map(|(x, _)| x))
+            .cloned()
+            .collect()
+    }
 }
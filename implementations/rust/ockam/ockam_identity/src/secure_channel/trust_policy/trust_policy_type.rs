@@ -5,14 +5,19 @@ use ockam_core::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::credential::Credential;
 use crate::identity::IdentityIdentifier;
 use crate::secure_channel::trust_policy::{AllTrustPolicy, AnyTrustPolicy};
+use ockam_core::compat::vec::Vec;
 
 /// Authenticated data of the newly created SecureChannel to perform `TrustPolicy` check
 #[derive(Clone, Serialize, Deserialize)]
 pub struct SecureChannelTrustInfo {
     /// identity of the other end of the secure channel
     pub their_identity_id: IdentityIdentifier,
+    /// credentials presented by the other end with its identity, after the key exchange, not verified yet
+    #[serde(default)]
+    pub their_credentials: Vec<Credential>,
 }
 
 impl SecureChannelTrustInfo {
@@ -20,12 +25,27 @@ impl SecureChannelTrustInfo {
     pub fn their_identity_id(&self) -> &IdentityIdentifier {
         &self.their_identity_id
     }
+
+    /// Credentials presented by the other participant. They must be verified by the
+    /// `TrustPolicy` before their attributes can be trusted
+    pub fn their_credentials(&self) -> &[Credential] {
+        &self.their_credentials
+    }
 }
 
 impl SecureChannelTrustInfo {
     /// Constructor
     pub fn new(their_identity_id: IdentityIdentifier) -> Self {
-        Self { their_identity_id }
+        Self {
+            their_identity_id,
+            their_credentials: Vec::new(),
+        }
+    }
+
+    /// Set the credentials presented by the other participant
+    pub fn with_credentials(mut self, their_credentials: Vec<Credential>) -> Self {
+        self.their_credentials = their_credentials;
+        self
     }
 }
 
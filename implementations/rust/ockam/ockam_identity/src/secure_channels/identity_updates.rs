@@ -0,0 +1,152 @@
+use crate::identities::Identities;
+use crate::identity::{Identity, IdentityIdentifier};
+use crate::secure_channel::IdentitySecureChannelLocalInfo;
+use minicbor::{Decode, Decoder, Encode};
+use ockam_core::api::{self, Method, Request, Response, Status};
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::string::ToString;
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result, Route, Routed, Worker};
+use ockam_node::api::request;
+use ockam_node::{Context, MessageSendReceiveOptions};
+use tracing::{debug, info, trace};
+
+#[cfg(feature = "tag")]
+use crate::TypeTag;
+
+/// Change history of an identity, sent to the peers of its secure channels
+/// after one of its keys was rotated
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct IdentityUpdate {
+    #[cfg(feature = "tag")]
+    #[n(0)] tag: TypeTag<6285394>,
+    /// Exported change history
+    #[cbor(with = "minicbor::bytes")]
+    #[b(1)] change_history: Vec<u8>,
+}
+
+impl IdentityUpdate {
+    /// Create an update for an identity
+    pub fn new(identity: &Identity) -> Result<Self> {
+        Ok(Self {
+            #[cfg(feature = "tag")]
+            tag: TypeTag,
+            change_history: identity.export()?,
+        })
+    }
+
+    /// Return the exported change history
+    pub fn change_history(&self) -> &[u8] {
+        &self.change_history
+    }
+}
+
+/// Worker receiving the change histories of the identities at the other end of secure channels
+///
+/// An update is only accepted from the identity it is about, and is merged
+/// in the identities repository if it is newer than the known history.
+pub struct IdentityUpdatesWorker {
+    identities: Arc<Identities>,
+}
+
+impl IdentityUpdatesWorker {
+    /// Create a new identity updates worker
+    pub fn new(identities: Arc<Identities>) -> Self {
+        Self { identities }
+    }
+
+    async fn update(&self, from: &IdentityIdentifier, update: IdentityUpdate) -> Result<()> {
+        let identity = self
+            .identities
+            .identities_creation()
+            .import_identity(update.change_history())
+            .await?;
+        if &identity.identifier() != from {
+            return Err(Error::new(
+                Origin::Identity,
+                Kind::Invalid,
+                "an identity can only be updated by itself",
+            ));
+        }
+        if self.identities.update_identity(&identity).await? {
+            info!("Updated the change history of {}", from);
+        }
+        Ok(())
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for IdentityUpdatesWorker {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        let mut dec = Decoder::new(m.as_body());
+        let req: Request = dec.decode()?;
+        let from = match IdentitySecureChannelLocalInfo::find_info(m.local_message()) {
+            Ok(info) => info.their_identity_id(),
+            Err(_) => {
+                let res = api::forbidden(&req, "secure channel required").to_vec()?;
+                return c.send(m.return_route(), res).await;
+            }
+        };
+        trace! {
+            target: "ockam_identity::secure_channels::identity_updates",
+            from   = %from,
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            body   = %req.has_body(),
+            "request"
+        }
+        let res = match (req.method(), req.path()) {
+            (Some(Method::Post), "/") => {
+                let update: IdentityUpdate = dec.decode()?;
+                match self.update(&from, update).await {
+                    Ok(()) => Response::ok(req.id()).to_vec()?,
+                    Err(error) => api::bad_request(&req, &error.to_string()).to_vec()?,
+                }
+            }
+            _ => api::unknown_path(&req).to_vec()?,
+        };
+        c.send(m.return_route(), res).await
+    }
+}
+
+/// Send the change history of an identity over a route, which shall use a secure channel
+/// created by that identity
+pub async fn push_identity_update(
+    ctx: &Context,
+    route: Route,
+    identity: &Identity,
+    options: MessageSendReceiveOptions,
+) -> Result<()> {
+    debug!(
+        "Pushing the change history of {} to {}",
+        identity.identifier(),
+        route
+    );
+    let buf = request(
+        ctx,
+        "identity_update",
+        None,
+        route,
+        Request::post("/").body(IdentityUpdate::new(identity)?),
+        options,
+    )
+    .await?;
+
+    let res: Response = minicbor::decode(&buf)?;
+    match res.status() {
+        Some(Status::Ok) => Ok(()),
+        _ => Err(Error::new(
+            Origin::Identity,
+            Kind::Invalid,
+            "identity update failed",
+        )),
+    }
+}
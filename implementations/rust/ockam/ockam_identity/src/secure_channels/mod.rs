@@ -1,7 +1,9 @@
+mod identity_updates;
 /// Services for creating secure channels
 #[allow(clippy::module_inception)]
 pub mod secure_channels;
 mod secure_channels_builder;
 
+pub use identity_updates::*;
 pub use secure_channels::*;
 pub use secure_channels_builder::*;
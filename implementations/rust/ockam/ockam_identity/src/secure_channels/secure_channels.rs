@@ -5,12 +5,17 @@ use crate::secure_channel::{
     Addresses, DecryptorWorker, IdentityChannelListener, Role, SecureChannelListenerOptions,
     SecureChannelOptions, SecureChannelRegistry,
 };
+use crate::secure_channels::identity_updates::{push_identity_update, IdentityUpdatesWorker};
 use crate::SecureChannelsBuilder;
 use core::time::Duration;
+use ockam_core::compat::future::join_all;
 use ockam_core::compat::sync::Arc;
-use ockam_core::Result;
+use ockam_core::compat::vec::Vec;
+use ockam_core::flow_control::FlowControls;
+use ockam_core::{route, AllowAll, Mailboxes, Result};
 use ockam_core::{Address, Route};
-use ockam_node::Context;
+use ockam_node::{Context, MessageSendReceiveOptions, WorkerBuilder};
+use tracing::debug;
 
 /// Identity implementation
 #[derive(Clone)]
@@ -99,7 +104,9 @@ impl SecureChannels {
             route,
             addresses,
             options.trust_policy,
+            options.credentials,
             access_control.decryptor_outgoing_access_control,
+            access_control.flow_controls,
             Duration::from_secs(120),
         )
         .await
@@ -129,7 +136,9 @@ impl SecureChannels {
             route,
             addresses,
             options.trust_policy,
+            options.credentials,
             access_control.decryptor_outgoing_access_control,
+            access_control.flow_controls,
             timeout,
         )
         .await
@@ -160,3 +169,90 @@ impl SecureChannels {
         Ok(())
     }
 }
+
+impl SecureChannels {
+    /// Start a service receiving the updated change histories of the identities
+    /// at the other end of secure channels
+    pub async fn start_identity_updates_service(
+        &self,
+        ctx: &Context,
+        address: impl Into<Address>,
+    ) -> Result<()> {
+        WorkerBuilder::with_mailboxes(
+            Mailboxes::main(
+                address,
+                Arc::new(AllowAll), // We check for Identity secure channel inside the worker
+                Arc::new(AllowAll), // FIXME: @ac Allow to respond anywhere using return_route
+            ),
+            IdentityUpdatesWorker::new(self.identities()),
+        )
+        .start(ctx)
+        .await?;
+        Ok(())
+    }
+
+    /// Rotate the root key of an identity and store its new change history
+    ///
+    /// The new change history must then be pushed to the peers of the secure
+    /// channels of that identity with [`SecureChannels::propagate_identity_update`].
+    pub async fn rotate_root_key(&self, identity: &mut Identity) -> Result<()> {
+        self.identities
+            .identities_keys()
+            .rotate_root_key(identity)
+            .await?;
+        self.identities
+            .repository()
+            .update_known_identity(identity)
+            .await
+    }
+
+    /// Push the change history of an identity to the identity updates service of
+    /// the peers of all the secure channels of that identity
+    ///
+    /// The peers are updated concurrently. Peers which can't be updated, for example
+    /// because they don't run the service, are skipped. Return the number of peers
+    /// which accepted the update.
+    pub async fn propagate_identity_update(
+        &self,
+        ctx: &Context,
+        identity: &Identity,
+        service: &Address,
+        flow_controls: Option<&FlowControls>,
+    ) -> usize {
+        let channels: Vec<_> = self
+            .secure_channel_registry
+            .get_channel_list()
+            .into_iter()
+            .filter(|channel| channel.my_id() == identity.identifier())
+            .collect();
+        let updates = channels.iter().map(|channel| async move {
+            let route = route![
+                channel.encryptor_messaging_address().clone(),
+                service.clone()
+            ];
+            let options = match flow_controls {
+                Some(flow_controls) => {
+                    MessageSendReceiveOptions::new().with_flow_control(flow_controls)
+                }
+                None => MessageSendReceiveOptions::new(),
+            };
+            match push_identity_update(ctx, route, identity, options).await {
+                Ok(()) => true,
+                Err(e) => {
+                    debug!(
+                        "Failed to push the change history of {} to {}: {}",
+                        identity.identifier(),
+                        channel.their_id(),
+                        e
+                    );
+                    false
+                }
+            }
+        });
+        join_all(updates)
+            .await
+            .into_iter()
+            .filter(|updated| *updated)
+            .count()
+    }
+}
@@ -5,8 +5,8 @@ use ockam_core::{route, Address, AllowAll, Any, DenyAll, Mailboxes, Result, Rout
 use ockam_identity::secure_channels::secure_channels;
 use ockam_identity::{
     DecryptionResponse, EncryptionRequest, EncryptionResponse, IdentityAccessControlBuilder,
-    IdentitySecureChannelLocalInfo, SecureChannelListenerOptions, SecureChannelOptions,
-    TrustEveryonePolicy, TrustIdentifierPolicy,
+    IdentityHistoryComparison, IdentitySecureChannelLocalInfo, SecureChannelListenerOptions,
+    SecureChannelOptions, TrustEveryonePolicy, TrustIdentifierPolicy,
 };
 use ockam_node::{Context, WorkerBuilder};
 use tokio::time::sleep;
@@ -658,3 +658,193 @@ async fn access_control__no_secure_channel__should_not_pass_messages(
 
     ctx.stop().await
 }
+
+#[ockam_macros::test]
+async fn test_rotated_identity_is_pushed_to_peers(ctx: &mut Context) -> Result<()> {
+    let alice_secure_channels = secure_channels();
+    let bob_secure_channels = secure_channels();
+
+    let mut alice = alice_secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let alice_before_rotation = alice.clone();
+    let bob = bob_secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+
+    bob_secure_channels
+        .start_identity_updates_service(ctx, "identity_updates")
+        .await?;
+    bob_secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+    alice_secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    alice_secure_channels.rotate_root_key(&mut alice).await?;
+    let updated_peers = alice_secure_channels
+        .propagate_identity_update(ctx, &alice, &"identity_updates".into(), None)
+        .await;
+    assert_eq!(updated_peers, 1);
+
+    let known_by_bob = bob_secure_channels
+        .identities()
+        .repository()
+        .get_identity(&alice.identifier())
+        .await?
+        .unwrap();
+    assert_eq!(
+        known_by_bob.compare(&alice),
+        IdentityHistoryComparison::Equal
+    );
+
+    // A channel created with an outdated copy of the identity presents its latest history
+    alice_secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice_before_rotation,
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn test_channel_with_device_key(ctx: &mut Context) -> Result<()> {
+    let owner_secure_channels = secure_channels();
+    let device_secure_channels = secure_channels();
+    let bob_secure_channels = secure_channels();
+
+    let mut alice = owner_secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let bob = bob_secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+
+    // The device generates its own key, only its public key is given to the owner
+    let device_key = device_secure_channels
+        .identities()
+        .identities_keys()
+        .create_device_key()
+        .await?;
+    owner_secure_channels
+        .identities()
+        .identities_keys()
+        .add_device_key(&mut alice, "laptop".into(), device_key.clone())
+        .await?;
+    assert_eq!(
+        alice.get_device_public_keys().get("laptop"),
+        Some(&device_key)
+    );
+
+    let alice_on_device = device_secure_channels
+        .identities()
+        .identities_creation()
+        .import_identity(&alice.export()?)
+        .await?;
+
+    bob_secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+
+    let channel = device_secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice_on_device,
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    // The responder registers its end of the channel once it received the last handshake message
+    let responder_channels = || {
+        bob_secure_channels
+            .secure_channel_registry()
+            .get_channel_list()
+            .into_iter()
+            .filter(|c| !c.is_initiator())
+            .collect::<Vec<_>>()
+    };
+    let mut bob_channels = responder_channels();
+    for _ in 0..50 {
+        if !bob_channels.is_empty() {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+        bob_channels = responder_channels();
+    }
+    assert_eq!(bob_channels.len(), 1);
+    assert_eq!(bob_channels[0].their_id(), alice.identifier());
+    device_secure_channels
+        .stop_secure_channel(ctx, &channel)
+        .await?;
+
+    // Once bob knows that the device key was revoked
+    owner_secure_channels
+        .identities()
+        .identities_keys()
+        .revoke_device_key(&mut alice, "laptop")
+        .await?;
+    assert!(alice.get_device_public_keys().is_empty());
+    assert!(
+        bob_secure_channels
+            .identities()
+            .update_identity(&alice)
+            .await?
+    );
+
+    // he rejects the device presenting the history of the identity before the revocation.
+    // The initiator completes the handshake before the responder verified its identity,
+    // so the rejection is only visible on the responder side
+    let _ = device_secure_channels
+        .create_secure_channel_extended(
+            ctx,
+            &alice_on_device,
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+            Duration::from_millis(500),
+        )
+        .await;
+    sleep(Duration::from_millis(500)).await;
+    assert_eq!(responder_channels().len(), 1);
+
+    let known_by_bob = bob_secure_channels
+        .identities()
+        .repository()
+        .get_identity(&alice.identifier())
+        .await?
+        .unwrap();
+    assert_eq!(
+        known_by_bob.compare(&alice),
+        IdentityHistoryComparison::Equal
+    );
+
+    ctx.stop().await
+}
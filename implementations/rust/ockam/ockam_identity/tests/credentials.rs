@@ -1,13 +1,19 @@
 use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::FlowControls;
 use ockam_core::{async_trait, AllowAll, Any, DenyAll, Mailboxes};
 use ockam_core::{route, Result, Routed, Worker};
 
 use ockam_identity::secure_channels::secure_channels;
 use ockam_identity::{
-    AuthorityService, CredentialAccessControl, CredentialData, CredentialsMemoryRetriever,
-    SecureChannelListenerOptions, SecureChannelOptions, TrustContext, TrustIdentifierPolicy,
+    AttributeSchema, AttributeType, AttributesEntry, AttributesScope, AuthorityService, Credential,
+    CredentialAccessControl, CredentialData, CredentialSchema, Credentials, CredentialsIssuer,
+    CredentialsMemoryRetriever, CredentialsRefresher, CredentialsRetriever, Identity,
+    RemoteCredentialsRetriever, RemoteCredentialsRetrieverInfo, RevocationListData, RevokedSubject,
+    SchemaId, SecureChannelListenerOptions, SecureChannelOptions, Timestamp, TrustContext,
+    TrustIdentifierPolicy,
 };
 use ockam_node::{Context, MessageSendReceiveOptions, WorkerBuilder};
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicI8, Ordering};
 use std::time::Duration;
 
@@ -157,7 +163,7 @@ async fn full_flow_twoway(ctx: &mut Context) -> Result<()> {
         .present_credential_mutual(
             ctx,
             route![channel, "credential_exchange"],
-            &[trust_context.authority()?.identity()],
+            &trust_context,
             credential,
             MessageSendReceiveOptions::new(),
         )
@@ -180,6 +186,897 @@ async fn full_flow_twoway(ctx: &mut Context) -> Result<()> {
     ctx.stop().await
 }
 
+#[ockam_macros::test]
+async fn revoked_credential_is_rejected(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities = secure_channels.identities();
+    let identities_creation = identities.identities_creation();
+    let identities_repository = identities.repository();
+    let credentials = identities.credentials();
+
+    let authority = identities_creation.create_identity().await?;
+    let client = identities_creation.create_identity().await?;
+
+    let credential_data = CredentialData::builder(client.identifier(), authority.identifier())
+        .with_attribute("is_superuser", b"true")
+        .build()?;
+
+    let credential = credentials
+        .issue_credential(&authority, credential_data)
+        .await?;
+
+    credentials
+        .receive_presented_credential(
+            &client.identifier(),
+            &[authority.clone()],
+            credential.clone(),
+        )
+        .await?;
+    assert!(identities_repository
+        .get_attributes(&client.identifier())
+        .await?
+        .is_some());
+
+    let now = Timestamp::now().unwrap();
+    let revocation_list = credentials
+        .issue_revocation_list(
+            &authority,
+            RevocationListData::new(
+                authority.identifier(),
+                now,
+                vec![RevokedSubject::new(client.identifier(), now)],
+            ),
+        )
+        .await?;
+
+    // A revocation list whose signature doesn't cover the signature domain is rejected
+    let mut unprefixed = revocation_list.clone();
+    unprefixed.signature = identities
+        .identities_keys()
+        .create_signature(&authority, revocation_list.unverified_data(), None)
+        .await?
+        .as_ref()
+        .to_vec();
+    assert!(credentials
+        .receive_revocation_list(&[authority.clone()], unprefixed)
+        .await
+        .is_err());
+
+    // A revocation list signed by an unknown authority is rejected
+    let other = identities_creation.create_identity().await?;
+    assert!(credentials
+        .receive_revocation_list(&[other], revocation_list.clone())
+        .await
+        .is_err());
+
+    credentials
+        .receive_revocation_list(&[authority.clone()], revocation_list)
+        .await?;
+
+    // The attributes attested by the revoked credential are removed
+    assert!(identities_repository
+        .get_attributes(&client.identifier())
+        .await?
+        .is_none());
+
+    // and the revoked credential can't be presented again
+    assert!(credentials
+        .verify_credential(&client.identifier(), &[authority.clone()], credential)
+        .await
+        .is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn credentials_refresher(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities = secure_channels.identities();
+    let identities_creation = identities.identities_creation();
+    let credentials = identities.credentials();
+
+    let authority = identities_creation.create_identity().await?;
+    let client = identities_creation.create_identity().await?;
+
+    let credential_data = CredentialData::builder(client.identifier(), authority.identifier())
+        .with_attribute("is_superuser", b"true")
+        .valid_for(Duration::from_secs(3600))
+        .build()?;
+    let created = credential_data.created_at();
+    let credential = credentials
+        .issue_credential(&authority, credential_data)
+        .await?;
+
+    let authority_service = AuthorityService::new(
+        credentials.clone(),
+        authority.clone(),
+        Some(Arc::new(CredentialsMemoryRetriever::new(
+            credential.clone(),
+        ))),
+    );
+
+    let refresher = CredentialsRefresher::new(
+        authority_service.clone(),
+        identities.credentials_server(),
+        client.clone(),
+    );
+    assert!(refresher.clone().with_refresh_fraction(0.0).is_err());
+    assert!(refresher.clone().with_refresh_fraction(1.5).is_err());
+    let refresher = refresher.with_refresh_fraction(0.8)?;
+
+    assert_eq!(refresher.status().last_refresh, None);
+    assert_eq!(refresher.credential(ctx).await?, credential);
+
+    let status = refresher.status();
+    assert!(status.last_refresh.is_some());
+    assert_eq!(
+        status.expires.unwrap().elapsed(created),
+        Some(Duration::from_secs(3600))
+    );
+    assert_eq!(status.failures, 0);
+
+    // A renewal returning the same credential fails, the current credential is kept
+    let refresher = CredentialsRefresher::new(
+        authority_service,
+        identities.credentials_server(),
+        client.clone(),
+    )
+    .with_refresh_fraction(0.0001)?;
+    assert_eq!(refresher.credential(ctx).await?, credential);
+    assert_eq!(refresher.credential(ctx).await?, credential);
+    let status = refresher.status();
+    assert!(status.last_error.is_some());
+    assert_eq!(status.failures, 1);
+
+    // A refresher which can't retrieve a credential reports the failure
+    let refresher = CredentialsRefresher::new(
+        AuthorityService::new(credentials, authority, None),
+        identities.credentials_server(),
+        client,
+    );
+    assert!(refresher.credential(ctx).await.is_err());
+    let status = refresher.status();
+    assert!(status.last_error.is_some());
+    assert_eq!(status.failures, 1);
+
+    ctx.stop().await
+}
+
+/// Retriever issuing a new credential each time it is called
+struct IssuingRetriever {
+    credentials: Arc<dyn Credentials>,
+    authority: Identity,
+    valid_for: Duration,
+}
+
+#[async_trait]
+impl CredentialsRetriever for IssuingRetriever {
+    async fn retrieve(&self, _ctx: &Context, for_identity: &Identity) -> Result<Credential> {
+        let credential_data =
+            CredentialData::builder(for_identity.identifier(), self.authority.identifier())
+                .with_attribute("is_superuser", b"true")
+                .valid_for(self.valid_for)
+                .build()?;
+        self.credentials
+            .issue_credential(&self.authority, credential_data)
+            .await
+    }
+}
+
+#[ockam_macros::test(timeout = 15_000)]
+async fn credentials_refresher_renews_and_presents_credentials(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels();
+    let identities = secure_channels.identities();
+    let identities_creation = identities.identities_creation();
+    let identities_repository = identities.repository();
+    let credentials = identities.credentials();
+    let credentials_service = identities.credentials_server();
+
+    let authority = identities_creation.create_identity().await?;
+    let server = identities_creation.create_identity().await?;
+    let client = identities_creation.create_identity().await?;
+
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &server,
+            "listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+    let trust_context = TrustContext::new(
+        "test_trust_context_id".to_string(),
+        Some(AuthorityService::new(
+            credentials.clone(),
+            authority.clone(),
+            None,
+        )),
+    );
+    credentials_service
+        .start(
+            ctx,
+            trust_context,
+            server.clone(),
+            "credential_exchange".into(),
+            false,
+        )
+        .await?;
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &client,
+            route!["listener"],
+            SecureChannelOptions::new()
+                .with_trust_policy(TrustIdentifierPolicy::new(server.identifier().clone())),
+        )
+        .await?;
+
+    // Credentials are renewed after half of their 4 seconds lifetime
+    let refresher = CredentialsRefresher::new(
+        AuthorityService::new(
+            credentials.clone(),
+            authority.clone(),
+            Some(Arc::new(IssuingRetriever {
+                credentials,
+                authority,
+                valid_for: Duration::from_secs(4),
+            })),
+        ),
+        credentials_service.clone(),
+        client.clone(),
+    );
+    let credential = refresher.credential(ctx).await?;
+    let first_expires = refresher.status().expires.unwrap();
+
+    let presentation = route![channel, "credential_exchange"];
+    credentials_service
+        .present_credential(
+            ctx,
+            presentation.clone(),
+            credential.clone(),
+            MessageSendReceiveOptions::new(),
+        )
+        .await?;
+    let attributes = identities_repository
+        .get_attributes(&client.identifier())
+        .await?
+        .unwrap();
+    assert_eq!(attributes.expires(), Some(first_expires));
+
+    refresher.add_presentation(presentation);
+    refresher.start(ctx).await?;
+
+    // The credential is renewed in the background before it expires
+    // and the renewed credential is presented on the secure channel
+    let mut renewed = None;
+    for _ in 0..50 {
+        ctx.sleep(Duration::from_millis(100)).await;
+        let expires = identities_repository
+            .get_attributes(&client.identifier())
+            .await?
+            .and_then(|a| a.expires());
+        if matches!(expires, Some(expires) if expires > first_expires) {
+            renewed = expires;
+            break;
+        }
+    }
+    refresher.stop();
+
+    let renewed = renewed.expect("the renewed credential was not presented");
+    assert!(Timestamp::now().unwrap() < first_expires);
+    assert_eq!(refresher.status().expires, Some(renewed));
+    assert_ne!(refresher.credential(ctx).await?, credential);
+    assert_eq!(refresher.status().failures, 0);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn expired_attributes_are_deleted(ctx: &mut Context) -> Result<()> {
+    let identities = secure_channels().identities();
+    let identities_creation = identities.identities_creation();
+    let repository = identities.repository();
+
+    let expired = identities_creation.create_identity().await?;
+    let valid = identities_creation.create_identity().await?;
+    let now = Timestamp::now().unwrap();
+    let attributes: BTreeMap<String, Vec<u8>> =
+        [("role".to_string(), b"contractor".to_vec())].into();
+
+    repository
+        .put_attributes(
+            &expired.identifier(),
+            AttributesEntry::new(attributes.clone(), now, Some(now), None),
+        )
+        .await?;
+    repository
+        .put_attributes(
+            &valid.identifier(),
+            AttributesEntry::new(attributes, now, Some(now.add_seconds(3600)), None),
+        )
+        .await?;
+
+    assert_eq!(
+        repository.delete_expired().await?,
+        vec![expired.identifier()]
+    );
+    assert!(repository
+        .get_attributes(&expired.identifier())
+        .await?
+        .is_none());
+    assert!(repository
+        .get_attributes(&valid.identifier())
+        .await?
+        .is_some());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn scoped_authorities(ctx: &mut Context) -> Result<()> {
+    let identities = secure_channels().identities();
+    let identities_creation = identities.identities_creation();
+    let credentials = identities.credentials();
+    let repository = identities.repository();
+
+    let main = identities_creation.create_identity().await?;
+    let hr = identities_creation.create_identity().await?;
+    let devices = identities_creation.create_identity().await?;
+    let unknown = identities_creation.create_identity().await?;
+    let member = identities_creation.create_identity().await?;
+
+    let trust_context = TrustContext::new(
+        "trust_context_id".to_string(),
+        Some(AuthorityService::new(
+            credentials.clone(),
+            main.clone(),
+            None,
+        )),
+    )
+    .with_scoped_authority(hr.clone(), AttributesScope::new(vec!["role".to_string()]))
+    .with_scoped_authority(
+        devices.clone(),
+        AttributesScope::new(vec!["device.*".to_string()]),
+    );
+
+    // Only the attributes that an authority is trusted for are kept
+    let credential = credentials
+        .issue_credential(
+            &hr,
+            CredentialData::builder(member.identifier(), hr.identifier())
+                .with_attribute("role", b"admin")
+                .with_attribute("device.id", b"laptop")
+                .build()?,
+        )
+        .await?;
+    credentials
+        .receive_trust_context_credential(&member.identifier(), &trust_context, credential)
+        .await?;
+
+    // The attributes attested to by another authority are kept
+    let credential = credentials
+        .issue_credential(
+            &devices,
+            CredentialData::builder(member.identifier(), devices.identifier())
+                .with_attribute("device.id", b"phone")
+                .with_attribute("role", b"root")
+                .build()?,
+        )
+        .await?;
+    credentials
+        .receive_trust_context_credential(&member.identifier(), &trust_context, credential)
+        .await?;
+
+    let attributes = repository
+        .get_attributes(&member.identifier())
+        .await?
+        .unwrap();
+    assert_eq!(attributes.attrs().get("role"), Some(&b"admin".to_vec()));
+    assert_eq!(
+        attributes.attrs().get("device.id"),
+        Some(&b"phone".to_vec())
+    );
+
+    // The attributes of the scoped authorities are kept when the main authority
+    // issues a credential after them, and its values win for the same attribute
+    let credential = credentials
+        .issue_credential(
+            &main,
+            CredentialData::builder(member.identifier(), main.identifier())
+                .with_attribute("team", b"blue")
+                .with_attribute("role", b"user")
+                .build()?,
+        )
+        .await?;
+    credentials
+        .receive_trust_context_credential(&member.identifier(), &trust_context, credential)
+        .await?;
+
+    let attributes = repository
+        .get_attributes(&member.identifier())
+        .await?
+        .unwrap();
+    assert_eq!(attributes.attrs().get("team"), Some(&b"blue".to_vec()));
+    assert_eq!(attributes.attrs().get("role"), Some(&b"user".to_vec()));
+    assert_eq!(
+        attributes.attrs().get("device.id"),
+        Some(&b"phone".to_vec())
+    );
+
+    // A new credential replaces the attributes previously attested by its issuer only
+    let credential = credentials
+        .issue_credential(
+            &hr,
+            CredentialData::builder(member.identifier(), hr.identifier())
+                .with_attribute("role", b"guest")
+                .build()?,
+        )
+        .await?;
+    credentials
+        .receive_trust_context_credential(&member.identifier(), &trust_context, credential)
+        .await?;
+    let credential = credentials
+        .issue_credential(
+            &main,
+            CredentialData::builder(member.identifier(), main.identifier())
+                .with_attribute("team", b"red")
+                .build()?,
+        )
+        .await?;
+    credentials
+        .receive_trust_context_credential(&member.identifier(), &trust_context, credential)
+        .await?;
+
+    let attributes = repository
+        .get_attributes(&member.identifier())
+        .await?
+        .unwrap();
+    assert_eq!(attributes.attrs().get("team"), Some(&b"red".to_vec()));
+    assert_eq!(attributes.attrs().get("role"), Some(&b"guest".to_vec()));
+    assert_eq!(
+        attributes.attrs().get("device.id"),
+        Some(&b"phone".to_vec())
+    );
+
+    // A scoped authority can only attest to the ID of the trust context
+    let credential = credentials
+        .issue_credential(
+            &devices,
+            CredentialData::builder(member.identifier(), devices.identifier())
+                .with_attribute("device.id", b"phone")
+                .with_attribute("trust_context_id", b"other_trust_context_id")
+                .with_attribute("project_id", b"trust_context_id")
+                .build()?,
+        )
+        .await?;
+    credentials
+        .receive_trust_context_credential(&member.identifier(), &trust_context, credential)
+        .await?;
+
+    let attributes = repository
+        .get_attributes(&member.identifier())
+        .await?
+        .unwrap();
+    assert_eq!(attributes.attrs().get("trust_context_id"), None);
+    assert_eq!(
+        attributes.attrs().get("project_id"),
+        Some(&b"trust_context_id".to_vec())
+    );
+
+    // Credentials issued by other authorities are rejected
+    let credential = credentials
+        .issue_credential(
+            &unknown,
+            CredentialData::builder(member.identifier(), unknown.identifier())
+                .with_attribute("role", b"root")
+                .build()?,
+        )
+        .await?;
+    assert!(credentials
+        .receive_trust_context_credential(&member.identifier(), &trust_context, credential)
+        .await
+        .is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn scoped_authorities_attributes_expire_and_are_revoked_separately(
+    ctx: &mut Context,
+) -> Result<()> {
+    let identities = secure_channels().identities();
+    let identities_creation = identities.identities_creation();
+    let credentials = identities.credentials();
+    let repository = identities.repository();
+
+    let main = identities_creation.create_identity().await?;
+    let hr = identities_creation.create_identity().await?;
+    let member = identities_creation.create_identity().await?;
+
+    let trust_context = TrustContext::new(
+        "trust_context_id".to_string(),
+        Some(AuthorityService::new(
+            credentials.clone(),
+            main.clone(),
+            None,
+        )),
+    )
+    .with_scoped_authority(hr.clone(), AttributesScope::new(vec!["role".to_string()]));
+
+    let credential = credentials
+        .issue_credential(
+            &main,
+            CredentialData::builder(member.identifier(), main.identifier())
+                .with_attribute("team", b"blue")
+                .build()?,
+        )
+        .await?;
+    credentials
+        .receive_trust_context_credential(&member.identifier(), &trust_context, credential)
+        .await?;
+    let credential = credentials
+        .issue_credential(
+            &hr,
+            CredentialData::builder(member.identifier(), hr.identifier())
+                .with_attribute("role", b"admin")
+                .valid_for(Duration::from_secs(1))
+                .build()?,
+        )
+        .await?;
+    credentials
+        .receive_trust_context_credential(&member.identifier(), &trust_context, credential)
+        .await?;
+
+    // The expiration of a short-lived scoped credential only removes its own attributes
+    ctx.sleep(Duration::from_secs(2)).await;
+    let attributes = repository
+        .get_attributes(&member.identifier())
+        .await?
+        .unwrap();
+    assert_eq!(attributes.attrs().get("team"), Some(&b"blue".to_vec()));
+    assert_eq!(attributes.attrs().get("role"), None);
+    assert_eq!(attributes.attested_by(), Some(main.identifier()));
+
+    // A revocation by the main authority removes its attributes
+    // even when a scoped authority issued the latest credential
+    let credential = credentials
+        .issue_credential(
+            &hr,
+            CredentialData::builder(member.identifier(), hr.identifier())
+                .with_attribute("role", b"user")
+                .build()?,
+        )
+        .await?;
+    credentials
+        .receive_trust_context_credential(&member.identifier(), &trust_context, credential)
+        .await?;
+    assert_eq!(
+        repository
+            .get_attributes(&member.identifier())
+            .await?
+            .unwrap()
+            .attested_by(),
+        Some(hr.identifier())
+    );
+
+    let now = Timestamp::now().unwrap();
+    let revocation_list = credentials
+        .issue_revocation_list(
+            &main,
+            RevocationListData::new(
+                main.identifier(),
+                now,
+                vec![RevokedSubject::new(member.identifier(), now)],
+            ),
+        )
+        .await?;
+    credentials
+        .receive_revocation_list(&[main.clone()], revocation_list)
+        .await?;
+
+    let attributes = repository
+        .get_attributes(&member.identifier())
+        .await?
+        .unwrap();
+    assert_eq!(attributes.attrs().get("team"), None);
+    assert_eq!(attributes.attrs().get("role"), Some(&b"user".to_vec()));
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn selective_disclosure(ctx: &mut Context) -> Result<()> {
+    let identities = secure_channels().identities();
+    let identities_creation = identities.identities_creation();
+    let credentials = identities.credentials();
+
+    let authority = identities_creation.create_identity().await?;
+    let member = identities_creation.create_identity().await?;
+    let authorities = vec![authority.clone()];
+
+    let data = CredentialData::builder(member.identifier(), authority.identifier())
+        .with_attribute("role", b"admin")
+        .with_attribute("team", b"blue")
+        .build()?;
+    let credential = credentials
+        .issue_selective_credential(&authority, data.clone())
+        .await?;
+    let other_credential = credentials
+        .issue_selective_credential(&authority, data)
+        .await?;
+
+    // Only the disclosed attributes are returned by the verification
+    let data = credentials
+        .verify_selective_credential(
+            &member.identifier(),
+            &authorities,
+            credential.disclose(&["role"]),
+        )
+        .await?;
+    assert_eq!(data.attributes().len(), 1);
+    assert_eq!(data.attributes().get("role"), Some(b"admin".as_slice()));
+
+    let data = credentials
+        .verify_selective_credential(&member.identifier(), &authorities, credential.clone())
+        .await?;
+    assert_eq!(data.attributes().len(), 2);
+
+    // A disclosure which was not signed by the issuer is rejected
+    let mut forged = credential.disclose(&["role"]);
+    forged
+        .disclosures
+        .extend(other_credential.disclose(&["team"]).disclosures);
+    assert!(credentials
+        .verify_selective_credential(&member.identifier(), &authorities, forged)
+        .await
+        .is_err());
+
+    // A disclosure can't be presented twice
+    let mut duplicated = credential.disclose(&["role"]);
+    duplicated
+        .disclosures
+        .extend(credential.disclose(&["role"]).disclosures);
+    assert!(credentials
+        .verify_selective_credential(&member.identifier(), &authorities, duplicated)
+        .await
+        .is_err());
+
+    // The credential is bound to its subject
+    assert!(credentials
+        .verify_selective_credential(&authority.identifier(), &authorities, credential)
+        .await
+        .is_err());
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn selective_credential_presentation(ctx: &mut Context) -> Result<()> {
+    // The authority issues selective credentials for the attributes of its members
+    let authority_channels = secure_channels();
+    let authority = authority_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let member_channels = secure_channels();
+    let member = member_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    authority_channels
+        .identities()
+        .repository()
+        .put_attributes(
+            &member.identifier(),
+            AttributesEntry::new(
+                BTreeMap::from([
+                    ("role".to_string(), b"admin".to_vec()),
+                    ("team".to_string(), b"blue".to_vec()),
+                ]),
+                Timestamp::now().unwrap(),
+                None,
+                None,
+            ),
+        )
+        .await?;
+    authority_channels
+        .create_secure_channel_listener(
+            ctx,
+            &authority,
+            "authority_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+    let issuer = CredentialsIssuer::new(
+        authority_channels.identities(),
+        authority.clone(),
+        "trust_context_id".into(),
+    )
+    .await?;
+    ctx.start_worker("issuer", issuer, AllowAll, AllowAll)
+        .await?;
+
+    // The member retrieves a selective credential over a secure channel
+    let retriever = RemoteCredentialsRetriever::new(
+        member_channels.clone(),
+        RemoteCredentialsRetrieverInfo::new(
+            authority.clone(),
+            route!["authority_listener"],
+            "issuer".into(),
+        ),
+        FlowControls::default(),
+    );
+    let authority_service = AuthorityService::new(
+        member_channels.identities().credentials(),
+        authority.clone(),
+        Some(Arc::new(retriever)),
+    );
+    let credential = authority_service.selective_credential(ctx, &member).await?;
+    assert_eq!(credential.attribute_names().len(), 4);
+
+    // and only discloses its role to a server
+    let server_channels = secure_channels();
+    let server = server_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    server_channels
+        .create_secure_channel_listener(
+            ctx,
+            &server,
+            "server_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+    server_channels
+        .identities()
+        .credentials_server()
+        .start(
+            ctx,
+            TrustContext::new(
+                "trust_context_id".to_string(),
+                Some(AuthorityService::new(
+                    server_channels.identities().credentials(),
+                    authority,
+                    None,
+                )),
+            ),
+            server,
+            "credentials".into(),
+            false,
+        )
+        .await?;
+
+    let channel = member_channels
+        .create_secure_channel(
+            ctx,
+            &member,
+            route!["server_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    member_channels
+        .identities()
+        .credentials_server()
+        .present_selective_credential(
+            ctx,
+            route![channel, "credentials"],
+            credential.disclose(&["role"]),
+            MessageSendReceiveOptions::new(),
+        )
+        .await?;
+
+    let attributes = server_channels
+        .identities()
+        .repository()
+        .get_attributes(&member.identifier())
+        .await?
+        .unwrap();
+    assert_eq!(attributes.attrs().get("role"), Some(&b"admin".to_vec()));
+    assert_eq!(attributes.attrs().get("team"), None);
+
+    ctx.stop().await
+}
+
+#[ockam_macros::test]
+async fn credential_schemas(ctx: &mut Context) -> Result<()> {
+    let issuer_identities = secure_channels().identities();
+    let verifier_identities = secure_channels().identities();
+    let issuer_credentials = issuer_identities.credentials();
+
+    let authority = issuer_identities
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let member = issuer_identities
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let authorities = vec![authority.clone()];
+
+    let schema = CredentialSchema::new(SchemaId(42), "member").with_attribute(
+        AttributeSchema::new("role", AttributeType::Utf8)
+            .required()
+            .with_allowed_values(vec!["admin".to_string(), "member".to_string()]),
+    );
+    let schema_id = schema.id();
+    let data = |role: &[u8]| {
+        CredentialData::builder(member.identifier(), authority.identifier())
+            .with_schema(schema_id)
+            .with_attribute("role", role)
+            .build()
+    };
+
+    // The builder validates the attributes against a schema definition
+    assert!(
+        CredentialData::builder(member.identifier(), authority.identifier())
+            .with_schema_definition(&schema)
+            .with_attribute("role", b"root")
+            .build()
+            .is_err()
+    );
+
+    // A credential violating a schema unknown to the issuer is issued
+    let invalid = issuer_credentials
+        .issue_credential(&authority, data(b"root")?)
+        .await?;
+    let valid = issuer_credentials
+        .issue_credential(&authority, data(b"admin")?)
+        .await?;
+
+    // but rejected by a verifier which declared the schema
+    verifier_identities
+        .credential_schemas()
+        .register(schema.clone());
+    let verifier_credentials = verifier_identities.credentials();
+    assert!(verifier_credentials
+        .verify_credential(&member.identifier(), &authorities, invalid)
+        .await
+        .is_err());
+    verifier_credentials
+        .verify_credential(&member.identifier(), &authorities, valid)
+        .await?;
+
+    // Once a schema is declared, credentials without a known schema are rejected
+    let unknown = issuer_credentials
+        .issue_credential(
+            &authority,
+            CredentialData::builder(member.identifier(), authority.identifier())
+                .with_schema(SchemaId(7))
+                .with_attribute("role", b"admin")
+                .build()?,
+        )
+        .await?;
+    assert!(verifier_credentials
+        .verify_credential(&member.identifier(), &authorities, unknown)
+        .await
+        .is_err());
+    let missing = issuer_credentials
+        .issue_credential(
+            &authority,
+            CredentialData::builder(member.identifier(), authority.identifier())
+                .with_attribute("role", b"admin")
+                .build()?,
+        )
+        .await?;
+    assert!(verifier_credentials
+        .verify_credential(&member.identifier(), &authorities, missing)
+        .await
+        .is_err());
+
+    // An issuer which declared the schema refuses to sign invalid credentials
+    issuer_identities.credential_schemas().register(schema);
+    assert!(issuer_credentials
+        .issue_credential(&authority, data(b"root")?)
+        .await
+        .is_err());
+
+    ctx.stop().await
+}
+
 #[ockam_macros::test]
 async fn access_control(ctx: &mut Context) -> Result<()> {
     let secure_channels = secure_channels();
@@ -142,6 +142,30 @@ async fn test_update_contact_and_reprove(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn only_known_identities_are_updated(ctx: &mut Context) -> Result<()> {
+    let identities = identities();
+    let identities_creation = identities.identities_creation();
+    let identities_keys = identities.identities_keys();
+
+    let mut alice = identities_creation.create_identity().await?;
+    let before_rotation = alice.clone();
+    identities_keys.rotate_root_key(&mut alice).await?;
+
+    // The history of an unknown identity is rejected
+    assert!(identities.update_identity(&alice).await.is_err());
+
+    // A newer history of a known identity is stored, an older one is ignored
+    identities
+        .repository()
+        .update_known_identity(&before_rotation)
+        .await?;
+    assert!(identities.update_identity(&alice).await?);
+    assert!(!identities.update_identity(&before_rotation).await?);
+
+    ctx.stop().await
+}
+
 #[ockam_macros::test]
 async fn add_key(ctx: &mut Context) -> Result<()> {
     let identities = identities();
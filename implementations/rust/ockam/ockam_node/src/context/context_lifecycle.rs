@@ -1,7 +1,7 @@
 use crate::async_drop::AsyncDrop;
 use crate::channel_types::{message_channel, small_channel, SmallReceiver, SmallSender};
 use crate::tokio::{self, runtime::Handle};
-use crate::{debugger, Context};
+use crate::{debugger, Context, InterceptorChain};
 use crate::{error::*, relay::CtrlSignal, router::SenderPair, NodeMessage};
 use core::time::Duration;
 use ockam_core::compat::{boxed::Box, sync::Arc, vec::Vec};
@@ -50,11 +50,15 @@ impl Context {
     ///
     /// `async_drop_sender` must be provided when creating a detached
     /// Context type (i.e. not backed by a worker relay).
+    ///
+    /// `interceptors` are the node-wide [`MessageInterceptor`](crate::MessageInterceptor)s,
+    /// which are inherited by every context created from this one.
     pub(crate) fn new(
         rt: Handle,
         sender: SmallSender<NodeMessage>,
         mailboxes: Mailboxes,
         async_drop_sender: Option<AsyncDropSender>,
+        interceptors: InterceptorChain,
     ) -> (Self, SenderPair, SmallReceiver<CtrlSignal>) {
         let (mailbox_tx, receiver) = message_channel();
         let (ctrl_tx, ctrl_rx) = small_channel();
@@ -66,6 +70,7 @@ impl Context {
                 receiver,
                 async_drop_sender,
                 mailbox_count: Arc::new(0.into()),
+                interceptors,
             },
             SenderPair {
                 msgs: mailbox_tx,
@@ -147,6 +152,7 @@ impl Context {
             self.sender.clone(),
             mailboxes,
             Some(drop_sender),
+            self.interceptors.clone(),
         );
 
         // Create a "detached relay" and register it with the router
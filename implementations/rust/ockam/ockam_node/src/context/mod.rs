@@ -14,7 +14,7 @@ pub use worker_lifecycle::*;
 
 use crate::channel_types::{SmallReceiver, SmallSender};
 use crate::tokio::runtime::Handle;
-use crate::{error::*, NodeMessage};
+use crate::{error::*, InterceptorChain, NodeMessage};
 use core::sync::atomic::AtomicUsize;
 use ockam_core::compat::{string::String, sync::Arc, vec::Vec};
 use ockam_core::{Address, Mailboxes, RelayMessage, Result};
@@ -33,6 +33,7 @@ pub struct Context {
     receiver: SmallReceiver<RelayMessage>,
     async_drop_sender: Option<AsyncDropSender>,
     mailbox_count: Arc<AtomicUsize>,
+    interceptors: InterceptorChain,
 }
 
 /// This trait can be used to integrate transports into a node
@@ -63,6 +64,11 @@ impl Context {
         self.mailbox_count.clone()
     }
 
+    /// Return the node-wide interceptors inherited by this context
+    pub(crate) fn interceptors(&self) -> &InterceptorChain {
+        &self.interceptors
+    }
+
     /// Return a reference to sender
     pub(crate) fn sender(&self) -> &SmallSender<NodeMessage> {
         &self.sender
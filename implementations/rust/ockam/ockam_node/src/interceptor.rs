@@ -0,0 +1,137 @@
+use crate::Context;
+use ockam_core::compat::{boxed::Box, sync::Arc, vec::Vec};
+use ockam_core::{async_trait, RelayMessage, Result};
+
+/// Defines the interface for a message interceptor (middleware)
+///
+/// Interceptors are invoked by the worker relay for every message
+/// that passed incoming access control, right before and right after
+/// [`Worker::handle_message`](ockam_core::Worker::handle_message).
+/// They can be registered for a single worker via
+/// [`WorkerBuilder::with_interceptor`](crate::WorkerBuilder::with_interceptor)
+/// or for every worker of a node via
+/// [`NodeBuilder::with_interceptor`](crate::NodeBuilder::with_interceptor).
+///
+/// Node-wide interceptors run before worker interceptors, in
+/// registration order. `after_handle_message` hooks run in reverse
+/// order.
+///
+/// # Examples
+///
+/// ```
+/// # use ockam_core::{async_trait, RelayMessage, Result};
+/// # use ockam_node::{Context, MessageInterceptor};
+/// #[derive(Debug)]
+/// pub struct LoggingInterceptor;
+///
+/// #[async_trait]
+/// impl MessageInterceptor for LoggingInterceptor {
+///     async fn before_handle_message(
+///         &self,
+///         _ctx: &Context,
+///         relay_msg: RelayMessage,
+///     ) -> Result<Option<RelayMessage>> {
+///         println!("{} -> {}", relay_msg.source(), relay_msg.destination());
+///         Ok(Some(relay_msg))
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait MessageInterceptor: Send + Sync + 'static {
+    /// Called before the message is passed to the worker
+    ///
+    /// Return the (possibly modified) message to continue processing
+    /// or `None` to drop it. Returning an error aborts the handling of
+    /// this message, the same way an error from `handle_message` does.
+    async fn before_handle_message(
+        &self,
+        _ctx: &Context,
+        relay_msg: RelayMessage,
+    ) -> Result<Option<RelayMessage>> {
+        Ok(Some(relay_msg))
+    }
+
+    /// Called after the worker handled the message, with the result
+    /// of `handle_message`
+    async fn after_handle_message(
+        &self,
+        _ctx: &Context,
+        _relay_msg: &RelayMessage,
+        _result: &Result<()>,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// An ordered list of [`MessageInterceptor`]s
+#[derive(Clone, Default)]
+pub struct InterceptorChain(Arc<Vec<Arc<dyn MessageInterceptor>>>);
+
+impl InterceptorChain {
+    /// Create an empty chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return a new chain with the given interceptor appended
+    pub fn with(&self, interceptor: Arc<dyn MessageInterceptor>) -> Self {
+        let mut interceptors = self.0.as_ref().clone();
+        interceptors.push(interceptor);
+        Self(Arc::new(interceptors))
+    }
+
+    /// Return a new chain running `self` followed by `other`
+    pub fn chain(&self, other: &InterceptorChain) -> Self {
+        if other.is_empty() {
+            return self.clone();
+        }
+        if self.is_empty() {
+            return other.clone();
+        }
+        let mut interceptors = self.0.as_ref().clone();
+        interceptors.extend(other.0.iter().cloned());
+        Self(Arc::new(interceptors))
+    }
+
+    /// Return `true` if there are no interceptors in this chain
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Number of interceptors in this chain
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Run all `before_handle_message` hooks in order
+    ///
+    /// Stops at the first interceptor that drops the message.
+    pub(crate) async fn before_handle_message(
+        &self,
+        ctx: &Context,
+        mut relay_msg: RelayMessage,
+    ) -> Result<Option<RelayMessage>> {
+        for interceptor in self.0.iter() {
+            relay_msg = match interceptor.before_handle_message(ctx, relay_msg).await? {
+                Some(relay_msg) => relay_msg,
+                None => return Ok(None),
+            };
+        }
+        Ok(Some(relay_msg))
+    }
+
+    /// Run all `after_handle_message` hooks in reverse order
+    pub(crate) async fn after_handle_message(
+        &self,
+        ctx: &Context,
+        relay_msg: &RelayMessage,
+        result: &Result<()>,
+    ) -> Result<()> {
+        for interceptor in self.0.iter().rev() {
+            interceptor
+                .after_handle_message(ctx, relay_msg, result)
+                .await?;
+        }
+        Ok(())
+    }
+}
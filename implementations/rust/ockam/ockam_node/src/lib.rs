@@ -41,11 +41,15 @@ pub mod api;
 /// Debugger
 pub mod debugger;
 
+#[cfg(feature = "std")]
+pub mod simulation;
+
 mod async_drop;
 mod context;
 mod delayed;
 mod error;
 mod executor;
+mod interceptor;
 mod messages;
 mod node;
 mod parser;
@@ -59,6 +63,7 @@ pub use context::*;
 pub use delayed::*;
 pub use error::*;
 pub use executor::*;
+pub use interceptor::*;
 pub use messages::*;
 pub use processor_builder::ProcessorBuilder;
 pub use rpc_client::*;
@@ -1,4 +1,4 @@
-use crate::{debugger, Context, Executor};
+use crate::{debugger, Context, Executor, InterceptorChain, MessageInterceptor};
 use ockam_core::compat::sync::Arc;
 use ockam_core::{Address, AllowAll, Mailbox, Mailboxes};
 
@@ -17,6 +17,7 @@ impl ockam_core::Worker for NullWorker {
 /// builder API to customise the underlying node that is created.
 pub struct NodeBuilder {
     logging: bool,
+    interceptors: InterceptorChain,
 }
 
 impl Default for NodeBuilder {
@@ -28,12 +29,24 @@ impl Default for NodeBuilder {
 impl NodeBuilder {
     /// Create a node
     pub fn new() -> Self {
-        Self { logging: true }
+        Self {
+            logging: true,
+            interceptors: InterceptorChain::new(),
+        }
     }
 
     /// Disable logging on this node
     pub fn no_logging(self) -> Self {
-        Self { logging: false }
+        Self {
+            logging: false,
+            ..self
+        }
+    }
+
+    /// Add a [`MessageInterceptor`] applied to every worker started on this node
+    pub fn with_interceptor(mut self, interceptor: impl MessageInterceptor) -> Self {
+        self.interceptors = self.interceptors.with(Arc::new(interceptor));
+        self
     }
 
     /// Consume this builder and yield a new Ockam Node
@@ -58,6 +71,7 @@ impl NodeBuilder {
                 vec![],
             ),
             None,
+            self.interceptors,
         );
 
         debugger::log_inherit_context("NODE", &ctx, &ctx);
@@ -81,6 +81,7 @@ where
             context.sender().clone(),
             mailboxes,
             None,
+            context.interceptors().clone(),
         );
 
         debugger::log_inherit_context("PROCESSOR", context, &ctx);
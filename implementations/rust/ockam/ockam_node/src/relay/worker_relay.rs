@@ -1,7 +1,7 @@
 use crate::channel_types::SmallReceiver;
 use crate::relay::CtrlSignal;
 use crate::tokio::runtime::Handle;
-use crate::{parser, Context};
+use crate::{parser, Context, InterceptorChain};
 use core::marker::PhantomData;
 use ockam_core::{Message, RelayMessage, Result, Routed, Worker};
 
@@ -17,6 +17,7 @@ where
 {
     worker: W,
     ctx: Context,
+    interceptors: InterceptorChain,
     _phantom: PhantomData<M>,
 }
 
@@ -25,10 +26,11 @@ where
     W: Worker<Context = Context, Message = M>,
     M: Message + Send + 'static,
 {
-    pub fn new(worker: W, ctx: Context) -> Self {
+    pub fn new(worker: W, ctx: Context, interceptors: InterceptorChain) -> Self {
         Self {
             worker,
             ctx,
+            interceptors,
             _phantom: PhantomData,
         }
     }
@@ -64,6 +66,35 @@ where
         Ok(routed)
     }
 
+    /// Handle a single message, running it through the interceptor chain
+    async fn intercept_message(&mut self, relay_msg: RelayMessage) -> Result<()> {
+        let relay_msg = match self
+            .interceptors
+            .before_handle_message(&self.ctx, relay_msg)
+            .await?
+        {
+            Some(msg) => msg,
+            None => {
+                trace!(
+                    "Message for worker {} was dropped by an interceptor",
+                    self.ctx.address()
+                );
+                return Ok(());
+            }
+        };
+
+        let result = match Self::wrap_direct_message(relay_msg.clone()) {
+            Ok(routed) => self.worker.handle_message(&mut self.ctx, routed).await,
+            Err(e) => Err(e),
+        };
+
+        self.interceptors
+            .after_handle_message(&self.ctx, &relay_msg, &result)
+            .await?;
+
+        result
+    }
+
     /// Receive and handle a single message
     ///
     /// Report errors as they occur, and signal whether the loop should
@@ -77,9 +108,13 @@ where
             }
         };
 
-        // Call the worker handle function - pass errors up
-        let routed = Self::wrap_direct_message(relay_msg)?;
-        self.worker.handle_message(&mut self.ctx, routed).await?;
+        if self.interceptors.is_empty() {
+            // Call the worker handle function - pass errors up
+            let routed = Self::wrap_direct_message(relay_msg)?;
+            self.worker.handle_message(&mut self.ctx, routed).await?;
+        } else {
+            self.intercept_message(relay_msg).await?;
+        }
 
         // Signal to the outer loop that we would like to run again
         Ok(true)
@@ -172,8 +207,14 @@ where
     }
 
     /// Build and spawn a new worker relay, returning a send handle to it
-    pub(crate) fn init(rt: &Handle, worker: W, ctx: Context, ctrl_rx: SmallReceiver<CtrlSignal>) {
-        let relay = WorkerRelay::<W, M>::new(worker, ctx);
+    pub(crate) fn init(
+        rt: &Handle,
+        worker: W,
+        ctx: Context,
+        interceptors: InterceptorChain,
+        ctrl_rx: SmallReceiver<CtrlSignal>,
+    ) {
+        let relay = WorkerRelay::<W, M>::new(worker, ctx, interceptors);
         rt.spawn(relay.run(ctrl_rx));
     }
 }
@@ -85,6 +85,31 @@ impl RpcClient {
         }
     }
 
+    /// Encode request header and body (if any) and send the package to the server.
+    ///
+    /// Return `None` if the server responds that the resource was not found.
+    pub async fn request_optional<T, R>(&self, req: &RequestBuilder<'_, T>) -> Result<Option<R>>
+    where
+        T: Encode<()>,
+        R: for<'a> Decode<'a, ()>,
+    {
+        let mut buf = Vec::new();
+        req.encode(&mut buf)?;
+
+        let vec = self
+            .ctx
+            .send_and_receive_extended::<Vec<u8>>(self.route.clone(), buf, self.options())
+            .await?
+            .body();
+        let mut d = Decoder::new(&vec);
+        let resp: Response = d.decode()?;
+        match resp.status() {
+            Some(Status::Ok) => Ok(Some(d.decode()?)),
+            Some(Status::NotFound) => Ok(None),
+            _ => Err(error("request", &resp, &mut d)),
+        }
+    }
+
     /// Encode request header and body (if any) and send the package to the server.
     pub async fn request_no_resp_body<T>(&self, req: &RequestBuilder<'_, T>) -> Result<()>
     where
@@ -0,0 +1,97 @@
+use crate::tokio::sync::watch;
+use core::fmt::Debug;
+use core::future::Future;
+use core::pin::Pin;
+use core::time::Duration;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::sync::Arc;
+
+/// A source of timers
+///
+/// Components which wait for some time, like session health checks,
+/// can take a `Clock` so that tests can drive them with a
+/// [`VirtualClock`] instead of real time.
+pub trait Clock: Debug + Send + Sync + 'static {
+    /// Wait for the given duration, starting from the time of this call
+    fn sleep(&self, duration: Duration) -> Sleep;
+}
+
+/// A future completing when a [`Clock`] reaches the wake up time of a sleep
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// The real time clock, using `tokio::time`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(crate::tokio::time::sleep(duration))
+    }
+}
+
+/// A virtual clock shared by all the links of a [`SimNetwork`](super::SimNetwork)
+///
+/// The clock starts at zero and only moves forward, either when a
+/// message is delivered or when it is explicitly advanced. Sleeping on
+/// this clock waits until it has been moved past the wake up time.
+#[derive(Clone, Debug)]
+pub struct VirtualClock {
+    time: Arc<watch::Sender<Duration>>,
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        let (time, _) = watch::channel(Duration::ZERO);
+        Self {
+            time: Arc::new(time),
+        }
+    }
+}
+
+impl VirtualClock {
+    /// Create a new clock starting at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Time elapsed since the start of the simulation
+    pub fn now(&self) -> Duration {
+        *self.time.borrow()
+    }
+
+    /// Move the clock forward by the given duration
+    pub fn advance(&self, duration: Duration) {
+        self.time.send_modify(|time| *time += duration);
+    }
+
+    /// Move the clock forward to the given time, if it is in the future
+    pub fn advance_to(&self, time: Duration) {
+        self.time.send_if_modified(|now| {
+            if time > *now {
+                *now = time;
+                true
+            } else {
+                false
+            }
+        });
+    }
+
+    /// Wait until the clock reaches the given time
+    pub fn sleep_until(&self, deadline: Duration) -> Sleep {
+        let mut time = self.time.subscribe();
+        Box::pin(async move {
+            while *time.borrow_and_update() < deadline {
+                // Fails only once every copy of this clock is dropped
+                if time.changed().await.is_err() {
+                    return;
+                }
+            }
+        })
+    }
+}
+
+impl Clock for VirtualClock {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        self.sleep_until(self.now() + duration)
+    }
+}
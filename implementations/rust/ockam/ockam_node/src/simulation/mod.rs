@@ -0,0 +1,28 @@
+//! Deterministic in-process network simulation
+//!
+//! A [`SimNetwork`] connects simulated hosts living in the same Ockam
+//! node through links which behave like transport connections (the
+//! returned address can be used as the first hop of a route, exactly
+//! like the address returned by `TcpTransport::connect`).
+//!
+//! Every message crossing a link is scheduled on a [`VirtualClock`]
+//! according to the [`LinkConditions`] of that link (latency, jitter,
+//! packet loss) and delivered in virtual time order. Random decisions
+//! are taken by a seeded [`SimRng`], so that a test using the same
+//! seed and the same sequence of messages observes the same losses and
+//! the same delivery order. Links between partitioned hosts drop every
+//! message until the partition is healed, including the messages which
+//! were already in flight when the partition was created.
+//!
+//! The virtual clock drives the simulated network and the components
+//! taking a [`Clock`], like the session health checks of `ockam_api`.
+//! Timers started directly with `tokio::time` still use real time.
+
+mod clock;
+mod network;
+mod rng;
+mod workers;
+
+pub use clock::*;
+pub use network::*;
+pub use rng::*;
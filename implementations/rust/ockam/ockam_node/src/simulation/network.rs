@@ -0,0 +1,355 @@
+use super::workers::{SimEndpoint, SimScheduler};
+use super::{SimRng, VirtualClock};
+use crate::Context;
+use core::cmp::Ordering;
+use core::time::Duration;
+use ockam_core::compat::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::{Address, AllowAll, AllowOnwardAddress, LocalMessage, Result};
+
+/// Conditions applied to the messages sent over a simulated link
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LinkConditions {
+    latency: Duration,
+    jitter: Duration,
+    loss: f64,
+}
+
+impl LinkConditions {
+    /// A perfect link: no latency, no jitter and no packet loss
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the fixed latency of the link
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Set the maximum random latency added on top of the fixed latency
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set the probability, between `0.0` and `1.0`, of a message being lost
+    pub fn with_loss(mut self, loss: f64) -> Self {
+        self.loss = loss.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Fixed latency
+    pub fn latency(&self) -> Duration {
+        self.latency
+    }
+
+    /// Maximum jitter
+    pub fn jitter(&self) -> Duration {
+        self.jitter
+    }
+
+    /// Probability of a message being lost
+    pub fn loss(&self) -> f64 {
+        self.loss
+    }
+}
+
+/// Counters of the messages handled by a [`SimNetwork`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SimStats {
+    /// Messages delivered to the other side of a link
+    pub delivered: u64,
+    /// Messages lost because of the link conditions
+    pub lost: u64,
+    /// Messages dropped because of a partition
+    pub partitioned: u64,
+}
+
+/// A message waiting to be delivered
+struct InFlight {
+    deliver_at: Duration,
+    seq: u64,
+    from: String,
+    to: String,
+    local_msg: LocalMessage,
+}
+
+impl PartialEq for InFlight {
+    fn eq(&self, other: &Self) -> bool {
+        self.deliver_at == other.deliver_at && self.seq == other.seq
+    }
+}
+
+impl Eq for InFlight {}
+
+impl PartialOrd for InFlight {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for InFlight {
+    // Reversed so that the BinaryHeap pops the earliest message first
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.deliver_at, other.seq).cmp(&(self.deliver_at, self.seq))
+    }
+}
+
+struct NetworkState {
+    rng: SimRng,
+    default_conditions: LinkConditions,
+    conditions: BTreeMap<(String, String), LinkConditions>,
+    partitions: BTreeSet<(String, String)>,
+    in_flight: BinaryHeap<InFlight>,
+    next_seq: u64,
+    stats: SimStats,
+}
+
+impl NetworkState {
+    fn partition_key(a: &str, b: &str) -> (String, String) {
+        if a <= b {
+            (a.to_string(), b.to_string())
+        } else {
+            (b.to_string(), a.to_string())
+        }
+    }
+
+    fn is_partitioned(&self, a: &str, b: &str) -> bool {
+        self.partitions.contains(&Self::partition_key(a, b))
+    }
+}
+
+/// An in-process simulated network with a virtual clock
+///
+/// See the [module documentation](crate::simulation) for an overview.
+///
+/// ```
+/// # use core::time::Duration;
+/// # use ockam_core::Result;
+/// # use ockam_node::Context;
+/// # use ockam_node::simulation::{LinkConditions, SimNetwork};
+/// # async fn example(ctx: &Context) -> Result<()> {
+/// let network = SimNetwork::create(ctx, 42).await?;
+/// network.set_link_conditions(
+///     "client",
+///     "server",
+///     LinkConditions::new().with_latency(Duration::from_millis(20)),
+/// );
+/// let server = network.connect(ctx, "client", "server").await?;
+/// // `server` can now be used like a TCP connection address
+/// network.partition("client", "server");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SimNetwork {
+    state: Arc<Mutex<NetworkState>>,
+    clock: VirtualClock,
+    scheduler_address: Address,
+}
+
+impl SimNetwork {
+    /// Create a new network using the given seed and start its scheduler
+    pub async fn create(ctx: &Context, seed: u64) -> Result<Self> {
+        let network = Self::new(seed);
+
+        ctx.start_worker(
+            network.scheduler_address.clone(),
+            SimScheduler::new(network.clone()),
+            AllowAll,
+            AllowAll,
+        )
+        .await?;
+
+        Ok(network)
+    }
+
+    fn new(seed: u64) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(NetworkState {
+                rng: SimRng::new(seed),
+                default_conditions: LinkConditions::default(),
+                conditions: Default::default(),
+                partitions: Default::default(),
+                in_flight: Default::default(),
+                next_seq: 0,
+                stats: SimStats::default(),
+            })),
+            clock: VirtualClock::new(),
+            scheduler_address: Address::random_tagged("SimNetwork.scheduler"),
+        }
+    }
+
+    /// The virtual clock of this network
+    pub fn clock(&self) -> &VirtualClock {
+        &self.clock
+    }
+
+    /// Counters of delivered and dropped messages
+    pub fn stats(&self) -> SimStats {
+        self.state.lock().unwrap().stats
+    }
+
+    /// Set the conditions used by links which have no specific conditions
+    pub fn set_default_conditions(&self, conditions: LinkConditions) {
+        self.state.lock().unwrap().default_conditions = conditions;
+    }
+
+    /// Set the conditions for the messages sent from `from` to `to`
+    pub fn set_link_conditions(&self, from: &str, to: &str, conditions: LinkConditions) {
+        self.state
+            .lock()
+            .unwrap()
+            .conditions
+            .insert((from.to_string(), to.to_string()), conditions);
+    }
+
+    /// Drop all messages exchanged between hosts `a` and `b`
+    pub fn partition(&self, a: &str, b: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .partitions
+            .insert(NetworkState::partition_key(a, b));
+    }
+
+    /// Remove a partition created with [`SimNetwork::partition`]
+    pub fn heal(&self, a: &str, b: &str) {
+        self.state
+            .lock()
+            .unwrap()
+            .partitions
+            .remove(&NetworkState::partition_key(a, b));
+    }
+
+    /// Remove all partitions
+    pub fn heal_all(&self) {
+        self.state.lock().unwrap().partitions.clear();
+    }
+
+    /// Create a link from host `from` to host `to`
+    ///
+    /// Return the address of the `from` end of the link. Messages sent
+    /// to this address are delivered to the rest of their onward route
+    /// with the address of the `to` end prepended to their return
+    /// route, so that replies travel back over the same link.
+    pub async fn connect(&self, ctx: &Context, from: &str, to: &str) -> Result<Address> {
+        let initiator = Address::random_tagged("SimEndpoint.initiator");
+        let responder = Address::random_tagged("SimEndpoint.responder");
+
+        for (address, peer, local_host, remote_host) in [
+            (&initiator, &responder, from, to),
+            (&responder, &initiator, to, from),
+        ] {
+            ctx.start_worker(
+                address.clone(),
+                SimEndpoint::new(self.clone(), peer.clone(), local_host, remote_host),
+                AllowAll,
+                AllowOnwardAddress(self.scheduler_address.clone()),
+            )
+            .await?;
+        }
+
+        Ok(initiator)
+    }
+
+    /// Stop both ends of a link created with [`SimNetwork::connect`]
+    pub async fn disconnect(&self, ctx: &Context, address: &Address) -> Result<()> {
+        ctx.stop_worker(address.clone()).await
+    }
+
+    pub(super) fn scheduler_address(&self) -> &Address {
+        &self.scheduler_address
+    }
+
+    /// Schedule a message sent from `from` to `to`
+    ///
+    /// Return `false` if the message was dropped.
+    pub(super) fn enqueue(&self, from: &str, to: &str, local_msg: LocalMessage) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        if state.is_partitioned(from, to) {
+            state.stats.partitioned += 1;
+            return false;
+        }
+
+        let conditions = state
+            .conditions
+            .get(&(from.to_string(), to.to_string()))
+            .unwrap_or(&state.default_conditions)
+            .clone();
+
+        if conditions.loss > 0.0 && state.rng.next_f64() < conditions.loss {
+            state.stats.lost += 1;
+            return false;
+        }
+
+        let mut latency = conditions.latency;
+        if !conditions.jitter.is_zero() {
+            latency += conditions.jitter.mul_f64(state.rng.next_f64());
+        }
+
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.in_flight.push(InFlight {
+            deliver_at: self.clock.now() + latency,
+            seq,
+            from: from.to_string(),
+            to: to.to_string(),
+            local_msg,
+        });
+
+        true
+    }
+
+    /// Take the earliest scheduled message and move the clock to its delivery time
+    ///
+    /// Return `None` if there is no message in flight, or if the hosts of the
+    /// message were partitioned while it was in flight.
+    pub(super) fn dequeue(&self) -> Option<LocalMessage> {
+        let mut state = self.state.lock().unwrap();
+        let in_flight = state.in_flight.pop()?;
+        self.clock.advance_to(in_flight.deliver_at);
+        if state.is_partitioned(&in_flight.from, &in_flight.to) {
+            state.stats.partitioned += 1;
+            return None;
+        }
+        state.stats.delivered += 1;
+        Some(in_flight.local_msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::{route, TransportMessage};
+
+    fn message() -> LocalMessage {
+        LocalMessage::new(
+            TransportMessage::v1(route!["echoer"], route![], vec![]),
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_partition_drops_messages_in_flight() {
+        let network = SimNetwork::new(1);
+        network
+            .set_default_conditions(LinkConditions::new().with_latency(Duration::from_millis(10)));
+        assert!(network.enqueue("client", "server", message()));
+        assert!(network.enqueue("server", "client", message()));
+        assert!(network.enqueue("client", "other", message()));
+
+        network.partition("server", "client");
+        assert!(network.dequeue().is_none());
+        assert!(network.dequeue().is_none());
+        assert!(network.dequeue().is_some());
+
+        let stats = network.stats();
+        assert_eq!(stats.partitioned, 2);
+        assert_eq!(stats.delivered, 1);
+        assert_eq!(network.clock().now(), Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,101 @@
+use super::SimNetwork;
+use crate::Context;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::{async_trait, Address, Any, LocalMessage, Result, Routed, Worker};
+
+/// One end of a simulated link
+///
+/// Messages sent to this worker are scheduled on the network and
+/// delivered by the [`SimScheduler`] on the other end of the link.
+pub(super) struct SimEndpoint {
+    network: SimNetwork,
+    peer: Address,
+    local_host: String,
+    remote_host: String,
+}
+
+impl SimEndpoint {
+    pub(super) fn new(
+        network: SimNetwork,
+        peer: Address,
+        local_host: &str,
+        remote_host: &str,
+    ) -> Self {
+        Self {
+            network,
+            peer,
+            local_host: local_host.to_string(),
+            remote_host: remote_host.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl Worker for SimEndpoint {
+    type Context = Context;
+    type Message = Any;
+
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        // A link is always stopped as a whole, the peer may already be stopped
+        let _ = ctx.stop_worker(self.peer.clone()).await;
+        Ok(())
+    }
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        let mut msg = msg.into_transport_message();
+
+        // Remove our own address from the route and make the other end
+        // of the link the first hop of the return route
+        msg.onward_route.step()?;
+        msg.return_route.modify().prepend(self.peer.clone());
+
+        if msg.onward_route.is_empty() {
+            warn!("Dropping simulated message without onward route");
+            return Ok(());
+        }
+
+        let local_msg = LocalMessage::new(msg, vec![]);
+        if self
+            .network
+            .enqueue(&self.local_host, &self.remote_host, local_msg)
+        {
+            ctx.send(self.network.scheduler_address().clone(), ())
+                .await?;
+        } else {
+            trace!(
+                "Simulated message from {} to {} was dropped",
+                self.local_host,
+                self.remote_host
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Delivers scheduled messages in virtual time order
+///
+/// Every scheduled message is announced by one `()` message, each of
+/// them delivers the earliest message in flight.
+pub(super) struct SimScheduler {
+    network: SimNetwork,
+}
+
+impl SimScheduler {
+    pub(super) fn new(network: SimNetwork) -> Self {
+        Self { network }
+    }
+}
+
+#[async_trait]
+impl Worker for SimScheduler {
+    type Context = Context;
+    type Message = ();
+
+    async fn handle_message(&mut self, ctx: &mut Context, _msg: Routed<()>) -> Result<()> {
+        if let Some(local_msg) = self.network.dequeue() {
+            ctx.forward(local_msg).await?;
+        }
+        Ok(())
+    }
+}
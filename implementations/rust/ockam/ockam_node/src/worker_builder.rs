@@ -1,6 +1,6 @@
 use crate::debugger;
 use crate::error::{NodeError, NodeReason};
-use crate::{relay::WorkerRelay, Context, NodeMessage};
+use crate::{relay::WorkerRelay, Context, InterceptorChain, MessageInterceptor, NodeMessage};
 use ockam_core::compat::sync::Arc;
 use ockam_core::{
     errcode::{Kind, Origin},
@@ -22,6 +22,7 @@ use ockam_core::{
 pub struct WorkerBuilder<W> {
     mailboxes: Mailboxes,
     worker: W,
+    interceptors: InterceptorChain,
 }
 
 impl<W> WorkerBuilder<W> {
@@ -49,13 +50,26 @@ where
             outgoing_access_control,
         );
 
-        Self { mailboxes, worker }
+        Self::with_mailboxes(mailboxes, worker)
     }
 
     /// Create a worker which uses the access control from the given
     /// [`Mailboxes`]
     pub fn with_mailboxes(mailboxes: Mailboxes, worker: W) -> Self {
-        Self { mailboxes, worker }
+        Self {
+            mailboxes,
+            worker,
+            interceptors: InterceptorChain::new(),
+        }
+    }
+
+    /// Add a [`MessageInterceptor`] for this worker
+    ///
+    /// Worker interceptors run after the node-wide interceptors, in
+    /// the order they were added.
+    pub fn with_interceptor(mut self, interceptor: impl MessageInterceptor) -> Self {
+        self.interceptors = self.interceptors.with(Arc::new(interceptor));
+        self
     }
 
     /// Consume this builder and start a new Ockam [`Worker`] from the given context
@@ -71,6 +85,7 @@ where
         let mailboxes = self.mailboxes;
         let addresses = mailboxes.addresses();
         let main_address = mailboxes.main_address().clone();
+        let interceptors = context.interceptors().chain(&self.interceptors);
 
         // Pass it to the context
         let (ctx, sender, ctrl_rx) = Context::new(
@@ -78,12 +93,13 @@ where
             context.sender().clone(),
             mailboxes,
             None,
+            context.interceptors().clone(),
         );
 
         debugger::log_inherit_context("WORKER", context, &ctx);
 
         // Then initialise the worker message relay
-        WorkerRelay::<W, M>::init(context.runtime(), self.worker, ctx, ctrl_rx);
+        WorkerRelay::<W, M>::init(context.runtime(), self.worker, ctx, interceptors, ctrl_rx);
 
         // Send start request to router
         let (msg, mut rx) =
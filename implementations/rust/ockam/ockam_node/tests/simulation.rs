@@ -0,0 +1,126 @@
+use core::time::Duration;
+use ockam_core::{route, Address, AllowAll, Result, Routed, Worker};
+use ockam_node::simulation::{Clock, LinkConditions, SimNetwork, SimRng, VirtualClock};
+use ockam_node::tokio;
+use ockam_node::{Context, MessageReceiveOptions};
+
+struct Echoer;
+
+#[ockam_core::worker]
+impl Worker for Echoer {
+    type Context = Context;
+    type Message = String;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
+
+#[allow(non_snake_case)]
+#[test]
+fn sim_rng__same_seed__should_produce_same_sequence() {
+    let mut a = SimRng::new(7);
+    let mut b = SimRng::new(7);
+    for _ in 0..100 {
+        assert_eq!(a.next_u64(), b.next_u64());
+    }
+    let f = a.next_f64();
+    assert!((0.0..1.0).contains(&f));
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn sim_network__latency__should_advance_virtual_clock(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker("echoer", Echoer, AllowAll, AllowAll)
+        .await?;
+
+    let network = SimNetwork::create(ctx, 1).await?;
+    let latency = LinkConditions::new().with_latency(Duration::from_millis(50));
+    network.set_link_conditions("client", "server", latency.clone());
+    network.set_link_conditions("server", "client", latency);
+
+    let link = network.connect(ctx, "client", "server").await?;
+    let reply: String = ctx
+        .send_and_receive(route![link, "echoer"], "Hello".to_string())
+        .await?;
+    assert_eq!(reply, "Hello");
+
+    // One round trip
+    assert_eq!(network.clock().now(), Duration::from_millis(100));
+    assert_eq!(network.stats().delivered, 2);
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn sim_network__partition__should_drop_messages(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker("echoer", Echoer, AllowAll, AllowAll)
+        .await?;
+
+    let network = SimNetwork::create(ctx, 2).await?;
+    let link = network.connect(ctx, "client", "server").await?;
+
+    network.partition("server", "client");
+    let mut child = ctx
+        .new_detached(Address::random_local(), AllowAll, AllowAll)
+        .await?;
+    child
+        .send(route![link.clone(), "echoer"], "Hello".to_string())
+        .await?;
+    let res = child
+        .receive_extended::<String>(
+            MessageReceiveOptions::new().with_timeout(Duration::from_millis(200)),
+        )
+        .await;
+    assert!(res.is_err());
+    assert_eq!(network.stats().partitioned, 1);
+
+    network.heal("client", "server");
+    let reply: String = ctx
+        .send_and_receive(route![link, "echoer"], "Hello".to_string())
+        .await?;
+    assert_eq!(reply, "Hello");
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn sim_network__total_loss__should_drop_messages(ctx: &mut Context) -> Result<()> {
+    let network = SimNetwork::create(ctx, 3).await?;
+    network.set_default_conditions(LinkConditions::new().with_loss(1.0));
+    let link = network.connect(ctx, "client", "server").await?;
+
+    for _ in 0..10 {
+        ctx.send(route![link.clone(), "app"], "Hello".to_string())
+            .await?;
+    }
+    ctx.sleep(Duration::from_millis(100)).await;
+
+    let stats = network.stats();
+    assert_eq!(stats.lost, 10);
+    assert_eq!(stats.delivered, 0);
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn virtual_clock__sleep__should_wait_for_the_clock(ctx: &mut Context) -> Result<()> {
+    let clock = VirtualClock::new();
+    let sleep = clock.sleep(Duration::from_secs(60));
+    let handle = ctx.runtime().spawn(sleep);
+
+    clock.advance(Duration::from_secs(30));
+    ctx.sleep(Duration::from_millis(50)).await;
+    assert!(!handle.is_finished());
+
+    clock.advance(Duration::from_secs(30));
+    tokio::time::timeout(Duration::from_secs(1), handle)
+        .await
+        .unwrap()
+        .unwrap();
+
+    ctx.stop().await
+}
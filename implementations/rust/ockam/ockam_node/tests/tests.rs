@@ -5,10 +5,13 @@ use ockam_core::compat::{
     string::{String, ToString},
     sync::Arc,
 };
-use ockam_core::{async_trait, Address, AllowAll, Any, Decodable, DenyAll, Message, LOCAL};
+use ockam_core::{
+    async_trait, Address, AllowAll, Any, Decodable, DenyAll, Encodable, Message, RelayMessage,
+    LOCAL,
+};
 use ockam_core::{route, Processor, Result, Routed, Worker};
 use ockam_node::compat::futures::FutureExt;
-use ockam_node::{Context, MessageReceiveOptions, NodeBuilder};
+use ockam_node::{Context, MessageInterceptor, MessageReceiveOptions, NodeBuilder, WorkerBuilder};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicI8, AtomicU32};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -564,3 +567,130 @@ async fn starting_worker_with_dup_address_should_fail(ctx: &mut Context) -> Resu
         .is_err());
     ctx.stop().await
 }
+
+struct CountingInterceptor {
+    before: Arc<AtomicU32>,
+    after: Arc<AtomicU32>,
+    drop_message: &'static str,
+}
+
+#[async_trait]
+impl MessageInterceptor for CountingInterceptor {
+    async fn before_handle_message(
+        &self,
+        _ctx: &Context,
+        relay_msg: RelayMessage,
+    ) -> Result<Option<RelayMessage>> {
+        self.before.fetch_add(1, Ordering::Relaxed);
+        let body = String::decode(&relay_msg.local_message().transport().payload)?;
+        if body == self.drop_message {
+            return Ok(None);
+        }
+        Ok(Some(relay_msg))
+    }
+
+    async fn after_handle_message(
+        &self,
+        _ctx: &Context,
+        _relay_msg: &RelayMessage,
+        result: &Result<()>,
+    ) -> Result<()> {
+        assert!(result.is_ok());
+        self.after.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn worker_interceptor__messages__should_be_intercepted(ctx: &mut Context) -> Result<()> {
+    let before = Arc::new(AtomicU32::new(0));
+    let after = Arc::new(AtomicU32::new(0));
+    let interceptor = CountingInterceptor {
+        before: before.clone(),
+        after: after.clone(),
+        drop_message: "drop me",
+    };
+
+    WorkerBuilder::with_access_control(
+        Arc::new(AllowAll),
+        Arc::new(AllowAll),
+        "intercepted_worker",
+        DummyWorker,
+    )
+    .with_interceptor(interceptor)
+    .start(ctx)
+    .await?;
+
+    ctx.send("intercepted_worker", "drop me".to_string())
+        .await?;
+    let reply = ctx
+        .send_and_receive::<String>("intercepted_worker", "hello".to_string())
+        .await?;
+    assert_eq!(reply, "hello");
+
+    // after_handle_message runs once the reply has been sent
+    ctx.sleep(Duration::from_millis(100)).await;
+    assert_eq!(before.load(Ordering::Relaxed), 2);
+    assert_eq!(after.load(Ordering::Relaxed), 1);
+
+    ctx.stop().await
+}
+
+struct RewritingInterceptor;
+
+#[async_trait]
+impl MessageInterceptor for RewritingInterceptor {
+    async fn before_handle_message(
+        &self,
+        _ctx: &Context,
+        relay_msg: RelayMessage,
+    ) -> Result<Option<RelayMessage>> {
+        let source = relay_msg.source().clone();
+        let destination = relay_msg.destination().clone();
+        let mut local_msg = relay_msg.into_local_message();
+        let body = String::decode(&local_msg.transport().payload)?;
+        if body == "drop me" {
+            return Ok(None);
+        }
+        local_msg.transport_mut().payload = body.to_uppercase().encode()?;
+        Ok(Some(RelayMessage::new(source, destination, local_msg)))
+    }
+}
+
+#[allow(non_snake_case)]
+#[test]
+fn node_interceptor__messages__should_be_modified_or_dropped() {
+    let (mut ctx, mut executor) = NodeBuilder::new()
+        .with_interceptor(RewritingInterceptor)
+        .build();
+    executor
+        .execute(async move {
+            let res = std::panic::AssertUnwindSafe(async {
+                ctx.start_worker("echoer", DummyWorker, AllowAll, AllowAll)
+                    .await?;
+                let mut child_ctx = ctx.new_detached("child", AllowAll, AllowAll).await?;
+
+                // The first message is dropped, the second one is modified
+                // before reaching the worker, which echoes it back
+                child_ctx.send("echoer", "drop me".to_string()).await?;
+                child_ctx.send("echoer", "hello".to_string()).await?;
+                let reply = child_ctx.receive::<String>().await?.body();
+                assert_eq!(reply, "HELLO");
+
+                let res = child_ctx
+                    .receive_extended::<String>(MessageReceiveOptions::new().with_timeout_secs(1))
+                    .await;
+                assert!(res.is_err());
+                Result::<()>::Ok(())
+            })
+            .catch_unwind()
+            .await;
+
+            ctx.stop().await?;
+
+            res.unwrap()
+        })
+        .unwrap()
+        .unwrap()
+}
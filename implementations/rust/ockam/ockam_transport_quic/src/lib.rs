@@ -0,0 +1,37 @@
+//! QUIC Transport utilities for Ockam's routing framework
+//!
+//! The `ockam_node` crate sits at the core
+//! of the Ockam routing framework, with transport specific
+//! abstraction plugins.  This crate implements a QUIC connection
+//! plugin for this architecture.
+//!
+//! Each QUIC connection multiplexes the messages sent over it on
+//! several streams: one stream per next hop of the onward route, so
+//! that a slow or congested route doesn't delay the other ones.
+//! Connections support client address migration and, when enabled,
+//! 0-RTT resumption of previously established sessions.
+#![deny(unsafe_code)]
+#![warn(
+    missing_docs,
+    dead_code,
+    trivial_casts,
+    trivial_numeric_casts,
+    unused_import_braces,
+    unused_qualifications
+)]
+
+mod options;
+mod registry;
+mod transport;
+
+pub use options::*;
+pub use registry::*;
+pub use transport::*;
+
+mod workers;
+pub(crate) use workers::*;
+
+pub(crate) const CLUSTER_NAME: &str = "_internals.transport.quic";
+
+/// ALPN protocol identifier negotiated by Ockam QUIC connections
+pub(crate) const ALPN_PROTOCOL: &[u8] = b"ockam/1";
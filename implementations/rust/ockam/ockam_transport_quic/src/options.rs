@@ -0,0 +1,184 @@
+use crate::workers::Addresses;
+use core::time::Duration;
+use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
+use ockam_core::{AllowAll, IncomingAccessControl, OutgoingAccessControl, Result};
+use ockam_transport_core::TransportError;
+
+pub(crate) struct QuicConnectionAccessControl {
+    pub sender_incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    pub flow_controls: Option<FlowControls>,
+}
+
+/// Trust Options for a QUIC connection
+#[derive(Clone, Debug)]
+pub struct QuicConnectionOptions {
+    pub(crate) producer_flow_control: Option<(FlowControls, FlowControlId)>,
+    pub(crate) zero_rtt: bool,
+    pub(crate) keep_alive_interval: Option<Duration>,
+}
+
+impl QuicConnectionOptions {
+    /// This constructor is insecure, because outgoing messages from such connections will not be
+    /// restricted and can reach any [`Address`] on this node.
+    /// Should only be used for testing purposes
+    pub fn insecure() -> Self {
+        Self::new()
+    }
+
+    /// This constructor is insecure, because outgoing messages from such connection will not be
+    /// restricted and can reach any [`Address`] on this node.
+    /// Should only be used for testing purposes
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            producer_flow_control: None,
+            zero_rtt: false,
+            keep_alive_interval: Some(Duration::from_secs(30)),
+        }
+    }
+
+    /// Mark this Quic Receivers as a Producer for a given [`FlowControlId`]
+    pub fn as_producer(flow_controls: &FlowControls, flow_control_id: &FlowControlId) -> Self {
+        Self {
+            producer_flow_control: Some((flow_controls.clone(), flow_control_id.clone())),
+            ..Self::new()
+        }
+    }
+
+    /// Send the first messages of the connection as 0-RTT data when
+    /// resuming a session with a peer this transport already connected to
+    ///
+    /// 0-RTT data can be replayed by an attacker, this is only acceptable
+    /// because messages are expected to be protected by a secure channel.
+    pub fn with_zero_rtt(mut self) -> Self {
+        self.zero_rtt = true;
+        self
+    }
+
+    /// Interval between keep-alive packets, `None` disables them
+    pub fn with_keep_alive_interval(mut self, interval: Option<Duration>) -> Self {
+        self.keep_alive_interval = interval;
+        self
+    }
+
+    pub(crate) fn setup_flow_control(&self, addresses: &Addresses) {
+        if let Some((flow_controls, flow_control_id)) = &self.producer_flow_control {
+            flow_controls.add_producer(
+                addresses.receiver_address(),
+                flow_control_id,
+                None,
+                vec![addresses.sender_address().clone()],
+            );
+        }
+    }
+
+    pub(crate) fn create_access_control(&self) -> QuicConnectionAccessControl {
+        match &self.producer_flow_control {
+            Some((flow_controls, flow_control_id)) => QuicConnectionAccessControl {
+                sender_incoming_access_control: Arc::new(AllowAll),
+                receiver_outgoing_access_control: Arc::new(FlowControlOutgoingAccessControl::new(
+                    flow_controls.clone(),
+                    flow_control_id.clone(),
+                    None,
+                )),
+                flow_controls: Some(flow_controls.clone()),
+            },
+            None => QuicConnectionAccessControl {
+                sender_incoming_access_control: Arc::new(AllowAll),
+                receiver_outgoing_access_control: Arc::new(AllowAll),
+                flow_controls: None,
+            },
+        }
+    }
+}
+
+/// Trust Options for a QUIC listener
+#[derive(Debug)]
+pub struct QuicListenerOptions {
+    pub(crate) spawner_flow_controls: Option<(FlowControls, FlowControlId)>,
+    pub(crate) zero_rtt: bool,
+}
+
+impl QuicListenerOptions {
+    /// This constructor is insecure, because outgoing messages from such connections will not be
+    /// restricted and can reach any [`Address`] on this node.
+    /// Should only be used for testing purposes
+    pub fn insecure() -> Self {
+        Self::new()
+    }
+
+    /// This constructor is insecure, because outgoing messages from such connections will not be
+    /// restricted and can reach any [`Address`] on this node.
+    /// Should only be used for testing purposes
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            spawner_flow_controls: None,
+            zero_rtt: false,
+        }
+    }
+
+    /// Mark this Quic Listener as a Spawner with given [`FlowControlId`].
+    /// NOTE: Spawned connections get fresh random [`FlowControlId`], however they are still marked
+    /// with Spawner's [`FlowControlId`]
+    pub fn as_spawner(flow_controls: &FlowControls, flow_control_id: &FlowControlId) -> Self {
+        Self {
+            spawner_flow_controls: Some((flow_controls.clone(), flow_control_id.clone())),
+            ..Self::new()
+        }
+    }
+
+    /// Accept 0-RTT data from clients resuming a previous session
+    ///
+    /// See [`QuicConnectionOptions::with_zero_rtt`].
+    pub fn with_zero_rtt(mut self) -> Self {
+        self.zero_rtt = true;
+        self
+    }
+
+    pub(crate) fn setup_flow_control(&self, addresses: &Addresses) -> Option<FlowControlId> {
+        if let Some((flow_controls, listener_flow_control_id)) = &self.spawner_flow_controls {
+            let flow_control_id = flow_controls.generate_id();
+
+            flow_controls.add_producer(
+                addresses.receiver_address(),
+                &flow_control_id,
+                Some(listener_flow_control_id),
+                vec![addresses.sender_address().clone()],
+            );
+
+            Some(flow_control_id)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn create_access_control(
+        &self,
+        flow_control_id: Option<FlowControlId>,
+    ) -> Result<QuicConnectionAccessControl> {
+        match (&self.spawner_flow_controls, flow_control_id) {
+            (Some((flow_controls, listener_flow_control_id)), Some(flow_control_id)) => {
+                Ok(QuicConnectionAccessControl {
+                    sender_incoming_access_control: Arc::new(AllowAll),
+                    receiver_outgoing_access_control: Arc::new(
+                        FlowControlOutgoingAccessControl::new(
+                            flow_controls.clone(),
+                            flow_control_id,
+                            Some(listener_flow_control_id.clone()),
+                        ),
+                    ),
+                    flow_controls: Some(flow_controls.clone()),
+                })
+            }
+            (None, None) => Ok(QuicConnectionAccessControl {
+                sender_incoming_access_control: Arc::new(AllowAll),
+                receiver_outgoing_access_control: Arc::new(AllowAll),
+                flow_controls: None,
+            }),
+            _ => Err(TransportError::FlowControlInconsistency.into()),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::Address;
+
+/// Registry of all active workers and processors in QUIC Transport to ease their lifecycle management
+#[derive(Default, Clone)]
+pub struct QuicRegistry {
+    registry: Arc<RwLock<InternalRegistry>>,
+}
+
+impl QuicRegistry {
+    pub(crate) fn add_listener_processor(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_listener_processor(addr);
+        }
+    }
+    pub(crate) fn remove_listener_processor(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_listener_processor(addr);
+        }
+    }
+    pub(crate) fn add_sender_worker(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_sender_worker(addr);
+        }
+    }
+    pub(crate) fn remove_sender_worker(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_sender_worker(addr);
+        }
+    }
+    pub(crate) fn add_zero_rtt_connection(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_zero_rtt_connection(addr);
+        }
+    }
+    pub(crate) fn add_receiver_processor(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_receiver_processor(addr);
+        }
+    }
+    pub(crate) fn remove_receiver_processor(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_receiver_processor(addr);
+        }
+    }
+}
+
+impl QuicRegistry {
+    /// Return [`Address`]es of all active listener processors
+    pub fn get_all_listener_processors(&self) -> Vec<Address> {
+        self.registry.read().unwrap().listener_processors.clone()
+    }
+
+    /// Return [`Address`]es of all active sender workers
+    pub fn get_all_sender_workers(&self) -> Vec<Address> {
+        self.registry.read().unwrap().sender_workers.clone()
+    }
+
+    /// Return the sender [`Address`]es of the active connections whose
+    /// first messages were sent as 0-RTT data accepted by the peer
+    pub fn get_all_zero_rtt_connections(&self) -> Vec<Address> {
+        self.registry.read().unwrap().zero_rtt_connections.clone()
+    }
+
+    /// Return [`Address`]es of all active receiver processors
+    pub fn get_all_receiver_processors(&self) -> Vec<Address> {
+        self.registry.read().unwrap().receiver_processors.clone()
+    }
+}
+
+#[derive(Default)]
+struct InternalRegistry {
+    listener_processors: Vec<Address>,
+    sender_workers: Vec<Address>,
+    zero_rtt_connections: Vec<Address>,
+    receiver_processors: Vec<Address>,
+}
+
+impl InternalRegistry {
+    fn add_listener_processor(&mut self, addr: &Address) {
+        self.listener_processors.push(addr.clone())
+    }
+    fn remove_listener_processor(&mut self, addr: &Address) {
+        self.listener_processors.retain(|x| x != addr);
+    }
+    fn add_sender_worker(&mut self, addr: &Address) {
+        self.sender_workers.push(addr.clone())
+    }
+    fn remove_sender_worker(&mut self, addr: &Address) {
+        self.sender_workers.retain(|x| x != addr);
+        self.zero_rtt_connections.retain(|x| x != addr);
+    }
+    fn add_zero_rtt_connection(&mut self, addr: &Address) {
+        self.zero_rtt_connections.push(addr.clone())
+    }
+    fn add_receiver_processor(&mut self, addr: &Address) {
+        self.receiver_processors.push(addr.clone())
+    }
+    fn remove_receiver_processor(&mut self, addr: &Address) {
+        self.receiver_processors.retain(|x| x != addr);
+    }
+}
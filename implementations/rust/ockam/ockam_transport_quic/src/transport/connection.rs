@@ -0,0 +1,109 @@
+use crate::transport::client_config;
+use crate::transport::common::resolve_peer;
+use crate::workers::{Addresses, ConnectionRole, QuicRecvProcessor, QuicSendWorker};
+use crate::{QuicConnectionOptions, QuicTransport};
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::{Address, Result};
+use ockam_transport_core::TransportError;
+use quinn::{Connecting, Connection};
+use tracing::debug;
+
+impl QuicTransport {
+    /// Establish an outgoing QUIC connection.
+    ///
+    /// If the options enable 0-RTT and this transport already connected
+    /// to the same peer, the session is resumed and messages are sent
+    /// before the handshake completes.
+    ///
+    /// ```rust
+    /// use ockam_transport_quic::{QuicConnectionOptions, QuicListenerOptions, QuicTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let quic = QuicTransport::create(&ctx).await?;
+    /// quic.listen("127.0.0.1:8000", QuicListenerOptions::new()).await?; // Listen on port 8000
+    /// let addr = quic.connect("127.0.0.1:5000", QuicConnectionOptions::new()).await?; // and connect to port 5000
+    /// # Ok(()) }
+    /// ```
+    pub async fn connect(
+        &self,
+        peer: impl Into<String>,
+        options: QuicConnectionOptions,
+    ) -> Result<Address> {
+        // Resolve peer address
+        let socket = resolve_peer(peer.into())?;
+
+        // The server name is only used to look up resumable sessions,
+        // certificates are not verified
+        let config = client_config(self.client_crypto.clone(), options.keep_alive_interval);
+        let connecting = self
+            .client
+            .connect_with(config, socket, &socket.ip().to_string())
+            .map_err(|e| {
+                debug!(addr = %socket, err = %e, "Failed to connect");
+                TransportError::InvalidAddress
+            })?;
+
+        let addresses = Addresses::generate(ConnectionRole::Initiator);
+
+        let connection = if options.zero_rtt {
+            match connecting.into_0rtt() {
+                Ok((connection, accepted)) => {
+                    debug!(addr = %socket, "Resumed session with 0-RTT");
+                    let registry = self.registry.clone();
+                    let sender_address = addresses.sender_address().clone();
+                    self.ctx.runtime().spawn(async move {
+                        if accepted.await {
+                            registry.add_zero_rtt_connection(&sender_address);
+                        } else {
+                            debug!(addr = %socket, "0-RTT data rejected");
+                        }
+                    });
+                    connection
+                }
+                // No session to resume with this peer yet
+                Err(connecting) => handshake(connecting, socket).await?,
+            }
+        } else {
+            handshake(connecting, socket).await?
+        };
+        debug!(addr = %socket, "Connected");
+
+        options.setup_flow_control(&addresses);
+        let access_control = options.create_access_control();
+
+        QuicSendWorker::start(
+            &self.ctx,
+            self.registry.clone(),
+            connection.clone(),
+            &addresses,
+            access_control.sender_incoming_access_control,
+        )
+        .await?;
+
+        QuicRecvProcessor::start(
+            &self.ctx,
+            self.registry.clone(),
+            connection,
+            &addresses,
+            access_control.receiver_outgoing_access_control,
+            access_control.flow_controls,
+        )
+        .await?;
+
+        Ok(addresses.sender_address().clone())
+    }
+
+    /// Interrupt an active QUIC connection given its `Address`
+    pub async fn disconnect(&self, address: &Address) -> Result<()> {
+        self.ctx.stop_worker(address.clone()).await
+    }
+}
+
+/// Wait for the handshake of an outgoing connection to complete
+async fn handshake(connecting: Connecting, peer: SocketAddr) -> Result<Connection> {
+    Ok(connecting.await.map_err(|e| {
+        debug!(addr = %peer, err = %e, "Failed to connect");
+        TransportError::PeerNotFound
+    })?)
+}
@@ -0,0 +1,75 @@
+use crate::transport::client_crypto;
+use crate::transport::common::parse_socket_addr;
+use crate::{QuicRegistry, QuicTransport};
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::{AsyncTryClone, Result};
+use ockam_node::Context;
+use ockam_transport_core::TransportError;
+use quinn::Endpoint;
+use std::net::UdpSocket;
+
+impl QuicTransport {
+    /// Create a QUIC transport
+    ///
+    /// Outgoing connections are made from a UDP socket bound to
+    /// `0.0.0.0:0`, use [`QuicTransport::rebind`] to connect to IPv6 peers.
+    ///
+    /// ```rust
+    /// use ockam_transport_quic::QuicTransport;
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let quic = QuicTransport::create(&ctx).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn create(ctx: &Context) -> Result<Self> {
+        let client =
+            Endpoint::client("0.0.0.0:0".parse().unwrap()).map_err(TransportError::from)?;
+
+        Ok(Self {
+            ctx: ctx.async_try_clone().await?,
+            registry: QuicRegistry::default(),
+            client,
+            client_crypto: client_crypto(),
+        })
+    }
+
+    /// Move all the outgoing connections to a new local UDP socket
+    ///
+    /// The established connections are migrated to the new address
+    /// without being interrupted, e.g. when the network interface of
+    /// this node changes.
+    ///
+    /// ```rust
+    /// use ockam_transport_quic::QuicTransport;
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let quic = QuicTransport::create(&ctx).await?;
+    /// quic.rebind("0.0.0.0:0").await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn rebind(&self, bind_addr: impl AsRef<str>) -> Result<SocketAddr> {
+        let bind_addr = parse_socket_addr(bind_addr.as_ref())?;
+        let socket = UdpSocket::bind(bind_addr).map_err(TransportError::from)?;
+        self.client.rebind(socket).map_err(TransportError::from)?;
+
+        self.local_addr()
+    }
+
+    /// Local address used by the outgoing connections
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.client.local_addr().map_err(TransportError::from)?)
+    }
+}
+
+impl QuicTransport {
+    /// Getter
+    pub fn ctx(&self) -> &Context {
+        &self.ctx
+    }
+    /// Registry of all active connections
+    pub fn registry(&self) -> &QuicRegistry {
+        &self.registry
+    }
+}
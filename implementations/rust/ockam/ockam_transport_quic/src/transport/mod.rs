@@ -0,0 +1,55 @@
+mod common;
+mod connection;
+mod lifecycle;
+mod listener;
+mod tls;
+
+pub(crate) use tls::*;
+
+use crate::QuicRegistry;
+use ockam_core::compat::sync::Arc;
+use ockam_core::{async_trait, AsyncTryClone, Result};
+use ockam_node::{Context, HasContext};
+use quinn::Endpoint;
+
+/// High level management interface for QUIC transports
+///
+/// To listen for incoming connections use
+/// [`quic.listen()`](crate::QuicTransport::listen).
+///
+/// To establish outgoing connections use
+/// [`quic.connect()`](crate::QuicTransport::connect). All outgoing
+/// connections share the same local UDP socket, which can be changed
+/// with [`quic.rebind()`](crate::QuicTransport::rebind) without
+/// interrupting the established connections.
+///
+/// ```rust
+/// use ockam_transport_quic::{QuicConnectionOptions, QuicListenerOptions, QuicTransport};
+/// # use ockam_node::Context;
+/// # use ockam_core::Result;
+/// # async fn test(ctx: Context) -> Result<()> {
+/// let quic = QuicTransport::create(&ctx).await?;
+/// quic.listen("127.0.0.1:8000", QuicListenerOptions::new()).await?; // Listen on port 8000
+/// quic.connect("127.0.0.1:5000", QuicConnectionOptions::new()).await?; // And connect to port 5000
+/// # Ok(()) }
+/// ```
+#[derive(AsyncTryClone)]
+#[async_try_clone(crate = "ockam_core")]
+pub struct QuicTransport {
+    ctx: Context,
+    registry: QuicRegistry,
+    client: Endpoint,
+    client_crypto: Arc<rustls::ClientConfig>,
+}
+
+/// This trait adds a `create_quic_transport` method to any struct returning a Context.
+/// This is the case for an ockam::Node, so you can write `node.create_quic_transport()`
+#[async_trait]
+pub trait QuicTransportExtension: HasContext {
+    /// Create a QUIC transport
+    async fn create_quic_transport(&self) -> Result<QuicTransport> {
+        QuicTransport::create(self.get_context()).await
+    }
+}
+
+impl<A: HasContext> QuicTransportExtension for A {}
@@ -0,0 +1,106 @@
+//! TLS configuration of the QUIC endpoints
+//!
+//! QUIC always runs TLS 1.3, but Ockam doesn't rely on it to
+//! authenticate peers: listeners use a freshly generated self-signed
+//! certificate and clients accept any certificate. Peers are expected
+//! to authenticate each other with an Ockam secure channel
+//! established over the QUIC connection.
+
+use crate::ALPN_PROTOCOL;
+use core::time::Duration;
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+use ockam_transport_core::TransportError;
+use quinn::{ServerConfig, TransportConfig, VarInt};
+use rustls::client::{ServerCertVerified, ServerCertVerifier};
+use rustls::{Certificate, PrivateKey, ServerName};
+use std::time::SystemTime;
+use tracing::debug;
+
+/// Maximum number of concurrent streams opened by a peer, i.e. the
+/// maximum number of routes a peer can use at the same time
+const MAX_CONCURRENT_STREAMS: u32 = 1024;
+
+/// Create the configuration of a listening endpoint
+pub(crate) fn server_config(zero_rtt: bool) -> Result<ServerConfig> {
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".into()]).map_err(|e| {
+        debug!(err = %e, "Failed to generate a certificate");
+        TransportError::BindFailed
+    })?;
+    let cert_der = cert
+        .serialize_der()
+        .map_err(|_| TransportError::BindFailed)?;
+    let key_der = cert.serialize_private_key_der();
+
+    let mut crypto = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(vec![Certificate(cert_der)], PrivateKey(key_der))
+        .map_err(|e| {
+            debug!(err = %e, "Invalid certificate");
+            TransportError::BindFailed
+        })?;
+    crypto.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+    if zero_rtt {
+        // QUIC requires the maximum amount of early data to be either 0 or u32::MAX
+        crypto.max_early_data_size = u32::MAX;
+    }
+
+    let mut config = ServerConfig::with_crypto(Arc::new(crypto));
+    config.transport_config(Arc::new(transport_config(None)));
+    // Let clients keep their connections when their address changes
+    config.migration(true);
+
+    Ok(config)
+}
+
+/// Create the TLS configuration shared by all the outgoing connections
+///
+/// Sharing it lets the connections to a peer resume the session of a
+/// previous connection to that peer.
+pub(crate) fn client_crypto() -> Arc<rustls::ClientConfig> {
+    let mut crypto = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate))
+        .with_no_client_auth();
+    crypto.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+    crypto.enable_early_data = true;
+
+    Arc::new(crypto)
+}
+
+/// Create the configuration of an outgoing connection
+pub(crate) fn client_config(
+    crypto: Arc<rustls::ClientConfig>,
+    keep_alive_interval: Option<Duration>,
+) -> quinn::ClientConfig {
+    let mut config = quinn::ClientConfig::new(crypto);
+    config.transport_config(Arc::new(transport_config(keep_alive_interval)));
+    config
+}
+
+fn transport_config(keep_alive_interval: Option<Duration>) -> TransportConfig {
+    let mut config = TransportConfig::default();
+    config
+        .max_concurrent_uni_streams(VarInt::from_u32(MAX_CONCURRENT_STREAMS))
+        .max_concurrent_bidi_streams(VarInt::from_u32(0))
+        .keep_alive_interval(keep_alive_interval);
+    config
+}
+
+/// Certificates are not verified, see the module documentation
+struct AcceptAnyCertificate;
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> core::result::Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
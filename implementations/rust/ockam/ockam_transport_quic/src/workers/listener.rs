@@ -0,0 +1,154 @@
+use crate::transport::server_config;
+use crate::workers::{Addresses, ConnectionRole, QuicRecvProcessor};
+use crate::{QuicListenerOptions, QuicRegistry, QuicSendWorker};
+use ockam_core::{async_trait, compat::net::SocketAddr, DenyAll};
+use ockam_core::{Address, Processor, Result};
+use ockam_node::Context;
+use ockam_transport_core::TransportError;
+use quinn::{Connecting, Connection, Endpoint, VarInt};
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tracing::debug;
+
+/// Maximum number of established connections waiting to be started
+const ESTABLISHED_QUEUE_CAPACITY: usize = 32;
+
+/// A QUIC Listen processor
+///
+/// QUIC listen processors are created by `QuicTransport`
+/// after a call is made to
+/// [`QuicTransport::listen`](crate::QuicTransport::listen).
+///
+/// Handshakes are run by separate tasks, so that a slow client
+/// doesn't prevent other clients from connecting.
+pub(crate) struct QuicListenProcessor {
+    registry: QuicRegistry,
+    endpoint: Endpoint,
+    options: QuicListenerOptions,
+    established_tx: Sender<Connection>,
+    established_rx: Receiver<Connection>,
+}
+
+impl QuicListenProcessor {
+    pub(crate) async fn start(
+        ctx: &Context,
+        registry: QuicRegistry,
+        addr: SocketAddr,
+        options: QuicListenerOptions,
+    ) -> Result<(SocketAddr, Address)> {
+        debug!("Binding QUIC endpoint to {}", addr);
+        let endpoint = Endpoint::server(server_config(options.zero_rtt)?, addr).map_err(|e| {
+            debug!(addr = %addr, err = %e, "Failed to bind");
+            TransportError::BindFailed
+        })?;
+        let saddr = endpoint.local_addr().map_err(TransportError::from)?;
+
+        let address = Address::random_tagged("QuicListenProcessor");
+        if let Some((flow_controls, flow_control_id)) = &options.spawner_flow_controls {
+            flow_controls.add_spawner(&address, flow_control_id);
+        }
+
+        let (established_tx, established_rx) = channel(ESTABLISHED_QUEUE_CAPACITY);
+        let processor = Self {
+            registry,
+            endpoint,
+            options,
+            established_tx,
+            established_rx,
+        };
+
+        ctx.start_processor(address.clone(), processor, DenyAll, DenyAll)
+            .await?;
+
+        Ok((saddr, address))
+    }
+
+    async fn start_connection(&self, ctx: &Context, connection: Connection) -> Result<()> {
+        let addresses = Addresses::generate(ConnectionRole::Responder);
+
+        let flow_control_id = self.options.setup_flow_control(&addresses);
+        let access_control = self
+            .options
+            .create_access_control(flow_control_id.clone())?;
+
+        // Worker to receive messages from the Node and send them over the wire
+        QuicSendWorker::start(
+            ctx,
+            self.registry.clone(),
+            connection.clone(),
+            &addresses,
+            access_control.sender_incoming_access_control,
+        )
+        .await?;
+
+        // Processor to receive messages over the wire and forward them to the node
+        QuicRecvProcessor::start(
+            ctx,
+            self.registry.clone(),
+            connection,
+            &addresses,
+            access_control.receiver_outgoing_access_control,
+            access_control.flow_controls,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Processor for QuicListenProcessor {
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await?;
+
+        self.registry.add_listener_processor(&ctx.address());
+
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.registry.remove_listener_processor(&ctx.address());
+
+        if let Some((flow_controls, _)) = &self.options.spawner_flow_controls {
+            flow_controls.cleanup_address(&ctx.address());
+        }
+
+        self.endpoint
+            .close(VarInt::from_u32(0), b"listener stopped");
+
+        Ok(())
+    }
+
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        debug!("Waiting for incoming QUIC connection...");
+
+        tokio::select! {
+            connecting = self.endpoint.accept() => match connecting {
+                Some(connecting) => {
+                    ctx.runtime()
+                        .spawn(handshake(connecting, self.established_tx.clone()));
+                }
+                // The endpoint was closed
+                None => return Ok(false),
+            },
+            Some(connection) = self.established_rx.recv() => {
+                debug!("QUIC connection accepted from {}", connection.remote_address());
+                self.start_connection(ctx, connection).await?;
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// Complete the handshake of an incoming connection
+async fn handshake(connecting: Connecting, established_tx: Sender<Connection>) {
+    let peer = connecting.remote_address();
+    match connecting.await {
+        Ok(connection) => {
+            let _ = established_tx.send(connection).await;
+        }
+        Err(e) => debug!(addr = %peer, err = %e, "QUIC handshake failed"),
+    }
+}
@@ -0,0 +1,219 @@
+use crate::workers::{Addresses, MAX_MESSAGE_SIZE};
+use crate::{QuicRegistry, QuicSendWorkerMsg};
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::FlowControls;
+use ockam_core::{async_trait, DenyAll, Mailbox, Mailboxes, OutgoingAccessControl};
+use ockam_core::{Decodable, LocalMessage, Processor, Result, TransportMessage};
+use ockam_node::{Context, ProcessorBuilder};
+use ockam_transport_core::TransportError;
+use quinn::{Connection, RecvStream};
+use tokio::runtime::Handle;
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tracing::{debug, info, trace};
+
+/// Maximum number of received messages waiting to be relayed into the node
+const RECEIVED_QUEUE_CAPACITY: usize = 128;
+
+/// Error code sent to the peer when stopping a stream whose next hop is gone
+const UNKNOWN_NEXT_HOP: u32 = 1;
+
+/// A message read from a stream
+struct ReceivedMessage {
+    buf: Vec<u8>,
+    /// Asks the task reading the stream to stop it
+    stop_stream: Sender<()>,
+}
+
+/// A QUIC receiving message processor
+///
+/// This half of the worker is created when spawning a new connection
+/// worker pair. The streams opened by the peer are read by separate
+/// tasks, which pass the received messages to this processor to relay
+/// them into the node message system. A stream is stopped when its
+/// messages can't be relayed because their next hop doesn't exist.
+pub(crate) struct QuicRecvProcessor {
+    registry: QuicRegistry,
+    rx: Receiver<ReceivedMessage>,
+    peer: SocketAddr,
+    addresses: Addresses,
+    flow_controls: Option<FlowControls>,
+}
+
+impl QuicRecvProcessor {
+    pub async fn start(
+        ctx: &Context,
+        registry: QuicRegistry,
+        connection: Connection,
+        addresses: &Addresses,
+        receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        flow_controls: Option<FlowControls>,
+    ) -> Result<()> {
+        let peer = connection.remote_address();
+        let (tx, rx) = channel(RECEIVED_QUEUE_CAPACITY);
+        let runtime = ctx.runtime().clone();
+        ctx.runtime()
+            .spawn(accept_streams(runtime, connection, peer, tx));
+
+        let receiver = Self {
+            registry,
+            rx,
+            peer,
+            addresses: addresses.clone(),
+            flow_controls,
+        };
+
+        let mailbox = Mailbox::new(
+            addresses.receiver_address().clone(),
+            Arc::new(DenyAll),
+            receiver_outgoing_access_control,
+        );
+        ProcessorBuilder::with_mailboxes(Mailboxes::new(mailbox, vec![]), receiver)
+            .start(ctx)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Processor for QuicRecvProcessor {
+    type Context = Context;
+
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await?;
+
+        self.registry.add_receiver_processor(&ctx.address());
+
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.registry.remove_receiver_processor(&ctx.address());
+
+        if let Some(flow_controls) = &self.flow_controls {
+            flow_controls.cleanup_address(&ctx.address());
+        }
+
+        Ok(())
+    }
+
+    /// Get the next message read from any stream of the connection
+    /// and forward it to the next hop in the route.
+    async fn process(&mut self, ctx: &mut Context) -> Result<bool> {
+        let ReceivedMessage { buf, stop_stream } = match self.rx.recv().await {
+            Some(msg) => msg,
+            None => {
+                info!(
+                    "Connection to peer '{}' was closed; dropping streams",
+                    self.peer
+                );
+
+                // Notify sender tx is closed
+                ctx.send(
+                    self.addresses.sender_internal_addr().clone(),
+                    QuicSendWorkerMsg::ConnectionClosed,
+                )
+                .await?;
+
+                return Ok(false);
+            }
+        };
+
+        // Deserialize the message now
+        let mut msg = TransportMessage::decode(&buf).map_err(|_| TransportError::RecvBadMessage)?;
+
+        // Insert the peer address into the return route so that
+        // reply routing can be properly resolved
+        msg.return_route
+            .modify()
+            .prepend(self.addresses.sender_address().clone());
+
+        trace!("Message onward route: {}", msg.onward_route);
+        trace!("Message return route: {}", msg.return_route);
+
+        // Forward the message to the next hop in the route
+        if let Err(e) = ctx.forward(LocalMessage::new(msg, vec![])).await {
+            debug!(addr = %self.peer, err = %e, "Failed to relay a message, stopping its stream");
+            let _ = stop_stream.try_send(());
+        }
+
+        Ok(true)
+    }
+}
+
+/// Accept the streams opened by the peer and start a task reading each of them
+///
+/// Stops when the connection is closed.
+async fn accept_streams(
+    runtime: Handle,
+    connection: Connection,
+    peer: SocketAddr,
+    tx: Sender<ReceivedMessage>,
+) {
+    loop {
+        match connection.accept_uni().await {
+            Ok(stream) => {
+                trace!("Accepted a new stream from {}", peer);
+                runtime.spawn(read_stream(stream, peer, tx.clone()));
+            }
+            Err(e) => {
+                debug!(addr = %peer, err = %e, "Connection closed");
+                return;
+            }
+        }
+    }
+}
+
+/// Read the length-prefixed messages sent over one stream
+///
+/// Stops when the stream is finished, or when the receiver processor
+/// asks to stop it.
+async fn read_stream(mut stream: RecvStream, peer: SocketAddr, tx: Sender<ReceivedMessage>) {
+    let (stop_stream, mut stop_rx) = channel(1);
+    loop {
+        let buf = tokio::select! {
+            buf = read_message(&mut stream, peer) => match buf {
+                Some(buf) => buf,
+                None => return,
+            },
+            _ = stop_rx.recv() => {
+                let _ = stream.stop(UNKNOWN_NEXT_HOP.into());
+                return;
+            }
+        };
+
+        let msg = ReceivedMessage {
+            buf,
+            stop_stream: stop_stream.clone(),
+        };
+        if tx.send(msg).await.is_err() {
+            // The receiver processor is stopped
+            return;
+        }
+    }
+}
+
+/// Read the next length-prefixed message of a stream
+async fn read_message(stream: &mut RecvStream, peer: SocketAddr) -> Option<Vec<u8>> {
+    let mut len = [0; 4];
+    if stream.read_exact(&mut len).await.is_err() {
+        trace!("Stream from {} finished", peer);
+        return None;
+    }
+
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_MESSAGE_SIZE {
+        debug!("Message of length {} from {} is too large", len, peer);
+        let _ = stream.stop(0u32.into());
+        return None;
+    }
+
+    let mut buf = vec![0; len];
+    if stream.read_exact(&mut buf).await.is_err() {
+        debug!("Failed to receive message of length {} from {}", len, peer);
+        return None;
+    }
+
+    Some(buf)
+}
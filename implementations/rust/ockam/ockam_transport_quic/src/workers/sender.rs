@@ -0,0 +1,306 @@
+use crate::workers::Addresses;
+use crate::QuicRegistry;
+use ockam_core::compat::collections::HashMap;
+use ockam_core::{
+    async_trait,
+    compat::{net::SocketAddr, sync::Arc},
+    AllowSourceAddress, DenyAll, IncomingAccessControl,
+};
+use ockam_core::{
+    Address, Any, Decodable, Encodable, Mailbox, Mailboxes, Message, Result, Routed,
+    TransportMessage, Worker,
+};
+use ockam_node::{Context, WorkerBuilder};
+use ockam_transport_core::TransportError;
+use quinn::{Connection, SendStream, VarInt, WriteError};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tracing::{debug, info, trace, warn};
+
+/// Maximum size of an encoded message sent over a QUIC stream
+pub(crate) const MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+/// Maximum number of messages waiting to be written to a stream
+const STREAM_QUEUE_CAPACITY: usize = 32;
+
+#[derive(Serialize, Deserialize, Message, Clone)]
+pub(crate) enum QuicSendWorkerMsg {
+    ConnectionClosed,
+}
+
+pub(crate) enum ConnectionRole {
+    Initiator,
+    Responder,
+}
+
+impl ConnectionRole {
+    pub(crate) fn str(&self) -> &'static str {
+        match self {
+            ConnectionRole::Initiator => "initiator",
+            ConnectionRole::Responder => "responder",
+        }
+    }
+}
+
+/// A QUIC sending message worker
+///
+/// This half of the worker is created when spawning a new connection
+/// worker pair, and listens for messages from the node message system
+/// to dispatch to a remote peer.
+///
+/// Every next hop of the onward route gets its own unidirectional
+/// QUIC stream, written by a separate task, so that messages for a
+/// route which is slow to consume its data don't delay the messages
+/// sent to other routes, until the queue of that stream is full.
+///
+/// The peer stops the stream of a next hop which doesn't exist
+/// anymore, its write task then ends and the stream is forgotten.
+pub(crate) struct QuicSendWorker {
+    registry: QuicRegistry,
+    connection: Connection,
+    streams: HashMap<Address, Sender<Vec<u8>>>,
+    peer: SocketAddr,
+    addresses: Addresses,
+    rx_should_be_stopped: bool,
+}
+
+impl QuicSendWorker {
+    /// Create a new `QuicSendWorker`
+    fn new(
+        registry: QuicRegistry,
+        connection: Connection,
+        peer: SocketAddr,
+        addresses: Addresses,
+    ) -> Self {
+        Self {
+            registry,
+            connection,
+            streams: HashMap::new(),
+            peer,
+            addresses,
+            rx_should_be_stopped: true,
+        }
+    }
+}
+
+impl QuicSendWorker {
+    /// Start the sending half of a `(QuicSendWorker, QuicRecvProcessor)` pair
+    /// managing the given connection
+    pub(crate) async fn start(
+        ctx: &Context,
+        registry: QuicRegistry,
+        connection: Connection,
+        addresses: &Addresses,
+        sender_incoming_access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Result<()> {
+        trace!("Creating new QUIC worker pair");
+        let peer = connection.remote_address();
+        let sender_worker = Self::new(registry, connection, peer, addresses.clone());
+
+        let main_mailbox = Mailbox::new(
+            addresses.sender_address().clone(),
+            sender_incoming_access_control,
+            Arc::new(DenyAll),
+        );
+
+        let internal_mailbox = Mailbox::new(
+            addresses.sender_internal_addr().clone(),
+            Arc::new(AllowSourceAddress(addresses.receiver_address().clone())),
+            Arc::new(DenyAll),
+        );
+
+        WorkerBuilder::with_mailboxes(
+            Mailboxes::new(main_mailbox, vec![internal_mailbox]),
+            sender_worker,
+        )
+        .start(ctx)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn stop(&self, ctx: &Context) -> Result<()> {
+        ctx.stop_worker(self.addresses.sender_address().clone())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Open a new stream for the given next hop and start its write task
+    async fn open_stream(&mut self, ctx: &Context, next_hop: &Address) -> Result<Sender<Vec<u8>>> {
+        let stream = self.connection.open_uni().await.map_err(|e| {
+            debug!(addr = %self.peer, err = %e, "Failed to open a stream");
+            TransportError::ConnectionDrop
+        })?;
+        trace!("Opened a new stream to {} for {}", self.peer, next_hop);
+
+        let (tx, rx) = channel(STREAM_QUEUE_CAPACITY);
+        ctx.runtime()
+            .spawn(write_stream(self.connection.clone(), stream, self.peer, rx));
+        self.streams.insert(next_hop.clone(), tx.clone());
+
+        Ok(tx)
+    }
+}
+
+#[async_trait]
+impl Worker for QuicSendWorker {
+    type Context = Context;
+    type Message = Any;
+
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await?;
+
+        self.registry
+            .add_sender_worker(self.addresses.sender_address());
+
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.registry
+            .remove_sender_worker(self.addresses.sender_address());
+
+        // Closing the connection also stops the tasks reading and writing its streams
+        self.connection.close(VarInt::from_u32(0), b"closed");
+
+        if self.rx_should_be_stopped {
+            let _ = ctx
+                .stop_processor(self.addresses.receiver_address().clone())
+                .await;
+        }
+
+        Ok(())
+    }
+
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let recipient = msg.msg_addr();
+        if &recipient == self.addresses.sender_internal_addr() {
+            let msg = QuicSendWorkerMsg::decode(msg.payload())?;
+
+            match msg {
+                QuicSendWorkerMsg::ConnectionClosed => {
+                    info!("Stopping sender due to closed connection {}", self.peer);
+                    // No need to stop Receiver as it notified us about connection drop and will
+                    // stop itself
+                    self.rx_should_be_stopped = false;
+                    self.stop(ctx).await?;
+
+                    return Ok(());
+                }
+            }
+        }
+
+        let mut msg = msg.into_transport_message();
+        // Remove our own address from the route so the other end
+        // knows what to do with the incoming message
+        msg.onward_route.step()?;
+
+        let next_hop = match msg.onward_route.next() {
+            Ok(next_hop) => next_hop.clone(),
+            Err(_) => {
+                warn!("Dropping message to {} without onward route", self.peer);
+                return Ok(());
+            }
+        };
+
+        let msg = prepare_message(msg)?;
+
+        // Forget the streams whose write task stopped, e.g. because
+        // the peer stopped the stream when its next hop went away
+        self.streams.retain(|_, stream| !stream.is_closed());
+
+        // Sending only fails if the write task of the stream stopped, in
+        // which case a new stream is opened, unless the connection is closed
+        let msg = match self.streams.get(&next_hop) {
+            Some(stream) => match stream.send(msg).await {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    self.streams.remove(&next_hop);
+                    e.0
+                }
+            },
+            None => msg,
+        };
+
+        if self.connection.close_reason().is_some() {
+            warn!("Failed to send message to peer {}", self.peer);
+            self.stop(ctx).await?;
+
+            return Ok(());
+        }
+
+        match self.open_stream(ctx, &next_hop).await {
+            Ok(stream) if stream.send(msg).await.is_ok() => {}
+            _ => {
+                warn!("Failed to send message to peer {}", self.peer);
+                self.stop(ctx).await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Write the queued messages of one route to its stream
+///
+/// Stops when writing fails, e.g. when the peer stopped the stream, or
+/// when the [`QuicSendWorker`] is dropped.
+/// If the stream was opened with 0-RTT data which the peer rejected,
+/// the stream is reopened once the handshake completes. Messages which
+/// were already written to the rejected stream are lost.
+async fn write_stream(
+    connection: Connection,
+    mut stream: SendStream,
+    peer: SocketAddr,
+    mut rx: Receiver<Vec<u8>>,
+) {
+    while let Some(msg) = rx.recv().await {
+        match stream.write_all(msg.as_slice()).await {
+            Ok(()) => {}
+            Err(WriteError::ZeroRttRejected) => {
+                debug!(addr = %peer, "0-RTT data rejected, reopening the stream");
+                stream = match connection.open_uni().await {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        debug!(addr = %peer, err = %e, "Failed to open a stream");
+                        return;
+                    }
+                };
+                if let Err(e) = stream.write_all(msg.as_slice()).await {
+                    debug!(addr = %peer, err = %e, "Failed to write to a stream");
+                    return;
+                }
+            }
+            Err(e) => {
+                debug!(addr = %peer, err = %e, "Failed to write to a stream");
+                return;
+            }
+        }
+    }
+
+    let _ = stream.finish().await;
+    trace!("Stream to peer {} finished", peer);
+}
+
+/// Helper that creates a length-prefixed buffer containing the given
+/// `TransportMessage`'s payload
+///
+/// The length-prefix is encoded as a big-endian 32-bit unsigned
+/// integer.
+fn prepare_message(msg: TransportMessage) -> Result<Vec<u8>> {
+    let msg = msg.encode().map_err(|_| TransportError::SendBadMessage)?;
+    if msg.len() > MAX_MESSAGE_SIZE {
+        return Err(TransportError::Capacity.into());
+    }
+
+    let mut buf = Vec::with_capacity(4 + msg.len());
+    buf.extend_from_slice(&(msg.len() as u32).to_be_bytes());
+    buf.extend_from_slice(&msg);
+
+    Ok(buf)
+}
@@ -0,0 +1,149 @@
+use core::time::Duration;
+use ockam_core::compat::rand::{self, Rng};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{route, AllowAll, Mailboxes, Result, Routed, Worker};
+use ockam_node::{Context, WorkerBuilder};
+use ockam_transport_quic::{QuicConnectionOptions, QuicListenerOptions, QuicTransport};
+
+pub struct Echoer;
+
+#[ockam_core::worker]
+impl Worker for Echoer {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        ctx.send(msg.return_route(), msg.body()).await
+    }
+}
+
+async fn start_echoer(ctx: &Context, address: &str) -> Result<()> {
+    WorkerBuilder::with_mailboxes(
+        Mailboxes::main(address, Arc::new(AllowAll), Arc::new(AllowAll)),
+        Echoer,
+    )
+    .start(ctx)
+    .await?;
+    Ok(())
+}
+
+fn random_message() -> String {
+    rand::thread_rng()
+        .sample_iter(&rand::distributions::Alphanumeric)
+        .take(256)
+        .map(char::from)
+        .collect()
+}
+
+#[ockam_macros::test]
+async fn send_receive(ctx: &mut Context) -> Result<()> {
+    let transport = QuicTransport::create(ctx).await?;
+    let (listener_address, _) = transport
+        .listen("127.0.0.1:0", QuicListenerOptions::new())
+        .await?;
+    start_echoer(ctx, "echoer").await?;
+    start_echoer(ctx, "other_echoer").await?;
+
+    let addr = transport
+        .connect(listener_address.to_string(), QuicConnectionOptions::new())
+        .await?;
+
+    // Each route uses its own stream
+    for echoer in ["echoer", "other_echoer"] {
+        let msg = random_message();
+        let reply = ctx
+            .send_and_receive::<String>(route![addr.clone(), echoer], msg.clone())
+            .await?;
+
+        assert_eq!(reply, msg, "Should receive the same message");
+    }
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn send_receive_after_rebind(ctx: &mut Context) -> Result<()> {
+    let transport = QuicTransport::create(ctx).await?;
+    let (listener_address, _) = transport
+        .listen("127.0.0.1:0", QuicListenerOptions::new())
+        .await?;
+    start_echoer(ctx, "echoer").await?;
+
+    let addr = transport
+        .connect(listener_address.to_string(), QuicConnectionOptions::new())
+        .await?;
+
+    let msg = random_message();
+    let reply = ctx
+        .send_and_receive::<String>(route![addr.clone(), "echoer"], msg.clone())
+        .await?;
+    assert_eq!(reply, msg);
+
+    // The connection migrates to the new local address
+    let old_local_addr = transport.local_addr()?;
+    let new_local_addr = transport.rebind("127.0.0.1:0").await?;
+    assert_ne!(old_local_addr, new_local_addr);
+
+    let msg = random_message();
+    let reply = ctx
+        .send_and_receive::<String>(route![addr, "echoer"], msg.clone())
+        .await?;
+    assert_eq!(reply, msg);
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn send_receive_with_zero_rtt(ctx: &mut Context) -> Result<()> {
+    let transport = QuicTransport::create(ctx).await?;
+    let (listener_address, _) = transport
+        .listen("127.0.0.1:0", QuicListenerOptions::new().with_zero_rtt())
+        .await?;
+    start_echoer(ctx, "echoer").await?;
+
+    // The second connection resumes the session of the first one
+    for resumed in [false, true] {
+        let addr = transport
+            .connect(
+                listener_address.to_string(),
+                QuicConnectionOptions::new().with_zero_rtt(),
+            )
+            .await?;
+
+        let msg = random_message();
+        let reply = ctx
+            .send_and_receive::<String>(route![addr.clone(), "echoer"], msg.clone())
+            .await?;
+        assert_eq!(reply, msg);
+
+        // The peer acknowledges the 0-RTT data when the handshake completes
+        let mut zero_rtt = false;
+        for _ in 0..50 {
+            zero_rtt = transport
+                .registry()
+                .get_all_zero_rtt_connections()
+                .contains(&addr);
+            if zero_rtt {
+                break;
+            }
+            ctx.sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(zero_rtt, resumed);
+
+        transport.disconnect(&addr).await?;
+    }
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
@@ -7,6 +7,7 @@ use ockam_transport_core::TransportError;
 pub(crate) struct TcpConnectionAccessControl {
     pub sender_incoming_access_control: Arc<dyn IncomingAccessControl>,
     pub receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    pub flow_controls: Option<FlowControls>,
 }
 
 /// Trust Options for a TCP connection
@@ -55,17 +56,19 @@ impl TcpConnectionOptions {
 
     pub(crate) fn create_access_control(self) -> TcpConnectionAccessControl {
         match self.producer_flow_control {
-            Some((flow_controls, flow_control_id)) => {
-                TcpConnectionAccessControl {
-                    sender_incoming_access_control: Arc::new(AllowAll),
-                    receiver_outgoing_access_control: Arc::new(
-                        FlowControlOutgoingAccessControl::new(flow_controls, flow_control_id, None),
-                    ),
-                }
-            }
+            Some((flow_controls, flow_control_id)) => TcpConnectionAccessControl {
+                sender_incoming_access_control: Arc::new(AllowAll),
+                receiver_outgoing_access_control: Arc::new(FlowControlOutgoingAccessControl::new(
+                    flow_controls.clone(),
+                    flow_control_id,
+                    None,
+                )),
+                flow_controls: Some(flow_controls),
+            },
             None => TcpConnectionAccessControl {
                 sender_incoming_access_control: Arc::new(AllowAll),
                 receiver_outgoing_access_control: Arc::new(AllowAll),
+                flow_controls: None,
             },
         }
     }
@@ -138,11 +141,13 @@ impl TcpListenerOptions {
                             Some(listener_flow_control_id.clone()),
                         ),
                     ),
+                    flow_controls: Some(flow_controls.clone()),
                 })
             }
             (None, None) => Ok(TcpConnectionAccessControl {
                 sender_incoming_access_control: Arc::new(AllowAll),
                 receiver_outgoing_access_control: Arc::new(AllowAll),
+                flow_controls: None,
             }),
             _ => Err(TransportError::FlowControlInconsistency.into()),
         }
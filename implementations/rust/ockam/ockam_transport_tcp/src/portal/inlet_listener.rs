@@ -96,6 +96,8 @@ impl Processor for TcpInletListenProcessor {
             outlet_listener_route,
             addresses,
             self.options.incoming_access_control.clone(),
+            self.options.outgoing_access_control.clone(),
+            self.options.consumer_flow_controls.clone(),
         )
         .await?;
 
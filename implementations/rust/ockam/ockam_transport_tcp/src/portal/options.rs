@@ -1,13 +1,14 @@
 use crate::portal::addresses::Addresses;
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControlPolicy, FlowControls};
-use ockam_core::{Address, AllowAll, IncomingAccessControl, Result};
+use ockam_core::{Address, AllowAll, IncomingAccessControl, OutgoingAccessControl, Result};
 use ockam_transport_core::TransportError;
 
 /// Trust Options for an Inlet
 pub struct TcpInletOptions {
     pub(super) consumer_flow_controls: Option<FlowControls>,
     pub(super) incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub(super) outgoing_access_control: Option<Arc<dyn OutgoingAccessControl>>,
 }
 
 impl TcpInletOptions {
@@ -16,6 +17,7 @@ impl TcpInletOptions {
         Self {
             consumer_flow_controls: None,
             incoming_access_control: Arc::new(AllowAll),
+            outgoing_access_control: None,
         }
     }
 
@@ -37,6 +39,16 @@ impl TcpInletOptions {
         self
     }
 
+    /// Set the Outgoing Access Control checking the Outlet at the other side of the
+    /// portals, once when they connect. A portal whose Outlet is denied is closed
+    pub fn with_outgoing_access_control(
+        mut self,
+        access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Self {
+        self.outgoing_access_control = Some(access_control);
+        self
+    }
+
     /// Mark that created Inlets are Consumer for to the given [`FlowControlId`]
     pub fn as_consumer(mut self, flow_controls: &FlowControls) -> Self {
         self.consumer_flow_controls = Some(flow_controls.clone());
@@ -82,6 +94,8 @@ pub(super) struct ConsumerFlowControl {
 pub struct TcpOutletOptions {
     pub(super) consumer_flow_control: Option<ConsumerFlowControl>,
     pub(super) incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub(super) outgoing_access_control: Option<Arc<dyn OutgoingAccessControl>>,
+    pub(super) connect_access_control: Option<Arc<dyn IncomingAccessControl>>,
 }
 
 impl TcpOutletOptions {
@@ -90,6 +104,8 @@ impl TcpOutletOptions {
         Self {
             consumer_flow_control: None,
             incoming_access_control: Arc::new(AllowAll),
+            outgoing_access_control: None,
+            connect_access_control: None,
         }
     }
 
@@ -111,6 +127,28 @@ impl TcpOutletOptions {
         self
     }
 
+    /// Set the Incoming Access Control of the Outlet listener, checking the requests
+    /// to open new connections to the Outlet target
+    ///
+    /// The Incoming Access Control is used if it is not set.
+    pub fn with_connect_access_control(
+        mut self,
+        access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Self {
+        self.connect_access_control = Some(access_control);
+        self
+    }
+
+    /// Set the Outgoing Access Control checking the Inlet at the other side of the
+    /// portals, once when they connect. A portal whose Inlet is denied is closed
+    pub fn with_outgoing_access_control(
+        mut self,
+        access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Self {
+        self.outgoing_access_control = Some(access_control);
+        self
+    }
+
     /// Mark that this Outlet listener is a Consumer for to the given [`FlowControlId`]
     /// Also, in this case spawned Outlets will be marked as Consumers with [`FlowControlId`]
     /// of the message that was used to create the Outlet
@@ -35,7 +35,10 @@ impl TcpOutletListenWorker {
         peer: SocketAddr,
         options: TcpOutletOptions,
     ) -> Result<()> {
-        let access_control = options.incoming_access_control.clone();
+        let access_control = options
+            .connect_access_control
+            .clone()
+            .unwrap_or_else(|| options.incoming_access_control.clone());
 
         if let Some(consumer_flow_control) = &options.consumer_flow_control {
             consumer_flow_control.flow_controls.add_consumer(
@@ -71,6 +74,12 @@ impl Worker for TcpOutletListenWorker {
     async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
         self.registry.remove_outlet_listener_worker(&ctx.address());
 
+        if let Some(consumer_flow_control) = &self.options.consumer_flow_control {
+            consumer_flow_control
+                .flow_controls
+                .cleanup_address(&ctx.address());
+        }
+
         Ok(())
     }
 
@@ -112,6 +121,11 @@ impl Worker for TcpOutletListenWorker {
             return_route.clone(),
             addresses.clone(),
             self.options.incoming_access_control.clone(),
+            self.options.outgoing_access_control.clone(),
+            self.options
+                .consumer_flow_control
+                .as_ref()
+                .map(|x| x.flow_controls.clone()),
         )
         .await?;
 
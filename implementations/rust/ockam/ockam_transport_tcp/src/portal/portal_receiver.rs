@@ -1,7 +1,7 @@
 use crate::portal::portal_message::MAX_PAYLOAD_SIZE;
 use crate::{PortalInternalMessage, PortalMessage, TcpRegistry};
 use ockam_core::compat::vec::Vec;
-use ockam_core::{async_trait, Encodable, LocalMessage, Route, TransportMessage};
+use ockam_core::{async_trait, Encodable, LocalMessage, MessagePriority, Route, TransportMessage};
 use ockam_core::{route, Address, Processor, Result};
 use ockam_node::Context;
 use tokio::{io::AsyncReadExt, net::tcp::OwnedReadHalf};
@@ -98,7 +98,9 @@ impl Processor for TcpPortalRecvProcessor {
                 self.sender_address.clone(),
                 PortalMessage::Payload(chunk.to_vec()).encode()?,
             );
-            ctx.forward(LocalMessage::new(msg, vec![])).await?;
+            // Portal data must not delay control messages sharing the same connection
+            let local_info = vec![MessagePriority::Bulk.to_local_info()];
+            ctx.forward(LocalMessage::new(msg, local_info)).await?;
         }
 
         Ok(true)
@@ -2,9 +2,11 @@ use crate::portal::addresses::{Addresses, PortalType};
 use crate::{PortalInternalMessage, PortalMessage, TcpPortalRecvProcessor, TcpRegistry};
 use core::time::Duration;
 use ockam_core::compat::{boxed::Box, net::SocketAddr, sync::Arc};
+use ockam_core::flow_control::FlowControls;
 use ockam_core::{
-    async_trait, AllowAll, AllowOnwardAddresses, AllowSourceAddress, Decodable, DenyAll,
-    IncomingAccessControl, Mailbox, Mailboxes,
+    async_trait, route, AllowAll, AllowOnwardAddresses, AllowSourceAddress, Decodable, DenyAll,
+    IncomingAccessControl, LocalMessage, Mailbox, Mailboxes, OutgoingAccessControl, RelayMessage,
+    TransportMessage,
 };
 use ockam_core::{Any, Result, Route, Routed, Worker};
 use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
@@ -44,10 +46,13 @@ pub(crate) struct TcpPortalWorker {
     remote_route: Option<Route>,
     is_disconnecting: bool,
     portal_type: PortalType,
+    outgoing_access_control: Option<Arc<dyn OutgoingAccessControl>>,
+    flow_controls: Option<FlowControls>,
 }
 
 impl TcpPortalWorker {
     /// Start a new `TcpPortalWorker` of type [`TypeName::Inlet`]
+    #[allow(clippy::too_many_arguments)]
     pub(super) async fn start_new_inlet(
         ctx: &Context,
         registry: TcpRegistry,
@@ -56,6 +61,8 @@ impl TcpPortalWorker {
         ping_route: Route,
         addresses: Addresses,
         access_control: Arc<dyn IncomingAccessControl>,
+        outgoing_access_control: Option<Arc<dyn OutgoingAccessControl>>,
+        flow_controls: Option<FlowControls>,
     ) -> Result<()> {
         Self::start(
             ctx,
@@ -66,11 +73,14 @@ impl TcpPortalWorker {
             addresses,
             PortalType::Inlet,
             access_control,
+            outgoing_access_control,
+            flow_controls,
         )
         .await
     }
 
     /// Start a new `TcpPortalWorker` of type [`TypeName::Outlet`]
+    #[allow(clippy::too_many_arguments)]
     pub(super) async fn start_new_outlet(
         ctx: &Context,
         registry: TcpRegistry,
@@ -78,6 +88,8 @@ impl TcpPortalWorker {
         pong_route: Route,
         addresses: Addresses,
         access_control: Arc<dyn IncomingAccessControl>,
+        outgoing_access_control: Option<Arc<dyn OutgoingAccessControl>>,
+        flow_controls: Option<FlowControls>,
     ) -> Result<()> {
         Self::start(
             ctx,
@@ -88,6 +100,8 @@ impl TcpPortalWorker {
             addresses,
             PortalType::Outlet,
             access_control,
+            outgoing_access_control,
+            flow_controls,
         )
         .await
     }
@@ -103,6 +117,8 @@ impl TcpPortalWorker {
         addresses: Addresses,
         portal_type: PortalType,
         access_control: Arc<dyn IncomingAccessControl>,
+        outgoing_access_control: Option<Arc<dyn OutgoingAccessControl>>,
+        flow_controls: Option<FlowControls>,
     ) -> Result<()> {
         info!(
             "Creating new {:?} at internal: {}, remote: {}",
@@ -129,6 +145,8 @@ impl TcpPortalWorker {
             remote_route: None,
             is_disconnecting: false,
             portal_type,
+            outgoing_access_control,
+            flow_controls,
         };
 
         let internal_mailbox = Mailbox::new(
@@ -177,13 +195,14 @@ impl TcpPortalWorker {
                 onward_route,
             );
 
+            // Only sends messages to `onward_route` and Sender
             let mailbox = Mailbox::new(
                 self.addresses.receiver.clone(),
                 Arc::new(DenyAll),
                 Arc::new(AllowOnwardAddresses(vec![
                     next_hop,
                     self.addresses.internal.clone(),
-                ])), // Only sends messages to `onward_route` and Sender
+                ])),
             );
             ProcessorBuilder::with_mailboxes(Mailboxes::new(mailbox, vec![]), receiver)
                 .start(ctx)
@@ -277,6 +296,44 @@ impl TcpPortalWorker {
         Ok(())
     }
 
+    /// Return true if the other side of the portal, reached with the given route,
+    /// is allowed by the outgoing access control of the portal
+    ///
+    /// This is checked once when the portal connects, rather than for every
+    /// message sent by the portal.
+    async fn is_remote_authorized(&self, remote_route: &Route) -> Result<bool> {
+        let access_control = match &self.outgoing_access_control {
+            Some(access_control) => access_control,
+            None => return Ok(true),
+        };
+        let transport_msg = TransportMessage::v1(
+            remote_route.clone(),
+            route![self.addresses.remote.clone()],
+            vec![],
+        );
+        let relay_msg = RelayMessage::new(
+            self.addresses.remote.clone(),
+            remote_route.next()?.clone(),
+            LocalMessage::new(transport_msg, vec![]),
+        );
+        access_control.is_authorized(&relay_msg).await
+    }
+
+    /// Close a portal whose other side is not allowed by its outgoing access control,
+    /// without sending it anything
+    async fn close_unauthorized(&mut self, ctx: &Context) -> Result<()> {
+        warn!(
+            "{:?} at: {} closed, the other side of the portal was denied by the outgoing access control",
+            self.portal_type.str(),
+            self.addresses.internal
+        );
+        self.is_disconnecting = true;
+        // Dropping the stream closes the connection of an Inlet
+        self.write_half = None;
+        self.read_half = None;
+        ctx.stop_worker(self.addresses.internal.clone()).await
+    }
+
     async fn handle_send_ping(&self, ctx: &Context, ping_route: Route) -> Result<State> {
         // Force creation of Outlet on the other side
         ctx.send_from_address(
@@ -333,9 +390,15 @@ impl Worker for TcpPortalWorker {
 
         match state {
             State::SendPing { ping_route } => {
+                if !self.is_remote_authorized(&ping_route).await? {
+                    return self.close_unauthorized(ctx).await;
+                }
                 self.state = self.handle_send_ping(ctx, ping_route.clone()).await?;
             }
             State::SendPong { pong_route } => {
+                if !self.is_remote_authorized(&pong_route).await? {
+                    return self.close_unauthorized(ctx).await;
+                }
                 self.state = self.handle_send_pong(ctx, pong_route.clone()).await?;
             }
             State::ReceivePong | State::Initialized { .. } => {
@@ -351,6 +414,10 @@ impl Worker for TcpPortalWorker {
     async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
         self.registry.remove_portal_worker(&self.addresses.remote);
 
+        if let Some(flow_controls) = &self.flow_controls {
+            flow_controls.cleanup_address(&self.addresses.remote);
+        }
+
         Ok(())
     }
 
@@ -48,6 +48,7 @@ impl TcpTransport {
             &addresses,
             socket,
             access_control.receiver_outgoing_access_control,
+            access_control.flow_controls,
         )
         .await?;
 
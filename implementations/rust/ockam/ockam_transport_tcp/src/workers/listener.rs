@@ -64,6 +64,10 @@ impl Processor for TcpListenProcessor {
     async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
         self.registry.remove_listener_processor(&ctx.address());
 
+        if let Some((flow_controls, _)) = &self.options.spawner_flow_controls {
+            flow_controls.cleanup_address(&ctx.address());
+        }
+
         Ok(())
     }
 
@@ -102,6 +106,7 @@ impl Processor for TcpListenProcessor {
             &addresses,
             peer,
             access_control.receiver_outgoing_access_control,
+            access_control.flow_controls,
         )
         .await?;
 
@@ -2,8 +2,9 @@ use crate::workers::Addresses;
 use crate::{TcpRegistry, TcpSendWorkerMsg};
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::FlowControls;
 use ockam_core::{async_trait, DenyAll, Mailbox, Mailboxes, OutgoingAccessControl};
-use ockam_core::{Decodable, LocalMessage, Processor, Result, TransportMessage};
+use ockam_core::{Decodable, LocalMessage, Processor, Result, TransportMessage, TransportPeerInfo};
 use ockam_node::{Context, ProcessorBuilder};
 use ockam_transport_core::TransportError;
 use tokio::{io::AsyncReadExt, net::tcp::OwnedReadHalf};
@@ -22,6 +23,7 @@ pub(crate) struct TcpRecvProcessor {
     read_half: OwnedReadHalf,
     peer: SocketAddr,
     addresses: Addresses,
+    flow_controls: Option<FlowControls>,
 }
 
 impl TcpRecvProcessor {
@@ -31,12 +33,14 @@ impl TcpRecvProcessor {
         read_half: OwnedReadHalf,
         peer: SocketAddr,
         addresses: Addresses,
+        flow_controls: Option<FlowControls>,
     ) -> Self {
         Self {
             registry,
             read_half,
             peer,
             addresses,
+            flow_controls,
         }
     }
 
@@ -47,8 +51,10 @@ impl TcpRecvProcessor {
         addresses: &Addresses,
         peer: SocketAddr,
         receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        flow_controls: Option<FlowControls>,
     ) -> Result<()> {
-        let receiver = TcpRecvProcessor::new(registry, read_half, peer, addresses.clone());
+        let receiver =
+            TcpRecvProcessor::new(registry, read_half, peer, addresses.clone(), flow_controls);
 
         let mailbox = Mailbox::new(
             addresses.receiver_address().clone(),
@@ -78,6 +84,10 @@ impl Processor for TcpRecvProcessor {
     async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
         self.registry.remove_receiver_processor(&ctx.address());
 
+        if let Some(flow_controls) = &self.flow_controls {
+            flow_controls.cleanup_address(&ctx.address());
+        }
+
         Ok(())
     }
 
@@ -146,8 +156,10 @@ impl Processor for TcpRecvProcessor {
         trace!("Message onward route: {}", msg.onward_route);
         trace!("Message return route: {}", msg.return_route);
 
-        // Forward the message to the next hop in the route
-        ctx.forward(LocalMessage::new(msg, vec![])).await?;
+        // Forward the message to the next hop in the route,
+        // marked with the address of the peer it was received from
+        let local_info = vec![TransportPeerInfo::new(self.peer.ip().to_string()).to_local_info()];
+        ctx.forward(LocalMessage::new(msg, local_info)).await?;
 
         Ok(true)
     }
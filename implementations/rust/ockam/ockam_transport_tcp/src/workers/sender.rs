@@ -4,12 +4,16 @@ use cfg_if::cfg_if;
 use core::time::Duration;
 use ockam_core::{
     async_trait,
-    compat::{net::SocketAddr, sync::Arc},
+    compat::{
+        collections::VecDeque,
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+    },
     AllowSourceAddress, DenyAll, IncomingAccessControl,
 };
 use ockam_core::{
-    Any, Decodable, Encodable, Mailbox, Mailboxes, Message, Result, Routed, TransportMessage,
-    Worker,
+    Any, Decodable, Encodable, Mailbox, Mailboxes, Message, MessagePriority, Result, Routed,
+    TransportMessage, Worker,
 };
 use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::TransportError;
@@ -18,6 +22,8 @@ use socket2::{SockRef, TcpKeepalive};
 use tokio::io::AsyncWriteExt;
 use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::TcpStream;
+use tokio::sync::mpsc::error::{TryRecvError, TrySendError};
+use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tracing::{debug, info, trace, warn};
 
 #[derive(Serialize, Deserialize, Message, Clone)]
@@ -39,6 +45,55 @@ impl ConnectionRole {
     }
 }
 
+/// Maximum number of [`MessagePriority::Control`] messages waiting to be written
+const CONTROL_QUEUE_CAPACITY: usize = 128;
+/// Maximum number of [`MessagePriority::Bulk`] messages waiting to be written
+///
+/// Bulk messages can be close to the 64KiB limit of a TCP message, so this
+/// queue is kept short.
+const BULK_QUEUE_CAPACITY: usize = 32;
+
+/// Bulk messages which didn't fit in the bulk queue, in the order they were sent
+type BulkOverflow = Arc<Mutex<VecDeque<Vec<u8>>>>;
+
+/// Queues of encoded messages waiting to be written to the socket, one per [`MessagePriority`]
+///
+/// When the socket can't keep up, the sender worker waits for some room
+/// in the control queue. It never waits for the bulk queue: bulk messages
+/// which don't fit are kept in an overflow list drained by the write loop,
+/// so that control messages are never stuck in the worker mailbox behind
+/// bulk ones.
+struct PriorityQueues {
+    control: Sender<Vec<u8>>,
+    bulk: Sender<Vec<u8>>,
+    bulk_overflow: BulkOverflow,
+}
+
+impl PriorityQueues {
+    /// Queue a message, return false if the write loop is stopped
+    async fn send(&self, priority: MessagePriority, msg: Vec<u8>) -> bool {
+        match priority {
+            MessagePriority::Control => self.control.send(msg).await.is_ok(),
+            MessagePriority::Bulk => {
+                let mut bulk_overflow = self.bulk_overflow.lock().unwrap();
+                // Keep the order of bulk messages once some of them overflowed
+                if !bulk_overflow.is_empty() {
+                    bulk_overflow.push_back(msg);
+                    return !self.bulk.is_closed();
+                }
+                match self.bulk.try_send(msg) {
+                    Ok(()) => true,
+                    Err(TrySendError::Full(msg)) => {
+                        bulk_overflow.push_back(msg);
+                        true
+                    }
+                    Err(TrySendError::Closed(_)) => false,
+                }
+            }
+        }
+    }
+}
+
 /// A TCP sending message worker
 ///
 /// Create this worker type by calling
@@ -47,9 +102,13 @@ impl ConnectionRole {
 /// This half of the worker is created when spawning a new connection
 /// worker pair, and listens for messages from the node message system
 /// to dispatch to a remote peer.
+///
+/// Messages are queued according to their [`MessagePriority`] and
+/// written to the socket by a separate task, so that control messages
+/// are not stuck behind a backlog of bulk messages (e.g. portal data).
 pub(crate) struct TcpSendWorker {
     registry: TcpRegistry,
-    write_half: OwnedWriteHalf,
+    queues: PriorityQueues,
     peer: SocketAddr,
     addresses: Addresses,
     rx_should_be_stopped: bool,
@@ -59,13 +118,13 @@ impl TcpSendWorker {
     /// Create a new `TcpSendWorker`
     fn new(
         registry: TcpRegistry,
-        write_half: OwnedWriteHalf,
+        queues: PriorityQueues,
         peer: SocketAddr,
         addresses: Addresses,
     ) -> Self {
         Self {
             registry,
-            write_half,
+            queues,
             peer,
             addresses,
             rx_should_be_stopped: true,
@@ -85,7 +144,23 @@ impl TcpSendWorker {
         sender_incoming_access_control: Arc<dyn IncomingAccessControl>,
     ) -> Result<()> {
         trace!("Creating new TCP worker pair");
-        let sender_worker = Self::new(registry, write_half, peer, addresses.clone());
+        let (control, control_rx) = channel(CONTROL_QUEUE_CAPACITY);
+        let (bulk, bulk_rx) = channel(BULK_QUEUE_CAPACITY);
+        let bulk_overflow = BulkOverflow::default();
+        ctx.runtime().spawn(write_loop(
+            write_half,
+            peer,
+            control_rx,
+            bulk_rx,
+            bulk_overflow.clone(),
+        ));
+
+        let queues = PriorityQueues {
+            control,
+            bulk,
+            bulk_overflow,
+        };
+        let sender_worker = Self::new(registry, queues, peer, addresses.clone());
 
         let main_mailbox = Mailbox::new(
             addresses.sender_address().clone(),
@@ -196,6 +271,7 @@ impl Worker for TcpSendWorker {
                 }
             }
         } else {
+            let priority = MessagePriority::find_info(msg.local_message()).unwrap_or_default();
             let mut msg = msg.into_transport_message();
             // Remove our own address from the route so the other end
             // knows what to do with the incoming message
@@ -203,7 +279,8 @@ impl Worker for TcpSendWorker {
             // Create a message buffer with prepended length
             let msg = prepare_message(msg)?;
 
-            if self.write_half.write_all(msg.as_slice()).await.is_err() {
+            // Sending only fails if the write loop stopped because the socket failed
+            if !self.queues.send(priority, msg).await {
                 warn!("Failed to send message to peer {}", self.peer);
                 self.stop(ctx).await?;
 
@@ -215,6 +292,56 @@ impl Worker for TcpSendWorker {
     }
 }
 
+/// Write the queued messages to the socket, always sending
+/// [`MessagePriority::Control`] messages before [`MessagePriority::Bulk`] ones
+///
+/// Bulk messages are taken from the bulk queue first, then from its overflow.
+/// Stops when writing fails or when the [`TcpSendWorker`] is dropped.
+async fn write_loop(
+    mut write_half: OwnedWriteHalf,
+    peer: SocketAddr,
+    mut control_rx: Receiver<Vec<u8>>,
+    mut bulk_rx: Receiver<Vec<u8>>,
+    bulk_overflow: BulkOverflow,
+) {
+    loop {
+        let msg = match control_rx.try_recv() {
+            Ok(msg) => msg,
+            Err(_) => match next_bulk_message(&mut bulk_rx, &bulk_overflow) {
+                Some(msg) => msg,
+                // Nothing is queued, wait for the next message
+                None => tokio::select! {
+                    biased;
+                    Some(msg) = control_rx.recv() => msg,
+                    Some(msg) = bulk_rx.recv() => msg,
+                    else => break,
+                },
+            },
+        };
+
+        if write_half.write_all(msg.as_slice()).await.is_err() {
+            warn!("Failed to send message to peer {}", peer);
+            break;
+        }
+    }
+
+    trace!("Write loop for peer {} stopped", peer);
+}
+
+/// Return the oldest bulk message waiting to be written, if any
+fn next_bulk_message(
+    bulk_rx: &mut Receiver<Vec<u8>>,
+    bulk_overflow: &BulkOverflow,
+) -> Option<Vec<u8>> {
+    // The overflow is locked while checking the queue, so that the worker
+    // can't add a message to the overflow while the queue looks empty
+    let mut bulk_overflow = bulk_overflow.lock().unwrap();
+    match bulk_rx.try_recv() {
+        Ok(msg) => Some(msg),
+        Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => bulk_overflow.pop_front(),
+    }
+}
+
 /// Helper that creates a length-prefixed buffer containing the given
 /// `TransportMessage`'s payload
 ///
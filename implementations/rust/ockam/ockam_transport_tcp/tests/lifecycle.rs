@@ -1,5 +1,6 @@
 use core::time::Duration;
 use ockam_core::compat::rand::{self, Rng};
+use ockam_core::flow_control::{FlowControlPolicy, FlowControls};
 use ockam_core::{route, AllowAll, Result, Routed, Worker};
 use ockam_node::Context;
 use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
@@ -189,3 +190,47 @@ async fn tcp_lifecycle__stop_listener__should_stop_accepting_connections(
 
     Ok(())
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn tcp_lifecycle__disconnect__should_remove_flow_control_consumers(
+    ctx: &mut Context,
+) -> Result<()> {
+    let transport = TcpTransport::create(ctx).await?;
+    let listener_address = transport
+        .listen("127.0.0.1:0", TcpListenerOptions::new())
+        .await?
+        .0
+        .to_string();
+
+    let flow_controls = FlowControls::default();
+    let flow_control_id = flow_controls.generate_id();
+    let tx_address = transport
+        .connect(
+            &listener_address,
+            TcpConnectionOptions::as_producer(&flow_controls, &flow_control_id),
+        )
+        .await?;
+    flow_controls.add_consumer(
+        &ctx.address(),
+        &flow_control_id,
+        FlowControlPolicy::ProducerAllowMultiple,
+    );
+
+    assert_eq!(flow_controls.get_flow_controls_info().len(), 1);
+
+    transport.disconnect(&tx_address).await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+
+    // The consumer was only reachable through the closed connection
+    assert!(flow_controls
+        .get_flow_controls_with_consumer(&ctx.address())
+        .is_empty());
+    assert!(flow_controls.get_flow_controls_info().is_empty());
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use ockam_core::compat::rand::{self, Rng};
 use ockam_core::compat::sync::Arc;
-use ockam_core::{route, AllowAll, Mailboxes, Result, Routed, Worker};
+use ockam_core::{route, AllowAll, Mailboxes, MessagePriority, Result, Routed, Worker};
 use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
 
@@ -54,3 +54,63 @@ async fn send_receive(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+#[ockam_macros::test]
+async fn send_receive_with_priorities(ctx: &mut Context) -> Result<()> {
+    let transport = TcpTransport::create(ctx).await?;
+    let (listener_address, _) = transport
+        .listen("127.0.0.1:0", TcpListenerOptions::new())
+        .await?;
+    WorkerBuilder::with_mailboxes(
+        Mailboxes::main("echoer", Arc::new(AllowAll), Arc::new(AllowAll)),
+        Echoer,
+    )
+    .start(ctx)
+    .await?;
+
+    let addr = transport
+        .connect(listener_address.to_string(), TcpConnectionOptions::new())
+        .await?;
+
+    // Queue large bulk messages, so that they are still waiting to be
+    // written to the socket when the control message is sent
+    let bulk_count = 200;
+    let payload = "x".repeat(60_000);
+    for i in 0..bulk_count {
+        ctx.send_with_local_info(
+            route![addr.clone(), "echoer"],
+            format!("bulk {i} {payload}"),
+            vec![MessagePriority::Bulk.to_local_info()],
+        )
+        .await?;
+    }
+    ctx.send_with_local_info(
+        route![addr, "echoer"],
+        "control".to_string(),
+        vec![MessagePriority::Control.to_local_info()],
+    )
+    .await?;
+
+    let mut replies = vec![];
+    for _ in 0..bulk_count + 1 {
+        replies.push(ctx.receive::<String>().await?.body());
+    }
+    let bulk_replies: Vec<&String> = replies.iter().filter(|r| r.starts_with("bulk")).collect();
+    assert_eq!(bulk_replies.len(), bulk_count);
+    // Bulk messages keep their order
+    for (i, reply) in bulk_replies.iter().enumerate() {
+        assert!(reply.starts_with(&format!("bulk {i} ")));
+    }
+    // The control message overtakes most of the bulk messages still queued
+    let control_position = replies.iter().position(|r| r == "control").unwrap();
+    assert!(
+        control_position < bulk_count / 2,
+        "control message arrived after {control_position} bulk messages"
+    );
+
+    if let Err(e) = ctx.stop().await {
+        println!("Unclean stop: {}", e)
+    }
+
+    Ok(())
+}